@@ -0,0 +1,283 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use caps::Caps;
+use element::{Element, ElementT};
+use pad::Pad;
+use subclass::*;
+
+// GstAggregator was added to gstbase in GStreamer 1.14, after ffi.rs was
+// generated
+#[repr(C)]
+pub struct GstAggregator{
+    pub parent: GstElement,
+    pub srcpad: *mut GstPad,
+    _priv: gpointer,
+    _gst_reserved: [gpointer; 20],
+}
+
+#[repr(C)]
+pub struct GstAggregatorPad{
+    pub parent: GstPad,
+    pub segment: GstSegment,
+    _priv: gpointer,
+    _gst_reserved: [gpointer; 4],
+}
+
+#[repr(C)]
+struct GstAggregatorClass{
+    parent_class: GstElementClass,
+    flush: gpointer,
+    clip: gpointer,
+    finish_buffer: gpointer,
+    sink_event: gpointer,
+    sink_query: gpointer,
+    src_event: gpointer,
+    src_query: gpointer,
+    src_activate: gpointer,
+    aggregate: Option<extern "C" fn(*mut GstAggregator, gboolean) -> GstFlowReturn>,
+    stop: Option<extern "C" fn(*mut GstAggregator) -> gboolean>,
+    start: Option<extern "C" fn(*mut GstAggregator) -> gboolean>,
+    get_next_time: Option<extern "C" fn(*mut GstAggregator) -> GstClockTime>,
+}
+
+extern "C"{
+    fn gst_aggregator_get_type() -> GType;
+    fn gst_aggregator_finish_buffer(aggregator: *mut GstAggregator, buffer: *mut GstBuffer) -> GstFlowReturn;
+    fn gst_aggregator_set_src_caps(aggregator: *mut GstAggregator, caps: *mut GstCaps);
+    fn gst_aggregator_set_latency(aggregator: *mut GstAggregator, min_latency: GstClockTime, max_latency: GstClockTime);
+    fn gst_aggregator_get_latency(aggregator: *mut GstAggregator) -> GstClockTime;
+    fn gst_aggregator_pad_peek_buffer(pad: *mut GstAggregatorPad) -> *mut GstBuffer;
+    fn gst_aggregator_pad_pop_buffer(pad: *mut GstAggregatorPad) -> *mut GstBuffer;
+    fn gst_aggregator_pad_drop_buffer(pad: *mut GstAggregatorPad) -> gboolean;
+    fn gst_aggregator_pad_has_buffer(pad: *mut GstAggregatorPad) -> gboolean;
+    fn gst_aggregator_pad_is_eos(pad: *mut GstAggregatorPad) -> gboolean;
+    // added in GStreamer 1.16
+    fn gst_aggregator_simple_get_next_time(aggregator: *mut GstAggregator) -> GstClockTime;
+}
+
+/// An N-to-1 element implemented in Rust, as a mixer or a muxer, on top
+/// of GstAggregator which queues the buffers of each sink pad and calls
+/// aggregate once all of them have data, or in live pipelines once the
+/// latency expired. Register it with register_aggregator.
+///
+/// The sink pad templates are usually request pads named "sink_%u".
+pub trait AggregatorImpl: ElementImpl{
+    /// Produces the output from the buffers queued in the sink pads,
+    /// peeking and popping them, and pushes it with finish_buffer.
+    /// timeout is true when called because the latency expired with some
+    /// pads still empty. Return GST_FLOW_EOS once all the pads are EOS
+    fn aggregate(&self, aggregator: &Aggregator, timeout: bool) -> GstFlowReturn;
+
+    /// Called when the element starts, to allocate resources
+    fn start(&self, _aggregator: &Aggregator) -> bool{
+        true
+    }
+
+    fn stop(&self, _aggregator: &Aggregator) -> bool{
+        true
+    }
+
+    /// Running time in nanoseconds of the next output, in live pipelines
+    /// aggregate is called with timeout at this time plus the latency.
+    /// By default the end of the last output or the start of the
+    /// segment
+    fn next_time(&self, aggregator: &Aggregator) -> Option<u64>{
+        unsafe{
+            let time = gst_aggregator_simple_get_next_time(aggregator.gst_aggregator());
+            if time == GST_CLOCK_TIME_NONE{ None }else{ Some(time) }
+        }
+    }
+}
+
+/// An aggregator as passed to the methods of an AggregatorImpl
+pub struct Aggregator{
+    aggregator: Element
+}
+
+unsafe impl Sync for Aggregator {}
+unsafe impl Send for Aggregator {}
+
+impl Aggregator{
+    unsafe fn new(aggregator: *mut GstAggregator) -> Aggregator{
+        gst_object_ref(aggregator as gpointer);
+        Aggregator{ aggregator: Element::new_from_gst_element(aggregator as *mut GstElement).unwrap() }
+    }
+
+    /// The sink pads, in the order they were added
+    pub fn sink_pads(&self) -> Vec<AggregatorPad>{
+        unsafe{
+            iterator_objects(gst_element_iterate_sink_pads(self.aggregator.gst_element() as *mut GstElement)).into_iter()
+                .filter_map(|pad| Pad::new(pad as *mut GstPad, true))
+                .map(|pad| AggregatorPad{ pad: pad })
+                .collect()
+        }
+    }
+
+    pub fn src_pad(&self) -> Pad{
+        unsafe{
+            Pad::new((*self.gst_aggregator()).srcpad, false).unwrap()
+        }
+    }
+
+    /// Pushes an output buffer, sending the caps and segment events
+    /// before if needed
+    pub fn finish_buffer(&self, buffer: Buffer) -> GstFlowReturn{
+        unsafe{
+            gst_aggregator_finish_buffer(self.gst_aggregator(), ::Transfer::transfer(buffer))
+        }
+    }
+
+    /// Sets the caps of the output, sent with the next finish_buffer
+    pub fn set_src_caps(&self, caps: &Caps){
+        unsafe{
+            gst_aggregator_set_src_caps(self.gst_aggregator(), caps.gst_caps() as *mut GstCaps);
+        }
+    }
+
+    /// Latency in nanoseconds the element adds on top of the upstream one,
+    /// max None if it can buffer without limit
+    pub fn set_latency(&self, min: u64, max: Option<u64>){
+        unsafe{
+            gst_aggregator_set_latency(self.gst_aggregator(), min, max.unwrap_or(GST_CLOCK_TIME_NONE));
+        }
+    }
+
+    /// Total latency in nanoseconds once configured in a live pipeline
+    pub fn latency(&self) -> Option<u64>{
+        unsafe{
+            let latency = gst_aggregator_get_latency(self.gst_aggregator());
+            if latency == GST_CLOCK_TIME_NONE{ None }else{ Some(latency) }
+        }
+    }
+
+    pub unsafe fn gst_aggregator(&self) -> *mut GstAggregator{
+        self.aggregator.gst_element() as *mut GstAggregator
+    }
+}
+
+impl ElementT for Aggregator{
+    fn as_element(&self) -> &Element{
+        &self.aggregator
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.aggregator
+    }
+}
+
+impl ::Transfer for Aggregator{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.aggregator.transfer()
+    }
+}
+
+/// A sink pad of an Aggregator with its queue of buffers
+pub struct AggregatorPad{
+    pad: Pad
+}
+
+impl AggregatorPad{
+    /// The next buffer in the queue without removing it
+    pub fn peek_buffer(&self) -> Option<Buffer>{
+        unsafe{
+            Buffer::new(gst_aggregator_pad_peek_buffer(self.gst_aggregator_pad()), true)
+        }
+    }
+
+    /// Removes the next buffer from the queue and returns it
+    pub fn pop_buffer(&self) -> Option<Buffer>{
+        unsafe{
+            Buffer::new(gst_aggregator_pad_pop_buffer(self.gst_aggregator_pad()), true)
+        }
+    }
+
+    /// Removes the next buffer from the queue, returns false if it was
+    /// empty
+    pub fn drop_buffer(&self) -> bool{
+        unsafe{
+            gst_aggregator_pad_drop_buffer(self.gst_aggregator_pad()) == 1
+        }
+    }
+
+    pub fn has_buffer(&self) -> bool{
+        unsafe{
+            gst_aggregator_pad_has_buffer(self.gst_aggregator_pad()) == 1
+        }
+    }
+
+    /// Whether the pad received EOS and has no more buffers queued
+    pub fn is_eos(&self) -> bool{
+        unsafe{
+            gst_aggregator_pad_is_eos(self.gst_aggregator_pad()) == 1
+        }
+    }
+
+    /// The segment of the pad, to convert the timestamps of its buffers
+    /// to running time
+    pub fn segment(&self) -> GstSegment{
+        unsafe{
+            (*self.gst_aggregator_pad()).segment
+        }
+    }
+
+    pub fn pad(&self) -> &Pad{
+        &self.pad
+    }
+
+    pub unsafe fn gst_aggregator_pad(&self) -> *mut GstAggregatorPad{
+        self.pad.gst_pad() as *mut GstAggregatorPad
+    }
+}
+
+extern "C" fn aggregator_class_init<T: AggregatorImpl>(class: gpointer, _data: gpointer){
+    unsafe{
+        element_class_init::<T>(class);
+        let aggregator_class = class as *mut GstAggregatorClass;
+        (*aggregator_class).aggregate = Some(aggregator_aggregate::<T>);
+        (*aggregator_class).start = Some(aggregator_start::<T>);
+        (*aggregator_class).stop = Some(aggregator_stop::<T>);
+        (*aggregator_class).get_next_time = Some(aggregator_get_next_time::<T>);
+    }
+}
+
+extern "C" fn aggregator_aggregate<T: AggregatorImpl>(aggregator: *mut GstAggregator, timeout: gboolean) -> GstFlowReturn{
+    unsafe{
+        imp::<T>(aggregator as gpointer).aggregate(&Aggregator::new(aggregator), timeout != 0)
+    }
+}
+
+extern "C" fn aggregator_start<T: AggregatorImpl>(aggregator: *mut GstAggregator) -> gboolean{
+    unsafe{
+        if let Some(start) = (*parent_class::<GstAggregatorClass>(aggregator as gpointer)).start{
+            if start(aggregator) == 0{
+                return 0;
+            }
+        }
+        imp::<T>(aggregator as gpointer).start(&Aggregator::new(aggregator)) as gboolean
+    }
+}
+
+extern "C" fn aggregator_stop<T: AggregatorImpl>(aggregator: *mut GstAggregator) -> gboolean{
+    unsafe{
+        let stopped = imp::<T>(aggregator as gpointer).stop(&Aggregator::new(aggregator));
+        if let Some(stop) = (*parent_class::<GstAggregatorClass>(aggregator as gpointer)).stop{
+            return (stop(aggregator) != 0 && stopped) as gboolean;
+        }
+        stopped as gboolean
+    }
+}
+
+extern "C" fn aggregator_get_next_time<T: AggregatorImpl>(aggregator: *mut GstAggregator) -> GstClockTime{
+    unsafe{
+        imp::<T>(aggregator as gpointer).next_time(&Aggregator::new(aggregator)).unwrap_or(GST_CLOCK_TIME_NONE)
+    }
+}
+
+/// Registers an aggregator implemented in Rust so it can be created by
+/// name with Element::new or in launch lines
+pub fn register_aggregator<T: AggregatorImpl>(name: &str, rank: u32) -> bool{
+    unsafe{
+        register_element(gst_aggregator_get_type(), name, rank, aggregator_class_init::<T>, instance_init::<T>)
+    }
+}
@@ -0,0 +1,362 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use element::{Element, ElementT};
+use pad::Pad;
+use value::Value;
+
+#[cfg_attr(not(target_os="macos"), link(name = "gstaudio-1.0"))]
+extern "C"{
+    fn gst_audio_channel_position_get_type() -> GType;
+}
+
+/// Position of a channel in the speaker layout, GstAudioChannelPosition
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum AudioChannelPosition{
+    /// The only channel of a mono stream
+    Mono,
+    FrontLeft,
+    FrontRight,
+    FrontCenter,
+    /// Low frequency effects, the subwoofer
+    Lfe1,
+    RearLeft,
+    RearRight,
+    FrontLeftOfCenter,
+    FrontRightOfCenter,
+    RearCenter,
+    Lfe2,
+    SideLeft,
+    SideRight,
+    TopFrontLeft,
+    TopFrontRight,
+    TopFrontCenter,
+    TopCenter,
+    TopRearLeft,
+    TopRearRight,
+    TopSideLeft,
+    TopSideRight,
+    TopRearCenter,
+    BottomFrontCenter,
+    BottomFrontLeft,
+    BottomFrontRight,
+    WideLeft,
+    WideRight,
+    SurroundLeft,
+    SurroundRight,
+}
+
+use self::AudioChannelPosition::*;
+
+// In the order of their value in GstAudioChannelPosition, which is also
+// the bit of each position in a channel-mask
+const POSITIONS: [AudioChannelPosition; 28] = [
+    FrontLeft, FrontRight, FrontCenter, Lfe1, RearLeft, RearRight, FrontLeftOfCenter,
+    FrontRightOfCenter, RearCenter, Lfe2, SideLeft, SideRight, TopFrontLeft, TopFrontRight,
+    TopFrontCenter, TopCenter, TopRearLeft, TopRearRight, TopSideLeft, TopSideRight,
+    TopRearCenter, BottomFrontCenter, BottomFrontLeft, BottomFrontRight, WideLeft, WideRight,
+    SurroundLeft, SurroundRight,
+];
+
+const GST_AUDIO_CHANNEL_POSITION_MONO: gint = -2;
+
+impl AudioChannelPosition{
+    /// The position for a GstAudioChannelPosition value, None for NONE
+    /// and INVALID
+    pub fn from_gst(position: gint) -> Option<AudioChannelPosition>{
+        if position == GST_AUDIO_CHANNEL_POSITION_MONO{
+            Some(Mono)
+        }else if position >= 0{
+            POSITIONS.get(position as usize).cloned()
+        }else{
+            None
+        }
+    }
+
+    pub fn to_gst(&self) -> gint{
+        match *self{
+            Mono => GST_AUDIO_CHANNEL_POSITION_MONO,
+            position => POSITIONS.iter().position(|p| *p == position).unwrap() as gint
+        }
+    }
+
+    /// The bit of the position in a channel-mask, 0 for Mono which has no
+    /// bit
+    pub fn mask_bit(&self) -> u64{
+        match *self{
+            Mono => 0,
+            position => 1 << position.to_gst()
+        }
+    }
+
+    /// The usual layout for a number of channels: mono, stereo, 2.1,
+    /// quad, 5.0, 5.1, 6.1 and 7.1. None for other numbers of channels
+    pub fn default_layout(channels: u32) -> Option<Vec<AudioChannelPosition>>{
+        let layout: &[AudioChannelPosition] = match channels{
+            1 => &[Mono],
+            2 => &[FrontLeft, FrontRight],
+            3 => &[FrontLeft, FrontRight, Lfe1],
+            4 => &[FrontLeft, FrontRight, RearLeft, RearRight],
+            5 => &[FrontLeft, FrontRight, FrontCenter, RearLeft, RearRight],
+            6 => &[FrontLeft, FrontRight, FrontCenter, Lfe1, RearLeft, RearRight],
+            7 => &[FrontLeft, FrontRight, FrontCenter, Lfe1, RearCenter, SideLeft, SideRight],
+            8 => &[FrontLeft, FrontRight, FrontCenter, Lfe1, RearLeft, RearRight, SideLeft, SideRight],
+            _ => return None
+        };
+        Some(layout.to_vec())
+    }
+}
+
+/// The channel-mask for the caps of a stream with these positions. None
+/// if a position is repeated or Mono is combined with others.
+///
+/// Channels in interleaved audio are always in the order of their bit in
+/// the mask, sort_positions gives that order
+pub fn channel_mask(positions: &[AudioChannelPosition]) -> Option<u64>{
+    if positions == [Mono]{
+        return Some(0);
+    }
+    let mut mask = 0u64;
+    for position in positions{
+        let bit = position.mask_bit();
+        if bit == 0 || mask & bit != 0{
+            return None;
+        }
+        mask |= bit;
+    }
+    Some(mask)
+}
+
+/// The positions of the channels of a stream with channel-mask mask, in
+/// the order of the channels. None if the mask doesn't have as many bits
+/// set as channels
+pub fn positions_from_mask(channels: u32, mask: u64) -> Option<Vec<AudioChannelPosition>>{
+    if mask == 0{
+        return match channels{
+            1 => Some(vec![Mono]),
+            _ => AudioChannelPosition::default_layout(channels)
+        };
+    }
+    let positions = POSITIONS.iter().cloned()
+        .filter(|position| mask & position.mask_bit() != 0)
+        .collect::<Vec<_>>();
+    if positions.len() == channels as usize{
+        Some(positions)
+    }else{
+        None
+    }
+}
+
+/// Sorts positions in the order the channels have in interleaved audio
+pub fn sort_positions(positions: &mut [AudioChannelPosition]){
+    positions.sort_by_key(|position| position.to_gst());
+}
+
+impl Caps{
+    /// The positions of the channels of raw audio caps, in the order of
+    /// the channels
+    pub fn audio_channel_positions(&self) -> Option<Vec<AudioChannelPosition>>{
+        let info = self.audio_info()?;
+        info.position[..info.channels as usize].iter()
+            .map(|position| AudioChannelPosition::from_gst(*position))
+            .collect()
+    }
+
+    /// Raw audio caps of format, as "S16LE" or "F32LE", with the channels
+    /// and channel-mask for positions
+    pub fn new_audio(format: &str, rate: i32, positions: &[AudioChannelPosition]) -> Option<Caps>{
+        let mask = channel_mask(positions)?;
+        let mut caps = Caps::from_string(&format!("audio/x-raw, format={}, layout=interleaved, rate={}, channels={}",
+            format, rate, positions.len()))?;
+        if positions.len() > 1{
+            caps.set_field("channel-mask", Value::Bitmask(mask));
+        }
+        Some(caps)
+    }
+}
+
+/// Wrapper around interleave, which merges mono streams into a single
+/// multichannel stream, as six mono tracks into 5.1
+pub struct Interleave{
+    interleave: Element
+}
+
+unsafe impl Sync for Interleave {}
+unsafe impl Send for Interleave {}
+
+impl Interleave{
+    pub fn new(name: &str) -> Option<Interleave>{
+        Element::new("interleave", name).map(|interleave| Interleave{ interleave: interleave })
+    }
+
+    pub fn new_from_element(element: Element) -> Interleave{
+        Interleave{ interleave: element }
+    }
+
+    /// Requests the input for the next channel without linking it
+    pub fn request_input(&mut self) -> Option<Pad>{
+        unsafe{
+            Pad::new(gst_element_get_request_pad(self.interleave.gst_element_mut(), to_c_str!("sink_%u")), true)
+        }
+    }
+
+    /// Requests the input for the next channel and links the src pad of
+    /// source to it, both elements have to be in the same bin
+    pub fn add_input(&mut self, source: &ElementT) -> Option<Pad>{
+        let mut src = source.static_pad("src")?;
+        let mut input = self.request_input()?;
+        if src.link(&mut input) == GST_PAD_LINK_OK{
+            Some(input)
+        }else{
+            unsafe{
+                gst_element_release_request_pad(self.interleave.gst_element_mut(), input.gst_pad_mut());
+            }
+            None
+        }
+    }
+
+    /// The position of each input in the output, in the order the inputs
+    /// were requested, instead of taking them from the channel-mask of
+    /// the input caps
+    pub fn set_channel_positions(&self, positions: &[AudioChannelPosition]){
+        unsafe{
+            let array = g_value_array_new(positions.len() as guint);
+            for position in positions{
+                let mut gvalue: GValue = mem::zeroed();
+                g_value_init(&mut gvalue, gst_audio_channel_position_get_type());
+                g_value_set_enum(&mut gvalue, position.to_gst());
+                g_value_array_append(array, &gvalue);
+                g_value_unset(&mut gvalue);
+            }
+            self.interleave.set("channel-positions", array);
+            self.interleave.set("channel-positions-from-input", 0 as gboolean);
+            g_value_array_free(array);
+        }
+    }
+}
+
+impl ElementT for Interleave{
+    fn as_element(&self) -> &Element{
+        &self.interleave
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.interleave
+    }
+}
+
+impl ::Transfer for Interleave{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.interleave.transfer()
+    }
+}
+
+/// Wrapper around deinterleave, which splits a multichannel stream into
+/// a mono stream per channel
+pub struct Deinterleave{
+    deinterleave: Element
+}
+
+unsafe impl Sync for Deinterleave {}
+unsafe impl Send for Deinterleave {}
+
+impl Deinterleave{
+    pub fn new(name: &str) -> Option<Deinterleave>{
+        Element::new("deinterleave", name).map(|deinterleave| Deinterleave{ deinterleave: deinterleave })
+    }
+
+    pub fn new_from_element(element: Element) -> Deinterleave{
+        Deinterleave{ deinterleave: element }
+    }
+
+    /// Whether the mono outputs keep the position of their channel in
+    /// their channel-mask, so an Interleave can put them back in place
+    pub fn set_keep_positions(&self, keep: bool){
+        self.deinterleave.set("keep-positions", keep as gboolean);
+    }
+
+    /// Calls f with the src pad of each channel and its index in the
+    /// input once the input format is known, from the streaming thread.
+    /// The position of the channel is in the positions of the input caps
+    pub fn on_channel<F: Fn(&Pad, usize) + Send + Sync + 'static>(&self, f: F){
+        unsafe{
+            connect_boxed(self.deinterleave.gst_element() as gpointer, "pad-added",
+                pad_added::<F> as extern "C" fn(*mut GstElement, *mut GstPad, gpointer) as gpointer,
+                Box::new(f));
+        }
+    }
+}
+
+extern "C" fn pad_added<F: Fn(&Pad, usize)>(_deinterleave: *mut GstElement, pad: *mut GstPad, data: gpointer){
+    unsafe{
+        if let Some(pad) = Pad::new(pad, false){
+            // the pads are named src_0, src_1... after the channel
+            if let Ok(index) = pad.name().trim_start_matches("src_").parse::<usize>(){
+                (*(data as *const F))(&pad, index);
+            }
+        }
+    }
+}
+
+impl ElementT for Deinterleave{
+    fn as_element(&self) -> &Element{
+        &self.deinterleave
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.deinterleave
+    }
+}
+
+impl ::Transfer for Deinterleave{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.deinterleave.transfer()
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn masks(){
+        assert_eq!(channel_mask(&[Mono]), Some(0));
+        assert_eq!(channel_mask(&[FrontLeft, FrontRight]), Some(0x3));
+        // the order of the positions doesn't change the mask
+        assert_eq!(channel_mask(&[FrontRight, FrontLeft, Lfe1]), Some(0xb));
+        assert_eq!(channel_mask(&AudioChannelPosition::default_layout(6).unwrap()), Some(0x3f));
+        assert_eq!(channel_mask(&[FrontLeft, FrontLeft]), None);
+        assert_eq!(channel_mask(&[Mono, FrontLeft]), None);
+    }
+
+    #[test]
+    fn positions_from_masks(){
+        assert_eq!(positions_from_mask(1, 0), Some(vec![Mono]));
+        assert_eq!(positions_from_mask(2, 0), Some(vec![FrontLeft, FrontRight]));
+        assert_eq!(positions_from_mask(3, 0xb), Some(vec![FrontLeft, FrontRight, Lfe1]));
+        assert_eq!(positions_from_mask(2, 0xb), None);
+        assert_eq!(positions_from_mask(9, 0), None);
+    }
+
+    #[test]
+    fn masks_round_trip(){
+        for channels in 1..9{
+            let layout = AudioChannelPosition::default_layout(channels).unwrap();
+            let mask = channel_mask(&layout).unwrap();
+            let mut sorted = layout.clone();
+            sort_positions(&mut sorted);
+            assert_eq!(positions_from_mask(channels, mask), Some(sorted));
+        }
+    }
+
+    #[test]
+    fn gst_values(){
+        assert_eq!(AudioChannelPosition::from_gst(-2), Some(Mono));
+        assert_eq!(AudioChannelPosition::from_gst(0), Some(FrontLeft));
+        assert_eq!(AudioChannelPosition::from_gst(27), Some(SurroundRight));
+        assert_eq!(AudioChannelPosition::from_gst(28), None);
+        assert_eq!(AudioChannelPosition::from_gst(-1), None);
+        assert_eq!(SurroundRight.mask_bit(), 1 << 27);
+        assert_eq!(Mono.mask_bit(), 0);
+    }
+}
@@ -0,0 +1,469 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use caps::Caps;
+use element::{Element, ElementT};
+use meta::GstAudioInfo;
+use subclass::*;
+
+// The audio codec base classes aren't in ffi.rs, which was generated
+// without gstaudio
+#[repr(C)]
+pub struct GstAudioDecoder{
+    pub element: GstElement,
+    pub sinkpad: *mut GstPad,
+    pub srcpad: *mut GstPad,
+    pub stream_lock: GRecMutex,
+    pub input_segment: GstSegment,
+    pub output_segment: GstSegment,
+    _priv: gpointer,
+    _gst_reserved: [gpointer; 20],
+}
+
+#[repr(C)]
+struct GstAudioDecoderClass{
+    element_class: GstElementClass,
+    start: Option<extern "C" fn(*mut GstAudioDecoder) -> gboolean>,
+    stop: Option<extern "C" fn(*mut GstAudioDecoder) -> gboolean>,
+    set_format: Option<extern "C" fn(*mut GstAudioDecoder, *mut GstCaps) -> gboolean>,
+    parse: gpointer,
+    handle_frame: Option<extern "C" fn(*mut GstAudioDecoder, *mut GstBuffer) -> GstFlowReturn>,
+    flush: Option<extern "C" fn(*mut GstAudioDecoder, gboolean)>,
+}
+
+#[repr(C)]
+pub struct GstAudioEncoder{
+    pub element: GstElement,
+    pub sinkpad: *mut GstPad,
+    pub srcpad: *mut GstPad,
+    pub stream_lock: GRecMutex,
+    pub input_segment: GstSegment,
+    pub output_segment: GstSegment,
+    _priv: gpointer,
+    _gst_reserved: [gpointer; 20],
+}
+
+#[repr(C)]
+struct GstAudioEncoderClass{
+    element_class: GstElementClass,
+    start: Option<extern "C" fn(*mut GstAudioEncoder) -> gboolean>,
+    stop: Option<extern "C" fn(*mut GstAudioEncoder) -> gboolean>,
+    set_format: Option<extern "C" fn(*mut GstAudioEncoder, *mut GstAudioInfo) -> gboolean>,
+    handle_frame: Option<extern "C" fn(*mut GstAudioEncoder, *mut GstBuffer) -> GstFlowReturn>,
+    flush: Option<extern "C" fn(*mut GstAudioEncoder)>,
+}
+
+#[cfg_attr(not(target_os="macos"), link(name = "gstaudio-1.0"))]
+extern "C"{
+    fn gst_audio_decoder_get_type() -> GType;
+    fn gst_audio_decoder_set_output_format(dec: *mut GstAudioDecoder, info: *const GstAudioInfo) -> gboolean;
+    // added in GStreamer 1.16
+    fn gst_audio_decoder_set_output_caps(dec: *mut GstAudioDecoder, caps: *mut GstCaps) -> gboolean;
+    fn gst_audio_decoder_finish_frame(dec: *mut GstAudioDecoder, buf: *mut GstBuffer, frames: gint) -> GstFlowReturn;
+    fn gst_audio_decoder_set_drainable(dec: *mut GstAudioDecoder, enabled: gboolean);
+    fn gst_audio_decoder_set_plc_aware(dec: *mut GstAudioDecoder, plc: gboolean);
+    fn gst_audio_decoder_set_plc(dec: *mut GstAudioDecoder, enabled: gboolean);
+    fn gst_audio_decoder_get_plc(dec: *mut GstAudioDecoder) -> gboolean;
+    fn gst_audio_decoder_set_latency(dec: *mut GstAudioDecoder, min: GstClockTime, max: GstClockTime);
+    fn gst_audio_decoder_set_max_errors(dec: *mut GstAudioDecoder, num: gint);
+    fn gst_audio_decoder_set_needs_format(dec: *mut GstAudioDecoder, enabled: gboolean);
+
+    fn gst_audio_encoder_get_type() -> GType;
+    fn gst_audio_encoder_set_output_format(enc: *mut GstAudioEncoder, caps: *mut GstCaps) -> gboolean;
+    fn gst_audio_encoder_finish_frame(enc: *mut GstAudioEncoder, buffer: *mut GstBuffer, samples: gint) -> GstFlowReturn;
+    fn gst_audio_encoder_set_frame_samples_min(enc: *mut GstAudioEncoder, num: gint);
+    fn gst_audio_encoder_set_frame_samples_max(enc: *mut GstAudioEncoder, num: gint);
+    fn gst_audio_encoder_set_frame_max(enc: *mut GstAudioEncoder, num: gint);
+    fn gst_audio_encoder_set_hard_min(enc: *mut GstAudioEncoder, enabled: gboolean);
+    fn gst_audio_encoder_set_drainable(enc: *mut GstAudioEncoder, enabled: gboolean);
+    fn gst_audio_encoder_set_latency(enc: *mut GstAudioEncoder, min: GstClockTime, max: GstClockTime);
+    fn gst_audio_encoder_set_lookahead(enc: *mut GstAudioEncoder, num: gint);
+    fn gst_audio_encoder_set_headers(enc: *mut GstAudioEncoder, headers: *mut GList);
+}
+
+/// An audio decoder implemented in Rust, on top of GstAudioDecoder which
+/// handles the timestamps, negotiation and packet loss concealment
+/// requests. Register it with register_audio_decoder.
+///
+/// The pad templates have to be named "sink" and "src"
+pub trait AudioDecoderImpl: ElementImpl{
+    /// Called when the element starts, to create the decoder
+    fn start(&self, _decoder: &AudioDecoder) -> bool{
+        true
+    }
+
+    fn stop(&self, _decoder: &AudioDecoder) -> bool{
+        true
+    }
+
+    /// Called with the caps of the input when they're known or change,
+    /// the output format has to be set here with set_output_caps or once
+    /// known from the first packets
+    fn set_format(&self, decoder: &AudioDecoder, caps: &Caps) -> bool;
+
+    /// Decodes a packet and outputs the decoded samples with
+    /// finish_frame, which can be called once for several packets.
+    ///
+    /// buffer is None when the decoder has to be drained, at EOS or on a
+    /// discontinuity, if it's drainable. With packet loss concealment
+    /// enabled, an empty buffer means a packet was lost and samples for
+    /// its duration have to be generated
+    fn handle_frame(&self, decoder: &AudioDecoder, buffer: Option<&Buffer>) -> GstFlowReturn;
+
+    /// Called on seeks to discard the state, hard when the stream changes
+    fn flush(&self, _decoder: &AudioDecoder, _hard: bool){}
+}
+
+/// An audio decoder as passed to the methods of an AudioDecoderImpl
+pub struct AudioDecoder{
+    decoder: Element
+}
+
+unsafe impl Sync for AudioDecoder {}
+unsafe impl Send for AudioDecoder {}
+
+impl AudioDecoder{
+    unsafe fn new(decoder: *mut GstAudioDecoder) -> AudioDecoder{
+        gst_object_ref(decoder as gpointer);
+        AudioDecoder{ decoder: Element::new_from_gst_element(decoder as *mut GstElement).unwrap() }
+    }
+
+    /// Sets the raw audio caps of the output, as
+    /// "audio/x-raw,format=F32LE,rate=48000,channels=2,layout=interleaved"
+    pub fn set_output_caps(&self, caps: &Caps) -> bool{
+        unsafe{
+            gst_audio_decoder_set_output_caps(self.gst_audio_decoder(), caps.gst_caps() as *mut GstCaps) == 1
+        }
+    }
+
+    pub fn set_output_format(&self, info: &GstAudioInfo) -> bool{
+        unsafe{
+            gst_audio_decoder_set_output_format(self.gst_audio_decoder(), info) == 1
+        }
+    }
+
+    /// Outputs the samples decoded from the last frames packets, buffer
+    /// can be None if they didn't produce any samples
+    pub fn finish_frame(&self, buffer: Option<Buffer>, frames: i32) -> GstFlowReturn{
+        unsafe{
+            let buffer = buffer.map(|buffer| ::Transfer::transfer(buffer)).unwrap_or(ptr::null_mut());
+            gst_audio_decoder_finish_frame(self.gst_audio_decoder(), buffer, frames)
+        }
+    }
+
+    /// Whether handle_frame is called with None to output the samples
+    /// the decoder keeps, true by default
+    pub fn set_drainable(&self, drainable: bool){
+        unsafe{
+            gst_audio_decoder_set_drainable(self.gst_audio_decoder(), drainable as gboolean);
+        }
+    }
+
+    /// Whether the decoder can conceal lost packets, if so they're passed
+    /// as empty buffers to handle_frame once enabled with set_plc or the
+    /// plc property
+    pub fn set_plc_aware(&self, plc_aware: bool){
+        unsafe{
+            gst_audio_decoder_set_plc_aware(self.gst_audio_decoder(), plc_aware as gboolean);
+        }
+    }
+
+    pub fn set_plc(&self, enabled: bool){
+        unsafe{
+            gst_audio_decoder_set_plc(self.gst_audio_decoder(), enabled as gboolean);
+        }
+    }
+
+    pub fn plc(&self) -> bool{
+        unsafe{
+            gst_audio_decoder_get_plc(self.gst_audio_decoder()) == 1
+        }
+    }
+
+    /// Whether set_format has to be called before handle_frame, for
+    /// codecs that can't decode without knowing the format
+    pub fn set_needs_format(&self, needs_format: bool){
+        unsafe{
+            gst_audio_decoder_set_needs_format(self.gst_audio_decoder(), needs_format as gboolean);
+        }
+    }
+
+    pub fn set_latency(&self, min: u64, max: Option<u64>){
+        unsafe{
+            gst_audio_decoder_set_latency(self.gst_audio_decoder(), min, max.unwrap_or(GST_CLOCK_TIME_NONE));
+        }
+    }
+
+    /// Number of consecutive decoding errors tolerated before failing,
+    /// -1 for no limit
+    pub fn set_max_errors(&self, max_errors: i32){
+        unsafe{
+            gst_audio_decoder_set_max_errors(self.gst_audio_decoder(), max_errors);
+        }
+    }
+
+    pub unsafe fn gst_audio_decoder(&self) -> *mut GstAudioDecoder{
+        self.decoder.gst_element() as *mut GstAudioDecoder
+    }
+}
+
+impl ElementT for AudioDecoder{
+    fn as_element(&self) -> &Element{
+        &self.decoder
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.decoder
+    }
+}
+
+impl ::Transfer for AudioDecoder{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.decoder.transfer()
+    }
+}
+
+extern "C" fn decoder_class_init<T: AudioDecoderImpl>(class: gpointer, _data: gpointer){
+    unsafe{
+        element_class_init::<T>(class);
+        let decoder_class = class as *mut GstAudioDecoderClass;
+        (*decoder_class).start = Some(decoder_start::<T>);
+        (*decoder_class).stop = Some(decoder_stop::<T>);
+        (*decoder_class).set_format = Some(decoder_set_format::<T>);
+        (*decoder_class).handle_frame = Some(decoder_handle_frame::<T>);
+        (*decoder_class).flush = Some(decoder_flush::<T>);
+    }
+}
+
+extern "C" fn decoder_start<T: AudioDecoderImpl>(decoder: *mut GstAudioDecoder) -> gboolean{
+    unsafe{
+        imp::<T>(decoder as gpointer).start(&AudioDecoder::new(decoder)) as gboolean
+    }
+}
+
+extern "C" fn decoder_stop<T: AudioDecoderImpl>(decoder: *mut GstAudioDecoder) -> gboolean{
+    unsafe{
+        imp::<T>(decoder as gpointer).stop(&AudioDecoder::new(decoder)) as gboolean
+    }
+}
+
+extern "C" fn decoder_set_format<T: AudioDecoderImpl>(decoder: *mut GstAudioDecoder, caps: *mut GstCaps) -> gboolean{
+    unsafe{
+        match Caps::new(caps, false){
+            Some(caps) => imp::<T>(decoder as gpointer).set_format(&AudioDecoder::new(decoder), &caps) as gboolean,
+            None => 0
+        }
+    }
+}
+
+// The input buffer stays owned by the base class
+extern "C" fn decoder_handle_frame<T: AudioDecoderImpl>(decoder: *mut GstAudioDecoder, buffer: *mut GstBuffer) -> GstFlowReturn{
+    unsafe{
+        let buffer = Buffer::new(buffer, false);
+        imp::<T>(decoder as gpointer).handle_frame(&AudioDecoder::new(decoder), buffer.as_ref())
+    }
+}
+
+extern "C" fn decoder_flush<T: AudioDecoderImpl>(decoder: *mut GstAudioDecoder, hard: gboolean){
+    unsafe{
+        imp::<T>(decoder as gpointer).flush(&AudioDecoder::new(decoder), hard != 0);
+    }
+}
+
+/// Registers an audio decoder implemented in Rust so it can be created by
+/// name with Element::new, in launch lines, and picked by decodebin if
+/// the rank is GST_RANK_PRIMARY or higher and the classification
+/// contains "Decoder/Audio"
+pub fn register_audio_decoder<T: AudioDecoderImpl>(name: &str, rank: u32) -> bool{
+    unsafe{
+        register_element(gst_audio_decoder_get_type(), name, rank, decoder_class_init::<T>, instance_init::<T>)
+    }
+}
+
+/// An audio encoder implemented in Rust, on top of GstAudioEncoder which
+/// splits the input in frames of the sizes the codec needs and handles
+/// the timestamps. Register it with register_audio_encoder.
+///
+/// The pad templates have to be named "sink" and "src"
+pub trait AudioEncoderImpl: ElementImpl{
+    /// Called when the element starts, to create the encoder
+    fn start(&self, _encoder: &AudioEncoder) -> bool{
+        true
+    }
+
+    fn stop(&self, _encoder: &AudioEncoder) -> bool{
+        true
+    }
+
+    /// Called with the format of the raw input when it's known or
+    /// changes. The output caps have to be set here with
+    /// set_output_format and the frame size with set_frame_samples
+    fn set_format(&self, encoder: &AudioEncoder, info: &GstAudioInfo) -> bool;
+
+    /// Encodes the samples in buffer, outputting the encoded packets with
+    /// finish_frame. buffer is None when the encoder has to be drained
+    /// at EOS, if it's drainable
+    fn handle_frame(&self, encoder: &AudioEncoder, buffer: Option<&Buffer>) -> GstFlowReturn;
+
+    /// Called on seeks to discard the state
+    fn flush(&self, _encoder: &AudioEncoder){}
+}
+
+/// An audio encoder as passed to the methods of an AudioEncoderImpl
+pub struct AudioEncoder{
+    encoder: Element
+}
+
+unsafe impl Sync for AudioEncoder {}
+unsafe impl Send for AudioEncoder {}
+
+impl AudioEncoder{
+    unsafe fn new(encoder: *mut GstAudioEncoder) -> AudioEncoder{
+        gst_object_ref(encoder as gpointer);
+        AudioEncoder{ encoder: Element::new_from_gst_element(encoder as *mut GstElement).unwrap() }
+    }
+
+    /// Sets the caps of the output, as "audio/x-opus,channel-mapping-family=0"
+    pub fn set_output_format(&self, caps: &Caps) -> bool{
+        unsafe{
+            gst_audio_encoder_set_output_format(self.gst_audio_encoder(), caps.gst_caps() as *mut GstCaps) == 1
+        }
+    }
+
+    /// Outputs an encoded packet made from the first samples per channel
+    /// of the input, -1 if it's made from all the input
+    pub fn finish_frame(&self, buffer: Buffer, samples: i32) -> GstFlowReturn{
+        unsafe{
+            gst_audio_encoder_finish_frame(self.gst_audio_encoder(), ::Transfer::transfer(buffer), samples)
+        }
+    }
+
+    /// Number of samples per channel handle_frame receives, as 960 for
+    /// 20ms of opus at 48kHz. With min and max equal every frame has
+    /// exactly that size, the last one padded with silence
+    pub fn set_frame_samples(&self, min: i32, max: i32){
+        unsafe{
+            gst_audio_encoder_set_frame_samples_min(self.gst_audio_encoder(), min);
+            gst_audio_encoder_set_frame_samples_max(self.gst_audio_encoder(), max);
+            gst_audio_encoder_set_hard_min(self.gst_audio_encoder(), (min == max) as gboolean);
+        }
+    }
+
+    /// Number of frames passed at once to handle_frame, 0 for as many as
+    /// available
+    pub fn set_frame_max(&self, frames: i32){
+        unsafe{
+            gst_audio_encoder_set_frame_max(self.gst_audio_encoder(), frames);
+        }
+    }
+
+    /// Whether handle_frame is called with None at EOS to output the
+    /// packets the encoder keeps, true by default
+    pub fn set_drainable(&self, drainable: bool){
+        unsafe{
+            gst_audio_encoder_set_drainable(self.gst_audio_encoder(), drainable as gboolean);
+        }
+    }
+
+    /// Number of samples the encoder needs ahead of the ones it outputs
+    pub fn set_lookahead(&self, samples: i32){
+        unsafe{
+            gst_audio_encoder_set_lookahead(self.gst_audio_encoder(), samples);
+        }
+    }
+
+    pub fn set_latency(&self, min: u64, max: Option<u64>){
+        unsafe{
+            gst_audio_encoder_set_latency(self.gst_audio_encoder(), min, max.unwrap_or(GST_CLOCK_TIME_NONE));
+        }
+    }
+
+    /// Header packets sent before the first encoded packet, as the
+    /// OpusHead and OpusTags of ogg
+    pub fn set_headers(&self, headers: Vec<Buffer>){
+        unsafe{
+            let mut list = ptr::null_mut();
+            for header in headers{
+                list = g_list_append(list, ::Transfer::transfer(header) as gpointer);
+            }
+            gst_audio_encoder_set_headers(self.gst_audio_encoder(), list);
+        }
+    }
+
+    pub unsafe fn gst_audio_encoder(&self) -> *mut GstAudioEncoder{
+        self.encoder.gst_element() as *mut GstAudioEncoder
+    }
+}
+
+impl ElementT for AudioEncoder{
+    fn as_element(&self) -> &Element{
+        &self.encoder
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.encoder
+    }
+}
+
+impl ::Transfer for AudioEncoder{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.encoder.transfer()
+    }
+}
+
+extern "C" fn encoder_class_init<T: AudioEncoderImpl>(class: gpointer, _data: gpointer){
+    unsafe{
+        element_class_init::<T>(class);
+        let encoder_class = class as *mut GstAudioEncoderClass;
+        (*encoder_class).start = Some(encoder_start::<T>);
+        (*encoder_class).stop = Some(encoder_stop::<T>);
+        (*encoder_class).set_format = Some(encoder_set_format::<T>);
+        (*encoder_class).handle_frame = Some(encoder_handle_frame::<T>);
+        (*encoder_class).flush = Some(encoder_flush::<T>);
+    }
+}
+
+extern "C" fn encoder_start<T: AudioEncoderImpl>(encoder: *mut GstAudioEncoder) -> gboolean{
+    unsafe{
+        imp::<T>(encoder as gpointer).start(&AudioEncoder::new(encoder)) as gboolean
+    }
+}
+
+extern "C" fn encoder_stop<T: AudioEncoderImpl>(encoder: *mut GstAudioEncoder) -> gboolean{
+    unsafe{
+        imp::<T>(encoder as gpointer).stop(&AudioEncoder::new(encoder)) as gboolean
+    }
+}
+
+extern "C" fn encoder_set_format<T: AudioEncoderImpl>(encoder: *mut GstAudioEncoder, info: *mut GstAudioInfo) -> gboolean{
+    unsafe{
+        if info == ptr::null_mut(){
+            return 0;
+        }
+        imp::<T>(encoder as gpointer).set_format(&AudioEncoder::new(encoder), &*info) as gboolean
+    }
+}
+
+// The input buffer stays owned by the base class
+extern "C" fn encoder_handle_frame<T: AudioEncoderImpl>(encoder: *mut GstAudioEncoder, buffer: *mut GstBuffer) -> GstFlowReturn{
+    unsafe{
+        let buffer = Buffer::new(buffer, false);
+        imp::<T>(encoder as gpointer).handle_frame(&AudioEncoder::new(encoder), buffer.as_ref())
+    }
+}
+
+extern "C" fn encoder_flush<T: AudioEncoderImpl>(encoder: *mut GstAudioEncoder){
+    unsafe{
+        imp::<T>(encoder as gpointer).flush(&AudioEncoder::new(encoder));
+    }
+}
+
+/// Registers an audio encoder implemented in Rust so it can be created by
+/// name with Element::new, in launch lines, and picked by encodebin if
+/// the rank is GST_RANK_PRIMARY or higher and the classification
+/// contains "Encoder/Audio"
+pub fn register_audio_encoder<T: AudioEncoderImpl>(name: &str, rank: u32) -> bool{
+    unsafe{
+        register_element(gst_audio_encoder_get_type(), name, rank, encoder_class_init::<T>, instance_init::<T>)
+    }
+}
@@ -0,0 +1,320 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use meta::GstAudioInfo;
+
+pub enum GstAudioConverter{}
+pub enum GstAudioResampler{}
+
+const GST_AUDIO_CONVERTER_FLAG_NONE: guint = 0;
+const GST_AUDIO_CONVERTER_FLAG_VARIABLE_RATE: guint = 2;
+const GST_AUDIO_RESAMPLER_FLAG_VARIABLE_RATE: guint = 4;
+
+#[cfg_attr(not(target_os="macos"), link(name = "gstaudio-1.0"))]
+extern "C"{
+    fn gst_audio_converter_new(flags: guint, in_info: *mut GstAudioInfo, out_info: *mut GstAudioInfo,
+        config: *mut GstStructure) -> *mut GstAudioConverter;
+    fn gst_audio_converter_free(convert: *mut GstAudioConverter);
+    fn gst_audio_converter_reset(convert: *mut GstAudioConverter);
+    fn gst_audio_converter_update_config(convert: *mut GstAudioConverter, in_rate: gint, out_rate: gint,
+        config: *mut GstStructure) -> gboolean;
+    fn gst_audio_converter_get_out_frames(convert: *mut GstAudioConverter, in_frames: gsize) -> gsize;
+    fn gst_audio_converter_get_max_latency(convert: *mut GstAudioConverter) -> gsize;
+    fn gst_audio_converter_samples(convert: *mut GstAudioConverter, flags: guint, input: *mut gpointer,
+        in_frames: gsize, out: *mut gpointer, out_frames: gsize) -> gboolean;
+    fn gst_audio_resampler_new(method: guint, flags: guint, format: gint, channels: gint, in_rate: gint,
+        out_rate: gint, options: *mut GstStructure) -> *mut GstAudioResampler;
+    fn gst_audio_resampler_free(resampler: *mut GstAudioResampler);
+    fn gst_audio_resampler_reset(resampler: *mut GstAudioResampler);
+    fn gst_audio_resampler_update(resampler: *mut GstAudioResampler, in_rate: gint, out_rate: gint,
+        options: *mut GstStructure) -> gboolean;
+    fn gst_audio_resampler_get_out_frames(resampler: *mut GstAudioResampler, in_frames: gsize) -> gsize;
+    fn gst_audio_resampler_get_max_latency(resampler: *mut GstAudioResampler) -> gsize;
+    fn gst_audio_resampler_resample(resampler: *mut GstAudioResampler, input: *mut gpointer, in_frames: gsize,
+        out: *mut gpointer, out_frames: gsize);
+    fn gst_audio_resampler_options_set_quality(method: guint, quality: guint, in_rate: gint, out_rate: gint,
+        options: *mut GstStructure);
+    fn gst_audio_resampler_method_get_type() -> GType;
+}
+
+/// Interpolation used to change the rate of audio, from the fastest to
+/// the best quality
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum ResamplerMethod{
+    Nearest,
+    Linear,
+    Cubic,
+    BlackmanNuttall,
+    /// The default of audioresample
+    Kaiser,
+}
+
+impl ResamplerMethod{
+    fn to_gst(&self) -> guint{
+        match *self{
+            ResamplerMethod::Nearest => 0,
+            ResamplerMethod::Linear => 1,
+            ResamplerMethod::Cubic => 2,
+            ResamplerMethod::BlackmanNuttall => 3,
+            ResamplerMethod::Kaiser => 4,
+        }
+    }
+}
+
+/// Default quality of audioresample, from 0 to 10
+pub const DEFAULT_RESAMPLER_QUALITY: u32 = 4;
+
+// The options of a resampler of method and quality, with the keys used
+// by both GstAudioConverter and GstAudioResampler
+unsafe fn resampler_options(method: ResamplerMethod, quality: u32, in_rate: gint, out_rate: gint) -> *mut GstStructure{
+    let options = gst_structure_new_empty(to_c_str!("GstAudioConverter.config"));
+    gst_structure_set(options, to_c_str!("GstAudioConverter.resampler-method"), gst_audio_resampler_method_get_type(),
+        method.to_gst(), ptr::null::<gchar>());
+    gst_audio_resampler_options_set_quality(method.to_gst(), quality, in_rate, out_rate, options);
+    options
+}
+
+/// Converts raw audio between formats, rates and channel layouts without
+/// a pipeline, as audioconvert ! audioresample do, to prepare PCM from
+/// another library for an appsrc.
+///
+/// The audio is passed as bytes of interleaved samples, in the format of
+/// the caps the converter was created with
+pub struct AudioConverter{
+    converter: *mut GstAudioConverter,
+    in_info: GstAudioInfo,
+    out_info: GstAudioInfo,
+    method: ResamplerMethod,
+    quality: u32,
+}
+
+unsafe impl Send for AudioConverter {}
+
+impl Drop for AudioConverter{
+    fn drop(&mut self){
+        unsafe{
+            gst_audio_converter_free(self.converter);
+        }
+    }
+}
+
+impl AudioConverter{
+    /// A converter from in_caps to out_caps, both fixed raw audio caps.
+    /// None if the caps aren't raw audio or the conversion isn't
+    /// supported
+    pub fn new(in_caps: &Caps, out_caps: &Caps) -> Option<AudioConverter>{
+        AudioConverter::with_resampler(in_caps, out_caps, ResamplerMethod::Kaiser, DEFAULT_RESAMPLER_QUALITY)
+    }
+
+    /// A converter that resamples with method and quality, from 0 to 10
+    pub fn with_resampler(in_caps: &Caps, out_caps: &Caps, method: ResamplerMethod, quality: u32) -> Option<AudioConverter>{
+        AudioConverter::create(in_caps, out_caps, method, quality, false)
+    }
+
+    /// A converter whose rates can be changed with set_rates even if they
+    /// are the same at first. It always resamples, delaying the output
+    /// max_latency frames even when the rates match
+    pub fn with_variable_rate(in_caps: &Caps, out_caps: &Caps, method: ResamplerMethod, quality: u32) -> Option<AudioConverter>{
+        AudioConverter::create(in_caps, out_caps, method, quality, true)
+    }
+
+    fn create(in_caps: &Caps, out_caps: &Caps, method: ResamplerMethod, quality: u32, variable_rate: bool) -> Option<AudioConverter>{
+        let mut in_info = in_caps.audio_info()?;
+        let mut out_info = out_caps.audio_info()?;
+        // converters with the variable rate flag always have a resampler,
+        // without it the rates can't be changed later
+        let flags = if variable_rate || in_info.rate != out_info.rate{
+            GST_AUDIO_CONVERTER_FLAG_VARIABLE_RATE
+        }else{
+            GST_AUDIO_CONVERTER_FLAG_NONE
+        };
+        unsafe{
+            let config = resampler_options(method, quality, in_info.rate, out_info.rate);
+            let converter = gst_audio_converter_new(flags, &mut in_info, &mut out_info, config);
+            if converter == ptr::null_mut(){
+                return None;
+            }
+            Some(AudioConverter{ converter: converter, in_info: in_info, out_info: out_info, method: method, quality: quality })
+        }
+    }
+
+    /// Bytes per frame of the input, the size of a sample of every
+    /// channel
+    pub fn in_bpf(&self) -> usize{
+        self.in_info.bpf as usize
+    }
+
+    pub fn out_bpf(&self) -> usize{
+        self.out_info.bpf as usize
+    }
+
+    /// Frames output for in_frames of input
+    pub fn out_frames(&self, in_frames: usize) -> usize{
+        unsafe{
+            gst_audio_converter_get_out_frames(self.converter, in_frames as gsize) as usize
+        }
+    }
+
+    /// Frames of delay the resampler adds
+    pub fn max_latency(&self) -> usize{
+        unsafe{
+            gst_audio_converter_get_max_latency(self.converter) as usize
+        }
+    }
+
+    /// Converts input, which has to be a whole number of frames, keeping
+    /// the state of the resampler between calls so consecutive chunks of
+    /// a stream join without clicks
+    pub fn convert(&mut self, input: &[u8]) -> Option<Vec<u8>>{
+        if input.len() % self.in_bpf() != 0{
+            return None;
+        }
+        let in_frames = input.len() / self.in_bpf();
+        let out_frames = self.out_frames(in_frames);
+        let mut output = vec![0u8; out_frames * self.out_bpf()];
+        unsafe{
+            let mut in_ptr = input.as_ptr() as gpointer;
+            let mut out_ptr = output.as_mut_ptr() as gpointer;
+            if gst_audio_converter_samples(self.converter, GST_AUDIO_CONVERTER_FLAG_NONE, &mut in_ptr, in_frames as gsize,
+                    &mut out_ptr, out_frames as gsize) == 0{
+                return None;
+            }
+        }
+        Some(output)
+    }
+
+    /// Outputs the frames still held by the resampler, which delays the
+    /// output max_latency frames, by feeding it silence. Call it at the
+    /// end of the stream or the last frames are lost
+    pub fn drain(&mut self) -> Vec<u8>{
+        let in_frames = self.max_latency();
+        let out_frames = self.out_frames(in_frames);
+        let mut output = vec![0u8; out_frames * self.out_bpf()];
+        if out_frames > 0{
+            unsafe{
+                // a NULL input is converted as silence
+                let mut out_ptr = output.as_mut_ptr() as gpointer;
+                if gst_audio_converter_samples(self.converter, GST_AUDIO_CONVERTER_FLAG_NONE, ptr::null_mut(),
+                        in_frames as gsize, &mut out_ptr, out_frames as gsize) == 0{
+                    output.clear();
+                }
+            }
+        }
+        output
+    }
+
+    /// Changes the rates of the resampler keeping its state, as to
+    /// follow a clock drifting from the one of the source. Fails if the
+    /// rates were the same when creating the converter, unless it was
+    /// created with_variable_rate
+    pub fn set_rates(&mut self, in_rate: u32, out_rate: u32) -> bool{
+        unsafe{
+            let config = resampler_options(self.method, self.quality, in_rate as gint, out_rate as gint);
+            if gst_audio_converter_update_config(self.converter, in_rate as gint, out_rate as gint, config) == 0{
+                return false;
+            }
+        }
+        self.in_info.rate = in_rate as gint;
+        self.out_info.rate = out_rate as gint;
+        true
+    }
+
+    /// Discards the state of the resampler, before converting a stream
+    /// that doesn't follow the previous data, as after a seek
+    pub fn reset(&mut self){
+        unsafe{
+            gst_audio_converter_reset(self.converter);
+        }
+    }
+
+    pub unsafe fn gst_audio_converter(&self) -> *mut GstAudioConverter{
+        self.converter
+    }
+}
+
+/// Changes the rate of interleaved raw audio without changing its format
+/// or channels. Supports S16, S32, F32 and F64 samples
+pub struct AudioResampler{
+    resampler: *mut GstAudioResampler,
+    method: ResamplerMethod,
+    quality: u32,
+    bpf: usize,
+}
+
+unsafe impl Send for AudioResampler {}
+
+impl Drop for AudioResampler{
+    fn drop(&mut self){
+        unsafe{
+            gst_audio_resampler_free(self.resampler);
+        }
+    }
+}
+
+impl AudioResampler{
+    /// A resampler of the audio of caps to out_rate, None if the caps
+    /// aren't raw audio or the format isn't supported
+    pub fn new(caps: &Caps, out_rate: u32, method: ResamplerMethod, quality: u32) -> Option<AudioResampler>{
+        let info = caps.audio_info()?;
+        unsafe{
+            // format is the first field of GstAudioFormatInfo
+            let format = *(info.finfo as *const gint);
+            let options = resampler_options(method, quality, info.rate, out_rate as gint);
+            let resampler = gst_audio_resampler_new(method.to_gst(), GST_AUDIO_RESAMPLER_FLAG_VARIABLE_RATE, format,
+                info.channels, info.rate, out_rate as gint, options);
+            gst_structure_free(options);
+            if resampler == ptr::null_mut(){
+                return None;
+            }
+            Some(AudioResampler{ resampler: resampler, method: method, quality: quality, bpf: info.bpf as usize })
+        }
+    }
+
+    /// Frames output for in_frames of input
+    pub fn out_frames(&self, in_frames: usize) -> usize{
+        unsafe{
+            gst_audio_resampler_get_out_frames(self.resampler, in_frames as gsize) as usize
+        }
+    }
+
+    pub fn max_latency(&self) -> usize{
+        unsafe{
+            gst_audio_resampler_get_max_latency(self.resampler) as usize
+        }
+    }
+
+    /// Resamples input, which has to be a whole number of frames
+    pub fn resample(&mut self, input: &[u8]) -> Option<Vec<u8>>{
+        if input.len() % self.bpf != 0{
+            return None;
+        }
+        let in_frames = input.len() / self.bpf;
+        let out_frames = self.out_frames(in_frames);
+        let mut output = vec![0u8; out_frames * self.bpf];
+        unsafe{
+            let mut in_ptr = input.as_ptr() as gpointer;
+            let mut out_ptr = output.as_mut_ptr() as gpointer;
+            gst_audio_resampler_resample(self.resampler, &mut in_ptr, in_frames as gsize, &mut out_ptr, out_frames as gsize);
+        }
+        Some(output)
+    }
+
+    pub fn set_rates(&mut self, in_rate: u32, out_rate: u32) -> bool{
+        unsafe{
+            let options = resampler_options(self.method, self.quality, in_rate as gint, out_rate as gint);
+            let updated = gst_audio_resampler_update(self.resampler, in_rate as gint, out_rate as gint, options) != 0;
+            gst_structure_free(options);
+            updated
+        }
+    }
+
+    pub fn reset(&mut self){
+        unsafe{
+            gst_audio_resampler_reset(self.resampler);
+        }
+    }
+
+    pub unsafe fn gst_audio_resampler(&self) -> *mut GstAudioResampler{
+        self.resampler
+    }
+}
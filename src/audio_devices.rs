@@ -0,0 +1,60 @@
+use device::{Device, DeviceMonitor};
+use element::{Element, ElementT};
+use element_factory::ElementFactory;
+use value::Value;
+
+#[cfg(target_os="linux")]
+const AUDIO_SINKS: &'static [&'static str] = &["pulsesink", "alsasink"];
+#[cfg(target_os="linux")]
+const AUDIO_SOURCES: &'static [&'static str] = &["pulsesrc", "alsasrc"];
+#[cfg(target_os="macos")]
+const AUDIO_SINKS: &'static [&'static str] = &["osxaudiosink"];
+#[cfg(target_os="macos")]
+const AUDIO_SOURCES: &'static [&'static str] = &["osxaudiosrc"];
+#[cfg(target_os="windows")]
+const AUDIO_SINKS: &'static [&'static str] = &["wasapisink", "directsoundsink"];
+#[cfg(target_os="windows")]
+const AUDIO_SOURCES: &'static [&'static str] = &["wasapisrc", "directsoundsrc"];
+#[cfg(not(any(target_os="linux", target_os="macos", target_os="windows")))]
+const AUDIO_SINKS: &'static [&'static str] = &[];
+#[cfg(not(any(target_os="linux", target_os="macos", target_os="windows")))]
+const AUDIO_SOURCES: &'static [&'static str] = &[];
+
+/// Lists the sound cards and microphones in the system, to let users
+/// pick where audio is played or recorded from.
+///
+/// The devices can be used directly with Device::create_element or
+/// PlayBin::set_audio_output, or stored by their Device::id and later
+/// opened with output_by_id / input_by_id.
+pub struct AudioDevices;
+
+impl AudioDevices{
+    /// Returns the audio output devices
+    pub fn outputs() -> Vec<Device>{
+        DeviceMonitor::new_with_filter("Audio/Sink").devices()
+    }
+
+    /// Returns the audio capture devices
+    pub fn inputs() -> Vec<Device>{
+        DeviceMonitor::new_with_filter("Audio/Source").devices()
+    }
+
+    /// Creates the audio sink of the platform playing to the device with
+    /// the passed id
+    pub fn output_by_id(id: &str) -> Option<Element>{
+        AudioDevices::create_with_device(AUDIO_SINKS, id)
+    }
+
+    /// Creates the audio source of the platform recording from the device
+    /// with the passed id
+    pub fn input_by_id(id: &str) -> Option<Element>{
+        AudioDevices::create_with_device(AUDIO_SOURCES, id)
+    }
+
+    fn create_with_device(factories: &[&str], id: &str) -> Option<Element>{
+        let factory = factories.iter().filter_map(|factory| ElementFactory::find(factory)).next()?;
+        let mut element = factory.create("")?;
+        element.set_property("device", &Value::String(id.to_string())).ok()?;
+        Some(element)
+    }
+}
@@ -0,0 +1,318 @@
+use ffi::*;
+use util::*;
+use bin::BinT;
+use caps::Caps;
+use decode_util::{gst_app_sink_try_pull_sample, link_decoded_pads, missing_element};
+use element::{Element, ElementT};
+use meta::GstAudioInfo;
+use pipeline::Pipeline;
+use sample::Sample;
+use error::{Error, Result};
+
+use std::cmp;
+
+#[cfg_attr(not(target_os="macos"), link(name = "gstaudio-1.0"))]
+extern "C"{
+    fn gst_audio_info_from_caps(info: *mut GstAudioInfo, caps: *const GstCaps) -> gboolean;
+}
+
+// How long next waits for a sample before checking the bus for errors
+const POLL_INTERVAL: GstClockTime = 100 * 1000000;
+
+/// Rate used by waveform, enough to find the peaks of speech and music
+/// while decoding long files quickly
+const WAVEFORM_RATE: u32 = 8000;
+
+/// Formats of the samples in native endianness, as AudioBuffer reads them
+#[cfg(target_endian="little")]
+const S16_FORMAT: &'static str = "S16LE";
+#[cfg(target_endian="little")]
+const F32_FORMAT: &'static str = "F32LE";
+#[cfg(target_endian="big")]
+const S16_FORMAT: &'static str = "S16BE";
+#[cfg(target_endian="big")]
+const F32_FORMAT: &'static str = "F32BE";
+
+impl Caps{
+    /// The audio info of raw audio caps, None for other caps
+    pub fn audio_info(&self) -> Option<GstAudioInfo>{
+        unsafe{
+            let mut info: GstAudioInfo = mem::zeroed();
+            if gst_audio_info_from_caps(&mut info, self.gst_caps()) == 1{
+                Some(info)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+/// Format of the samples produced by extract_audio, always interleaved
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum SampleFormat{
+    S16,
+    F32,
+}
+
+/// The raw audio extract_audio converts the stream to. The rate and
+/// channels of the stream are kept if None
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct AudioFormat{
+    pub sample_format: SampleFormat,
+    pub rate: Option<u32>,
+    pub channels: Option<u32>,
+}
+
+impl AudioFormat{
+    pub fn new(sample_format: SampleFormat) -> AudioFormat{
+        AudioFormat{ sample_format: sample_format, rate: None, channels: None }
+    }
+
+    pub fn caps(&self) -> Caps{
+        let format = match self.sample_format{
+            SampleFormat::S16 => S16_FORMAT,
+            SampleFormat::F32 => F32_FORMAT,
+        };
+        let mut caps = format!("audio/x-raw, format=(string){}, layout=(string)interleaved", format);
+        if let Some(rate) = self.rate{
+            caps += &format!(", rate=(int){}", rate);
+        }
+        if let Some(channels) = self.channels{
+            caps += &format!(", channels=(int){}", channels);
+        }
+        Caps::from_string(&caps).unwrap()
+    }
+}
+
+#[derive(Clone,Debug,PartialEq)]
+pub enum AudioSamples{
+    S16(Vec<i16>),
+    F32(Vec<f32>),
+}
+
+impl AudioSamples{
+    /// Number of samples of all the channels
+    pub fn len(&self) -> usize{
+        match *self{
+            AudioSamples::S16(ref samples) => samples.len(),
+            AudioSamples::F32(ref samples) => samples.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+}
+
+/// A buffer of decoded audio, as returned by extract_audio
+#[derive(Clone,Debug,PartialEq)]
+pub struct AudioBuffer{
+    /// Timestamp of the first sample in nanoseconds, if known
+    pub pts: Option<u64>,
+    pub rate: u32,
+    pub channels: u32,
+    /// Interleaved samples, the frames are samples.len() / channels
+    pub samples: AudioSamples,
+}
+
+impl AudioBuffer{
+    fn from_sample(sample: &Sample) -> Option<AudioBuffer>{
+        let info = sample.caps()?.audio_info()?;
+        let buffer = sample.buffer()?;
+        let samples = if info.bpf / info.channels == 2{
+            AudioSamples::S16(buffer.map_read(|map| map.data::<i16>().to_vec()).ok()?)
+        }else{
+            AudioSamples::F32(buffer.map_read(|map| map.data::<f32>().to_vec()).ok()?)
+        };
+        let pts = buffer.pts();
+        Some(AudioBuffer{
+            pts: if pts == GST_CLOCK_TIME_NONE{ None }else{ Some(pts) },
+            rate: info.rate as u32,
+            channels: info.channels as u32,
+            samples: samples,
+        })
+    }
+
+    /// Number of samples per channel
+    pub fn frames(&self) -> usize{
+        self.samples.len() / cmp::max(self.channels as usize, 1)
+    }
+}
+
+/// Decodes the audio of a file or stream, returned by extract_audio.
+/// Iterates the buffers as they're decoded, as fast as possible; the
+/// pipeline only decodes ahead while the buffers are consumed. The
+/// iteration ends at the end of the stream or on an error, see error
+pub struct AudioExtractor{
+    pipeline: Pipeline,
+    appsink: Element,
+    error: Option<Error>,
+    done: bool,
+}
+
+unsafe impl Send for AudioExtractor {}
+
+/// Decodes the first audio stream of uri converted to format
+///
+/// ```ignore
+/// for buffer in extract_audio("file:///tmp/song.ogg", AudioFormat::new(SampleFormat::F32))?{
+///     ...
+/// }
+/// ```
+pub fn extract_audio(uri: &str, format: AudioFormat) -> Result<AudioExtractor>{
+    let mut pipeline = Pipeline::new("audio-extractor").ok_or_else(|| missing_element("pipeline"))?;
+    let decodebin = Element::new("uridecodebin", "").ok_or_else(|| missing_element("uridecodebin"))?;
+    let audioconvert = Element::new("audioconvert", "").ok_or_else(|| missing_element("audioconvert"))?;
+    let audioresample = Element::new("audioresample", "").ok_or_else(|| missing_element("audioresample"))?;
+    let appsink = Element::new("appsink", "").ok_or_else(|| missing_element("appsink"))?;
+    decodebin.set("uri", to_c_str!(uri));
+    decodebin.set("caps", unsafe{ Caps::from_string("audio/x-raw").unwrap().gst_caps() });
+    appsink.set("caps", unsafe{ format.caps().gst_caps() });
+    appsink.set("sync", 0 as gboolean);
+    appsink.set("max-buffers", 16 as guint);
+
+    let mut decodebin_ref = decodebin.to_element();
+    let mut audioconvert_ref = audioconvert.to_element();
+    let mut audioresample_ref = audioresample.to_element();
+    let mut appsink_ref = appsink.to_element();
+    if !pipeline.add(decodebin) || !pipeline.add(audioconvert) || !pipeline.add(audioresample) || !pipeline.add(appsink) ||
+        !audioconvert_ref.link(&mut audioresample_ref) || !audioresample_ref.link(&mut appsink_ref){
+        return Err(Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_NEGOTIATION as i32,
+            "couldn't link the audio converters"));
+    }
+    link_decoded_pads(&mut decodebin_ref, audioconvert_ref);
+
+    if pipeline.set_state(GST_STATE_PLAYING) == GST_STATE_CHANGE_FAILURE{
+        let err = match pipeline.wait_for_message(0, 0){
+            Some(Err(err)) => err,
+            _ => Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_STATE_CHANGE as i32,
+                &format!("couldn't open {}", uri))
+        };
+        pipeline.set_state(GST_STATE_NULL);
+        return Err(err);
+    }
+    Ok(AudioExtractor{ pipeline: pipeline, appsink: appsink_ref, error: None, done: false })
+}
+
+impl AudioExtractor{
+    /// The error that stopped the iteration before the end of the
+    /// stream, if any
+    pub fn error(&self) -> Option<&Error>{
+        self.error.as_ref()
+    }
+
+    /// Duration of the stream in nanoseconds, once the first buffer was
+    /// decoded
+    pub fn duration_ns(&self) -> Option<i64>{
+        self.pipeline.duration_ns()
+    }
+}
+
+impl Iterator for AudioExtractor{
+    type Item = AudioBuffer;
+
+    fn next(&mut self) -> Option<AudioBuffer>{
+        while !self.done{
+            unsafe{
+                let appsink = self.appsink.gst_element() as *mut GstAppSink;
+                if let Some(sample) = Sample::new(gst_app_sink_try_pull_sample(appsink, POLL_INTERVAL), true){
+                    match AudioBuffer::from_sample(&sample){
+                        Some(buffer) => return Some(buffer),
+                        None => continue
+                    }
+                }
+                if gst_app_sink_is_eos(appsink) == 1{
+                    self.done = true;
+                }
+            }
+            if let Some(Err(err)) = self.pipeline.wait_for_message(0, 0){
+                self.error = Some(err);
+                self.done = true;
+            }
+        }
+        None
+    }
+}
+
+impl Drop for AudioExtractor{
+    fn drop(&mut self){
+        self.pipeline.set_state(GST_STATE_NULL);
+    }
+}
+
+/// Peak and RMS level of a part of a stream, from 0 to 1
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct WaveformBucket{
+    pub peak: f32,
+    pub rms: f32,
+}
+
+#[derive(Default)]
+struct BucketAccumulator{
+    peak: f32,
+    sum_squares: f64,
+    samples: u64,
+}
+
+impl BucketAccumulator{
+    fn push(&mut self, sample: f32){
+        self.peak = self.peak.max(sample.abs());
+        self.sum_squares += sample as f64 * sample as f64;
+        self.samples += 1;
+    }
+
+    fn take(&mut self) -> WaveformBucket{
+        let bucket = WaveformBucket{
+            peak: self.peak.min(1.0),
+            rms: if self.samples > 0{ (self.sum_squares / self.samples as f64).sqrt().min(1.0) as f32 }else{ 0.0 },
+        };
+        *self = BucketAccumulator::default();
+        bucket
+    }
+}
+
+/// Decodes the audio of uri and splits it in buckets parts of the same
+/// duration, returning the peak and RMS level of each one, downmixed to
+/// mono, to draw the waveform of the stream. Fails for streams without a
+/// known duration, as live streams
+pub fn waveform(uri: &str, buckets: usize) -> Result<Vec<WaveformBucket>>{
+    let format = AudioFormat{ sample_format: SampleFormat::F32, rate: Some(WAVEFORM_RATE), channels: Some(1) };
+    let mut extractor = extract_audio(uri, format)?;
+    let mut waveform = Vec::with_capacity(buckets);
+    if buckets == 0{
+        return Ok(waveform);
+    }
+
+    let mut bucket_len = None;
+    let mut accumulator = BucketAccumulator::default();
+    while let Some(buffer) = extractor.next(){
+        let bucket_len = match bucket_len{
+            Some(bucket_len) => bucket_len,
+            None => {
+                let duration = extractor.duration_ns().filter(|&duration| duration > 0)
+                    .ok_or_else(|| Error::new(unsafe{ gst_stream_error_quark() }, GST_STREAM_ERROR_FAILED as i32,
+                        &format!("unknown duration for {}", uri)))?;
+                let total = duration as u64 * WAVEFORM_RATE as u64 / 1000000000;
+                let len = cmp::max((total + buckets as u64 - 1) / buckets as u64, 1);
+                bucket_len = Some(len);
+                len
+            }
+        };
+        if let AudioSamples::F32(ref samples) = buffer.samples{
+            for &sample in samples{
+                accumulator.push(sample);
+                if accumulator.samples == bucket_len && waveform.len() < buckets{
+                    waveform.push(accumulator.take());
+                }
+            }
+        }
+    }
+    if let Some(err) = extractor.error.take(){
+        return Err(err);
+    }
+    if accumulator.samples > 0 && waveform.len() < buckets{
+        waveform.push(accumulator.take());
+    }
+    waveform.resize(buckets, WaveformBucket::default());
+    Ok(waveform)
+}
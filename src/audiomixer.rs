@@ -0,0 +1,118 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+use pad::Pad;
+
+/// Wrapper around audiomixer, which mixes several audio streams into one
+/// with an independent volume and mute for each input.
+///
+/// Sources can be added and removed while the pipeline is running, as
+/// when people join or leave a call.
+pub struct AudioMixer{
+    audiomixer: Element
+}
+
+unsafe impl Sync for AudioMixer {}
+unsafe impl Send for AudioMixer {}
+
+/// An input of an AudioMixer
+pub struct AudioMixerInput{
+    pad: Pad
+}
+
+unsafe impl Sync for AudioMixerInput {}
+unsafe impl Send for AudioMixerInput {}
+
+impl AudioMixer{
+    pub fn new(name: &str) -> Option<AudioMixer>{
+        Element::new("audiomixer", name).map(|audiomixer| AudioMixer{ audiomixer: audiomixer })
+    }
+
+    pub fn new_from_element(element: Element) -> AudioMixer{
+        AudioMixer{ audiomixer: element }
+    }
+
+    /// Requests a new input pad without linking it
+    pub fn request_input(&mut self) -> Option<AudioMixerInput>{
+        unsafe{
+            let pad = gst_element_get_request_pad(self.audiomixer.gst_element_mut(), to_c_str!("sink_%u"));
+            Pad::new(pad, true).map(|pad| AudioMixerInput{ pad: pad })
+        }
+    }
+
+    /// Requests a new input and links the src pad of source to it, both
+    /// elements have to be in the same bin
+    pub fn add_input(&mut self, source: &ElementT) -> Option<AudioMixerInput>{
+        let mut src = source.static_pad("src")?;
+        let mut input = self.request_input()?;
+        if src.link(&mut input.pad) == GST_PAD_LINK_OK{
+            Some(input)
+        }else{
+            self.remove_input(input);
+            None
+        }
+    }
+
+    /// Unlinks and releases the input. Stopping and removing the element
+    /// that fed it is up to the application
+    pub fn remove_input(&mut self, mut input: AudioMixerInput){
+        if let Some(mut peer) = input.pad.peer(){
+            peer.unlink(&mut input.pad);
+        }
+        unsafe{
+            gst_element_release_request_pad(self.audiomixer.gst_element_mut(), input.pad.gst_pad_mut());
+        }
+    }
+}
+
+impl AudioMixerInput{
+    /// Volume of the input, 1.0 is the original volume and the maximum is
+    /// 10.0
+    pub fn set_volume(&self, volume: f64){
+        unsafe{
+            g_object_set(self.pad.gst_pad() as gpointer, to_c_str!("volume"), volume, ptr::null::<gchar>());
+        }
+    }
+
+    pub fn volume(&self) -> f64{
+        unsafe{
+            let mut volume: f64 = 0.0;
+            g_object_get(self.pad.gst_pad() as gpointer, to_c_str!("volume"), &mut volume as *mut f64, ptr::null::<gchar>());
+            volume
+        }
+    }
+
+    pub fn set_mute(&self, mute: bool){
+        unsafe{
+            g_object_set(self.pad.gst_pad() as gpointer, to_c_str!("mute"), mute as gboolean, ptr::null::<gchar>());
+        }
+    }
+
+    pub fn is_muted(&self) -> bool{
+        unsafe{
+            let mut mute: gboolean = 0;
+            g_object_get(self.pad.gst_pad() as gpointer, to_c_str!("mute"), &mut mute as *mut gboolean, ptr::null::<gchar>());
+            mute != 0
+        }
+    }
+
+    pub fn pad(&self) -> &Pad{
+        &self.pad
+    }
+}
+
+impl ElementT for AudioMixer{
+    fn as_element(&self) -> &Element{
+        &self.audiomixer
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.audiomixer
+    }
+}
+
+impl ::Transfer for AudioMixer{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.audiomixer.transfer()
+    }
+}
@@ -0,0 +1,122 @@
+use ffi::*;
+use appsink::AppSink;
+use element::{Element, ElementT};
+use sample::Sample;
+use sink_impl::{AudioSink, VideoSink};
+
+/// Properties of the sinks deriving from GstBaseSink, as video and audio
+/// sinks, appsink and fakesink. On elements that aren't sinks the setters
+/// do nothing and the getters return the defaults
+pub trait BaseSinkT: ElementT{
+    /// Whether this element is a GstBaseSink
+    fn is_base_sink(&self) -> bool{
+        unsafe{
+            g_type_check_instance_is_a(self.gst_element() as *mut GTypeInstance, gst_base_sink_get_type()) != 0
+        }
+    }
+
+    /// The last buffer the sink rendered with its caps, as the frame
+    /// currently on screen for a video sink. Needs enable-last-sample,
+    /// which is on by default
+    fn last_sample(&self) -> Option<Sample>{
+        unsafe{
+            match base_sink(self){
+                Some(sink) => Sample::new(gst_base_sink_get_last_sample(sink), true),
+                None => None
+            }
+        }
+    }
+
+    /// Whether the sink keeps a reference to the last buffer for
+    /// last_sample, disabling it lets upstream reuse the buffer sooner
+    fn set_last_sample_enabled(&self, enabled: bool){
+        unsafe{
+            if let Some(sink) = base_sink(self){
+                gst_base_sink_set_last_sample_enabled(sink, enabled as gboolean);
+            }
+        }
+    }
+
+    fn is_last_sample_enabled(&self) -> bool{
+        unsafe{
+            base_sink(self).map(|sink| gst_base_sink_is_last_sample_enabled(sink) != 0).unwrap_or(false)
+        }
+    }
+
+    /// Whether buffers are rendered at their running time, false renders
+    /// them as soon as they arrive
+    fn set_sync(&self, sync: bool){
+        unsafe{
+            if let Some(sink) = base_sink(self){
+                gst_base_sink_set_sync(sink, sync as gboolean);
+            }
+        }
+    }
+
+    fn sync(&self) -> bool{
+        unsafe{
+            base_sink(self).map(|sink| gst_base_sink_get_sync(sink) != 0).unwrap_or(false)
+        }
+    }
+
+    /// Time in nanoseconds between rendering a buffer and it being
+    /// visible or audible, buffers are rendered earlier to compensate
+    fn set_render_delay(&self, delay: u64){
+        unsafe{
+            if let Some(sink) = base_sink(self){
+                gst_base_sink_set_render_delay(sink, delay);
+            }
+        }
+    }
+
+    fn render_delay(&self) -> u64{
+        unsafe{
+            base_sink(self).map(|sink| gst_base_sink_get_render_delay(sink)).unwrap_or(0)
+        }
+    }
+
+    /// Time in nanoseconds a buffer can be late and still be rendered, -1
+    /// to render all of them
+    fn set_max_lateness(&self, max_lateness: i64){
+        unsafe{
+            if let Some(sink) = base_sink(self){
+                gst_base_sink_set_max_lateness(sink, max_lateness);
+            }
+        }
+    }
+
+    fn max_lateness(&self) -> i64{
+        unsafe{
+            base_sink(self).map(|sink| gst_base_sink_get_max_lateness(sink)).unwrap_or(-1)
+        }
+    }
+
+    /// Whether late buffers are reported upstream with QoS events, so
+    /// decoders can skip work
+    fn set_qos_enabled(&self, enabled: bool){
+        unsafe{
+            if let Some(sink) = base_sink(self){
+                gst_base_sink_set_qos_enabled(sink, enabled as gboolean);
+            }
+        }
+    }
+
+    fn qos_enabled(&self) -> bool{
+        unsafe{
+            base_sink(self).map(|sink| gst_base_sink_is_qos_enabled(sink) != 0).unwrap_or(false)
+        }
+    }
+}
+
+unsafe fn base_sink<S: BaseSinkT + ?Sized>(sink: &S) -> Option<*mut GstBaseSink>{
+    if sink.is_base_sink(){
+        Some(sink.gst_element() as *mut GstBaseSink)
+    }else{
+        None
+    }
+}
+
+impl BaseSinkT for Element{}
+impl BaseSinkT for AppSink{}
+impl BaseSinkT for AudioSink{}
+impl BaseSinkT for VideoSink{}
@@ -1,6 +1,10 @@
 use ffi::*;
 use element::Element;
-use element::ElementT;
+use element::{ElementT, PendingState, pending_children};
+use pad::Pad;
+use topology::PipelineGraph;
+use error::{Error, Result};
+use parse::parse_bin;
 use util::*;
 
 /**
@@ -100,6 +104,14 @@ impl Bin{
         }
     }
     
+    /// Creates a bin from a gst-launch like description as in
+    /// `audioconvert ! equalizer-3bands band1=-6.0 ! audioconvert`, the
+    /// unlinked pads of the first and last elements are exposed as ghost
+    /// pads so it can be used as a filter or linked like any element.
+    pub fn new_from_description(description: &str) -> Result<Bin>{
+        parse_bin(description, true).map_err(Error::from)
+    }
+
     /// Creates a new bin from an already existing raw pointer to a GstBin.
     /// The passed element has to be fully referenced
     pub unsafe fn new_from_gst_bin(element: *mut GstBin) -> Option<Bin>{
@@ -149,6 +161,42 @@ pub trait BinT: ElementT{
     fn get_by_name(&self, name: &str) -> Option<Element>{
         self.as_bin().get_by_name(name)
     }
+
+    /// The elements directly inside this bin in the order they were
+    /// added, elements inside child bins are not included
+    fn children(&self) -> Vec<Element>{
+        self.as_bin().children()
+    }
+
+    /// The current and pending state of every element in the bin,
+    /// recursively, with the path of the element as /pipeline0/bin0/sink.
+    /// The pending state is GST_STATE_VOID_PENDING for the elements that
+    /// aren't changing state
+    fn children_states(&self) -> Vec<(String, GstState, GstState)>{
+        self.as_bin().children_states()
+    }
+
+    /// The elements in the bin, recursively, that didn't complete their
+    /// last state change, as the sink that keeps a pipeline from
+    /// prerolling. Bins are skipped since they wait for their children
+    fn find_stuck_children(&self) -> Vec<PendingState>{
+        unsafe{
+            pending_children(self.gst_element() as *mut GstElement)
+        }
+    }
+
+    /// Returns a snapshot of the elements in the bin, recursively, with
+    /// their states, pads, negotiated caps and links between them
+    fn topology(&self) -> PipelineGraph{
+        PipelineGraph::new(self.as_bin())
+    }
+
+    /// Exposes target, a pad of an element inside the bin, as a pad of
+    /// the bin itself with the given name so the bin can be linked like
+    /// any other element.
+    fn add_ghost_pad(&mut self, name: &str, target: &Pad) -> bool{
+        self.as_bin_mut().add_ghost_pad(name, target)
+    }
     
     /// Query bin for the current latency using and reconfigures this latency
     /// to all the elements with a LATENCY event.
@@ -220,6 +268,40 @@ impl BinT for Bin{
             Element::new_from_gst_element(element)
         }
     }
+
+    fn children(&self) -> Vec<Element>{
+        unsafe{
+            iterator_objects(gst_bin_iterate_elements(self.gst_bin() as *mut GstBin)).into_iter().rev()
+                .filter_map(|element| Element::new_from_gst_element(element as *mut GstElement))
+                .collect()
+        }
+    }
+
+    fn children_states(&self) -> Vec<(String, GstState, GstState)>{
+        unsafe{
+            iterator_objects(gst_bin_iterate_recurse(self.gst_bin() as *mut GstBin)).into_iter().rev()
+                .filter_map(|element| Element::new_from_gst_element(element as *mut GstElement))
+                .map(|element| {
+                    let (current, pending, _) = element.get_state(0);
+                    let path = gst_object_get_path_string(element.gst_element() as *mut GstObject);
+                    let name = from_c_str!(path).to_string();
+                    g_free(path as gpointer);
+                    (name, current, pending)
+                })
+                .collect()
+        }
+    }
+
+    fn add_ghost_pad(&mut self, name: &str, target: &Pad) -> bool{
+        unsafe{
+            let ghost = gst_ghost_pad_new(to_c_str!(name), target.gst_pad() as *mut GstPad);
+            if ghost == ptr::null_mut(){
+                return false;
+            }
+            gst_pad_set_active(ghost, 1);
+            gst_element_add_pad(self.gst_bin_mut() as *mut GstElement, ghost) == 1
+        }
+    }
     
     fn recalculate_latency(&self) -> bool{
         unsafe{
@@ -0,0 +1,111 @@
+use ffi::*;
+use bin::Bin;
+use message::Message;
+use subclass::*;
+
+/// A bin implemented in Rust, to ship a group of elements as a reusable
+/// element, as a source that reconnects or a decoder with fallbacks,
+/// that can be created by name and used in launch lines once registered
+/// with register_bin.
+///
+/// ```ignore
+/// #[derive(Default)]
+/// struct Thumbnailer;
+///
+/// impl ElementImpl for Thumbnailer{
+///     fn metadata() -> Metadata{ ... }
+///     fn pad_templates() -> Vec<PadTemplate>{ ... }
+/// }
+///
+/// impl BinImpl for Thumbnailer{
+///     fn init(&self, bin: &mut Bin){
+///         let scale = Element::new("videoscale", "scale").unwrap();
+///         ...
+///         bin.add_ghost_pad("sink", &sink_pad);
+///     }
+/// }
+///
+/// register_bin::<Thumbnailer>("thumbnailer", GST_RANK_NONE);
+/// ```
+pub trait BinImpl: ElementImpl{
+    /// Called once for each new bin, to add the children and expose
+    /// their pads as ghost pads. The pad names have to match the pad
+    /// templates
+    fn init(&self, _bin: &mut Bin){}
+
+    /// Called with every message posted by the children, in the thread
+    /// that posted it, before the bin forwards it to its parent. Return
+    /// the message, another one to replace it, or None to drop it, as to
+    /// turn the errors of a branch that can fail into warnings
+    fn handle_message(&self, _bin: &Bin, message: Message) -> Option<Message>{
+        Some(message)
+    }
+}
+
+pub(crate) unsafe fn wrap_bin(bin: *mut GstBin) -> Bin{
+    gst_object_ref(bin as gpointer);
+    Bin::new_from_gst_bin(bin).unwrap()
+}
+
+pub(crate) extern "C" fn bin_class_init<T: BinImpl>(class: gpointer, _data: gpointer){
+    unsafe{
+        element_class_init::<T>(class);
+        (*(class as *mut GstBinClass)).handle_message = Some(bin_handle_message::<T>);
+    }
+}
+
+pub(crate) extern "C" fn bin_instance_init<T: BinImpl>(instance: *mut GTypeInstance, class: gpointer){
+    instance_init::<T>(instance, class);
+    unsafe{
+        let mut bin = wrap_bin(instance as *mut GstBin);
+        imp::<T>(instance as gpointer).init(&mut bin);
+    }
+}
+
+extern "C" fn bin_handle_message<T: BinImpl>(bin: *mut GstBin, message: *mut GstMessage){
+    unsafe{
+        let wrapper = wrap_bin(bin);
+        let msg = Message::new(message);
+        gst_mini_object_unref(message as *mut GstMiniObject);
+        if let Some(msg) = msg.and_then(|msg| imp::<T>(bin as gpointer).handle_message(&wrapper, msg)){
+            if let Some(handle_message) = (*parent_class::<GstBinClass>(bin as gpointer)).handle_message{
+                handle_message(bin, ::Transfer::transfer(msg));
+            }
+        }
+    }
+}
+
+/// Registers a bin implemented in Rust so it can be created by name with
+/// Element::new or in launch lines
+pub fn register_bin<T: BinImpl>(name: &str, rank: u32) -> bool{
+    unsafe{
+        register_element(gst_bin_get_type(), name, rank, bin_class_init::<T>, bin_instance_init::<T>)
+    }
+}
+
+/// Registers a pipeline implemented in Rust, to intercept the messages of
+/// its children in handle_message before they reach the bus of the
+/// application. Create it with Pipeline::new_from_factory.
+///
+/// ```ignore
+/// #[derive(Default)]
+/// struct TolerantPipeline;
+///
+/// impl BinImpl for TolerantPipeline{
+///     fn handle_message(&self, _bin: &Bin, message: Message) -> Option<Message>{
+///         match message{
+///             // the preview branch can fail without stopping the recording
+///             Message::Error(_) if message.src_name().starts_with("preview") => None,
+///             message => Some(message)
+///         }
+///     }
+/// }
+///
+/// register_pipeline::<TolerantPipeline>("tolerantpipeline", GST_RANK_NONE);
+/// let pipeline = Pipeline::new_from_factory("tolerantpipeline", "recorder").unwrap();
+/// ```
+pub fn register_pipeline<T: BinImpl>(name: &str, rank: u32) -> bool{
+    unsafe{
+        register_element(gst_pipeline_get_type(), name, rank, bin_class_init::<T>, bin_instance_init::<T>)
+    }
+}
@@ -1,4 +1,5 @@
 use ffi::*;
+use debug::{track_new, track_release};
 
 use std::mem;
 use std::ptr;
@@ -8,9 +9,35 @@ pub struct Buffer{
     buffer: *mut GstBuffer
 }
 
+// SYNC_AFTER was added in GStreamer 1.6 and NON_DROPPABLE in 1.14, after
+// ffi.rs was generated
+pub const GST_BUFFER_FLAG_SYNC_AFTER: GstBufferFlags = GST_BUFFER_FLAG_TAG_MEMORY << 1;
+pub const GST_BUFFER_FLAG_NON_DROPPABLE: GstBufferFlags = GST_BUFFER_FLAG_TAG_MEMORY << 2;
+
+bitflags!{
+    /// Flags of a buffer, as returned by Buffer::buffer_flags
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct BufferFlags: u32{
+        const LIVE = GST_BUFFER_FLAG_LIVE;
+        const DECODE_ONLY = GST_BUFFER_FLAG_DECODE_ONLY;
+        const DISCONT = GST_BUFFER_FLAG_DISCONT;
+        const RESYNC = GST_BUFFER_FLAG_RESYNC;
+        const CORRUPTED = GST_BUFFER_FLAG_CORRUPTED;
+        const MARKER = GST_BUFFER_FLAG_MARKER;
+        const HEADER = GST_BUFFER_FLAG_HEADER;
+        const GAP = GST_BUFFER_FLAG_GAP;
+        const DROPPABLE = GST_BUFFER_FLAG_DROPPABLE;
+        const DELTA_UNIT = GST_BUFFER_FLAG_DELTA_UNIT;
+        const TAG_MEMORY = GST_BUFFER_FLAG_TAG_MEMORY;
+        const SYNC_AFTER = GST_BUFFER_FLAG_SYNC_AFTER;
+        const NON_DROPPABLE = GST_BUFFER_FLAG_NON_DROPPABLE;
+    }
+}
+
 impl Drop for Buffer{
     fn drop(&mut self){
         unsafe{
+            track_release(self.buffer);
        		gst_mini_object_unref(self.buffer as *mut GstMiniObject);
         }
     }
@@ -45,6 +72,7 @@ impl Buffer{
 	    	if !owned{
         		gst_mini_object_ref(buffer as *mut GstMiniObject);
         	}
+	        track_new("Buffer", buffer, true);
 	        let buff = Buffer{ buffer: buffer };
 	        Some(buff)
 	    }else{
@@ -107,6 +135,36 @@ impl Buffer{
         self.buffer
     }
 
+    /// Presentation timestamp of the buffer in nanoseconds, or
+    /// GST_CLOCK_TIME_NONE if it's unknown
+    pub fn pts(&self) -> GstClockTime {
+        unsafe { (*self.gst_buffer()).pts }
+    }
+
+    pub fn set_pts(&mut self, pts: GstClockTime) {
+        unsafe { (*self.gst_buffer_mut()).pts = pts; }
+    }
+
+    /// Decoding timestamp of the buffer in nanoseconds, or
+    /// GST_CLOCK_TIME_NONE if it's unknown
+    pub fn dts(&self) -> GstClockTime {
+        unsafe { (*self.gst_buffer()).dts }
+    }
+
+    pub fn set_dts(&mut self, dts: GstClockTime) {
+        unsafe { (*self.gst_buffer_mut()).dts = dts; }
+    }
+
+    /// Duration of the buffer in nanoseconds, or GST_CLOCK_TIME_NONE
+    /// if it's unknown
+    pub fn duration(&self) -> GstClockTime {
+        unsafe { (*self.gst_buffer()).duration }
+    }
+
+    pub fn set_duration(&mut self, duration: GstClockTime) {
+        unsafe { (*self.gst_buffer_mut()).duration = duration; }
+    }
+
     pub fn flags(&self) -> guint {
         unsafe { (*self.gst_buffer()).mini_object.flags }
     }
@@ -122,11 +180,35 @@ impl Buffer{
     gst_buffer_flag!(is_droppable, set_droppable, GST_BUFFER_FLAG_DROPPABLE);
     gst_buffer_flag!(is_delta_unit, set_delta_unit, GST_BUFFER_FLAG_DELTA_UNIT);
     gst_buffer_flag!(is_tag_memory, set_tag_memory, GST_BUFFER_FLAG_TAG_MEMORY);
+    gst_buffer_flag!(is_sync_after, set_sync_after, GST_BUFFER_FLAG_SYNC_AFTER);
+    gst_buffer_flag!(is_non_droppable, set_non_droppable, GST_BUFFER_FLAG_NON_DROPPABLE);
+
+    /// The flags of the buffer, flags unknown to this crate are kept
+    pub fn buffer_flags(&self) -> BufferFlags {
+        BufferFlags::from_bits_retain(self.flags())
+    }
+
+    /// Sets the given flags, leaving the rest as they were
+    pub fn set_buffer_flags(&mut self, flags: BufferFlags) {
+        unsafe { (*self.gst_buffer_mut()).mini_object.flags |= flags.bits(); }
+    }
+
+    /// Clears the given flags, leaving the rest as they were
+    pub fn unset_buffer_flags(&mut self, flags: BufferFlags) {
+        unsafe { (*self.gst_buffer_mut()).mini_object.flags &= !flags.bits(); }
+    }
+
+    /// True if the buffer can be decoded on its own, as a video key
+    /// frame or any audio buffer
+    pub fn is_keyframe(&self) -> bool {
+        !self.is_delta_unit()
+    }
 }
 
 impl ::Transfer<GstBuffer> for Buffer{
     unsafe fn transfer(self) ->  *mut GstBuffer{
         let buffer = self.buffer;
+        track_release(buffer);
 		mem::forget(self);
         buffer
     }
@@ -143,6 +225,9 @@ macro_rules! fmt_buffer_flag {
 impl Debug for Buffer {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
         try!(fmt.write_fmt(format_args!("GstBuffer<{} bytes", self.size())));
+        if self.pts() != GST_CLOCK_TIME_NONE {
+            try!(fmt.write_fmt(format_args!(", pts {}ns", self.pts())));
+        }
         fmt_buffer_flag!(self, fmt, is_live);
         fmt_buffer_flag!(self, fmt, is_decode_only);
         fmt_buffer_flag!(self, fmt, is_discont);
@@ -154,6 +239,8 @@ impl Debug for Buffer {
         fmt_buffer_flag!(self, fmt, is_droppable);
         fmt_buffer_flag!(self, fmt, is_delta_unit);
         fmt_buffer_flag!(self, fmt, is_tag_memory);
+        fmt_buffer_flag!(self, fmt, is_sync_after);
+        fmt_buffer_flag!(self, fmt, is_non_droppable);
         try!(fmt.write_str(">"));
         Ok(())
     }
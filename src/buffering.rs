@@ -0,0 +1,123 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+use playbin::PlayBin;
+use value::Value;
+
+/// Value of 100% in GST_FORMAT_PERCENT
+pub const GST_FORMAT_PERCENT_MAX: i64 = 1000000;
+
+/// The state of the buffering of a pipeline, as answered by the
+/// BUFFERING query
+#[derive(Clone,Debug,PartialEq)]
+pub struct BufferingStatus{
+    /// Whether the pipeline is buffering, waiting for the queues to fill
+    pub busy: bool,
+    /// Fill level of the queue that triggers the buffering
+    pub percent: i32,
+    /// GST_BUFFERING_STREAM, GST_BUFFERING_DOWNLOAD,
+    /// GST_BUFFERING_TIMESHIFT or GST_BUFFERING_LIVE
+    pub mode: GstBufferingMode,
+    /// Download rate in bytes per second, -1 if unknown
+    pub avg_in: i32,
+    /// Playback consumption rate in bytes per second, -1 if unknown
+    pub avg_out: i32,
+    /// Milliseconds until the buffering is complete
+    pub buffering_left: Option<i64>,
+    /// The parts of the stream already downloaded, as start and stop in
+    /// 0..1 of the whole stream. In download mode it can be drawn as the
+    /// "downloaded so far" bar, independent of the playback position
+    pub ranges: Vec<(f64, f64)>,
+    /// Milliseconds estimated to download the whole stream
+    pub estimated_total: Option<i64>,
+}
+
+pub(crate) unsafe fn query_buffering(element: *mut GstElement) -> Option<BufferingStatus>{
+    let query = gst_query_new_buffering(GST_FORMAT_PERCENT);
+    let mut status = None;
+    if gst_element_query(element, query) == 1{
+        let mut busy = 0;
+        let mut percent = 0;
+        gst_query_parse_buffering_percent(query, &mut busy, &mut percent);
+        let mut mode = GST_BUFFERING_STREAM;
+        let mut avg_in = -1;
+        let mut avg_out = -1;
+        let mut buffering_left = -1;
+        gst_query_parse_buffering_stats(query, &mut mode, &mut avg_in, &mut avg_out, &mut buffering_left);
+        let mut estimated_total = -1;
+        gst_query_parse_buffering_range(query, ptr::null_mut(), ptr::null_mut(), ptr::null_mut(), &mut estimated_total);
+        let ranges = (0..gst_query_get_n_buffering_ranges(query)).filter_map(|i| {
+            let mut start = 0;
+            let mut stop = 0;
+            if gst_query_parse_nth_buffering_range(query, i, &mut start, &mut stop) == 1{
+                Some((start as f64 / GST_FORMAT_PERCENT_MAX as f64, stop as f64 / GST_FORMAT_PERCENT_MAX as f64))
+            }else{
+                None
+            }
+        }).collect();
+        status = Some(BufferingStatus{
+            busy: busy == 1,
+            percent: percent,
+            mode: mode,
+            avg_in: avg_in,
+            avg_out: avg_out,
+            buffering_left: if buffering_left >= 0{ Some(buffering_left) }else{ None },
+            ranges: ranges,
+            estimated_total: if estimated_total >= 0{ Some(estimated_total) }else{ None },
+        });
+    }
+    gst_mini_object_unref(query as *mut GstMiniObject);
+    status
+}
+
+/// How much data a queue2 or downloadbuffer holds
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct QueueLevel{
+    pub bytes: u64,
+    /// Duration of the data in nanoseconds
+    pub time: u64,
+    /// Download rate in bytes per second, only reported by queue2
+    pub avg_in_rate: Option<i64>,
+}
+
+/// The current level of a queue2 or downloadbuffer element
+pub fn queue_level<E: ElementT>(queue: &E) -> Option<QueueLevel>{
+    let bytes = match queue.property("current-level-bytes")?{
+        Value::UInt(bytes) => bytes as u64,
+        Value::UInt64(bytes) => bytes,
+        _ => return None
+    };
+    let time = match queue.property("current-level-time")?{
+        Value::UInt64(time) => time,
+        _ => return None
+    };
+    let avg_in_rate = match queue.property("avg-in-rate"){
+        Some(Value::Int64(rate)) => Some(rate),
+        _ => None
+    };
+    Some(QueueLevel{ bytes: bytes, time: time, avg_in_rate: avg_in_rate })
+}
+
+impl PlayBin{
+    /// The queue2 or downloadbuffer playbin uses to download or buffer
+    /// network streams, once the stream started. Its level grows ahead of
+    /// the playback position
+    pub fn download_queue(&self) -> Option<Element>{
+        // wrap every child first so the refs of the ones after the match
+        // are released
+        let children = unsafe{
+            iterator_objects(gst_bin_iterate_recurse(self.gst_element() as *mut GstBin)).into_iter()
+                .filter_map(|child| Element::new_from_gst_element(child as *mut GstElement))
+                .collect::<Vec<_>>()
+        };
+        let is_factory = |child: &Element, factory: &str| child.factory_name().map(|name| name == factory).unwrap_or(false);
+        let position = children.iter().position(|child| is_factory(child, "downloadbuffer"))
+            .or_else(|| children.iter().position(|child| is_factory(child, "queue2")));
+        position.and_then(|i| children.into_iter().nth(i))
+    }
+
+    /// The level of the download queue, see download_queue
+    pub fn download_level(&self) -> Option<QueueLevel>{
+        queue_level(&self.download_queue()?)
+    }
+}
@@ -48,6 +48,14 @@ impl Bus{
 		self.add_watch(&watch);
 		receiver
 	}
+
+    pub unsafe fn gst_bus(&self) -> *const GstBus{
+        self.bus
+    }
+
+    pub unsafe fn gst_bus_mut(&mut self) -> *mut GstBus{
+        self.bus
+    }
 }
 
 extern "C" fn bus_callback(_bus: *mut GstBus, msg: *mut GstMessage, data: gpointer) -> gboolean {
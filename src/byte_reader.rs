@@ -0,0 +1,473 @@
+use ffi::*;
+use util::*;
+
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::slice;
+
+// GstByteReader, GstBitReader and GstByteWriter are part of gstbase but
+// weren't included when ffi.rs was generated
+#[repr(C)]
+pub struct GstByteReader{
+    pub data: *const guint8,
+    pub size: guint,
+    pub byte: guint,
+    _gst_reserved: [gpointer; 4],
+}
+
+#[repr(C)]
+pub struct GstBitReader{
+    pub data: *const guint8,
+    pub size: guint,
+    pub byte: guint,
+    pub bit: guint,
+    _gst_reserved: [gpointer; 4],
+}
+
+#[repr(C)]
+pub struct GstByteWriter{
+    pub parent: GstByteReader,
+    pub alloc_size: guint,
+    pub fixed: gboolean,
+    pub owned: gboolean,
+    _gst_reserved: [gpointer; 4],
+}
+
+extern "C"{
+    fn gst_byte_reader_init(reader: *mut GstByteReader, data: *const guint8, size: guint);
+    fn gst_byte_reader_set_pos(reader: *mut GstByteReader, pos: guint) -> gboolean;
+    fn gst_byte_reader_get_pos(reader: *const GstByteReader) -> guint;
+    fn gst_byte_reader_get_remaining(reader: *const GstByteReader) -> guint;
+    fn gst_byte_reader_skip(reader: *mut GstByteReader, nbytes: guint) -> gboolean;
+    fn gst_byte_reader_get_uint8(reader: *mut GstByteReader, val: *mut guint8) -> gboolean;
+    fn gst_byte_reader_peek_uint8(reader: *const GstByteReader, val: *mut guint8) -> gboolean;
+    fn gst_byte_reader_get_uint16_be(reader: *mut GstByteReader, val: *mut guint16) -> gboolean;
+    fn gst_byte_reader_get_uint16_le(reader: *mut GstByteReader, val: *mut guint16) -> gboolean;
+    fn gst_byte_reader_get_uint24_be(reader: *mut GstByteReader, val: *mut guint32) -> gboolean;
+    fn gst_byte_reader_get_uint24_le(reader: *mut GstByteReader, val: *mut guint32) -> gboolean;
+    fn gst_byte_reader_get_uint32_be(reader: *mut GstByteReader, val: *mut guint32) -> gboolean;
+    fn gst_byte_reader_get_uint32_le(reader: *mut GstByteReader, val: *mut guint32) -> gboolean;
+    fn gst_byte_reader_peek_uint32_be(reader: *const GstByteReader, val: *mut guint32) -> gboolean;
+    fn gst_byte_reader_get_uint64_be(reader: *mut GstByteReader, val: *mut guint64) -> gboolean;
+    fn gst_byte_reader_get_uint64_le(reader: *mut GstByteReader, val: *mut guint64) -> gboolean;
+    fn gst_byte_reader_get_data(reader: *mut GstByteReader, size: guint, val: *mut *const guint8) -> gboolean;
+    fn gst_byte_reader_peek_data(reader: *const GstByteReader, size: guint, val: *mut *const guint8) -> gboolean;
+    fn gst_byte_reader_masked_scan_uint32(reader: *const GstByteReader, mask: guint32, pattern: guint32,
+        offset: guint, size: guint) -> guint;
+
+    fn gst_bit_reader_init(reader: *mut GstBitReader, data: *const guint8, size: guint);
+    fn gst_bit_reader_set_pos(reader: *mut GstBitReader, pos: guint) -> gboolean;
+    fn gst_bit_reader_get_pos(reader: *const GstBitReader) -> guint;
+    fn gst_bit_reader_get_remaining(reader: *const GstBitReader) -> guint;
+    fn gst_bit_reader_skip(reader: *mut GstBitReader, nbits: guint) -> gboolean;
+    fn gst_bit_reader_skip_to_byte(reader: *mut GstBitReader) -> gboolean;
+    fn gst_bit_reader_get_bits_uint8(reader: *mut GstBitReader, val: *mut guint8, nbits: guint) -> gboolean;
+    fn gst_bit_reader_get_bits_uint16(reader: *mut GstBitReader, val: *mut guint16, nbits: guint) -> gboolean;
+    fn gst_bit_reader_get_bits_uint32(reader: *mut GstBitReader, val: *mut guint32, nbits: guint) -> gboolean;
+    fn gst_bit_reader_get_bits_uint64(reader: *mut GstBitReader, val: *mut guint64, nbits: guint) -> gboolean;
+    fn gst_bit_reader_peek_bits_uint32(reader: *const GstBitReader, val: *mut guint32, nbits: guint) -> gboolean;
+
+    fn gst_byte_writer_new() -> *mut GstByteWriter;
+    fn gst_byte_writer_new_with_size(size: guint, fixed: gboolean) -> *mut GstByteWriter;
+    fn gst_byte_writer_free(writer: *mut GstByteWriter);
+    fn gst_byte_writer_reset_and_get_data(writer: *mut GstByteWriter) -> *mut guint8;
+    fn gst_byte_writer_put_uint8(writer: *mut GstByteWriter, val: guint8) -> gboolean;
+    fn gst_byte_writer_put_uint16_be(writer: *mut GstByteWriter, val: guint16) -> gboolean;
+    fn gst_byte_writer_put_uint16_le(writer: *mut GstByteWriter, val: guint16) -> gboolean;
+    fn gst_byte_writer_put_uint24_be(writer: *mut GstByteWriter, val: guint32) -> gboolean;
+    fn gst_byte_writer_put_uint24_le(writer: *mut GstByteWriter, val: guint32) -> gboolean;
+    fn gst_byte_writer_put_uint32_be(writer: *mut GstByteWriter, val: guint32) -> gboolean;
+    fn gst_byte_writer_put_uint32_le(writer: *mut GstByteWriter, val: guint32) -> gboolean;
+    fn gst_byte_writer_put_uint64_be(writer: *mut GstByteWriter, val: guint64) -> gboolean;
+    fn gst_byte_writer_put_uint64_le(writer: *mut GstByteWriter, val: guint64) -> gboolean;
+    fn gst_byte_writer_put_data(writer: *mut GstByteWriter, data: *const guint8, size: guint) -> gboolean;
+    fn gst_byte_writer_fill(writer: *mut GstByteWriter, value: guint8, size: guint) -> gboolean;
+}
+
+macro_rules! byte_reader_get {
+    ($name:ident, $ty:ty, $ffi:ident) => (
+        pub fn $name(&mut self) -> Option<$ty>{
+            unsafe{
+                let mut val: $ty = 0;
+                if $ffi(&mut self.reader, &mut val) != 0{
+                    Some(val)
+                }else{
+                    None
+                }
+            }
+        }
+    )
+}
+
+/// Bounds checked reading of integers and byte strings from a slice, as
+/// used by the GStreamer parsers. Every read returns None without
+/// moving the position if there's not enough data left
+pub struct ByteReader<'a>{
+    reader: GstByteReader,
+    phantom: PhantomData<&'a [u8]>,
+}
+
+impl<'a> ByteReader<'a>{
+    /// Returns None if data is larger than u32::MAX bytes, the size
+    /// GstByteReader can address
+    pub fn new(data: &'a [u8]) -> Option<ByteReader<'a>>{
+        let size = u32::try_from(data.len()).ok()?;
+        unsafe{
+            let mut reader: GstByteReader = mem::zeroed();
+            gst_byte_reader_init(&mut reader, data.as_ptr(), size);
+            Some(ByteReader{ reader: reader, phantom: PhantomData })
+        }
+    }
+
+    /// Current position in bytes from the start of the data
+    pub fn pos(&self) -> u32{
+        unsafe{ gst_byte_reader_get_pos(&self.reader) }
+    }
+
+    pub fn set_pos(&mut self, pos: u32) -> bool{
+        unsafe{ gst_byte_reader_set_pos(&mut self.reader, pos) != 0 }
+    }
+
+    pub fn remaining(&self) -> u32{
+        unsafe{ gst_byte_reader_get_remaining(&self.reader) }
+    }
+
+    pub fn size(&self) -> u32{
+        self.reader.size
+    }
+
+    pub fn skip(&mut self, nbytes: u32) -> bool{
+        unsafe{ gst_byte_reader_skip(&mut self.reader, nbytes) != 0 }
+    }
+
+    byte_reader_get!(get_u8, u8, gst_byte_reader_get_uint8);
+    byte_reader_get!(get_u16_be, u16, gst_byte_reader_get_uint16_be);
+    byte_reader_get!(get_u16_le, u16, gst_byte_reader_get_uint16_le);
+    byte_reader_get!(get_u24_be, u32, gst_byte_reader_get_uint24_be);
+    byte_reader_get!(get_u24_le, u32, gst_byte_reader_get_uint24_le);
+    byte_reader_get!(get_u32_be, u32, gst_byte_reader_get_uint32_be);
+    byte_reader_get!(get_u32_le, u32, gst_byte_reader_get_uint32_le);
+    byte_reader_get!(get_u64_be, u64, gst_byte_reader_get_uint64_be);
+    byte_reader_get!(get_u64_le, u64, gst_byte_reader_get_uint64_le);
+
+    pub fn peek_u8(&self) -> Option<u8>{
+        unsafe{
+            let mut val = 0;
+            if gst_byte_reader_peek_uint8(&self.reader, &mut val) != 0{
+                Some(val)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn peek_u32_be(&self) -> Option<u32>{
+        unsafe{
+            let mut val = 0;
+            if gst_byte_reader_peek_uint32_be(&self.reader, &mut val) != 0{
+                Some(val)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns the next size bytes and advances past them
+    pub fn get_data(&mut self, size: u32) -> Option<&'a [u8]>{
+        unsafe{
+            let mut data = ptr::null();
+            if gst_byte_reader_get_data(&mut self.reader, size, &mut data) != 0{
+                Some(slice::from_raw_parts(data, size as usize))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn peek_data(&self, size: u32) -> Option<&'a [u8]>{
+        unsafe{
+            let mut data = ptr::null();
+            if gst_byte_reader_peek_data(&self.reader, size, &mut data) != 0{
+                Some(slice::from_raw_parts(data, size as usize))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns the remaining bytes without advancing
+    pub fn remaining_data(&self) -> &'a [u8]{
+        self.peek_data(self.remaining()).unwrap_or(&[])
+    }
+
+    /// Scans size bytes starting at offset from the current position
+    /// for the 32 bit big endian pattern, only comparing the bits in
+    /// mask, and returns its offset. Finding the next H.264 start code
+    /// is masked_scan_u32(0xffffff00, 0x00000100, 0, reader.remaining())
+    pub fn masked_scan_u32(&self, mask: u32, pattern: u32, offset: u32, size: u32) -> Option<u32>{
+        unsafe{
+            let pos = gst_byte_reader_masked_scan_uint32(&self.reader, mask, pattern, offset, size);
+            if pos != guint::max_value(){
+                Some(pos)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+macro_rules! bit_reader_get {
+    ($name:ident, $ty:ty, $ffi:ident) => (
+        pub fn $name(&mut self, nbits: u32) -> Option<$ty>{
+            unsafe{
+                let mut val: $ty = 0;
+                if $ffi(&mut self.reader, &mut val, nbits) != 0{
+                    Some(val)
+                }else{
+                    None
+                }
+            }
+        }
+    )
+}
+
+/// Bounds checked reading of bit fields from a slice, most significant
+/// bit first, as found in codec headers
+pub struct BitReader<'a>{
+    reader: GstBitReader,
+    phantom: PhantomData<&'a [u8]>,
+}
+
+impl<'a> BitReader<'a>{
+    /// Returns None if data has more than u32::MAX bits, as positions
+    /// are counted in bits
+    pub fn new(data: &'a [u8]) -> Option<BitReader<'a>>{
+        let size = u32::try_from(data.len()).ok()?;
+        size.checked_mul(8)?;
+        unsafe{
+            let mut reader: GstBitReader = mem::zeroed();
+            gst_bit_reader_init(&mut reader, data.as_ptr(), size);
+            Some(BitReader{ reader: reader, phantom: PhantomData })
+        }
+    }
+
+    /// Current position in bits from the start of the data
+    pub fn pos(&self) -> u32{
+        unsafe{ gst_bit_reader_get_pos(&self.reader) }
+    }
+
+    pub fn set_pos(&mut self, pos: u32) -> bool{
+        unsafe{ gst_bit_reader_set_pos(&mut self.reader, pos) != 0 }
+    }
+
+    /// Remaining bits
+    pub fn remaining(&self) -> u32{
+        unsafe{ gst_bit_reader_get_remaining(&self.reader) }
+    }
+
+    pub fn skip(&mut self, nbits: u32) -> bool{
+        unsafe{ gst_bit_reader_skip(&mut self.reader, nbits) != 0 }
+    }
+
+    /// Skips to the next byte boundary
+    pub fn skip_to_byte(&mut self) -> bool{
+        unsafe{ gst_bit_reader_skip_to_byte(&mut self.reader) != 0 }
+    }
+
+    bit_reader_get!(get_bits_u8, u8, gst_bit_reader_get_bits_uint8);
+    bit_reader_get!(get_bits_u16, u16, gst_bit_reader_get_bits_uint16);
+    bit_reader_get!(get_bits_u32, u32, gst_bit_reader_get_bits_uint32);
+    bit_reader_get!(get_bits_u64, u64, gst_bit_reader_get_bits_uint64);
+
+    pub fn peek_bits_u32(&self, nbits: u32) -> Option<u32>{
+        unsafe{
+            let mut val = 0;
+            if gst_bit_reader_peek_bits_uint32(&self.reader, &mut val, nbits) != 0{
+                Some(val)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn get_bit(&mut self) -> Option<bool>{
+        self.get_bits_u8(1).map(|bit| bit == 1)
+    }
+
+    /// Reads an unsigned Exp-Golomb code, ue(v) in the H.264 and H.265
+    /// specs. The position isn't restored if the code is truncated
+    pub fn get_ue(&mut self) -> Option<u32>{
+        let mut leading_zeros = 0;
+        while !self.get_bit()?{
+            leading_zeros += 1;
+            if leading_zeros > 31{
+                return None;
+            }
+        }
+        if leading_zeros == 0{
+            return Some(0);
+        }
+        let suffix = self.get_bits_u32(leading_zeros)?;
+        Some(((1u64 << leading_zeros) - 1 + suffix as u64) as u32)
+    }
+
+    /// Reads a signed Exp-Golomb code, se(v) in the H.264 and H.265 specs
+    pub fn get_se(&mut self) -> Option<i32>{
+        let code = self.get_ue()? as i64;
+        if code % 2 == 1{
+            Some(((code + 1) / 2) as i32)
+        }else{
+            Some((-code / 2) as i32)
+        }
+    }
+}
+
+macro_rules! byte_writer_put {
+    ($name:ident, $ty:ty, $ffi:ident) => (
+        pub fn $name(&mut self, val: $ty) -> bool{
+            unsafe{ $ffi(self.writer, val) != 0 }
+        }
+    )
+}
+
+/// Writes integers and byte strings to a growable buffer, or a fixed
+/// size one whose writes fail once it's full
+pub struct ByteWriter{
+    writer: *mut GstByteWriter
+}
+
+unsafe impl Send for ByteWriter {}
+
+impl Drop for ByteWriter{
+    fn drop(&mut self){
+        unsafe{
+            gst_byte_writer_free(self.writer);
+        }
+    }
+}
+
+impl ByteWriter{
+    pub fn new() -> ByteWriter{
+        unsafe{
+            ByteWriter{ writer: gst_byte_writer_new() }
+        }
+    }
+
+    /// Creates a writer with size bytes preallocated, if fixed it can't
+    /// grow beyond them
+    pub fn with_size(size: u32, fixed: bool) -> ByteWriter{
+        unsafe{
+            ByteWriter{ writer: gst_byte_writer_new_with_size(size, fixed as gboolean) }
+        }
+    }
+
+    /// Current position in bytes
+    pub fn pos(&self) -> u32{
+        unsafe{ gst_byte_reader_get_pos(&(*self.writer).parent) }
+    }
+
+    /// Moves the write position, it can't be moved past the written data
+    pub fn set_pos(&mut self, pos: u32) -> bool{
+        unsafe{ gst_byte_reader_set_pos(&mut (*self.writer).parent, pos) != 0 }
+    }
+
+    /// Number of bytes written
+    pub fn size(&self) -> u32{
+        unsafe{ (*self.writer).parent.size }
+    }
+
+    byte_writer_put!(put_u8, u8, gst_byte_writer_put_uint8);
+    byte_writer_put!(put_u16_be, u16, gst_byte_writer_put_uint16_be);
+    byte_writer_put!(put_u16_le, u16, gst_byte_writer_put_uint16_le);
+    byte_writer_put!(put_u24_be, u32, gst_byte_writer_put_uint24_be);
+    byte_writer_put!(put_u24_le, u32, gst_byte_writer_put_uint24_le);
+    byte_writer_put!(put_u32_be, u32, gst_byte_writer_put_uint32_be);
+    byte_writer_put!(put_u32_le, u32, gst_byte_writer_put_uint32_le);
+    byte_writer_put!(put_u64_be, u64, gst_byte_writer_put_uint64_be);
+    byte_writer_put!(put_u64_le, u64, gst_byte_writer_put_uint64_le);
+
+    /// Fails if data is larger than u32::MAX bytes
+    pub fn put_data(&mut self, data: &[u8]) -> bool{
+        match u32::try_from(data.len()){
+            Ok(size) => unsafe{ gst_byte_writer_put_data(self.writer, data.as_ptr(), size) != 0 },
+            Err(_) => false
+        }
+    }
+
+    /// Writes size bytes with the given value
+    pub fn fill(&mut self, value: u8, size: u32) -> bool{
+        unsafe{ gst_byte_writer_fill(self.writer, value, size) != 0 }
+    }
+
+    /// The data written so far
+    pub fn data(&self) -> &[u8]{
+        unsafe{
+            let parent = &(*self.writer).parent;
+            if parent.data != ptr::null(){
+                slice::from_raw_parts(parent.data, parent.size as usize)
+            }else{
+                &[]
+            }
+        }
+    }
+
+    pub fn into_vec(self) -> Vec<u8>{
+        unsafe{
+            let size = self.size() as usize;
+            let data = gst_byte_writer_reset_and_get_data(self.writer);
+            if data != ptr::null_mut(){
+                let vec = slice::from_raw_parts(data, size).to_vec();
+                g_free(data as gpointer);
+                vec
+            }else{
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn exp_golomb(){
+        // ue: 1 -> 0, 010 -> 1, 011 -> 2, 00100 -> 3, 00111 -> 6
+        let data = [0b1010_0110, 0b0100_0011, 0b1000_0000];
+        let mut reader = BitReader::new(&data).unwrap();
+        assert_eq!(reader.get_ue(), Some(0));
+        assert_eq!(reader.get_ue(), Some(1));
+        assert_eq!(reader.get_ue(), Some(2));
+        assert_eq!(reader.get_ue(), Some(3));
+        assert_eq!(reader.get_ue(), Some(6));
+        assert_eq!(reader.pos(), 17);
+    }
+
+    #[test]
+    fn signed_exp_golomb(){
+        // se: 1 -> 0, 010 -> 1, 011 -> -1, 00100 -> 2, 00101 -> -2
+        let data = [0b1010_0110, 0b0100_0010, 0b1000_0000];
+        let mut reader = BitReader::new(&data).unwrap();
+        assert_eq!(reader.get_se(), Some(0));
+        assert_eq!(reader.get_se(), Some(1));
+        assert_eq!(reader.get_se(), Some(-1));
+        assert_eq!(reader.get_se(), Some(2));
+        assert_eq!(reader.get_se(), Some(-2));
+    }
+
+    #[test]
+    fn exp_golomb_limits(){
+        // 31 leading zeros and a suffix of ones is the largest code
+        let data = [0, 0, 0, 1, 0xff, 0xff, 0xff, 0xff];
+        let mut reader = BitReader::new(&data).unwrap();
+        assert_eq!(reader.get_ue(), Some(u32::max_value() - 1));
+
+        // more leading zeros than a u32 can hold
+        let data = [0, 0, 0, 0, 0xff];
+        assert_eq!(BitReader::new(&data).unwrap().get_ue(), None);
+
+        // the suffix is cut by the end of the data
+        let data = [0b0000_0010];
+        assert_eq!(BitReader::new(&data).unwrap().get_ue(), None);
+    }
+}
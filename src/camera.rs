@@ -0,0 +1,142 @@
+use ffi::*;
+use bin::{Bin, BinT};
+use element::{Element, ElementT};
+use element_factory::ElementFactory;
+use device::{Device, DeviceMonitor};
+use pipeline::Pipeline;
+use appsink::AppSink;
+use value::Value;
+
+#[cfg(target_os="linux")]
+const CAMERA_SOURCES: &'static [&'static str] = &["v4l2src"];
+#[cfg(target_os="macos")]
+const CAMERA_SOURCES: &'static [&'static str] = &["avfvideosrc"];
+#[cfg(target_os="windows")]
+const CAMERA_SOURCES: &'static [&'static str] = &["mfvideosrc", "ksvideosrc"];
+#[cfg(not(any(target_os="linux", target_os="macos", target_os="windows")))]
+const CAMERA_SOURCES: &'static [&'static str] = &[];
+
+/// A camera as a bin with a single raw video src pad.
+///
+/// It wraps the capture element of the platform, v4l2src, avfvideosrc,
+/// mfvideosrc or ksvideosrc, followed by a capsfilter to request a
+/// resolution and framerate and a videoconvert. It can be added to a
+/// pipeline and linked like any other element or turned into a pipeline
+/// ending in an appsink with into_appsink_pipeline.
+pub struct CameraSource{
+    bin: Bin,
+    capsfilter: Element,
+    resolution: Option<(i32,i32)>,
+    framerate: Option<(i32,i32)>,
+}
+
+unsafe impl Sync for CameraSource {}
+unsafe impl Send for CameraSource {}
+
+impl CameraSource{
+    /// Returns the cameras available in the system
+    pub fn devices() -> Vec<Device>{
+        DeviceMonitor::new_with_filter("Video/Source").devices()
+    }
+
+    /// Name of the capture element used for the default camera on this
+    /// platform, falls back to autovideosrc if none is installed
+    pub fn source_factory() -> &'static str{
+        CAMERA_SOURCES.iter()
+            .find(|factory| ElementFactory::find(factory).is_some())
+            .map(|factory| *factory)
+            .unwrap_or("autovideosrc")
+    }
+
+    /// Creates a camera source for the default camera
+    pub fn new(name: &str) -> Option<CameraSource>{
+        Element::new(CameraSource::source_factory(), "").and_then(|source| CameraSource::new_from_source(name, source))
+    }
+
+    /// Creates a camera source for one of the devices returned by
+    /// devices()
+    pub fn new_from_device(name: &str, device: &Device) -> Option<CameraSource>{
+        device.create_element("").and_then(|source| CameraSource::new_from_source(name, source))
+    }
+
+    fn new_from_source(name: &str, source: Element) -> Option<CameraSource>{
+        let mut bin = Bin::new(name)?;
+        let capsfilter = Element::new("capsfilter", "")?;
+        let convert = Element::new("videoconvert", "")?;
+        let mut source_ref = source.to_element();
+        let mut capsfilter_ref = capsfilter.to_element();
+        let mut convert_ref = convert.to_element();
+        if !bin.add(source) || !bin.add(capsfilter) || !bin.add(convert){
+            return None;
+        }
+        if !source_ref.link(&mut capsfilter_ref) || !capsfilter_ref.link(&mut convert_ref){
+            return None;
+        }
+        let src = convert_ref.static_pad("src")?;
+        if !bin.add_ghost_pad("src", &src){
+            return None;
+        }
+        Some(CameraSource{
+            bin: bin,
+            capsfilter: capsfilter_ref,
+            resolution: None,
+            framerate: None,
+        })
+    }
+
+    /// Requests a capture resolution, the camera has to support it or
+    /// the pipeline will fail to negotiate
+    pub fn set_resolution(&mut self, width: i32, height: i32) -> bool{
+        self.resolution = Some((width, height));
+        self.update_caps()
+    }
+
+    /// Requests a capture framerate as a fraction, e.g. 30/1
+    pub fn set_framerate(&mut self, numerator: i32, denominator: i32) -> bool{
+        self.framerate = Some((numerator, denominator));
+        self.update_caps()
+    }
+
+    fn update_caps(&mut self) -> bool{
+        let mut caps = "video/x-raw".to_string();
+        if let Some((width, height)) = self.resolution{
+            caps += &format!(",width={},height={}", width, height);
+        }
+        if let Some((numerator, denominator)) = self.framerate{
+            caps += &format!(",framerate={}/{}", numerator, denominator);
+        }
+        self.capsfilter.set_property("caps", &Value::String(caps)).is_ok()
+    }
+
+    /// Creates a pipeline with this camera linked to an appsink, so the
+    /// application can receive the frames
+    pub fn into_appsink_pipeline(self, name: &str) -> Option<(Pipeline, AppSink)>{
+        let mut pipeline = Pipeline::new(name)?;
+        let appsink = AppSink::new("")?;
+        let mut camera = self.to_element();
+        let mut sink = appsink.to_element();
+        if !pipeline.add(self) || !pipeline.add(appsink.to_element()){
+            return None;
+        }
+        if !camera.link(&mut sink){
+            return None;
+        }
+        Some((pipeline, appsink))
+    }
+}
+
+impl BinT for CameraSource{
+    fn as_bin(&self) -> &Bin{
+        &self.bin
+    }
+
+    fn as_bin_mut(&mut self) -> &mut Bin{
+        &mut self.bin
+    }
+}
+
+impl ::Transfer for CameraSource{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.bin.transfer()
+    }
+}
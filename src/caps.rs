@@ -1,7 +1,14 @@
 use ffi::*;
-use std::mem;
-use std::ptr;
-use std::ffi::CString;
+use util::*;
+use debug::{track_new, track_release};
+use structure::Structure;
+use ::Transfer;
+use std::fmt::{self, Debug, Display, Formatter};
+
+#[cfg(feature="serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+#[cfg(feature="serde")]
+use serde::de::Error as SerdeError;
 
 pub struct Caps{
 	caps: *mut GstCaps
@@ -10,6 +17,7 @@ pub struct Caps{
 impl Drop for Caps{
 	fn drop(&mut self){
         unsafe{
+			track_release(self.caps);
 			gst_mini_object_unref(self.caps as *mut GstMiniObject);
 		}
 	}
@@ -19,6 +27,7 @@ impl Caps{
 	pub unsafe fn new(caps: *mut GstCaps, owned: bool) -> Option<Caps>{
 	    if caps != ptr::null_mut(){
 			if !owned {gst_mini_object_ref(caps as *mut GstMiniObject);}
+			track_new("Caps", caps, true);
 			Some(Caps{caps: caps})
 		}else{
 		    None
@@ -31,6 +40,109 @@ impl Caps{
 	    }
 	}
 	
+	/// Creates new caps that don't match any media format
+	pub fn new_empty() -> Caps{
+	    unsafe{
+	    	let caps = gst_caps_new_empty();
+	    	track_new("Caps", caps, true);
+	    	Caps{ caps: caps }
+	    }
+	}
+
+	/// Creates new caps that match any media format
+	pub fn new_any() -> Caps{
+	    unsafe{
+	    	let caps = gst_caps_new_any();
+	    	track_new("Caps", caps, true);
+	    	Caps{ caps: caps }
+	    }
+	}
+
+	pub fn is_any(&self) -> bool{
+		unsafe{
+			gst_caps_is_any(self.caps) == 1
+		}
+	}
+
+	pub fn is_empty(&self) -> bool{
+		unsafe{
+			gst_caps_is_empty(self.caps) == 1
+		}
+	}
+
+	/// Checks if both caps have some format in common
+	pub fn can_intersect(&self, other: &Caps) -> bool{
+		unsafe{
+			gst_caps_can_intersect(self.caps, other.caps) == 1
+		}
+	}
+
+	/// Returns the number of structures in the caps
+	pub fn size(&self) -> u32{
+		unsafe{
+			gst_caps_get_size(self.caps)
+		}
+	}
+
+	/// Returns a copy of the structure at index or None if index is out
+	/// of bounds
+	pub fn structure(&self, index: u32) -> Option<Structure>{
+		unsafe{
+			if index < self.size(){
+				Structure::new_from_gst_structure(gst_structure_copy(gst_caps_get_structure(self.caps, index)))
+			}else{
+				None
+			}
+		}
+	}
+
+	/// Appends structure to the caps, the caps are made writable first
+	pub fn append_structure(&mut self, structure: Structure){
+		unsafe{
+			self.caps = gst_mini_object_make_writable(self.caps as *mut GstMiniObject) as *mut GstCaps;
+			gst_caps_append_structure(self.caps, structure.transfer());
+		}
+	}
+
+	/// Returns the caps features of the structure at index, as in
+	/// "memory:GLMemory", empty for system memory
+	pub fn features(&self, index: u32) -> Vec<String>{
+		unsafe{
+			if index >= self.size(){
+				return Vec::new();
+			}
+			let features = gst_caps_get_features(self.caps, index);
+			if features == ptr::null_mut(){
+				return Vec::new();
+			}
+			(0..gst_caps_features_get_size(features))
+				.map(|i| from_c_str!(gst_caps_features_get_nth(features, i)).to_string())
+				.collect()
+		}
+	}
+
+	/// Checks if any of the structures of the caps has the feature
+	pub fn has_feature(&self, feature: &str) -> bool{
+		(0..self.size()).any(|i| self.features(i).iter().any(|f| f == feature))
+	}
+
+	/// Sets field to value in all the structures of the caps, the caps
+	/// are made writable first. Returns false if the value couldn't be
+	/// converted
+	pub fn set_field<V: Into<::Value>>(&mut self, field: &str, value: V) -> bool{
+		unsafe{
+			match value.into().to_gvalue(){
+				Some(mut gvalue) => {
+					self.caps = gst_mini_object_make_writable(self.caps as *mut GstMiniObject) as *mut GstCaps;
+					gst_caps_set_value(self.caps, to_c_str!(field), &gvalue);
+					g_value_unset(&mut gvalue);
+					true
+				}
+				None => false
+			}
+		}
+	}
+
 	pub fn video_info(&self) -> Option<::VideoInfo>{
 		unsafe{
 			let videoinfo = ::VideoInfo::new();
@@ -52,10 +164,62 @@ impl Caps{
 }
 
 
+/// Formats the caps in the same format accepted by from_string
+impl Display for Caps{
+	fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+		unsafe{
+			let c_str = gst_caps_to_string(self.caps);
+			let ret = fmt.write_str(from_c_str!(c_str));
+			g_free(c_str as gpointer);
+			ret
+		}
+	}
+}
+
+impl Debug for Caps{
+	fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+		write!(fmt, "Caps({})", self)
+	}
+}
+
+impl Clone for Caps{
+	fn clone(&self) -> Caps{
+		unsafe{
+			gst_mini_object_ref(self.caps as *mut GstMiniObject);
+			track_new("Caps", self.caps, true);
+			Caps{ caps: self.caps }
+		}
+	}
+}
+
+impl PartialEq for Caps{
+	fn eq(&self, other: &Caps) -> bool{
+		unsafe{
+			gst_caps_is_equal(self.caps, other.caps) == 1
+		}
+	}
+}
+
 impl ::Transfer<GstCaps> for Caps{
     unsafe fn transfer(self) ->  *mut GstCaps{
         let caps = self.caps;
+        track_release(caps);
 		mem::forget(self);
         caps
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature="serde")]
+impl Serialize for Caps{
+	fn serialize<S: Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>{
+		serializer.serialize_str(&self.to_string())
+	}
+}
+
+#[cfg(feature="serde")]
+impl<'de> Deserialize<'de> for Caps{
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> ::std::result::Result<Caps, D::Error>{
+		let desc = String::deserialize(deserializer)?;
+		Caps::from_string(&desc).ok_or_else(|| D::Error::custom(format!("invalid caps {}", desc)))
+	}
+}
@@ -0,0 +1,221 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use caps::Caps;
+use element::{Element, ElementT};
+use meta::MetaApi;
+use pad::Pad;
+
+use std::os::raw::c_uint;
+use std::slice;
+
+// GstVideoCaptionMeta was added in GStreamer 1.16, after ffi.rs was
+// generated
+pub type GstVideoCaptionType = c_uint;
+pub const GST_VIDEO_CAPTION_TYPE_UNKNOWN: GstVideoCaptionType = 0;
+pub const GST_VIDEO_CAPTION_TYPE_CEA608_RAW: GstVideoCaptionType = 1;
+pub const GST_VIDEO_CAPTION_TYPE_CEA608_S334_1A: GstVideoCaptionType = 2;
+pub const GST_VIDEO_CAPTION_TYPE_CEA708_RAW: GstVideoCaptionType = 3;
+pub const GST_VIDEO_CAPTION_TYPE_CEA708_CDP: GstVideoCaptionType = 4;
+
+#[repr(C)]
+pub struct GstVideoCaptionMeta{
+    pub meta: GstMeta,
+    pub caption_type: GstVideoCaptionType,
+    pub data: *mut guint8,
+    pub size: gsize,
+}
+
+extern "C"{
+    fn gst_video_caption_meta_api_get_type() -> GType;
+    fn gst_buffer_add_video_caption_meta(buffer: *mut GstBuffer, caption_type: GstVideoCaptionType,
+        data: *const guint8, size: gsize) -> *mut GstVideoCaptionMeta;
+}
+
+unsafe impl MetaApi for GstVideoCaptionMeta{
+    fn api_type() -> GType{
+        unsafe{ gst_video_caption_meta_api_get_type() }
+    }
+}
+
+/// Caps of the closed caption formats, in the same order as CaptionType
+pub const CEA608_RAW_CAPS: &'static str = "closedcaption/x-cea-608,format=raw";
+pub const CEA608_S334_1A_CAPS: &'static str = "closedcaption/x-cea-608,format=s334-1a";
+pub const CEA708_RAW_CAPS: &'static str = "closedcaption/x-cea-708,format=cc_data";
+pub const CEA708_CDP_CAPS: &'static str = "closedcaption/x-cea-708,format=cdp";
+
+/// Format of closed caption data
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum CaptionType{
+    /// CEA-608 byte pairs for field 1
+    Cea608Raw,
+    /// CEA-608 byte triplets as in SMPTE S334-1 Annex A, with the field
+    /// in the first byte
+    Cea608S334_1A,
+    /// CEA-708 cc_data triplets
+    Cea708Raw,
+    /// CEA-708 caption distribution packets as in SMPTE S334-2
+    Cea708Cdp,
+}
+
+impl CaptionType{
+    pub fn from_gst(caption_type: GstVideoCaptionType) -> Option<CaptionType>{
+        match caption_type{
+            GST_VIDEO_CAPTION_TYPE_CEA608_RAW => Some(CaptionType::Cea608Raw),
+            GST_VIDEO_CAPTION_TYPE_CEA608_S334_1A => Some(CaptionType::Cea608S334_1A),
+            GST_VIDEO_CAPTION_TYPE_CEA708_RAW => Some(CaptionType::Cea708Raw),
+            GST_VIDEO_CAPTION_TYPE_CEA708_CDP => Some(CaptionType::Cea708Cdp),
+            _ => None
+        }
+    }
+
+    pub fn to_gst(&self) -> GstVideoCaptionType{
+        match *self{
+            CaptionType::Cea608Raw => GST_VIDEO_CAPTION_TYPE_CEA608_RAW,
+            CaptionType::Cea608S334_1A => GST_VIDEO_CAPTION_TYPE_CEA608_S334_1A,
+            CaptionType::Cea708Raw => GST_VIDEO_CAPTION_TYPE_CEA708_RAW,
+            CaptionType::Cea708Cdp => GST_VIDEO_CAPTION_TYPE_CEA708_CDP,
+        }
+    }
+
+    /// The caps string of a stream of this caption type
+    pub fn caps_str(&self) -> &'static str{
+        match *self{
+            CaptionType::Cea608Raw => CEA608_RAW_CAPS,
+            CaptionType::Cea608S334_1A => CEA608_S334_1A_CAPS,
+            CaptionType::Cea708Raw => CEA708_RAW_CAPS,
+            CaptionType::Cea708Cdp => CEA708_CDP_CAPS,
+        }
+    }
+
+    pub fn caps(&self) -> Option<Caps>{
+        Caps::from_string(self.caps_str())
+    }
+
+    /// Returns the caption type of a stream with these caps
+    pub fn from_caps(caps: &Caps) -> Option<CaptionType>{
+        [CaptionType::Cea608Raw, CaptionType::Cea608S334_1A, CaptionType::Cea708Raw, CaptionType::Cea708Cdp].iter()
+            .find(|caption_type| caption_type.caps().map(|c| caps.can_intersect(&c)).unwrap_or(false))
+            .cloned()
+    }
+}
+
+/// Closed caption data carried by a video buffer
+#[derive(Clone,Debug,PartialEq)]
+pub struct VideoCaption{
+    pub caption_type: CaptionType,
+    pub data: Vec<u8>,
+}
+
+impl Buffer{
+    /// Returns the closed captions attached to this video buffer, as done
+    /// by decoders and demuxers for captions embedded in the video
+    pub fn captions(&self) -> Vec<VideoCaption>{
+        self.metas_of::<GstVideoCaptionMeta>().into_iter()
+            .filter_map(|meta| CaptionType::from_gst(meta.caption_type).map(|caption_type| VideoCaption{
+                caption_type: caption_type,
+                data: unsafe{ slice::from_raw_parts(meta.data, meta.size as usize).to_vec() },
+            }))
+            .collect()
+    }
+
+    /// Attaches closed caption data to this video buffer so encoders and
+    /// muxers supporting it embed it in the output
+    pub fn add_caption(&mut self, caption_type: CaptionType, data: &[u8]){
+        unsafe{
+            gst_buffer_add_video_caption_meta(self.gst_buffer_mut(), caption_type.to_gst(),
+                data.as_ptr(), data.len() as gsize);
+        }
+    }
+}
+
+/// Wrapper around ccextractor, which passes the video through and outputs
+/// the captions found in it on its "caption" pad
+pub struct CcExtractor{
+    ccextractor: Element
+}
+
+unsafe impl Sync for CcExtractor {}
+unsafe impl Send for CcExtractor {}
+
+impl CcExtractor{
+    pub fn new(name: &str) -> Option<CcExtractor>{
+        Element::new("ccextractor", name).map(|ccextractor| CcExtractor{ ccextractor: ccextractor })
+    }
+
+    pub fn new_from_element(element: Element) -> CcExtractor{
+        CcExtractor{ ccextractor: element }
+    }
+
+    /// Removes the captions from the video once extracted
+    pub fn set_remove_caption_meta(&self, remove: bool){
+        self.ccextractor.set("remove-caption-meta", remove as gboolean);
+    }
+
+    /// The src pad with the captions, it's only created once the first
+    /// caption is found in the video
+    pub fn caption_pad(&self) -> Option<Pad>{
+        self.ccextractor.static_pad("caption")
+    }
+}
+
+/// Wrapper around cccombiner, which attaches the captions received on
+/// its "caption" pad to the video received on its "sink" pad
+pub struct CcCombiner{
+    cccombiner: Element
+}
+
+unsafe impl Sync for CcCombiner {}
+unsafe impl Send for CcCombiner {}
+
+impl CcCombiner{
+    pub fn new(name: &str) -> Option<CcCombiner>{
+        Element::new("cccombiner", name).map(|cccombiner| CcCombiner{ cccombiner: cccombiner })
+    }
+
+    pub fn new_from_element(element: Element) -> CcCombiner{
+        CcCombiner{ cccombiner: element }
+    }
+
+    /// Links the src pad of an element producing captions, like a
+    /// ccconverter or an appsrc, to the caption pad. Both elements have to
+    /// be in the same bin
+    pub fn link_captions(&mut self, source: &mut ElementT) -> bool{
+        unsafe{
+            gst_element_link_pads(source.gst_element_mut(), ptr::null(),
+                self.cccombiner.gst_element_mut(), to_c_str!("caption")) == 1
+        }
+    }
+}
+
+impl ElementT for CcExtractor{
+    fn as_element(&self) -> &Element{
+        &self.ccextractor
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.ccextractor
+    }
+}
+
+impl ElementT for CcCombiner{
+    fn as_element(&self) -> &Element{
+        &self.cccombiner
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.cccombiner
+    }
+}
+
+impl ::Transfer for CcExtractor{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.ccextractor.transfer()
+    }
+}
+
+impl ::Transfer for CcCombiner{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.cccombiner.transfer()
+    }
+}
@@ -0,0 +1,166 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+use pad::Pad;
+
+/// Wrapper around compositor or glvideomixer, which mix several video
+/// streams into one, placing each input at a position, size, transparency
+/// and depth.
+///
+/// Inputs are request pads, created with add_input or request_input and
+/// released with remove_input, which can be done while the pipeline is
+/// running to build picture-in-picture or grid layouts.
+pub struct Compositor{
+    compositor: Element
+}
+
+unsafe impl Sync for Compositor {}
+unsafe impl Send for Compositor {}
+
+/// An input of a Compositor, the properties can be changed at any time
+/// and take effect on the next output frame
+pub struct CompositorInput{
+    pad: Pad
+}
+
+unsafe impl Sync for CompositorInput {}
+unsafe impl Send for CompositorInput {}
+
+impl Compositor{
+    /// Creates a compositor mixing in software
+    pub fn new(name: &str) -> Option<Compositor>{
+        Element::new("compositor", name).map(|compositor| Compositor{ compositor: compositor })
+    }
+
+    /// Creates a compositor mixing in the GPU with glvideomixer
+    pub fn new_gl(name: &str) -> Option<Compositor>{
+        Element::new("glvideomixer", name).map(|compositor| Compositor{ compositor: compositor })
+    }
+
+    pub fn new_from_element(element: Element) -> Compositor{
+        Compositor{ compositor: element }
+    }
+
+    /// Requests a new input pad without linking it
+    pub fn request_input(&mut self) -> Option<CompositorInput>{
+        unsafe{
+            let pad = gst_element_get_request_pad(self.compositor.gst_element_mut(), to_c_str!("sink_%u"));
+            Pad::new(pad, true).map(|pad| CompositorInput{ pad: pad })
+        }
+    }
+
+    /// Requests a new input and links the src pad of source to it, both
+    /// elements have to be in the same bin
+    pub fn add_input(&mut self, source: &ElementT) -> Option<CompositorInput>{
+        let mut src = source.static_pad("src")?;
+        let mut input = self.request_input()?;
+        if src.link(&mut input.pad) == GST_PAD_LINK_OK{
+            Some(input)
+        }else{
+            self.remove_input(input);
+            None
+        }
+    }
+
+    /// Unlinks and releases the input. Stopping and removing the element
+    /// that fed it is up to the application
+    pub fn remove_input(&mut self, mut input: CompositorInput){
+        if let Some(mut peer) = input.pad.peer(){
+            peer.unlink(&mut input.pad);
+        }
+        unsafe{
+            gst_element_release_request_pad(self.compositor.gst_element_mut(), input.pad.gst_pad_mut());
+        }
+    }
+
+    /// Places the inputs in a grid with the given number of columns,
+    /// in order, scaling each of them to the cell size
+    pub fn set_grid_layout(&self, inputs: &[CompositorInput], columns: u32, cell_width: i32, cell_height: i32){
+        let columns = columns.max(1);
+        for (i, input) in inputs.iter().enumerate(){
+            let i = i as u32;
+            input.set_position((i % columns) as i32 * cell_width, (i / columns) as i32 * cell_height);
+            input.set_size(cell_width, cell_height);
+        }
+    }
+
+    /// Sets the background shown where no input covers the output, one
+    /// of "checker", "black", "white" or "transparent"
+    pub fn set_background(&mut self, background: &str) -> bool{
+        self.compositor.set_property("background", &background.into()).is_ok()
+    }
+}
+
+impl CompositorInput{
+    /// Position of the top left corner of the input in the output frame
+    pub fn set_position(&self, xpos: i32, ypos: i32){
+        self.set("xpos", xpos);
+        self.set("ypos", ypos);
+    }
+
+    pub fn position(&self) -> (i32, i32){
+        (self.get("xpos"), self.get("ypos"))
+    }
+
+    /// Size the input is scaled to, 0 keeps the size of the input
+    pub fn set_size(&self, width: i32, height: i32){
+        self.set("width", width);
+        self.set("height", height);
+    }
+
+    pub fn size(&self) -> (i32, i32){
+        (self.get("width"), self.get("height"))
+    }
+
+    /// Opacity of the input, from 0.0 (transparent) to 1.0
+    pub fn set_alpha(&self, alpha: f64){
+        self.set("alpha", alpha);
+    }
+
+    pub fn alpha(&self) -> f64{
+        self.get("alpha")
+    }
+
+    /// Inputs with a higher zorder are drawn on top
+    pub fn set_zorder(&self, zorder: u32){
+        self.set("zorder", zorder);
+    }
+
+    pub fn zorder(&self) -> u32{
+        self.get("zorder")
+    }
+
+    pub fn pad(&self) -> &Pad{
+        &self.pad
+    }
+
+    fn set<T>(&self, name: &str, value: T){
+        unsafe{
+            g_object_set(self.pad.gst_pad() as gpointer, to_c_str!(name), value, ptr::null::<gchar>());
+        }
+    }
+
+    fn get<T: Default>(&self, name: &str) -> T{
+        unsafe{
+            let mut value = T::default();
+            g_object_get(self.pad.gst_pad() as gpointer, to_c_str!(name), &mut value as *mut T, ptr::null::<gchar>());
+            value
+        }
+    }
+}
+
+impl ElementT for Compositor{
+    fn as_element(&self) -> &Element{
+        &self.compositor
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.compositor
+    }
+}
+
+impl ::Transfer for Compositor{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.compositor.transfer()
+    }
+}
@@ -0,0 +1,135 @@
+use ffi::*;
+use util::*;
+use structure::Structure;
+use value::Value;
+
+use std::fmt::{self, Debug, Formatter};
+
+const G_TYPE_OBJECT: GType = 20 << 2;
+
+/// Shares resources like a GL display, a Vulkan device or an HTTP
+/// session between elements, and between the application and elements.
+///
+/// Elements ask for a context with a NEED_CONTEXT message, which can be
+/// answered with ElementT::set_context, and announce the context they
+/// created with a HAVE_CONTEXT message.
+pub struct Context{
+    context: *mut GstContext
+}
+
+unsafe impl Send for Context {}
+
+impl Drop for Context{
+    fn drop(&mut self){
+        unsafe{
+            gst_mini_object_unref(self.context as *mut GstMiniObject);
+        }
+    }
+}
+
+impl Clone for Context{
+    fn clone(&self) -> Context{
+        unsafe{
+            gst_mini_object_ref(self.context as *mut GstMiniObject);
+            Context{ context: self.context }
+        }
+    }
+}
+
+impl Context{
+    /// Creates an empty context of the given type, persistent contexts
+    /// are kept by elements when going to NULL
+    pub fn new(context_type: &str, persistent: bool) -> Option<Context>{
+        unsafe{
+            Context::new_from_gst_context(gst_context_new(to_c_str!(context_type), persistent as gboolean), true)
+        }
+    }
+
+    /// Wraps a raw GstContext, if owned is false a new reference is taken
+    pub unsafe fn new_from_gst_context(context: *mut GstContext, owned: bool) -> Option<Context>{
+        if context != ptr::null_mut(){
+            if !owned{
+                gst_mini_object_ref(context as *mut GstMiniObject);
+            }
+            Some(Context{ context: context })
+        }else{
+            None
+        }
+    }
+
+    pub fn context_type(&self) -> String{
+        unsafe{
+            from_c_str!(gst_context_get_context_type(self.context)).to_string()
+        }
+    }
+
+    pub fn has_context_type(&self, context_type: &str) -> bool{
+        unsafe{
+            gst_context_has_context_type(self.context, to_c_str!(context_type)) == 1
+        }
+    }
+
+    pub fn is_persistent(&self) -> bool{
+        unsafe{
+            gst_context_is_persistent(self.context) == 1
+        }
+    }
+
+    /// Returns a copy of the fields of the context
+    pub fn structure(&self) -> Option<Structure>{
+        unsafe{
+            Structure::new_from_gst_structure(gst_structure_copy(gst_context_get_structure(self.context)))
+        }
+    }
+
+    /// Sets a field of the context, the context is copied if it's shared
+    pub fn set<V: Into<Value>>(&mut self, field: &str, value: V) -> bool{
+        unsafe{
+            match value.into().to_gvalue(){
+                Some(mut gvalue) => {
+                    self.context = gst_mini_object_make_writable(self.context as *mut GstMiniObject) as *mut GstContext;
+                    gst_structure_take_value(gst_context_writable_structure(self.context), to_c_str!(field), &mut gvalue);
+                    true
+                }
+                None => false
+            }
+        }
+    }
+
+    /// Returns a field holding an object, like the GstGLDisplay or
+    /// GstVulkanDevice stored by elements, without taking a reference
+    pub unsafe fn object_field(&self, field: &str) -> *mut GObject{
+        let value = gst_structure_get_value(gst_context_get_structure(self.context), to_c_str!(field));
+        if value != ptr::null() && g_type_check_value_holds(value as *mut GValue, G_TYPE_OBJECT) != 0{
+            g_value_get_object(value) as *mut GObject
+        }else{
+            ptr::null_mut()
+        }
+    }
+
+    pub unsafe fn gst_context(&self) -> *const GstContext{
+        self.context
+    }
+
+    pub unsafe fn gst_context_mut(&mut self) -> *mut GstContext{
+        self.context
+    }
+}
+
+impl ::Transfer<GstContext> for Context{
+    unsafe fn transfer(self) -> *mut GstContext{
+        let context = self.context;
+        mem::forget(self);
+        context
+    }
+}
+
+impl Debug for Context{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "Context {{ type: \"{}\"", self.context_type())?;
+        if let Some(structure) = self.structure(){
+            write!(fmt, ", structure: {}", structure)?;
+        }
+        write!(fmt, " }}")
+    }
+}
@@ -0,0 +1,220 @@
+//! CUDA and NVMM memory access for NVIDIA pipelines, needs the `cuda`
+//! feature.
+//!
+//! The nvcodec decoders (nvh264dec, nvh265dec...) and cudaupload can
+//! output video in CUDA device memory, negotiated with
+//! `memory:CUDAMemory` caps. Buffers received in an appsink with those
+//! caps can be mapped with Buffer::map_cuda_read to get the device
+//! pointer of every plane and passed to a CUDA kernel without copying
+//! the frame to system memory. Setting the context returned by
+//! CudaContext::context on the pipeline makes the elements use the
+//! application CUcontext, so the pointers are valid in it.
+//!
+//! On Jetson and DeepStream the nvv4l2 elements use `memory:NVMM` caps
+//! instead, mapping those buffers gives the NvBufSurface of the frame.
+
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use caps::Caps;
+use context::Context;
+use videoinfo::VideoInfo;
+
+use std::os::raw::c_void;
+
+/// Caps feature of video in CUDA device memory, as in
+/// `video/x-raw(memory:CUDAMemory),format=NV12`
+pub const CAPS_FEATURE_MEMORY_CUDA_MEMORY: &'static str = "memory:CUDAMemory";
+
+/// Caps feature of the NVIDIA Jetson and DeepStream elements, buffers
+/// map to an NvBufSurface
+pub const CAPS_FEATURE_MEMORY_NVMM: &'static str = "memory:NVMM";
+
+/// Context type used by the nvcodec elements to share the CUDA context
+pub const CUDA_CONTEXT_TYPE: &'static str = "gst.cuda.context";
+
+/// Map flag making gst_buffer_map return the CUdeviceptr of CUDA
+/// memory instead of a copy in system memory
+pub const GST_MAP_CUDA: GstMapFlags = GST_MAP_FLAG_LAST << 1;
+
+pub enum GstCudaContext {}
+
+// Head of GstCudaMemory, only the fields needed to find the planes
+#[repr(C)]
+struct GstCudaMemory{
+    mem: GstMemory,
+    context: *mut GstCudaContext,
+    info: GstVideoInfo,
+}
+
+#[link(name = "gstcuda-1.0")]
+extern "C"{
+    fn gst_cuda_context_new(device_id: guint) -> *mut GstCudaContext;
+    fn gst_cuda_context_new_wrapped(handler: *mut c_void, device: i32) -> *mut GstCudaContext;
+    fn gst_cuda_context_get_handle(ctx: *mut GstCudaContext) -> *mut c_void;
+    fn gst_context_new_cuda_context(cuda_ctx: *mut GstCudaContext) -> *mut GstContext;
+    fn gst_is_cuda_memory(mem: *mut GstMemory) -> gboolean;
+}
+
+/// Returns caps for video of the given format in CUDA memory
+pub fn cuda_caps(format: &str) -> Option<Caps>{
+    Caps::from_string(&format!("video/x-raw({}),format={}", CAPS_FEATURE_MEMORY_CUDA_MEMORY, format))
+}
+
+/// Returns caps for video of the given format in NVMM memory
+pub fn nvmm_caps(format: &str) -> Option<Caps>{
+    Caps::from_string(&format!("video/x-raw({}),format={}", CAPS_FEATURE_MEMORY_NVMM, format))
+}
+
+/// A CUDA context as used by the nvcodec elements
+pub struct CudaContext{
+    context: *mut GstCudaContext
+}
+
+unsafe impl Sync for CudaContext {}
+unsafe impl Send for CudaContext {}
+
+impl Drop for CudaContext{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.context as *mut c_void);
+        }
+    }
+}
+
+impl Clone for CudaContext{
+    fn clone(&self) -> CudaContext{
+        unsafe{
+            gst_object_ref(self.context as *mut c_void);
+            CudaContext{ context: self.context }
+        }
+    }
+}
+
+impl CudaContext{
+    /// Creates a new CUDA context on the device with the given index
+    pub fn new_for_device(device_id: u32) -> Option<CudaContext>{
+        unsafe{
+            CudaContext::new(gst_cuda_context_new(device_id), true)
+        }
+    }
+
+    /// Wraps a CUcontext created by the application for the CUdevice
+    /// device, the CUcontext has to outlive the returned context
+    pub unsafe fn new_wrapped(handle: *mut c_void, device: i32) -> Option<CudaContext>{
+        CudaContext::new(gst_cuda_context_new_wrapped(handle, device), true)
+    }
+
+    /// Wraps a raw GstCudaContext, if owned is false a new reference is
+    /// taken
+    pub unsafe fn new(context: *mut GstCudaContext, owned: bool) -> Option<CudaContext>{
+        if context != ptr::null_mut(){
+            if !owned{
+                gst_object_ref(context as *mut c_void);
+            }
+            Some(CudaContext{ context: context })
+        }else{
+            None
+        }
+    }
+
+    /// Returns the CUDA context of a gst.cuda.context context, as
+    /// received in a HAVE_CONTEXT message
+    pub fn from_context(context: &Context) -> Option<CudaContext>{
+        if !context.has_context_type(CUDA_CONTEXT_TYPE){
+            return None;
+        }
+        unsafe{
+            CudaContext::new(context.object_field("cuda-context") as *mut GstCudaContext, false)
+        }
+    }
+
+    /// Creates a context to share this CUDA context with the elements
+    pub fn context(&self) -> Option<Context>{
+        unsafe{
+            Context::new_from_gst_context(gst_context_new_cuda_context(self.context), true)
+        }
+    }
+
+    /// The CUcontext
+    pub fn handle(&self) -> *mut c_void{
+        unsafe{
+            gst_cuda_context_get_handle(self.context)
+        }
+    }
+
+    pub unsafe fn gst_cuda_context(&self) -> *const GstCudaContext{
+        self.context
+    }
+
+    pub unsafe fn gst_cuda_context_mut(&mut self) -> *mut GstCudaContext{
+        self.context
+    }
+}
+
+/// A plane of a frame in CUDA device memory
+#[derive(Clone,Copy,Debug)]
+pub struct CudaPlane{
+    /// The CUdeviceptr of the first pixel
+    pub device_ptr: u64,
+    pub stride: i32,
+}
+
+impl Buffer{
+    /// True if the buffer holds CUDA memory
+    pub fn is_cuda_memory(&self) -> bool{
+        unsafe{
+            let buffer = self.gst_buffer() as *mut GstBuffer;
+            if gst_buffer_n_memory(buffer) != 1{
+                return false;
+            }
+            let memory = gst_buffer_peek_memory(buffer, 0);
+            memory != ptr::null_mut() && gst_is_cuda_memory(memory) != 0
+        }
+    }
+
+    /// Maps a buffer with `memory:CUDAMemory` caps and calls f with the
+    /// device pointers of its planes. The pointers are only valid
+    /// during the call and in the CUDA context used by the element
+    /// that produced the buffer
+    pub fn map_cuda_read<F: FnMut(&[CudaPlane]) -> U, U>(&self, f: F) -> Result<U,()>{
+        unsafe{
+            map_cuda(self.gst_buffer() as *mut GstBuffer, GST_MAP_READ | GST_MAP_CUDA, f)
+        }
+    }
+
+    /// Same as map_cuda_read but the kernel can also write to the planes
+    pub fn map_cuda_write<F: FnMut(&[CudaPlane]) -> U, U>(&mut self, f: F) -> Result<U,()>{
+        unsafe{
+            map_cuda(self.gst_buffer_mut(), GST_MAP_READ | GST_MAP_WRITE | GST_MAP_CUDA, f)
+        }
+    }
+
+    /// Maps a buffer with `memory:NVMM` caps and calls f with its
+    /// NvBufSurface, only valid during the call
+    pub fn map_nvmm<F: FnMut(*mut c_void) -> U, U>(&self, mut f: F) -> Result<U,()>{
+        self.map_read(|mapinfo| f(mapinfo.data as *mut c_void))
+    }
+}
+
+unsafe fn map_cuda<F: FnMut(&[CudaPlane]) -> U, U>(buffer: *mut GstBuffer, flags: GstMapFlags, mut f: F) -> Result<U,()>{
+    if gst_buffer_n_memory(buffer) != 1{
+        return Err(());
+    }
+    let memory = gst_buffer_peek_memory(buffer, 0);
+    if memory == ptr::null_mut() || gst_is_cuda_memory(memory) == 0{
+        return Err(());
+    }
+    let mut mapinfo = ::MapInfo::new();
+    if gst_buffer_map(buffer, &mut mapinfo, flags) == 0{
+        return Err(());
+    }
+    let info: &VideoInfo = &(*(memory as *mut GstCudaMemory)).info;
+    let base = mapinfo.data as u64;
+    let planes: Vec<CudaPlane> = (0..info.n_planes() as usize)
+        .map(|p| CudaPlane{ device_ptr: base + info.plane_offset(p), stride: info.plane_stride(p) })
+        .collect();
+    let ret = f(&planes);
+    gst_buffer_unmap(buffer, &mut mapinfo);
+    Ok(ret)
+}
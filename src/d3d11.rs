@@ -0,0 +1,150 @@
+//! Direct3D 11 interop on Windows, needs the `d3d11` feature.
+//!
+//! Pipelines using the d3d11 elements (d3d11h264dec, d3d11convert,
+//! d3d11videosink...) keep decoded video in D3D11 textures. An
+//! application rendering with D3D11 can make the elements use its own
+//! ID3D11Device, by setting the context returned by D3D11Device::context
+//! on the pipeline or in answer to a NEED_CONTEXT message of type
+//! D3D11_DEVICE_CONTEXT_TYPE, and then sample the textures of the buffers
+//! received in an appsink with `memory:D3D11Memory` caps directly.
+
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use caps::Caps;
+use context::Context;
+
+use std::os::raw::c_void;
+
+/// Caps feature of video in D3D11 textures, as in
+/// `video/x-raw(memory:D3D11Memory),format=NV12`
+pub const CAPS_FEATURE_MEMORY_D3D11_MEMORY: &'static str = "memory:D3D11Memory";
+
+/// Context type used by the d3d11 elements to share the device
+pub const D3D11_DEVICE_CONTEXT_TYPE: &'static str = "gst.d3d11.device.handle";
+
+pub enum GstD3D11Device {}
+
+#[link(name = "gstd3d11-1.0")]
+extern "C"{
+    fn gst_d3d11_device_new_wrapped(device: *mut c_void) -> *mut GstD3D11Device;
+    fn gst_d3d11_device_get_device_handle(device: *mut GstD3D11Device) -> *mut c_void;
+    fn gst_d3d11_device_get_device_context_handle(device: *mut GstD3D11Device) -> *mut c_void;
+    fn gst_d3d11_context_new(device: *mut GstD3D11Device) -> *mut GstContext;
+    fn gst_is_d3d11_memory(memory: *mut GstMemory) -> gboolean;
+    fn gst_d3d11_memory_get_resource_handle(memory: *mut GstMemory) -> *mut c_void;
+    fn gst_d3d11_memory_get_subresource_index(memory: *mut GstMemory) -> guint;
+}
+
+/// Returns caps for video of the given format in D3D11 textures
+pub fn d3d11_caps(format: &str) -> Option<Caps>{
+    Caps::from_string(&format!("video/x-raw({}),format={}", CAPS_FEATURE_MEMORY_D3D11_MEMORY, format))
+}
+
+/// A D3D11 device as used by the d3d11 elements
+pub struct D3D11Device{
+    device: *mut GstD3D11Device
+}
+
+unsafe impl Sync for D3D11Device {}
+unsafe impl Send for D3D11Device {}
+
+impl Drop for D3D11Device{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.device as *mut c_void);
+        }
+    }
+}
+
+impl Clone for D3D11Device{
+    fn clone(&self) -> D3D11Device{
+        unsafe{
+            gst_object_ref(self.device as *mut c_void);
+            D3D11Device{ device: self.device }
+        }
+    }
+}
+
+impl D3D11Device{
+    /// Wraps an ID3D11Device created by the application, a reference is
+    /// added to it. The device has to be created with
+    /// D3D11_CREATE_DEVICE_BGRA_SUPPORT and video support for decoding
+    pub unsafe fn new_wrapped(device: *mut c_void) -> Option<D3D11Device>{
+        D3D11Device::new(gst_d3d11_device_new_wrapped(device), true)
+    }
+
+    /// Wraps a raw GstD3D11Device, if owned is false a new reference is
+    /// taken
+    pub unsafe fn new(device: *mut GstD3D11Device, owned: bool) -> Option<D3D11Device>{
+        if device != ptr::null_mut(){
+            if !owned{
+                gst_object_ref(device as *mut c_void);
+            }
+            Some(D3D11Device{ device: device })
+        }else{
+            None
+        }
+    }
+
+    /// Returns the device of a gst.d3d11.device.handle context, as
+    /// received in a HAVE_CONTEXT message
+    pub fn from_context(context: &Context) -> Option<D3D11Device>{
+        if !context.has_context_type(D3D11_DEVICE_CONTEXT_TYPE){
+            return None;
+        }
+        unsafe{
+            D3D11Device::new(context.object_field("device") as *mut GstD3D11Device, false)
+        }
+    }
+
+    /// Creates a context to share this device with the d3d11 elements
+    pub fn context(&self) -> Option<Context>{
+        unsafe{
+            Context::new_from_gst_context(gst_d3d11_context_new(self.device), true)
+        }
+    }
+
+    /// The ID3D11Device, without adding a reference
+    pub fn device_handle(&self) -> *mut c_void{
+        unsafe{
+            gst_d3d11_device_get_device_handle(self.device)
+        }
+    }
+
+    /// The immediate ID3D11DeviceContext, without adding a reference.
+    /// It has to be used with the device lock of the elements held
+    pub fn device_context_handle(&self) -> *mut c_void{
+        unsafe{
+            gst_d3d11_device_get_device_context_handle(self.device)
+        }
+    }
+
+    pub unsafe fn gst_d3d11_device(&self) -> *const GstD3D11Device{
+        self.device
+    }
+
+    pub unsafe fn gst_d3d11_device_mut(&mut self) -> *mut GstD3D11Device{
+        self.device
+    }
+}
+
+impl Buffer{
+    /// Returns the ID3D11Texture2D of a buffer with `memory:D3D11Memory`
+    /// caps and the index of the subresource, the array slice, holding
+    /// the frame. The texture is valid as long as the buffer is
+    pub fn d3d11_texture(&self) -> Option<(*mut c_void, u32)>{
+        unsafe{
+            let buffer = self.gst_buffer() as *mut GstBuffer;
+            if gst_buffer_n_memory(buffer) == 0{
+                return None;
+            }
+            let memory = gst_buffer_peek_memory(buffer, 0);
+            if memory != ptr::null_mut() && gst_is_d3d11_memory(memory) != 0{
+                Some((gst_d3d11_memory_get_resource_handle(memory), gst_d3d11_memory_get_subresource_index(memory)))
+            }else{
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,307 @@
+//! Helpers to find leaked GStreamer objects.
+//!
+//! Object tracking records the Element, Bin, Pad, Buffer and Caps wrappers
+//! created by this crate while it's enabled, so the objects still held by
+//! the application can be listed at any point, as before shutting down a
+//! pipeline that doesn't reach the NULL state:
+//!
+//! ```ignore
+//! gst::debug::set_object_tracking(true);
+//! run_pipeline();
+//! for object in gst::debug::live_objects(){
+//!     println!("{} {:#x} held by {} wrappers, refcount {}",
+//!         object.kind, object.ptr, object.wrappers, object.refcount);
+//! }
+//! ```
+//!
+//! The leaks tracer in GStreamer's coretracers plugin finds every object
+//! alive, including the ones created by elements, LeaksTracer wraps the
+//! signals it uses to report them.
+
+use ffi::*;
+use util::*;
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static TRACKING: AtomicBool = AtomicBool::new(false);
+static LIVE: Mutex<BTreeMap<usize, Tracked>> = Mutex::new(BTreeMap::new());
+
+struct Tracked{
+    kind: &'static str,
+    mini_object: bool,
+    wrappers: usize,
+}
+
+/// An object held by wrappers of this crate, as returned by live_objects
+#[derive(Clone,Debug,PartialEq)]
+pub struct LiveObject{
+    /// The wrapper type, as "Element" or "Buffer"
+    pub kind: &'static str,
+    /// Address of the GStreamer object
+    pub ptr: usize,
+    /// Number of wrappers alive for this object
+    pub wrappers: usize,
+    /// Current reference count of the object, including references
+    /// held by GStreamer
+    pub refcount: u32,
+}
+
+/// Starts or stops recording the wrapper objects created by the crate.
+/// Wrappers created while tracking is disabled aren't listed, so this
+/// should be enabled right after init. Disabling it forgets the objects
+/// recorded until then
+pub fn set_object_tracking(enabled: bool){
+    TRACKING.store(enabled, Ordering::SeqCst);
+    if !enabled{
+        LIVE.lock().unwrap().clear();
+    }
+}
+
+pub fn is_object_tracking() -> bool{
+    TRACKING.load(Ordering::SeqCst)
+}
+
+/// The objects held by wrappers created since tracking was enabled
+pub fn live_objects() -> Vec<LiveObject>{
+    let live = LIVE.lock().unwrap();
+    let mut objects: Vec<LiveObject> = live.iter().map(|(&ptr, tracked)| LiveObject{
+        kind: tracked.kind,
+        ptr: ptr,
+        wrappers: tracked.wrappers,
+        refcount: unsafe{ refcount(ptr as gpointer, tracked.mini_object) },
+    }).collect();
+    objects.sort_by(|a, b| a.kind.cmp(b.kind).then(a.ptr.cmp(&b.ptr)));
+    objects
+}
+
+unsafe fn refcount(ptr: gpointer, mini_object: bool) -> u32{
+    if mini_object{
+        (*(ptr as *mut GstMiniObject)).refcount as u32
+    }else{
+        (*(ptr as *mut GObject)).ref_count
+    }
+}
+
+pub(crate) fn track_new<T>(kind: &'static str, ptr: *mut T, mini_object: bool){
+    if TRACKING.load(Ordering::Relaxed){
+        let mut live = LIVE.lock().unwrap();
+        live.entry(ptr as usize)
+            .or_insert(Tracked{ kind: kind, mini_object: mini_object, wrappers: 0 })
+            .wrappers += 1;
+    }
+}
+
+pub(crate) fn track_release<T>(ptr: *mut T){
+    if TRACKING.load(Ordering::Relaxed){
+        let mut live = LIVE.lock().unwrap();
+        let remove = match live.get_mut(&(ptr as usize)){
+            Some(tracked) => {
+                tracked.wrappers -= 1;
+                tracked.wrappers == 0
+            }
+            None => false
+        };
+        if remove{
+            live.remove(&(ptr as usize));
+        }
+    }
+}
+
+// gst_tracing_get_active_tracers was added in GStreamer 1.18, after ffi.rs
+// was generated
+extern "C"{
+    fn gst_tracing_get_active_tracers() -> *mut GList;
+}
+
+/// Enables the leaks tracer through GST_TRACERS, params are passed to it
+/// as in "filters=GstBuffer,stack-traces-flags=full" or can be empty.
+/// Tracers are only loaded by init so this has to be called before it,
+/// returns false if GStreamer is already initialized
+pub fn enable_leaks_tracer(params: &str) -> bool{
+    if unsafe{ gst_is_initialized() } != 0{
+        return false;
+    }
+    let tracers = if params.is_empty(){
+        "leaks".to_string()
+    }else{
+        format!("leaks({})", params)
+    };
+    ::std::env::set_var("GST_TRACERS", tracers);
+    true
+}
+
+/// An object reported alive by the leaks tracer
+#[derive(Clone,Debug,PartialEq)]
+pub struct LeakedObject{
+    pub type_name: String,
+    pub ptr: usize,
+    pub refcount: u32,
+    /// Stack trace of the creation of the object, only recorded if the
+    /// tracer was enabled with the stack-traces-flags parameter
+    pub trace: Option<String>,
+}
+
+/// An object created or destroyed since the last checkpoint
+#[derive(Clone,Debug,PartialEq)]
+pub struct ObjectActivity{
+    pub type_name: String,
+    pub ptr: usize,
+}
+
+/// Objects created and destroyed between two checkpoints of the leaks
+/// tracer, see LeaksTracer::start_tracking
+#[derive(Clone,Debug,PartialEq)]
+pub struct Checkpoint{
+    pub created: Vec<ObjectActivity>,
+    pub removed: Vec<ObjectActivity>,
+}
+
+/// The leaks tracer loaded by GStreamer, enabled with enable_leaks_tracer
+/// or by setting GST_TRACERS=leaks in the environment
+pub struct LeaksTracer{
+    tracer: *mut GstObject
+}
+
+unsafe impl Send for LeaksTracer{}
+unsafe impl Sync for LeaksTracer{}
+
+impl Drop for LeaksTracer{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.tracer as gpointer);
+        }
+    }
+}
+
+impl LeaksTracer{
+    /// Returns the active leaks tracer, None if it wasn't enabled
+    pub fn find() -> Option<LeaksTracer>{
+        unsafe{
+            let tracers = gst_tracing_get_active_tracers();
+            let mut found = ptr::null_mut();
+            let mut item = tracers;
+            while item != ptr::null_mut(){
+                let tracer = (*item).data as *mut GstObject;
+                let type_name = from_c_str!(g_type_name_from_instance(tracer as *mut GTypeInstance));
+                if found == ptr::null_mut() && type_name == "GstLeaksTracer"{
+                    found = tracer;
+                }else{
+                    gst_object_unref(tracer as gpointer);
+                }
+                item = (*item).next;
+            }
+            g_list_free(tracers);
+            if found != ptr::null_mut(){
+                Some(LeaksTracer{ tracer: found })
+            }else{
+                None
+            }
+        }
+    }
+
+    /// The objects currently alive that match the filters of the tracer
+    pub fn live_objects(&self) -> Vec<LeakedObject>{
+        unsafe{
+            let structure = self.emit_structure("get-live-objects");
+            if structure == ptr::null_mut(){
+                return Vec::new();
+            }
+            let objects = structure_list(structure, "live-objects-list").into_iter().map(|object| {
+                let value = gst_structure_get_value(object, to_c_str!("object"));
+                let ref_count = gst_structure_get_value(object, to_c_str!("ref-count"));
+                let trace = gst_structure_get_value(object, to_c_str!("trace"));
+                LeakedObject{
+                    type_name: if value != ptr::null(){
+                        from_c_str!(g_type_name((*value).g_type)).to_string()
+                    }else{
+                        String::new()
+                    },
+                    ptr: if value != ptr::null(){ g_value_peek_pointer(value) as usize }else{ 0 },
+                    refcount: if ref_count != ptr::null(){ g_value_get_uint(ref_count) }else{ 0 },
+                    trace: if trace != ptr::null() && g_value_get_string(trace) != ptr::null(){
+                        Some(from_c_str!(g_value_get_string(trace)).to_string())
+                    }else{
+                        None
+                    },
+                }
+            }).collect();
+            gst_structure_free(structure);
+            objects
+        }
+    }
+
+    /// Logs the objects currently alive to the GStreamer debug log
+    pub fn log_live_objects(&self){
+        unsafe{
+            g_signal_emit_by_name(self.tracer as gpointer, to_c_str!("log-live-objects"));
+        }
+    }
+
+    /// Starts recording the objects created and destroyed, the changes
+    /// are returned by checkpoint
+    pub fn start_tracking(&self){
+        unsafe{
+            g_signal_emit_by_name(self.tracer as gpointer, to_c_str!("activity-start-tracking"));
+        }
+    }
+
+    /// Returns the objects created and destroyed since tracking started or
+    /// since the previous checkpoint
+    pub fn checkpoint(&self) -> Checkpoint{
+        unsafe{
+            let structure = self.emit_structure("activity-get-checkpoint");
+            if structure == ptr::null_mut(){
+                return Checkpoint{ created: Vec::new(), removed: Vec::new() };
+            }
+            let checkpoint = Checkpoint{
+                created: structure_list(structure, "objects-created-list").into_iter().map(|object| object_activity(object)).collect(),
+                removed: structure_list(structure, "objects-removed-list").into_iter().map(|object| object_activity(object)).collect(),
+            };
+            gst_structure_free(structure);
+            checkpoint
+        }
+    }
+
+    pub fn stop_tracking(&self){
+        unsafe{
+            g_signal_emit_by_name(self.tracer as gpointer, to_c_str!("activity-stop-tracking"));
+        }
+    }
+
+    pub unsafe fn gst_tracer(&self) -> *const GstObject{
+        self.tracer
+    }
+
+    unsafe fn emit_structure(&self, signal: &str) -> *mut GstStructure{
+        let mut structure: *mut GstStructure = ptr::null_mut();
+        g_signal_emit_by_name(self.tracer as gpointer, to_c_str!(signal), &mut structure as *mut *mut GstStructure);
+        structure
+    }
+}
+
+// The structures in a list field, they belong to the parent structure
+unsafe fn structure_list(structure: *mut GstStructure, field: &str) -> Vec<*const GstStructure>{
+    let list = gst_structure_get_value(structure, to_c_str!(field));
+    if list == ptr::null(){
+        return Vec::new();
+    }
+    (0..gst_value_list_get_size(list))
+        .map(|i| g_value_get_boxed(gst_value_list_get_value(list, i)) as *const GstStructure)
+        .filter(|object| *object != ptr::null())
+        .collect()
+}
+
+unsafe fn object_activity(object: *const GstStructure) -> ObjectActivity{
+    let type_name = gst_structure_get_value(object, to_c_str!("type-name"));
+    let address = gst_structure_get_value(object, to_c_str!("address"));
+    ObjectActivity{
+        type_name: if type_name != ptr::null() && g_value_get_string(type_name) != ptr::null(){
+            from_c_str!(g_value_get_string(type_name)).to_string()
+        }else{
+            String::new()
+        },
+        ptr: if address != ptr::null(){ g_value_get_uint64(address) as usize }else{ 0 },
+    }
+}
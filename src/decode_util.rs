@@ -0,0 +1,62 @@
+// Helpers shared by the decoding utilities, as extract_audio,
+// extract_frames, Remuxer and keyframes
+
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+use error::Error;
+
+extern "C"{
+    // added in GStreamer 1.10, after ffi.rs was generated
+    pub(crate) fn gst_app_sink_try_pull_sample(appsink: *mut GstAppSink, timeout: GstClockTime) -> *mut GstSample;
+}
+
+pub(crate) fn missing_element(factory: &str) -> Error{
+    Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_MISSING_PLUGIN as i32,
+        &format!("couldn't create element {}, is the plugin installed?", factory))
+}
+
+/// Links the first stream decodebin exposes that target accepts to the
+/// sink pad of target, the rest go to a fakesink so they don't stop the
+/// demuxer
+pub(crate) fn link_decoded_pads(decodebin: &mut Element, target: Element){
+    unsafe{
+        let target = Box::new(target);
+        connect_boxed(decodebin.gst_element_mut() as gpointer, "pad-added",
+            pad_added as extern "C" fn(*mut GstElement, *mut GstPad, gpointer) as gpointer,
+            target);
+    }
+}
+
+extern "C" fn pad_added(decodebin: *mut GstElement, pad: *mut GstPad, data: gpointer){
+    unsafe{
+        let target = &*(data as *const Element);
+        let sink = gst_element_get_static_pad(target.gst_element() as *mut GstElement, to_c_str!("sink"));
+        let linked = gst_pad_is_linked(sink) == 0 && gst_pad_link(pad, sink) == GST_PAD_LINK_OK;
+        gst_object_unref(sink as gpointer);
+        if !linked{
+            let discard = discard_pad(decodebin);
+            if discard != ptr::null_mut(){
+                gst_pad_link(pad, discard);
+                gst_object_unref(discard as gpointer);
+            }
+        }
+    }
+}
+
+// Adds a fakesink next to decodebin and returns its sink pad
+pub(crate) unsafe fn discard_pad(decodebin: *mut GstElement) -> *mut GstPad{
+    let parent = gst_object_get_parent(decodebin as *mut GstObject);
+    if parent == ptr::null_mut(){
+        return ptr::null_mut();
+    }
+    let fakesink = gst_element_factory_make(to_c_str!("fakesink"), ptr::null());
+    if fakesink == ptr::null_mut(){
+        gst_object_unref(parent as gpointer);
+        return ptr::null_mut();
+    }
+    gst_bin_add(parent as *mut GstBin, fakesink);
+    gst_object_unref(parent as gpointer);
+    gst_element_sync_state_with_parent(fakesink);
+    gst_element_get_static_pad(fakesink, to_c_str!("sink"))
+}
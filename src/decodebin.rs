@@ -0,0 +1,220 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use element::{Element, ElementT};
+use element_factory::ElementFactory;
+use pad::Pad;
+
+/// What decodebin does with a factory it's about to plug, returned by
+/// the closures of DecodeBinT::on_autoplug_select
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum AutoplugSelect{
+    /// Tries to plug the factory
+    Try,
+    /// Stops autoplugging and exposes the stream as it is
+    Expose,
+    /// Skips the factory and tries the next one
+    Skip,
+}
+
+impl AutoplugSelect{
+    fn to_gst(&self) -> guint{
+        match *self{
+            AutoplugSelect::Try => 0,
+            AutoplugSelect::Expose => 1,
+            AutoplugSelect::Skip => 2,
+        }
+    }
+}
+
+/// Properties and autoplugging signals shared by decodebin and
+/// uridecodebin
+pub trait DecodeBinT: ElementT{
+    /// The caps at which autoplugging stops and the stream is exposed,
+    /// the default are the raw audio, video and text caps
+    fn set_caps(&self, caps: &Caps){
+        self.as_element().set("caps", unsafe{ caps.gst_caps() });
+    }
+
+    fn caps(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(self.as_element().get::<*mut GstCaps>("caps"), true)
+        }
+    }
+
+    /// Whether to expose the streams that can't be decoded to caps,
+    /// true by default
+    fn set_expose_all_streams(&self, expose: bool){
+        self.as_element().set("expose-all-streams", expose as gboolean);
+    }
+
+    fn expose_all_streams(&self) -> bool{
+        unsafe{
+            self.as_element().get::<gboolean>("expose-all-streams") != 0
+        }
+    }
+
+    /// Calls f with each new stream and its caps, before looking for
+    /// elements to handle it. Returning false exposes the stream as it
+    /// is, as at the caps set with set_caps. Called from the streaming
+    /// thread
+    fn on_autoplug_continue<F: Fn(&Pad, &Caps) -> bool + Send + Sync + 'static>(&self, f: F){
+        unsafe{
+            connect_boxed(self.gst_element() as gpointer, "autoplug-continue",
+                (autoplug_continue::<F> as extern "C" fn(*mut GstElement, *mut GstPad, *mut GstCaps, gpointer) -> gboolean) as gpointer,
+                Box::new(f));
+        }
+    }
+
+    /// Calls f with each factory decodebin is about to plug for a stream,
+    /// sorted by rank. Only the first closure connected decides. Called
+    /// from the streaming thread
+    fn on_autoplug_select<F: Fn(&Pad, &Caps, &ElementFactory) -> AutoplugSelect + Send + Sync + 'static>(&self, f: F){
+        unsafe{
+            connect_boxed(self.gst_element() as gpointer, "autoplug-select",
+                (autoplug_select::<F> as extern "C" fn(*mut GstElement, *mut GstPad, *mut GstCaps, *mut GstElementFactory, gpointer) -> guint) as gpointer,
+                Box::new(f));
+        }
+    }
+
+    /// Skips the hardware decoders, as vaapi or nvcodec, so the streams
+    /// are decoded in software
+    fn force_software_decoding(&self){
+        self.on_autoplug_select(|_, _, factory| {
+            let klass = factory.klass();
+            if klass.contains("Decoder") && klass.contains("Hardware"){
+                AutoplugSelect::Skip
+            }else{
+                AutoplugSelect::Try
+            }
+        });
+    }
+
+    /// Stops before plugging any decoder, exposing the parsed compressed
+    /// streams, to remux them without decoding
+    fn expose_parsed_streams(&self){
+        self.on_autoplug_select(|_, _, factory| {
+            if factory.klass().contains("Decoder"){
+                AutoplugSelect::Expose
+            }else{
+                AutoplugSelect::Try
+            }
+        });
+    }
+}
+
+extern "C" fn autoplug_continue<F: Fn(&Pad, &Caps) -> bool>(_bin: *mut GstElement, pad: *mut GstPad, caps: *mut GstCaps, data: gpointer) -> gboolean{
+    unsafe{
+        match (Pad::new(pad, false), Caps::new(caps, false)){
+            (Some(pad), Some(caps)) => (*(data as *const F))(&pad, &caps) as gboolean,
+            _ => 1
+        }
+    }
+}
+
+extern "C" fn autoplug_select<F: Fn(&Pad, &Caps, &ElementFactory) -> AutoplugSelect>(_bin: *mut GstElement, pad: *mut GstPad,
+        caps: *mut GstCaps, factory: *mut GstElementFactory, data: gpointer) -> guint{
+    unsafe{
+        match (Pad::new(pad, false), Caps::new(caps, false), ElementFactory::new(factory, false)){
+            (Some(pad), Some(caps), Some(factory)) => (*(data as *const F))(&pad, &caps, &factory).to_gst(),
+            _ => AutoplugSelect::Try.to_gst()
+        }
+    }
+}
+
+/// Wrapper around decodebin, which plugs the demuxers and decoders to
+/// decode the data pushed into its sink pad, exposing a pad for each
+/// stream
+pub struct DecodeBin{
+    decodebin: Element
+}
+
+unsafe impl Sync for DecodeBin {}
+unsafe impl Send for DecodeBin {}
+
+impl DecodeBin{
+    pub fn new(name: &str) -> Option<DecodeBin>{
+        Element::new("decodebin", name).map(|decodebin| DecodeBin{ decodebin: decodebin })
+    }
+
+    pub fn new_from_element(element: Element) -> DecodeBin{
+        DecodeBin{ decodebin: element }
+    }
+}
+
+impl ElementT for DecodeBin{
+    fn as_element(&self) -> &Element{
+        &self.decodebin
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.decodebin
+    }
+}
+
+impl DecodeBinT for DecodeBin{}
+
+impl ::Transfer for DecodeBin{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.decodebin.transfer()
+    }
+}
+
+/// Wrapper around uridecodebin, a decodebin with the source for an uri
+pub struct UriDecodeBin{
+    uridecodebin: Element
+}
+
+unsafe impl Sync for UriDecodeBin {}
+unsafe impl Send for UriDecodeBin {}
+
+impl UriDecodeBin{
+    pub fn new(name: &str) -> Option<UriDecodeBin>{
+        Element::new("uridecodebin", name).map(|uridecodebin| UriDecodeBin{ uridecodebin: uridecodebin })
+    }
+
+    pub fn new_from_element(element: Element) -> UriDecodeBin{
+        UriDecodeBin{ uridecodebin: element }
+    }
+
+    pub fn set_uri(&self, uri: &str){
+        self.uridecodebin.set("uri", to_c_str!(uri));
+    }
+
+    pub fn uri(&self) -> String{
+        unsafe{
+            let uri = self.uridecodebin.get::<*mut gchar>("uri");
+            if uri != ptr::null_mut(){
+                let string = from_c_str!(uri).to_string();
+                g_free(uri as gpointer);
+                string
+            }else{
+                String::new()
+            }
+        }
+    }
+
+    /// Whether to buffer network streams in a queue2, posting BUFFERING
+    /// messages
+    pub fn set_use_buffering(&self, use_buffering: bool){
+        self.uridecodebin.set("use-buffering", use_buffering as gboolean);
+    }
+}
+
+impl ElementT for UriDecodeBin{
+    fn as_element(&self) -> &Element{
+        &self.uridecodebin
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.uridecodebin
+    }
+}
+
+impl DecodeBinT for UriDecodeBin{}
+
+impl ::Transfer for UriDecodeBin{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.uridecodebin.transfer()
+    }
+}
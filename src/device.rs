@@ -0,0 +1,254 @@
+use ffi::*;
+use util::*;
+use bus::Bus;
+use caps::Caps;
+use element::Element;
+use structure::Structure;
+use value::Value;
+
+use std::os::raw::c_void;
+use std::fmt::{self, Debug, Formatter};
+
+extern "C"{
+    // added in GStreamer 1.6
+    fn gst_device_get_properties(device: *mut GstDevice) -> *mut GstStructure;
+}
+
+/// A capture or output device like a camera, microphone or sound card,
+/// as found by a DeviceMonitor
+pub struct Device{
+    device: *mut GstDevice
+}
+
+unsafe impl Sync for Device {}
+unsafe impl Send for Device {}
+
+impl Drop for Device{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.device as *mut c_void);
+        }
+    }
+}
+
+impl Clone for Device{
+    fn clone(&self) -> Device{
+        unsafe{
+            gst_object_ref(self.device as *mut c_void);
+            Device{ device: self.device }
+        }
+    }
+}
+
+impl Device{
+    /// Wraps a raw GstDevice, if owned is false a new reference is taken
+    pub unsafe fn new(device: *mut GstDevice, owned: bool) -> Option<Device>{
+        if device != ptr::null_mut(){
+            if !owned{
+                gst_object_ref(device as *mut c_void);
+            }
+            Some(Device{ device: device })
+        }else{
+            None
+        }
+    }
+
+    /// Human readable name of the device
+    pub fn display_name(&self) -> String{
+        unsafe{
+            let c_str = gst_device_get_display_name(self.device);
+            let name = from_c_str!(c_str).to_string();
+            g_free(c_str as gpointer);
+            name
+        }
+    }
+
+    /// Classes of the device as in "Video/Source" or "Audio/Sink"
+    pub fn device_class(&self) -> String{
+        unsafe{
+            let c_str = gst_device_get_device_class(self.device);
+            let class = from_c_str!(c_str).to_string();
+            g_free(c_str as gpointer);
+            class
+        }
+    }
+
+    /// Checks if the device has all the classes, separated by '/'
+    pub fn has_classes(&self, classes: &str) -> bool{
+        unsafe{
+            gst_device_has_classes(self.device, to_c_str!(classes)) == 1
+        }
+    }
+
+    /// Caps the device can produce or accept
+    pub fn caps(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(gst_device_get_caps(self.device), true)
+        }
+    }
+
+    /// Provider specific properties like the api or the device path
+    pub fn properties(&self) -> Option<Structure>{
+        unsafe{
+            Structure::new_from_gst_structure(gst_device_get_properties(self.device))
+        }
+    }
+
+    /// Provider specific id of the device, as accepted by the device
+    /// property of pulsesink/pulsesrc, wasapisink/wasapisrc or
+    /// osxaudiosink/osxaudiosrc, or the device path for v4l2 devices
+    pub fn id(&self) -> Option<String>{
+        ["internal-name", "device", "device-id", "device-path"].iter()
+            .filter_map(|name| self.object_property(name))
+            .filter_map(|value| match value{
+                Value::String(id) => Some(id),
+                Value::Int(id) => Some(id.to_string()),
+                Value::UInt(id) => Some(id.to_string()),
+                _ => None
+            })
+            .next()
+    }
+
+    fn object_property(&self, name: &str) -> Option<Value>{
+        unsafe{
+            let class = (*(self.device as *mut GTypeInstance)).g_class as *mut GObjectClass;
+            let pspec = g_object_class_find_property(class, to_c_str!(name));
+            if pspec == ptr::null_mut(){
+                return None;
+            }
+            let mut gvalue: GValue = mem::zeroed();
+            g_value_init(&mut gvalue, (*pspec).value_type);
+            g_object_get_property(self.device as *mut GObject, to_c_str!(name), &mut gvalue);
+            let value = Value::from_gvalue(&gvalue);
+            g_value_unset(&mut gvalue);
+            value
+        }
+    }
+
+    /// Creates a source or sink element configured to use this device
+    pub fn create_element(&self, name: &str) -> Option<Element>{
+        unsafe{
+            let name = if name != "" {
+                Some(CString::new(name).unwrap())
+            } else {
+                None
+            };
+            let element = gst_device_create_element(self.device,
+                name.as_ref().map(|name| name.as_ptr()).unwrap_or(ptr::null()));
+            if element != ptr::null_mut(){
+                gst_object_ref_sink(element as gpointer);
+            }
+            Element::new_from_gst_element(element)
+        }
+    }
+
+    pub unsafe fn gst_device(&self) -> *const GstDevice{
+        self.device
+    }
+
+    pub unsafe fn gst_device_mut(&mut self) -> *mut GstDevice{
+        self.device
+    }
+}
+
+impl Debug for Device{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "Device {{ name: \"{}\", class: \"{}\", id: {:?} }}", self.display_name(), self.device_class(), self.id())
+    }
+}
+
+/// Finds the devices available in the system and, once started, posts
+/// DEVICE_ADDED and DEVICE_REMOVED messages on its bus when they are
+/// plugged or unplugged
+pub struct DeviceMonitor{
+    monitor: *mut GstDeviceMonitor
+}
+
+unsafe impl Sync for DeviceMonitor {}
+unsafe impl Send for DeviceMonitor {}
+
+impl Drop for DeviceMonitor{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.monitor as *mut c_void);
+        }
+    }
+}
+
+impl DeviceMonitor{
+    pub fn new() -> DeviceMonitor{
+        unsafe{
+            DeviceMonitor{ monitor: gst_device_monitor_new() }
+        }
+    }
+
+    /// Creates a monitor that only reports devices with the given classes
+    /// as in "Video/Source"
+    pub fn new_with_filter(classes: &str) -> DeviceMonitor{
+        let mut monitor = DeviceMonitor::new();
+        monitor.add_filter(classes, None);
+        monitor
+    }
+
+    /// Only report devices matching the classes and, if passed, caps.
+    /// Returns an id to be used with remove_filter or 0 if no provider
+    /// matches
+    pub fn add_filter(&mut self, classes: &str, caps: Option<&Caps>) -> u32{
+        unsafe{
+            let caps = caps.map(|caps| caps.gst_caps() as *mut GstCaps).unwrap_or(ptr::null_mut());
+            gst_device_monitor_add_filter(self.monitor, to_c_str!(classes), caps)
+        }
+    }
+
+    pub fn remove_filter(&mut self, filter_id: u32) -> bool{
+        unsafe{
+            gst_device_monitor_remove_filter(self.monitor, filter_id) == 1
+        }
+    }
+
+    /// Starts monitoring for added and removed devices
+    pub fn start(&mut self) -> bool{
+        unsafe{
+            gst_device_monitor_start(self.monitor) == 1
+        }
+    }
+
+    pub fn stop(&mut self){
+        unsafe{
+            gst_device_monitor_stop(self.monitor);
+        }
+    }
+
+    /// Returns the devices currently available that match the filters,
+    /// it doesn't need the monitor to be started
+    pub fn devices(&self) -> Vec<Device>{
+        unsafe{
+            let list = gst_device_monitor_get_devices(self.monitor);
+            let mut devices = Vec::new();
+            let mut node = list;
+            while node != ptr::null_mut(){
+                if let Some(device) = Device::new((*node).data as *mut GstDevice, true){
+                    devices.push(device);
+                }
+                node = (*node).next;
+            }
+            g_list_free(list);
+            devices
+        }
+    }
+
+    /// The bus where DEVICE_ADDED and DEVICE_REMOVED messages are posted
+    pub fn bus(&self) -> Option<Bus>{
+        unsafe{
+            Bus::new(gst_device_monitor_get_bus(self.monitor), true)
+        }
+    }
+
+    pub unsafe fn gst_device_monitor(&self) -> *const GstDeviceMonitor{
+        self.monitor
+    }
+
+    pub unsafe fn gst_device_monitor_mut(&mut self) -> *mut GstDeviceMonitor{
+        self.monitor
+    }
+}
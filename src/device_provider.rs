@@ -0,0 +1,254 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use device::Device;
+use element::Element;
+use structure::Structure;
+use subclass::*;
+
+use std::os::raw::c_void;
+use std::sync::Once;
+
+/// Finds devices for a DeviceMonitor, implement it to make devices only
+/// known to the application, as proprietary capture cards, show up next
+/// to the ones GStreamer finds. Register it with register_device_provider.
+///
+/// A new instance is created with Default for every provider GStreamer
+/// instantiates, usually one per monitor
+pub trait DeviceProviderImpl: Default + Send + Sync + 'static{
+    fn metadata() -> Metadata;
+
+    /// Returns the devices currently available, create them with
+    /// Device::new_custom
+    fn probe(&self, provider: &DeviceProvider) -> Vec<Device>;
+
+    /// Starts monitoring, announcing the current devices and, from then
+    /// on, the ones plugged and unplugged with device_add and
+    /// device_remove. By default announces the probed devices once
+    fn start(&self, provider: &DeviceProvider) -> bool{
+        for device in self.probe(provider){
+            provider.device_add(&device);
+        }
+        true
+    }
+
+    /// Stops monitoring, called when the last monitor using the provider
+    /// stops
+    fn stop(&self, _provider: &DeviceProvider){}
+}
+
+/// A device provider as passed to the methods of a DeviceProviderImpl
+pub struct DeviceProvider{
+    provider: *mut GstDeviceProvider
+}
+
+unsafe impl Sync for DeviceProvider {}
+unsafe impl Send for DeviceProvider {}
+
+impl Drop for DeviceProvider{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.provider as *mut c_void);
+        }
+    }
+}
+
+impl Clone for DeviceProvider{
+    fn clone(&self) -> DeviceProvider{
+        unsafe{
+            gst_object_ref(self.provider as *mut c_void);
+            DeviceProvider{ provider: self.provider }
+        }
+    }
+}
+
+impl DeviceProvider{
+    /// Wraps a raw GstDeviceProvider, if owned is false a new reference
+    /// is taken
+    pub unsafe fn new(provider: *mut GstDeviceProvider, owned: bool) -> Option<DeviceProvider>{
+        if provider != ptr::null_mut(){
+            if !owned{
+                gst_object_ref(provider as *mut c_void);
+            }
+            Some(DeviceProvider{ provider: provider })
+        }else{
+            None
+        }
+    }
+
+    /// Announces a device that was plugged, posting DEVICE_ADDED on the
+    /// bus of the monitors
+    pub fn device_add(&self, device: &Device){
+        unsafe{
+            gst_device_provider_device_add(self.provider, device.gst_device() as *mut GstDevice);
+        }
+    }
+
+    /// Announces a device that was unplugged, it has to be one passed to
+    /// device_add
+    pub fn device_remove(&self, device: &Device){
+        unsafe{
+            gst_device_provider_device_remove(self.provider, device.gst_device() as *mut GstDevice);
+        }
+    }
+
+    /// The devices announced so far, or probed if not started
+    pub fn devices(&self) -> Vec<Device>{
+        unsafe{
+            let list = gst_device_provider_get_devices(self.provider);
+            let mut devices = Vec::new();
+            let mut node = list;
+            while node != ptr::null_mut(){
+                if let Some(device) = Device::new((*node).data as *mut GstDevice, true){
+                    devices.push(device);
+                }
+                node = (*node).next;
+            }
+            g_list_free(list);
+            devices
+        }
+    }
+
+    pub unsafe fn gst_device_provider(&self) -> *const GstDeviceProvider{
+        self.provider
+    }
+
+    pub unsafe fn gst_device_provider_mut(&mut self) -> *mut GstDeviceProvider{
+        self.provider
+    }
+}
+
+extern "C" fn provider_class_init<T: DeviceProviderImpl>(class: gpointer, _data: gpointer){
+    unsafe{
+        install_finalize::<T>(class);
+        let provider_class = class as *mut GstDeviceProviderClass;
+        (*provider_class).probe = Some(provider_probe::<T>);
+        (*provider_class).start = Some(provider_start::<T>);
+        (*provider_class).stop = Some(provider_stop::<T>);
+        let metadata = T::metadata();
+        gst_device_provider_class_set_metadata(provider_class, to_c_str!(metadata.long_name),
+            to_c_str!(metadata.classification), to_c_str!(metadata.description), to_c_str!(metadata.author));
+    }
+}
+
+extern "C" fn provider_probe<T: DeviceProviderImpl>(provider: *mut GstDeviceProvider) -> *mut GList{
+    unsafe{
+        let wrapper = DeviceProvider::new(provider, false).unwrap();
+        let mut list = ptr::null_mut();
+        for device in imp::<T>(provider as gpointer).probe(&wrapper){
+            gst_object_ref(device.gst_device() as *mut c_void);
+            list = g_list_append(list, device.gst_device() as gpointer);
+        }
+        list
+    }
+}
+
+extern "C" fn provider_start<T: DeviceProviderImpl>(provider: *mut GstDeviceProvider) -> gboolean{
+    unsafe{
+        let wrapper = DeviceProvider::new(provider, false).unwrap();
+        imp::<T>(provider as gpointer).start(&wrapper) as gboolean
+    }
+}
+
+extern "C" fn provider_stop<T: DeviceProviderImpl>(provider: *mut GstDeviceProvider){
+    unsafe{
+        let wrapper = DeviceProvider::new(provider, false).unwrap();
+        imp::<T>(provider as gpointer).stop(&wrapper);
+    }
+}
+
+/// Registers a device provider implemented in Rust with the name
+/// DeviceMonitor and gst-device-monitor-1.0 will know it by. Providers
+/// with higher rank are preferred when several find the same devices,
+/// GST_RANK_PRIMARY for the main one
+pub fn register_device_provider<T: DeviceProviderImpl>(name: &str, rank: u32) -> bool{
+    unsafe{
+        let provider_type = register_type(gst_device_provider_get_type(), &type_name(name),
+            provider_class_init::<T>, instance_init::<T>);
+        provider_type != 0 && gst_device_provider_register(ptr::null_mut(), to_c_str!(name), rank, provider_type) == 1
+    }
+}
+
+type CreateElement = Box<dyn Fn(Option<&str>) -> Option<Element> + Send + Sync>;
+
+#[repr(C)]
+struct RsDevice{
+    parent: GstDevice,
+    create_element: *mut CreateElement,
+}
+
+static REGISTER_DEVICE: Once = Once::new();
+static mut RS_DEVICE_TYPE: GType = 0;
+
+fn rs_device_type() -> GType{
+    unsafe{
+        REGISTER_DEVICE.call_once(|| {
+            let mut info: GTypeInfo = mem::zeroed();
+            info.class_size = mem::size_of::<GstDeviceClass>() as guint16;
+            info.class_init = Some(rs_device_class_init);
+            info.instance_size = mem::size_of::<RsDevice>() as guint16;
+            RS_DEVICE_TYPE = g_type_register_static(gst_device_get_type(), to_c_str!("GstRsDevice"), &info, 0);
+        });
+        RS_DEVICE_TYPE
+    }
+}
+
+extern "C" fn rs_device_class_init(class: gpointer, _data: gpointer){
+    unsafe{
+        (*(class as *mut GstDeviceClass)).create_element = Some(rs_device_create_element);
+        (*(class as *mut GObjectClass)).finalize = Some(rs_device_finalize);
+    }
+}
+
+extern "C" fn rs_device_create_element(device: *mut GstDevice, name: *const gchar) -> *mut GstElement{
+    unsafe{
+        let create_element = (*(device as *mut RsDevice)).create_element;
+        let name = if name != ptr::null(){ Some(from_c_str!(name)) }else{ None };
+        match (*create_element)(name){
+            Some(element) => {
+                let element = ::Transfer::transfer(element);
+                // create_element returns a floating reference
+                g_object_force_floating(element as *mut GObject);
+                element
+            }
+            None => ptr::null_mut()
+        }
+    }
+}
+
+extern "C" fn rs_device_finalize(object: *mut GObject){
+    unsafe{
+        let device = object as *mut RsDevice;
+        if (*device).create_element != ptr::null_mut(){
+            drop(Box::from_raw((*device).create_element));
+            (*device).create_element = ptr::null_mut();
+        }
+        let parent = g_type_class_peek(gst_device_get_type()) as *const GObjectClass;
+        if let Some(finalize) = (*parent).finalize{
+            finalize(object);
+        }
+    }
+}
+
+impl Device{
+    /// Creates a device to be announced by a DeviceProviderImpl.
+    /// create_element is called by Device::create_element with the name
+    /// of the element, and has to return a source or sink configured for
+    /// this device
+    pub fn new_custom<F>(display_name: &str, device_class: &str, caps: &Caps, properties: Option<&Structure>, create_element: F) -> Device
+            where F: Fn(Option<&str>) -> Option<Element> + Send + Sync + 'static{
+        unsafe{
+            let properties = properties.map(|properties| properties.gst_structure()).unwrap_or(ptr::null());
+            let device = g_object_new(rs_device_type(),
+                to_c_str!("display-name"), to_c_str!(display_name),
+                to_c_str!("device-class"), to_c_str!(device_class),
+                to_c_str!("caps"), caps.gst_caps(),
+                to_c_str!("properties"), properties,
+                ptr::null::<gchar>()) as *mut RsDevice;
+            let create_element: CreateElement = Box::new(create_element);
+            (*device).create_element = Box::into_raw(Box::new(create_element));
+            gst_object_ref_sink(device as gpointer);
+            Device::new(device as *mut GstDevice, true).unwrap()
+        }
+    }
+}
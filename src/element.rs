@@ -1,8 +1,19 @@
 use ffi::*;
 use bus::Bus;
+use pad::Pad;
+use value::Value;
+use structure::Structure;
+use format::{Bytes, Frames, ClockTimeDiff};
+use stream_collection::new_select_streams_event;
+use context::Context;
+use buffering::{BufferingStatus, query_buffering};
+use error::{Error, ErrorKind, Result};
 use util::*;
+use debug::{track_new, track_release};
 
 use std::os::raw::c_void;
+use std::result;
+use std::fmt::{self, Debug, Display, Formatter};
 
 unsafe impl Sync for GstElement {}
 unsafe impl Send for GstElement {}
@@ -16,6 +27,7 @@ pub struct Element{
 impl Drop for Element{
 	fn drop(&mut self){
 		unsafe{
+			track_release(self.element);
 			gst_object_unref(self.element as *mut c_void);
 		}
 	}
@@ -32,6 +44,7 @@ impl Element{
             let element = gst_element_factory_make(to_c_str!(element_name), name);
             if element != ptr::null_mut::<GstElement>(){
                 gst_object_ref_sink(mem::transmute(element));
+                track_new("Element", element, false);
                 Some( Element{element: element} )
             }else{
 				println!("Erroro creating {} return {:?}",element_name, element);
@@ -44,14 +57,34 @@ impl Element{
 		Element::new(element,name)
 	}
 
+    /// Creates an element from the factory and sets the passed properties
+    /// on it, see the element! macro for a more convenient syntax.
+    pub fn with_properties(factory: &str, properties: &[(&str, Value)]) -> Result<Element>{
+        let mut element = match Element::new(factory, ""){
+            Some(element) => element,
+            None => return Err(Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_MISSING_PLUGIN as i32,
+                &format!("couldn't create element {}, is the plugin installed?", factory)))
+        };
+        for &(name, ref value) in properties{
+            element.set_property(name, value)?;
+        }
+        Ok(element)
+    }
+
     pub unsafe fn new_from_gst_element(element: *mut GstElement) -> Option<Element>{
 		if element != ptr::null_mut::<GstElement>(){
+			track_new("Element", element, false);
 			Some( Element{element: element} )
 		}else{
 			None
 		}
     }
 
+    pub(crate) unsafe fn find_property(&self, name: &str) -> *mut GParamSpec{
+        let class = (*(self.element as *mut GTypeInstance)).g_class as *mut GObjectClass;
+        g_object_class_find_property(class, to_c_str!(name))
+    }
+
 }
 
 /// http://gstreamer.freedesktop.org/data/doc/gstreamer/head/gstreamer/html/GstElement.html
@@ -63,6 +96,7 @@ pub trait ElementT: ::Transfer{
     fn to_element(&self) -> Element{
 		let element = Element{element: self.as_element().element};
 		unsafe{ gst_object_ref(element.element as *mut c_void); }
+		track_new("Element", element.element, false);
 		element
     }
 
@@ -107,6 +141,52 @@ pub trait ElementT: ::Transfer{
         self.as_element_mut().set_name(name);
     }
 
+    /// Returns the name of the factory this element was created from,
+    /// as in "videotestsrc", or None for elements not created from a
+    /// factory
+    fn factory_name(&self) -> Option<String>{
+        self.as_element().factory_name()
+    }
+
+    /// Retrieves a pad from the element by name, this version only
+    /// retrieves already-existing (i.e. 'static') pads.
+    fn static_pad(&self, name: &str) -> Option<Pad>{
+        self.as_element().static_pad(name)
+    }
+
+    /// All the pads of the element, static and requested or dynamic
+    fn pads(&self) -> Vec<Pad>{
+        self.as_element().pads()
+    }
+
+    /// Returns the current value of the property name, or None if the
+    /// element has no such property
+    fn property(&self, name: &str) -> Option<Value>{
+        self.as_element().property(name)
+    }
+
+    /// Sets the property name converting value to the type of the
+    /// property. Strings are deserialized so enums and flags can be set
+    /// by their nick, as in gst-launch.
+    fn set_property(&mut self, name: &str, value: &Value) -> Result<()>{
+        self.as_element_mut().set_property(name, value)
+    }
+
+    /// The values of all the properties that can be read and written, in
+    /// a Structure named after the type of the element, to save settings
+    /// as camera controls or encoder tuning and restore them in a later
+    /// run with apply_snapshot
+    fn properties_snapshot(&self) -> Structure{
+        self.as_element().properties_snapshot()
+    }
+
+    /// Sets every property in snapshot. Properties that the element
+    /// doesn't have or that can't be set are skipped, and reported in the
+    /// error after setting the rest
+    fn apply_snapshot(&mut self, snapshot: &Structure) -> Result<()>{
+        self.as_element_mut().apply_snapshot(snapshot)
+    }
+
     /// Sets the state of the element. This function will try to
     /// set the requested state by going through all the intermediary
     /// states and calling the class's state change function for each.
@@ -155,6 +235,17 @@ pub trait ElementT: ::Transfer{
         self.as_element().get_state(timeout)
     }
 
+    /// Sets the state and waits up to timeout nanoseconds for it to
+    /// complete. If it doesn't, the error says which elements are still
+    /// changing state, as a sink that can't open its device blocking
+    /// the pipeline in READY to PAUSED.
+    ///
+    /// When the state change fails the error posted on the bus is
+    /// returned, popping it from the bus.
+    fn set_state_with_timeout(&mut self, state: GstState, timeout: GstClockTime) -> result::Result<GstStateChangeReturn, StateChangeError>{
+        self.as_element_mut().set_state_with_timeout(state, timeout)
+    }
+
     /// Sends an event to an element. If the element doesn't implement an event
     /// handler, the event will be pushed on a random linked sink pad for
     /// downstream events or a random linked source pad for upstream events.
@@ -165,6 +256,56 @@ pub trait ElementT: ::Transfer{
         self.as_element_mut().send_event(event)
     }
 
+    /// Flushes all the data in the element by sending a flush-start and a
+    /// flush-stop event, then waits for the element to complete the state
+    /// change it goes through after the flush, that is, for non live
+    /// pipelines, until it prerolls again.
+    ///
+    /// Useful to reset a live pipeline after a network hiccup without
+    /// setting it to NULL. Returns false if any of the events wasn't
+    /// handled or the element failed to recover or didn't recover within
+    /// timeout nanoseconds.
+    fn flush(&mut self, timeout: GstClockTime) -> bool{
+        self.as_element_mut().flush(timeout)
+    }
+
+    /// Selects the streams to play by sending a SELECT_STREAMS event, the
+    /// ids are those of the streams in the StreamCollection posted by
+    /// playbin3, uridecodebin3 or decodebin3. Streams not in the list
+    /// are deactivated, a STREAMS_SELECTED message is posted once the
+    /// selection is applied.
+    fn select_streams(&mut self, stream_ids: &[&str]) -> bool{
+        unsafe{
+            self.send_event(new_select_streams_event(stream_ids))
+        }
+    }
+
+    /// Sets a context on the element, usually in answer to a NEED_CONTEXT
+    /// message posted by it, or before starting the pipeline to share a
+    /// resource created by the application
+    fn set_context(&mut self, context: &Context){
+        unsafe{
+            gst_element_set_context(self.gst_element_mut(), context.gst_context() as *mut GstContext);
+        }
+    }
+
+    /// Posts an ERROR message from the element with the domain and code of
+    /// kind, as a failing element would. The message is shown to users,
+    /// debug has the details for developers and can be empty
+    fn post_error(&self, kind: ErrorKind, message: &str, debug: &str){
+        unsafe{
+            post_message(self.gst_element() as *mut GstElement, GST_MESSAGE_ERROR, kind, message, debug);
+        }
+    }
+
+    /// Posts a WARNING message from the element, the pipeline keeps
+    /// running
+    fn post_warning(&self, kind: ErrorKind, message: &str, debug: &str){
+        unsafe{
+            post_message(self.gst_element() as *mut GstElement, GST_MESSAGE_WARNING, kind, message, debug);
+        }
+    }
+
     /// Simple API to perform a seek on the given element, meaning it just
     /// seeks to the given position relative to the start of the stream.
     /// For more complex operations like segment seeks (e.g. for looping)
@@ -185,7 +326,8 @@ pub trait ElementT: ::Transfer{
 
     /// Sends a seek event to an element. See [gst_event_new_seek()](http://gstreamer.freedesktop.org/data/doc/gstreamer/head/gstreamer/html/GstEvent.html#gst-event-new-seek)
     /// for the details of the parameters. The seek event is sent to the
-    /// element using send_event().
+    /// element using send_event(). Seek::builder builds the parameters
+    /// with named methods.
     fn seek(&mut self, rate: f64, format: GstFormat, flags: GstSeekFlags, start_type: GstSeekType, start: i64, stop_type: GstSeekType, stop: i64) -> bool{
         self.as_element_mut().seek(rate,format,flags,start_type,start,stop_type,stop)
     }
@@ -230,8 +372,9 @@ pub trait ElementT: ::Transfer{
     }
 
     /// Shortcut for query_position with format == TIME and conversion to
-    /// pct as 0..1
-    fn position_pct(&self) -> Option<f64>{
+    /// pct as 0..1, clamped to that range. Fails for streams without a
+    /// known duration, as live ones
+    fn position_pct(&self) -> result::Result<f64, PositionError>{
         self.as_element().position_pct()
     }
 
@@ -241,6 +384,28 @@ pub trait ElementT: ::Transfer{
         self.as_element().position_s()
     }
 
+    /// Shortcut for query_position with format == BYTES
+    fn position_bytes(&self) -> Option<Bytes>{
+        self.as_element().position_bytes()
+    }
+
+    /// Shortcut for query_duration with format == BYTES
+    fn duration_bytes(&self) -> Option<Bytes>{
+        self.as_element().duration_bytes()
+    }
+
+    /// Shortcut for query_position with format == DEFAULT, that is frames
+    /// for video and samples for audio
+    fn position_frames(&self) -> Option<Frames>{
+        self.as_element().position_frames()
+    }
+
+    /// Shortcut for query_duration with format == DEFAULT, that is frames
+    /// for video and samples for audio
+    fn duration_frames(&self) -> Option<Frames>{
+        self.as_element().duration_frames()
+    }
+
     /// Shortcut for seek to a ceratin position in ns
     fn set_position_ns(&mut self, ns: i64) -> bool{
         self.as_element_mut().set_position_ns(ns)
@@ -251,8 +416,10 @@ pub trait ElementT: ::Transfer{
         self.as_element_mut().set_position_s(s)
     }
 
-    /// Shortcut for seek to a ceratin position in pcs as 0..1
-    fn set_position_pct(&mut self, pct: f64) -> bool{
+    /// Shortcut for seek to a ceratin position in pcs as 0..1, clamped to
+    /// that range. Keeps the current playback rate, so when playing
+    /// backwards the playback continues backwards from the position
+    fn set_position_pct(&mut self, pct: f64) -> result::Result<(), PositionError>{
         self.as_element_mut().set_position_pct(pct)
     }
 
@@ -262,6 +429,36 @@ pub trait ElementT: ::Transfer{
         self.as_element_mut().set_speed(speed)
     }
 
+    /// The playback rate currently configured in the sinks, as set by the
+    /// last seek from any component, queried with a SEGMENT query. None
+    /// before the pipeline prerolls
+    fn speed(&self) -> Option<f64>{
+        self.as_element().speed()
+    }
+
+    /// The window in nanoseconds where seeks are possible, from a SEEKING
+    /// query. For live streams with recording, as HLS or DASH with a DVR
+    /// window, it moves as the stream advances instead of being
+    /// 0..duration. None if the stream isn't seekable
+    fn seekable_range(&self) -> Option<(u64, u64)>{
+        self.as_element().seekable_range()
+    }
+
+    /// The buffering state of a pipeline playing a network stream, from a
+    /// BUFFERING query, including the ranges of the stream already
+    /// downloaded
+    fn buffering_status(&self) -> Option<BufferingStatus>{
+        self.as_element().buffering_status()
+    }
+
+    /// The rate already applied to the data by upstream elements, as a
+    /// demuxer doing a trickmode seek or a source sending the data
+    /// faster, from the segment received by the sinks. The data plays at
+    /// speed times applied_rate compared to the original stream
+    fn applied_rate(&self) -> Option<f64>{
+        self.as_element().applied_rate()
+    }
+
     // fn set<T>(&self, name: &str, value: T);
 
     /// shortcut to set_state with state == NULL
@@ -320,8 +517,202 @@ pub trait ElementT: ::Transfer{
             g_object_set(self.gst_element() as *mut  c_void, to_c_str!(name), value, ptr::null::<gchar>());
         }
     }
+
+    /// Sets the ts-offset property of a sink, which shifts the time at
+    /// which it renders buffers, positive values delay the rendering.
+    /// Only sinks deriving from GstBaseSink have this property.
+    fn set_ts_offset<T: Into<ClockTimeDiff>>(&self, offset: T)
+    	where Self:Sized{
+        self.set("ts-offset", offset.into().ns());
+    }
+
+    fn ts_offset(&self) -> ClockTimeDiff
+    	where Self:Sized{
+        unsafe{
+            ClockTimeDiff(self.get::<i64>("ts-offset"))
+        }
+    }
+
+    /// Reads a property directly into T, which has to have the exact
+    /// layout of the property type, e.g. gint for enums and flags, or
+    /// *mut gchar for strings which then have to be freed with g_free.
+    /// property() is the safe but slower alternative.
+    unsafe fn get<T>(&self, name: &str) -> T
+    	where Self:Sized{
+        let mut value: T = mem::zeroed();
+        g_object_get(self.gst_element() as *mut  c_void, to_c_str!(name), &mut value as *mut T, ptr::null::<gchar>());
+        value
+    }
+}
+
+
+/// Reasons why ElementT::position_pct and set_position_pct can fail
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum PositionError{
+    /// The position couldn't be queried, usually because the pipeline
+    /// didn't preroll yet
+    UnknownPosition,
+    /// The duration is unknown, as in live streams, so there's no
+    /// percentage
+    UnknownDuration,
+    /// The percentage is NaN
+    InvalidPct,
+    /// The element refused the seek, as non seekable streams do
+    SeekFailed,
+}
+
+impl Display for PositionError{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        match *self{
+            PositionError::UnknownPosition => write!(fmt, "position not available yet"),
+            PositionError::UnknownDuration => write!(fmt, "stream has no known duration"),
+            PositionError::InvalidPct => write!(fmt, "percentage is not a number"),
+            PositionError::SeekFailed => write!(fmt, "seek failed"),
+        }
+    }
+}
+
+/// An element that didn't complete its state change, as reported by
+/// StateChangeError::Timeout
+#[derive(Clone,Debug)]
+pub struct PendingState{
+    /// Path of the element in the pipeline, as /pipeline0/sink
+    pub name: String,
+    pub current: GstState,
+    pub pending: GstState,
+}
+
+/// Reasons why ElementT::set_state_with_timeout can fail
+#[derive(Debug)]
+pub enum StateChangeError{
+    /// The state change failed, with the error posted by the element that
+    /// failed if there was one
+    Failed(Option<Error>),
+    /// The state change didn't complete in time, stuck has the elements
+    /// still changing state, usually sinks that didn't preroll
+    Timeout{
+        target: GstState,
+        current: GstState,
+        pending: GstState,
+        stuck: Vec<PendingState>,
+    },
+}
+
+impl Display for StateChangeError{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        match *self{
+            StateChangeError::Failed(Some(ref err)) => write!(fmt, "state change failed: {}", err.message()),
+            StateChangeError::Failed(None) => write!(fmt, "state change failed"),
+            StateChangeError::Timeout{ target, current, ref stuck, .. } => {
+                write!(fmt, "timed out changing to {}, still in {}", state_name(target), state_name(current))?;
+                for (i, element) in stuck.iter().enumerate(){
+                    write!(fmt, "{} {} ({} -> {})", if i == 0{ ", waiting for" }else{ "," },
+                        element.name, state_name(element.current), state_name(element.pending))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+pub(crate) fn state_name(state: GstState) -> String{
+    unsafe{
+        from_c_str!(gst_element_state_get_name(state)).to_string()
+    }
+}
+
+// The elements of a bin, recursively, that are still changing state. Bins
+// are skipped since they're waiting for their children
+pub(crate) unsafe fn pending_children(element: *mut GstElement) -> Vec<PendingState>{
+    if g_type_check_instance_is_a(element as *mut GTypeInstance, gst_bin_get_type()) == 0{
+        return vec![];
+    }
+    let mut stuck = vec![];
+    for child in iterator_objects(gst_bin_iterate_recurse(element as *mut GstBin)){
+        let child = child as *mut GstElement;
+        if g_type_check_instance_is_a(child as *mut GTypeInstance, gst_bin_get_type()) == 0{
+            let mut current = GST_STATE_VOID_PENDING;
+            let mut pending = GST_STATE_VOID_PENDING;
+            let ret = gst_element_get_state(child, &mut current, &mut pending, 0);
+            let changing = match pending{
+                GST_STATE_VOID_PENDING => ret == GST_STATE_CHANGE_ASYNC,
+                _ => true
+            };
+            if changing{
+                let path = gst_object_get_path_string(child as *mut GstObject);
+                stuck.push(PendingState{ name: from_c_str!(path).to_string(), current: current, pending: pending });
+                g_free(path as gpointer);
+            }
+        }
+        gst_object_unref(child as gpointer);
+    }
+    stuck
+}
+
+// Posts an error, warning or info message from element, the strings are
+// owned by the message
+unsafe fn post_message(element: *mut GstElement, message_type: GstMessageType, kind: ErrorKind, message: &str, debug: &str){
+    let debug = if debug.is_empty(){ ptr::null_mut() }else{ g_strdup(to_c_str!(debug)) };
+    gst_element_message_full(element, message_type, kind.domain().quark(), kind.code(),
+        g_strdup(to_c_str!(message)), debug, to_c_str!(file!()), to_c_str!(module_path!()), line!() as gint);
 }
 
+// Pops the first error message from the bus of element, if it has one
+unsafe fn pop_bus_error(element: *mut GstElement) -> Option<Error>{
+    let bus = gst_element_get_bus(element);
+    if bus == ptr::null_mut(){
+        return None;
+    }
+    let message = gst_bus_pop_filtered(bus, GST_MESSAGE_ERROR);
+    gst_object_unref(bus as gpointer);
+    if message == ptr::null_mut(){
+        return None;
+    }
+    let mut error = ptr::null_mut::<GError>();
+    let mut debug = ptr::null_mut::<gchar>();
+    gst_message_parse_error(message, &mut error, &mut debug);
+    g_free(debug as gpointer);
+    gst_mini_object_unref(message as *mut GstMiniObject);
+    Some(Error::new_from_g_error(error))
+}
+
+/// The position as 0..1 of the duration, shared with CachedPosition
+pub(crate) fn position_pct(pos: Option<i64>, dur: Option<i64>) -> result::Result<f64, PositionError>{
+    let pos = pos.ok_or(PositionError::UnknownPosition)?;
+    match dur{
+        Some(dur) if dur > 0 => Ok((pos as f64 / dur as f64).max(0.0).min(1.0)),
+        _ => Err(PositionError::UnknownDuration)
+    }
+}
+
+// The segment received by the first sink that has one, the element itself
+// if it's not a bin
+unsafe fn sink_segment(element: *mut GstElement) -> Option<GstSegment>{
+    let sinks = if g_type_check_instance_is_a(element as *mut GTypeInstance, gst_bin_get_type()) != 0{
+        iterator_objects(gst_bin_iterate_sinks(element as *mut GstBin))
+    }else{
+        vec![gst_object_ref(element as gpointer)]
+    };
+    let mut segment = None;
+    for sink in sinks{
+        if segment.is_none(){
+            for pad in iterator_objects(gst_element_iterate_sink_pads(sink as *mut GstElement)){
+                let event = gst_pad_get_sticky_event(pad as *mut GstPad, GST_EVENT_SEGMENT, 0);
+                if event != ptr::null_mut() && segment.is_none(){
+                    let mut sink_segment = ptr::null();
+                    gst_event_parse_segment(event, &mut sink_segment);
+                    segment = Some(*sink_segment);
+                }
+                if event != ptr::null_mut(){
+                    gst_mini_object_unref(event as *mut GstMiniObject);
+                }
+                gst_object_unref(pad);
+            }
+        }
+        gst_object_unref(sink);
+    }
+    segment
+}
 
 impl ElementT for Element{
     fn as_element(&self) -> &Element{
@@ -363,6 +754,120 @@ impl ElementT for Element{
         }
     }
 
+    fn factory_name(&self) -> Option<String>{
+        unsafe{
+            let factory = gst_element_get_factory(self.gst_element() as *mut GstElement);
+            if factory != ptr::null_mut(){
+                let c_str_name = gst_object_get_name(factory as *mut GstObject);
+                let name = from_c_str!(c_str_name).to_string();
+                g_free(c_str_name as gpointer);
+                Some(name)
+            }else{
+                None
+            }
+        }
+    }
+
+    fn static_pad(&self, name: &str) -> Option<Pad>{
+        unsafe{
+            Pad::new(gst_element_get_static_pad(self.gst_element() as *mut GstElement, to_c_str!(name)), true)
+        }
+    }
+
+    fn pads(&self) -> Vec<Pad>{
+        unsafe{
+            iterator_objects(gst_element_iterate_pads(self.gst_element() as *mut GstElement)).into_iter()
+                .filter_map(|pad| Pad::new(pad as *mut GstPad, true))
+                .collect()
+        }
+    }
+
+    fn property(&self, name: &str) -> Option<Value>{
+        unsafe{
+            let pspec = self.find_property(name);
+            if pspec == ptr::null_mut(){
+                return None;
+            }
+            let mut gvalue: GValue = mem::zeroed();
+            g_value_init(&mut gvalue, (*pspec).value_type);
+            g_object_get_property(self.gst_element() as *mut GObject, to_c_str!(name), &mut gvalue);
+            let value = Value::from_gvalue(&gvalue);
+            g_value_unset(&mut gvalue);
+            value
+        }
+    }
+
+    fn set_property(&mut self, name: &str, value: &Value) -> Result<()>{
+        unsafe{
+            let pspec = self.find_property(name);
+            if pspec == ptr::null_mut(){
+                return Err(Error::new(gst_core_error_quark(), GST_CORE_ERROR_FAILED as i32,
+                    &format!("{} has no property {}", self.name(), name)));
+            }
+            let mut src = match value.to_gvalue(){
+                Some(src) => src,
+                None => return Err(Error::new(gst_core_error_quark(), GST_CORE_ERROR_FAILED as i32,
+                    &format!("invalid value {:?} for property {} of {}", value, name, self.name())))
+            };
+            let mut dest: GValue = mem::zeroed();
+            g_value_init(&mut dest, (*pspec).value_type);
+            let converted = match *value{
+                Value::String(ref string) if src.g_type != dest.g_type =>
+                    gst_value_deserialize(&mut dest, to_c_str!(string.as_str())) == 1,
+                _ => g_value_transform(&src, &mut dest) == 1
+            };
+            if converted{
+                g_object_set_property(self.gst_element_mut() as *mut GObject, to_c_str!(name), &dest);
+            }
+            g_value_unset(&mut src);
+            g_value_unset(&mut dest);
+            if converted{
+                Ok(())
+            }else{
+                Err(Error::new(gst_core_error_quark(), GST_CORE_ERROR_FAILED as i32,
+                    &format!("can't convert {:?} to the type of property {} of {}", value, name, self.name())))
+            }
+        }
+    }
+
+    fn properties_snapshot(&self) -> Structure{
+        unsafe{
+            let class = (*(self.element as *mut GTypeInstance)).g_class;
+            let mut snapshot = Structure::new(from_c_str!(g_type_name((*class).g_type))).unwrap();
+            let mut n_properties = 0;
+            let pspecs = g_object_class_list_properties(class as *mut GObjectClass, &mut n_properties);
+            for i in 0..n_properties as isize{
+                let pspec = *pspecs.offset(i);
+                let flags = (*pspec).flags as i32;
+                // name and parent of GstObject aren't settings
+                if flags & G_PARAM_READWRITE != G_PARAM_READWRITE ||
+                    flags & (G_PARAM_CONSTRUCT_ONLY | G_PARAM_DEPRECATED) != 0 ||
+                    (*pspec).owner_type == gst_object_get_type(){
+                    continue;
+                }
+                let name = from_c_str!((*pspec).name);
+                if let Some(value) = self.property(name){
+                    snapshot.set(name, value);
+                }
+            }
+            g_free(pspecs as gpointer);
+            snapshot
+        }
+    }
+
+    fn apply_snapshot(&mut self, snapshot: &Structure) -> Result<()>{
+        let failed = snapshot.fields().into_iter()
+            .filter(|&(ref name, ref value)| self.set_property(name, value).is_err())
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        if failed.is_empty(){
+            Ok(())
+        }else{
+            Err(Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_FAILED as i32,
+                &format!("couldn't restore {} of {}", failed.join(", "), self.name())))
+        }
+    }
+
     fn set_state(&mut self, state: GstState) -> GstStateChangeReturn{
         unsafe{
             gst_element_set_state(self.gst_element_mut(), state)
@@ -378,10 +883,48 @@ impl ElementT for Element{
         }
     }
 
+    fn set_state_with_timeout(&mut self, state: GstState, timeout: GstClockTime) -> result::Result<GstStateChangeReturn, StateChangeError>{
+        let ret = match self.set_state(state){
+            GST_STATE_CHANGE_ASYNC => {
+                let (current, pending, ret) = self.get_state(timeout);
+                if ret == GST_STATE_CHANGE_ASYNC{
+                    return Err(StateChangeError::Timeout{
+                        target: state,
+                        current: current,
+                        pending: pending,
+                        stuck: unsafe{ pending_children(self.element) },
+                    });
+                }
+                ret
+            }
+            ret => ret
+        };
+        if ret == GST_STATE_CHANGE_FAILURE{
+            Err(StateChangeError::Failed(unsafe{ pop_bus_error(self.element) }))
+        }else{
+            Ok(ret)
+        }
+    }
+
     unsafe fn send_event(&mut self, event: *mut GstEvent) -> bool{
         gst_element_send_event(self.gst_element_mut(), event) == 1
     }
 
+    fn flush(&mut self, timeout: GstClockTime) -> bool{
+        unsafe{
+            if !self.send_event(gst_event_new_flush_start()){
+                return false;
+            }
+            if !self.send_event(gst_event_new_flush_stop(1)){
+                return false;
+            }
+        }
+        match self.get_state(timeout).2{
+            GST_STATE_CHANGE_FAILURE | GST_STATE_CHANGE_ASYNC => false,
+            _ => true
+        }
+    }
+
     fn seek_simple(&mut self, format: GstFormat, flags: GstSeekFlags, pos: i64) -> bool{
         unsafe{
             gst_element_seek_simple(self.gst_element_mut(), format, flags, pos) == 1
@@ -432,14 +975,8 @@ impl ElementT for Element{
         self.query_position(GST_FORMAT_TIME)
     }
 
-    fn position_pct(&self) -> Option<f64>{
-        let pos = self.position_ns();
-        let dur = self.duration_ns();
-        if dur.is_some() && pos.is_some(){
-            Some( pos.unwrap() as f64 / dur.unwrap() as f64 )
-        }else{
-            None
-        }
+    fn position_pct(&self) -> result::Result<f64, PositionError>{
+        position_pct(self.position_ns(), self.duration_ns())
     }
 
     fn position_s(&self) -> Option<f64>{
@@ -450,6 +987,22 @@ impl ElementT for Element{
         }
     }
 
+    fn position_bytes(&self) -> Option<Bytes>{
+        self.query_position(GST_FORMAT_BYTES).filter(|pos| *pos >= 0).map(|pos| Bytes(pos as u64))
+    }
+
+    fn duration_bytes(&self) -> Option<Bytes>{
+        self.query_duration(GST_FORMAT_BYTES).filter(|dur| *dur >= 0).map(|dur| Bytes(dur as u64))
+    }
+
+    fn position_frames(&self) -> Option<Frames>{
+        self.query_position(GST_FORMAT_DEFAULT).filter(|pos| *pos >= 0).map(|pos| Frames(pos as u64))
+    }
+
+    fn duration_frames(&self) -> Option<Frames>{
+        self.query_duration(GST_FORMAT_DEFAULT).filter(|dur| *dur >= 0).map(|dur| Frames(dur as u64))
+    }
+
     fn set_position_ns(&mut self, ns: i64) -> bool{
         let format = GST_FORMAT_TIME;
 	    let flags = GST_SEEK_FLAG_FLUSH; // | GST_SEEK_FLAG_ACCURATE |
@@ -460,12 +1013,28 @@ impl ElementT for Element{
         self.set_position_ns(s_to_ns(s) as i64)
     }
 
-    fn set_position_pct(&mut self, pct: f64) -> bool{
-        let dur = self.duration_ns();
-        match dur{
-            Some(t) =>  self.set_position_ns((t as f64 * pct) as i64),
-            None => false
+    fn set_position_pct(&mut self, pct: f64) -> result::Result<(), PositionError>{
+        if pct.is_nan(){
+            return Err(PositionError::InvalidPct);
         }
+        let dur = match self.duration_ns(){
+            Some(dur) if dur > 0 => dur,
+            _ => return Err(PositionError::UnknownDuration)
+        };
+        let pos = (dur as f64 * pct.max(0.0).min(1.0)) as i64;
+        let speed = self.speed().unwrap_or(1.0);
+        let seeked = if speed < 0.0{
+            // seek_simple would reset the rate to 1.0, playing backwards
+            // the segment ends at the position
+            self.seek(speed, GST_FORMAT_TIME, GST_SEEK_FLAG_FLUSH | GST_SEEK_FLAG_ACCURATE,
+                GST_SEEK_TYPE_SET, 0, GST_SEEK_TYPE_SET, pos)
+        }else if speed != 1.0{
+            self.seek(speed, GST_FORMAT_TIME, GST_SEEK_FLAG_FLUSH | GST_SEEK_FLAG_ACCURATE,
+                GST_SEEK_TYPE_SET, pos, GST_SEEK_TYPE_SET, -1)
+        }else{
+            self.set_position_ns(pos)
+        };
+        if seeked{ Ok(()) }else{ Err(PositionError::SeekFailed) }
     }
 
     fn set_speed(&mut self, speed: f64) -> bool{
@@ -499,6 +1068,54 @@ impl ElementT for Element{
         }
     }
 
+    fn speed(&self) -> Option<f64>{
+        unsafe{
+            let query = gst_query_new_segment(GST_FORMAT_TIME);
+            let speed = if gst_element_query(self.element, query) == 1{
+                let mut rate = 0.0;
+                gst_query_parse_segment(query, &mut rate, ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
+                Some(rate)
+            }else{
+                None
+            };
+            gst_mini_object_unref(query as *mut GstMiniObject);
+            speed
+        }
+    }
+
+    fn seekable_range(&self) -> Option<(u64, u64)>{
+        unsafe{
+            let query = gst_query_new_seeking(GST_FORMAT_TIME);
+            let mut range = None;
+            if gst_element_query(self.element, query) == 1{
+                let mut seekable = 0;
+                let mut start = -1;
+                let mut end = -1;
+                gst_query_parse_seeking(query, ptr::null_mut(), &mut seekable, &mut start, &mut end);
+                if seekable == 1{
+                    // the end is unknown for some elements, seeking is
+                    // possible up to the duration
+                    let end = if end >= 0{ Some(end) }else{ self.duration_ns().filter(|dur| *dur > 0) };
+                    range = end.map(|end| (start.max(0) as u64, end as u64));
+                }
+            }
+            gst_mini_object_unref(query as *mut GstMiniObject);
+            range
+        }
+    }
+
+    fn buffering_status(&self) -> Option<BufferingStatus>{
+        unsafe{
+            query_buffering(self.element)
+        }
+    }
+
+    fn applied_rate(&self) -> Option<f64>{
+        unsafe{
+            sink_segment(self.element).map(|segment| segment.applied_rate)
+        }
+    }
+
     unsafe fn gst_element(&self) -> *const GstElement{
         self.element
     }
@@ -562,10 +1179,57 @@ impl ElementT for Element{
     }
 }
 
+/// Formats the element as its name
+impl Display for Element{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "{}", self.name())
+    }
+}
+
+/// Shows the name, factory and current state of the element. The state
+/// is queried without blocking so an ongoing async state change shows up
+/// as pending.
+impl Debug for Element{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "Element {{ name: \"{}\"", self.name())?;
+        if let Some(factory) = self.factory_name(){
+            write!(fmt, ", factory: \"{}\"", factory)?;
+        }
+        let (state, pending, _) = self.get_state(0);
+        write!(fmt, ", state: {:?}", state)?;
+        match pending{
+            GST_STATE_VOID_PENDING => (),
+            pending => write!(fmt, ", pending: {:?}", pending)?
+        }
+        write!(fmt, " }}")
+    }
+}
+
 impl ::Transfer for Element{
     unsafe fn transfer(self) -> *mut GstElement{
         let element = self.element;
+        track_release(element);
         mem::forget(self);
         element
     }
 }
+
+/// Creates an element and sets its properties in one go, returning a
+/// Result<Element>:
+///
+/// ```ignore
+/// let encoder = element!("x264enc", bitrate=2048, tune="zerolatency")?;
+/// ```
+///
+/// Values can be of any type convertible into a Value and are converted
+/// to the type of the property when set. Property names with dashes are
+/// written with underscores, as in `is_live=true`.
+#[macro_export]
+macro_rules! element{
+    ($factory: expr) => (
+        $crate::Element::with_properties($factory, &[])
+    );
+    ($factory: expr, $($property: ident = $value: expr),+ $(,)*) => (
+        $crate::Element::with_properties($factory, &[$((stringify!($property), $crate::Value::from($value))),+])
+    );
+}
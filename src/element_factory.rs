@@ -0,0 +1,157 @@
+use ffi::*;
+use util::*;
+use element::Element;
+
+use std::os::raw::c_void;
+use std::fmt::{self, Debug, Formatter};
+
+unsafe impl Sync for ElementFactory {}
+unsafe impl Send for ElementFactory {}
+
+/// A factory registered by a plugin, used to create elements and to
+/// inspect which elements are available
+pub struct ElementFactory{
+    factory: *mut GstElementFactory
+}
+
+impl Drop for ElementFactory{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.factory as *mut c_void);
+        }
+    }
+}
+
+impl Clone for ElementFactory{
+    fn clone(&self) -> ElementFactory{
+        unsafe{
+            gst_object_ref(self.factory as *mut c_void);
+            ElementFactory{ factory: self.factory }
+        }
+    }
+}
+
+impl ElementFactory{
+    /// Finds the factory with the given name, as in "videotestsrc"
+    pub fn find(name: &str) -> Option<ElementFactory>{
+        unsafe{
+            ElementFactory::new(gst_element_factory_find(to_c_str!(name)), true)
+        }
+    }
+
+    /// Wraps a raw GstElementFactory, if owned is false a new reference
+    /// is taken
+    pub unsafe fn new(factory: *mut GstElementFactory, owned: bool) -> Option<ElementFactory>{
+        if factory != ptr::null_mut(){
+            if !owned{
+                gst_object_ref(factory as *mut c_void);
+            }
+            Some(ElementFactory{ factory: factory })
+        }else{
+            None
+        }
+    }
+
+    /// Returns all the element factories in the registry
+    pub fn all() -> Vec<ElementFactory>{
+        unsafe{
+            let list = gst_registry_get_feature_list(gst_registry_get(), gst_element_factory_get_type());
+            let mut factories = Vec::new();
+            let mut node = list;
+            while node != ptr::null_mut(){
+                if let Some(factory) = ElementFactory::new((*node).data as *mut GstElementFactory, false){
+                    factories.push(factory);
+                }
+                node = (*node).next;
+            }
+            gst_plugin_feature_list_free(list);
+            factories
+        }
+    }
+
+    /// Returns the factories whose klass contains all the passed
+    /// components, e.g. "Visualization" or "Sink/Video", sorted by rank
+    pub fn list_by_klass(klass: &str) -> Vec<ElementFactory>{
+        let components: Vec<&str> = klass.split('/').collect();
+        let mut factories: Vec<ElementFactory> = ElementFactory::all().into_iter()
+            .filter(|factory| {
+                let factory_klass = factory.klass();
+                components.iter().all(|component| factory_klass.split('/').any(|c| c == *component))
+            })
+            .collect();
+        factories.sort_by(|a, b| b.rank().cmp(&a.rank()));
+        factories
+    }
+
+    pub fn name(&self) -> String{
+        unsafe{
+            let c_str_name = gst_object_get_name(self.factory as *mut GstObject);
+            let name = from_c_str!(c_str_name).to_string();
+            g_free(c_str_name as gpointer);
+            name
+        }
+    }
+
+    /// Human readable name, as in "Video test source"
+    pub fn long_name(&self) -> String{
+        self.metadata("long-name").unwrap_or_else(|| self.name())
+    }
+
+    /// Category of the element, as in "Source/Video"
+    pub fn klass(&self) -> String{
+        self.metadata("klass").unwrap_or_default()
+    }
+
+    pub fn description(&self) -> String{
+        self.metadata("description").unwrap_or_default()
+    }
+
+    pub fn metadata(&self, key: &str) -> Option<String>{
+        unsafe{
+            let value = gst_element_factory_get_metadata(self.factory, to_c_str!(key));
+            if value != ptr::null(){
+                Some(from_c_str!(value).to_string())
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn rank(&self) -> u32{
+        unsafe{
+            gst_plugin_feature_get_rank(self.factory as *mut GstPluginFeature)
+        }
+    }
+
+    /// Creates a new element from this factory, if name is empty a unique
+    /// name is generated
+    pub fn create(&self, name: &str) -> Option<Element>{
+        unsafe{
+            let name = if name != "" {
+                Some(CString::new(name).unwrap())
+            } else {
+                None
+            };
+            let element = gst_element_factory_create(self.factory,
+                name.as_ref().map(|name| name.as_ptr()).unwrap_or(ptr::null()));
+            if element != ptr::null_mut(){
+                gst_object_ref_sink(element as gpointer);
+            }
+            Element::new_from_gst_element(element)
+        }
+    }
+
+    pub unsafe fn gst_element_factory(&self) -> *const GstElementFactory{
+        self.factory
+    }
+
+    pub unsafe fn gst_element_factory_mut(&mut self) -> *mut GstElementFactory{
+        self.factory
+    }
+}
+
+impl Debug for ElementFactory{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "ElementFactory {{ name: \"{}\", klass: \"{}\", rank: {} }}", self.name(), self.klass(), self.rank())
+    }
+}
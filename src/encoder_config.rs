@@ -0,0 +1,241 @@
+use ffi::*;
+use util::*;
+use bin::{Bin, BinT};
+use caps::Caps;
+use element::{Element, ElementT};
+use element_factory::ElementFactory;
+use value::Value;
+
+/// Profile of an H.264 stream, the decoders that can play it depend on it
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum H264Profile{
+    /// Supported by every decoder, including old mobile devices
+    Baseline,
+    Main,
+    /// The best compression, supported by all current decoders
+    High,
+}
+
+impl H264Profile{
+    fn caps_name(&self) -> &'static str{
+        match *self{
+            H264Profile::Baseline => "constrained-baseline",
+            H264Profile::Main => "main",
+            H264Profile::High => "high",
+        }
+    }
+}
+
+/// The configuration of an encoder independent of the element used, so
+/// the same code works on machines with different plugins. Bitrates are
+/// in kbit/s for every encoder, even if the element uses bit/s.
+///
+/// ```ignore
+/// let config = EncoderConfig::h264(4000);
+/// let encoder = config.create_encoder("encoder").unwrap();
+/// ```
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum EncoderConfig{
+    H264{
+        bitrate: u32,
+        profile: H264Profile,
+        /// Encodes each frame as soon as it arrives, without B-frames or
+        /// lookahead, for live streaming and video calls
+        low_latency: bool,
+        /// Maximum number of frames between keyframes, None for the
+        /// default of the encoder
+        keyframe_interval: Option<u32>,
+    },
+    Vp9{
+        bitrate: u32,
+        low_latency: bool,
+        keyframe_interval: Option<u32>,
+    },
+    Aac{
+        bitrate: u32,
+    },
+    Opus{
+        bitrate: u32,
+    },
+}
+
+// The elements that can encode each format, in order of preference
+const H264_ENCODERS: &'static [&'static str] = &["x264enc", "nvh264enc", "vaapih264enc", "vtenc_h264", "mfh264enc"];
+const VP9_ENCODERS: &'static [&'static str] = &["vp9enc", "vaapivp9enc"];
+const AAC_ENCODERS: &'static [&'static str] = &["fdkaacenc", "avenc_aac", "voaacenc", "faac", "atenc"];
+const OPUS_ENCODERS: &'static [&'static str] = &["opusenc"];
+
+impl EncoderConfig{
+    /// H.264 with the high profile
+    pub fn h264(bitrate: u32) -> EncoderConfig{
+        EncoderConfig::H264{ bitrate: bitrate, profile: H264Profile::High, low_latency: false, keyframe_interval: None }
+    }
+
+    pub fn vp9(bitrate: u32) -> EncoderConfig{
+        EncoderConfig::Vp9{ bitrate: bitrate, low_latency: false, keyframe_interval: None }
+    }
+
+    pub fn aac(bitrate: u32) -> EncoderConfig{
+        EncoderConfig::Aac{ bitrate: bitrate }
+    }
+
+    pub fn opus(bitrate: u32) -> EncoderConfig{
+        EncoderConfig::Opus{ bitrate: bitrate }
+    }
+
+    pub fn is_video(&self) -> bool{
+        match *self{
+            EncoderConfig::H264{..} | EncoderConfig::Vp9{..} => true,
+            _ => false
+        }
+    }
+
+    /// Caps of the encoded stream, as used by encoding profiles and
+    /// muxers
+    pub fn caps(&self) -> Caps{
+        let caps = match *self{
+            EncoderConfig::H264{ profile, .. } =>
+                format!("video/x-h264, stream-format=(string)avc, alignment=(string)au, profile=(string){}", profile.caps_name()),
+            EncoderConfig::Vp9{..} => "video/x-vp9".to_string(),
+            EncoderConfig::Aac{..} => "audio/mpeg, mpegversion=(int)4".to_string(),
+            EncoderConfig::Opus{..} => "audio/x-opus".to_string(),
+        };
+        Caps::from_string(&caps).unwrap()
+    }
+
+    /// The elements that can encode this format, in order of preference
+    pub fn encoder_factories(&self) -> &'static [&'static str]{
+        match *self{
+            EncoderConfig::H264{..} => H264_ENCODERS,
+            EncoderConfig::Vp9{..} => VP9_ENCODERS,
+            EncoderConfig::Aac{..} => AAC_ENCODERS,
+            EncoderConfig::Opus{..} => OPUS_ENCODERS,
+        }
+    }
+
+    /// Name of the first encoder available on this machine
+    pub fn available_factory(&self) -> Option<&'static str>{
+        self.encoder_factories().iter().cloned().find(|factory| ElementFactory::find(factory).is_some())
+    }
+
+    /// Creates the first encoder available, configured with this
+    /// configuration, followed by a capsfilter for the profile. H.264
+    /// encoders are followed by h264parse too, which converts the output
+    /// of encoders that only produce byte-stream. Returns a bin with a
+    /// sink and a src pad, None if no encoder is available
+    pub fn create_encoder(&self, name: &str) -> Option<Bin>{
+        let factory = self.available_factory()?;
+        let encoder = Element::new(factory, "")?;
+        self.configure(factory, &encoder);
+
+        let mut bin = Bin::new(name)?;
+        let mut elements = vec![encoder];
+        if let EncoderConfig::H264{..} = *self{
+            elements.push(Element::new("h264parse", "")?);
+        }
+        let capsfilter = Element::new("capsfilter", "")?;
+        capsfilter.set("caps", unsafe{ self.caps().gst_caps() });
+        elements.push(capsfilter);
+
+        let mut refs = elements.iter().map(|element| element.to_element()).collect::<Vec<_>>();
+        for element in elements{
+            if !bin.add(element){
+                return None;
+            }
+        }
+        for i in 1..refs.len(){
+            let (prev, next) = refs.split_at_mut(i);
+            if !prev[i - 1].link(&mut next[0]){
+                return None;
+            }
+        }
+        let sink = refs.first()?.static_pad("sink")?;
+        let src = refs.last()?.static_pad("src")?;
+        if !bin.add_ghost_pad("sink", &sink) || !bin.add_ghost_pad("src", &src){
+            return None;
+        }
+        Some(bin)
+    }
+
+    /// Sets the properties of encoder, created from factory, that
+    /// implement this configuration. Properties the element doesn't
+    /// have in the installed version are skipped
+    pub fn configure<E: ElementT>(&self, factory: &str, encoder: &E){
+        let mut properties: Vec<(&str, Value)> = Vec::new();
+        match (*self, factory){
+            (EncoderConfig::H264{ bitrate, low_latency, keyframe_interval, .. }, "x264enc") => {
+                properties.push(("bitrate", Value::UInt(bitrate)));
+                if low_latency{
+                    properties.push(("tune", Value::String("zerolatency".to_string())));
+                }
+                if let Some(interval) = keyframe_interval{
+                    properties.push(("key-int-max", Value::UInt(interval)));
+                }
+            }
+            (EncoderConfig::H264{ bitrate, low_latency, keyframe_interval, .. }, "nvh264enc") => {
+                properties.push(("bitrate", Value::UInt(bitrate)));
+                if low_latency{
+                    properties.push(("zerolatency", Value::Bool(true)));
+                    properties.push(("bframes", Value::UInt(0)));
+                }
+                if let Some(interval) = keyframe_interval{
+                    properties.push(("gop-size", Value::Int(interval as i32)));
+                }
+            }
+            (EncoderConfig::H264{ bitrate, low_latency, keyframe_interval, .. }, "vaapih264enc") => {
+                properties.push(("bitrate", Value::UInt(bitrate)));
+                if low_latency{
+                    properties.push(("max-bframes", Value::UInt(0)));
+                }
+                if let Some(interval) = keyframe_interval{
+                    properties.push(("keyframe-period", Value::UInt(interval)));
+                }
+            }
+            (EncoderConfig::H264{ bitrate, low_latency, keyframe_interval, .. }, _) => {
+                properties.push(("bitrate", Value::UInt(bitrate)));
+                if low_latency{
+                    properties.push(("realtime", Value::Bool(true)));
+                }
+                if let Some(interval) = keyframe_interval{
+                    properties.push(("max-keyframe-interval", Value::Int(interval as i32)));
+                }
+            }
+            (EncoderConfig::Vp9{ bitrate, low_latency, keyframe_interval }, "vp9enc") => {
+                properties.push(("target-bitrate", Value::Int(bitrate as i32 * 1000)));
+                if low_latency{
+                    // VPX_DL_REALTIME
+                    properties.push(("deadline", Value::Int64(1)));
+                    properties.push(("lag-in-frames", Value::Int(0)));
+                }
+                if let Some(interval) = keyframe_interval{
+                    properties.push(("keyframe-max-dist", Value::Int(interval as i32)));
+                }
+            }
+            (EncoderConfig::Vp9{ bitrate, keyframe_interval, .. }, _) => {
+                properties.push(("bitrate", Value::UInt(bitrate)));
+                if let Some(interval) = keyframe_interval{
+                    properties.push(("keyframe-period", Value::UInt(interval)));
+                }
+            }
+            (EncoderConfig::Aac{ bitrate }, _) | (EncoderConfig::Opus{ bitrate }, _) => {
+                properties.push(("bitrate", Value::Int(bitrate as i32 * 1000)));
+            }
+        }
+        let mut element = encoder.as_element().to_element();
+        for (name, value) in properties{
+            let _ = element.set_property(name, &value);
+        }
+    }
+}
+
+/// Loads a preset saved for element, as the ones shipped with some
+/// encoders or saved by gst-inspect. Returns false if the element doesn't
+/// support presets or has no preset with that name
+pub fn load_preset<E: ElementT>(element: &E, preset: &str) -> bool{
+    unsafe{
+        if g_type_check_instance_is_a(element.gst_element() as *mut GTypeInstance, gst_preset_get_type()) == 0{
+            return false;
+        }
+        gst_preset_load_preset(element.gst_element() as *mut GstPreset, to_c_str!(preset)) == 1
+    }
+}
@@ -0,0 +1,160 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use encoder_config::EncoderConfig;
+
+pub enum GstEncodingProfile{}
+
+// The encoding profiles are part of gst-pbutils, which isn't linked by
+// default
+#[cfg_attr(not(target_os="macos"), link(name = "gstpbutils-1.0"))]
+extern "C"{
+    fn gst_encoding_container_profile_get_type() -> GType;
+    fn gst_encoding_container_profile_new(name: *const gchar, description: *const gchar,
+        format: *mut GstCaps, preset: *const gchar) -> *mut GstEncodingProfile;
+    fn gst_encoding_video_profile_new(format: *mut GstCaps, preset: *const gchar,
+        restriction: *mut GstCaps, presence: guint) -> *mut GstEncodingProfile;
+    fn gst_encoding_audio_profile_new(format: *mut GstCaps, preset: *const gchar,
+        restriction: *mut GstCaps, presence: guint) -> *mut GstEncodingProfile;
+    fn gst_encoding_container_profile_add_profile(container: *mut GstEncodingProfile,
+        profile: *mut GstEncodingProfile) -> gboolean;
+    fn gst_encoding_profile_get_format(profile: *mut GstEncodingProfile) -> *mut GstCaps;
+    fn gst_encoding_profile_set_restriction(profile: *mut GstEncodingProfile, restriction: *mut GstCaps);
+    fn gst_encoding_profile_set_preset_name(profile: *mut GstEncodingProfile, preset_name: *const gchar);
+}
+
+/// Describes the streams encodebin, transcodebin or Transcoder produce:
+/// a container with the format of the muxer and a profile for each
+/// encoded stream inside it.
+///
+/// ```ignore
+/// let mut profile = EncodingProfile::container(&Caps::from_string("video/quicktime").unwrap());
+/// profile.add_profile(EncodingProfile::from_encoder_config(&EncoderConfig::h264(4000)));
+/// profile.add_profile(EncodingProfile::from_encoder_config(&EncoderConfig::aac(128)));
+/// ```
+pub struct EncodingProfile{
+    profile: *mut GstEncodingProfile
+}
+
+unsafe impl Sync for EncodingProfile {}
+unsafe impl Send for EncodingProfile {}
+
+impl Drop for EncodingProfile{
+    fn drop(&mut self){
+        unsafe{
+            g_object_unref(self.profile as gpointer);
+        }
+    }
+}
+
+impl Clone for EncodingProfile{
+    fn clone(&self) -> EncodingProfile{
+        unsafe{
+            EncodingProfile::new(self.profile, false).unwrap()
+        }
+    }
+}
+
+impl EncodingProfile{
+    /// Wraps a raw GstEncodingProfile, if owned is false a new reference
+    /// is taken
+    pub unsafe fn new(profile: *mut GstEncodingProfile, owned: bool) -> Option<EncodingProfile>{
+        if profile != ptr::null_mut(){
+            if !owned{
+                g_object_ref(profile as gpointer);
+            }
+            Some(EncodingProfile{ profile: profile })
+        }else{
+            None
+        }
+    }
+
+    /// A container, as video/quicktime or video/x-matroska, without
+    /// streams until they're added with add_profile
+    pub fn container(format: &Caps) -> EncodingProfile{
+        unsafe{
+            EncodingProfile::new(gst_encoding_container_profile_new(ptr::null(), ptr::null(),
+                ::Transfer::transfer(format.clone()), ptr::null()), true).unwrap()
+        }
+    }
+
+    /// A video stream encoded to format, by any encoder that produces it
+    pub fn video(format: &Caps) -> EncodingProfile{
+        unsafe{
+            EncodingProfile::new(gst_encoding_video_profile_new(::Transfer::transfer(format.clone()),
+                ptr::null(), ptr::null_mut(), 0), true).unwrap()
+        }
+    }
+
+    /// An audio stream encoded to format, by any encoder that produces it
+    pub fn audio(format: &Caps) -> EncodingProfile{
+        unsafe{
+            EncodingProfile::new(gst_encoding_audio_profile_new(::Transfer::transfer(format.clone()),
+                ptr::null(), ptr::null_mut(), 0), true).unwrap()
+        }
+    }
+
+    /// A stream with the caps of config, encoded by the encoder the config
+    /// would pick. The properties of the config are only applied by
+    /// Transcoder, encodebin used directly uses the defaults of the
+    /// encoder
+    pub fn from_encoder_config(config: &EncoderConfig) -> EncodingProfile{
+        let mut profile = if config.is_video(){
+            EncodingProfile::video(&config.caps())
+        }else{
+            EncodingProfile::audio(&config.caps())
+        };
+        if let Some(factory) = config.available_factory(){
+            profile.set_preset_name(factory);
+        }
+        profile
+    }
+
+    /// Adds a stream to a container profile, returns false if this isn't
+    /// a container or it already has an equivalent stream
+    pub fn add_profile(&mut self, profile: EncodingProfile) -> bool{
+        unsafe{
+            self.is_container() &&
+                gst_encoding_container_profile_add_profile(self.profile, profile.transfer()) == 1
+        }
+    }
+
+    pub fn is_container(&self) -> bool{
+        unsafe{
+            g_type_check_instance_is_a(self.profile as *mut GTypeInstance, gst_encoding_container_profile_get_type()) != 0
+        }
+    }
+
+    /// The caps of the encoded stream or the container
+    pub fn format(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(gst_encoding_profile_get_format(self.profile), true)
+        }
+    }
+
+    /// Caps of the raw stream before the encoder, as a resolution or a
+    /// sample rate. The stream is converted to them
+    pub fn set_restriction(&mut self, restriction: &Caps){
+        unsafe{
+            gst_encoding_profile_set_restriction(self.profile, ::Transfer::transfer(restriction.clone()));
+        }
+    }
+
+    /// Name of the factory of the encoder or muxer to use, instead of any
+    /// that produces the format
+    pub fn set_preset_name(&mut self, factory: &str){
+        unsafe{
+            gst_encoding_profile_set_preset_name(self.profile, to_c_str!(factory));
+        }
+    }
+
+    pub unsafe fn gst_encoding_profile(&self) -> *mut GstEncodingProfile{
+        self.profile
+    }
+
+    unsafe fn transfer(self) -> *mut GstEncodingProfile{
+        let profile = self.profile;
+        mem::forget(self);
+        profile
+    }
+}
@@ -0,0 +1,114 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+
+/// Wrapper around equalizer-nbands, an equalizer with a configurable
+/// number of bands. Each band can be adjusted in gain, center frequency
+/// and bandwidth.
+///
+/// It can be used as audio filter of a PlayBin or as any other element.
+pub struct Equalizer{
+    equalizer: Element
+}
+
+unsafe impl Sync for Equalizer {}
+unsafe impl Send for Equalizer {}
+
+impl Equalizer{
+    /// Creates a new equalizer with the given number of bands, from 1 to
+    /// 64, spread logarithmically over the audible range
+    pub fn new(name: &str, num_bands: u32) -> Option<Equalizer>{
+        Element::new("equalizer-nbands", name).map(|equalizer| {
+            equalizer.set("num-bands", num_bands);
+            Equalizer{ equalizer: equalizer }
+        })
+    }
+
+    pub fn new_from_element(element: Element) -> Equalizer{
+        Equalizer{ equalizer: element }
+    }
+
+    pub fn num_bands(&self) -> u32{
+        unsafe{
+            gst_child_proxy_get_children_count(self.equalizer.gst_element() as *mut GstChildProxy)
+        }
+    }
+
+    /// Sets the gain of the band in dB, from -24 to +12
+    pub fn set_band_gain(&self, band: u32, gain: f64) -> bool{
+        self.set_band_property(band, "gain", gain)
+    }
+
+    pub fn band_gain(&self, band: u32) -> Option<f64>{
+        self.band_property(band, "gain")
+    }
+
+    /// Sets the center frequency of the band in Hz
+    pub fn set_band_freq(&self, band: u32, freq: f64) -> bool{
+        self.set_band_property(band, "freq", freq)
+    }
+
+    pub fn band_freq(&self, band: u32) -> Option<f64>{
+        self.band_property(band, "freq")
+    }
+
+    /// Sets the width of the band in Hz
+    pub fn set_band_bandwidth(&self, band: u32, bandwidth: f64) -> bool{
+        self.set_band_property(band, "bandwidth", bandwidth)
+    }
+
+    pub fn band_bandwidth(&self, band: u32) -> Option<f64>{
+        self.band_property(band, "bandwidth")
+    }
+
+    /// Sets the gain of all the bands at once, bands beyond the number of
+    /// gains are left untouched
+    pub fn set_gains(&self, gains: &[f64]){
+        for (band, gain) in gains.iter().enumerate(){
+            self.set_band_gain(band as u32, *gain);
+        }
+    }
+
+    fn set_band_property(&self, band: u32, name: &str, value: f64) -> bool{
+        unsafe{
+            let child = gst_child_proxy_get_child_by_index(self.equalizer.gst_element() as *mut GstChildProxy, band);
+            if child != ptr::null_mut(){
+                g_object_set(child as gpointer, to_c_str!(name), value, ptr::null::<gchar>());
+                g_object_unref(child as gpointer);
+                true
+            }else{
+                false
+            }
+        }
+    }
+
+    fn band_property(&self, band: u32, name: &str) -> Option<f64>{
+        unsafe{
+            let child = gst_child_proxy_get_child_by_index(self.equalizer.gst_element() as *mut GstChildProxy, band);
+            if child != ptr::null_mut(){
+                let mut value: f64 = 0.0;
+                g_object_get(child as gpointer, to_c_str!(name), &mut value as *mut f64, ptr::null::<gchar>());
+                g_object_unref(child as gpointer);
+                Some(value)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+impl ElementT for Equalizer{
+    fn as_element(&self) -> &Element{
+        &self.equalizer
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.equalizer
+    }
+}
+
+impl ::Transfer for Equalizer{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.equalizer.transfer()
+    }
+}
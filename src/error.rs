@@ -10,12 +10,112 @@ pub struct Error{
     error: *mut GError
 }
 
+/// The GError domains of the errors and warnings posted by elements
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum ErrorDomain{
+	/// Errors of the GStreamer core, as failed state changes or
+	/// negotiation
+	Core,
+	/// Errors of the libraries used by the elements
+	Library,
+	/// Errors opening, reading or writing files, devices or network
+	/// resources
+	Resource,
+	/// Errors in the data, as corrupt or unsupported streams
+	Stream,
+}
+
+impl ErrorDomain{
+	/// The GQuark of the domain, as used in Error::new
+	pub fn quark(&self) -> u32{
+		unsafe{
+			match *self{
+				ErrorDomain::Core => gst_core_error_quark(),
+				ErrorDomain::Library => gst_library_error_quark(),
+				ErrorDomain::Resource => gst_resource_error_quark(),
+				ErrorDomain::Stream => gst_stream_error_quark(),
+			}
+		}
+	}
+
+	/// The domain of quark, None for domains not defined by GStreamer
+	pub fn from_quark(quark: u32) -> Option<ErrorDomain>{
+		[ErrorDomain::Core, ErrorDomain::Library, ErrorDomain::Resource, ErrorDomain::Stream].iter()
+			.find(|domain| domain.quark() == quark)
+			.cloned()
+	}
+}
+
+/// Class of failure of an Error, from its domain and code, to decide
+/// whether to retry or what to tell the user without matching messages
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum ErrorKind{
+	/// The file, device or URI doesn't exist
+	NotFound,
+	/// No permission to open the resource
+	NotAuthorized,
+	/// The device is in use by another application, retrying later may
+	/// work
+	Busy,
+	/// The disk is full while writing
+	NoSpaceLeft,
+	/// Any other failure opening, reading, writing or seeking a resource
+	Resource,
+	/// The elements couldn't agree on a format
+	NotNegotiated,
+	/// A demuxer or decoder found corrupt or unsupported data
+	Decode,
+	/// An encoder or muxer failed
+	Encode,
+	/// The stream is encrypted and can't be decrypted
+	Decrypt,
+	/// The type of the stream couldn't be found or there's no plugin to
+	/// handle it
+	MissingPlugin,
+	/// An element failed to change state
+	StateChange,
+	/// Errors of other domains or codes
+	Other,
+}
+
 impl Debug for Error{
 	fn fmt(&self, fmt: &mut Formatter) -> result::Result<(), fmt::Error>{
 		fmt.write_str(format!("gst::Error: domain: {}, code: {}, message: {}",self.domain(),self.code(),self.message()).as_ref())
 	}
 }
 
+impl ErrorKind{
+	/// The domain used when posting an error of this kind
+	pub fn domain(&self) -> ErrorDomain{
+		match *self{
+			ErrorKind::NotFound | ErrorKind::NotAuthorized | ErrorKind::Busy |
+				ErrorKind::NoSpaceLeft | ErrorKind::Resource => ErrorDomain::Resource,
+			ErrorKind::Decode | ErrorKind::Encode | ErrorKind::Decrypt => ErrorDomain::Stream,
+			ErrorKind::NotNegotiated | ErrorKind::MissingPlugin | ErrorKind::StateChange |
+				ErrorKind::Other => ErrorDomain::Core,
+		}
+	}
+
+	/// The code used when posting an error of this kind, in its domain
+	pub fn code(&self) -> i32{
+		let code = match *self{
+			ErrorKind::NotFound => GST_RESOURCE_ERROR_NOT_FOUND,
+			ErrorKind::NotAuthorized => GST_RESOURCE_ERROR_NOT_AUTHORIZED,
+			ErrorKind::Busy => GST_RESOURCE_ERROR_BUSY,
+			ErrorKind::NoSpaceLeft => GST_RESOURCE_ERROR_NO_SPACE_LEFT,
+			ErrorKind::Resource => GST_RESOURCE_ERROR_FAILED,
+			ErrorKind::NotNegotiated => GST_CORE_ERROR_NEGOTIATION,
+			ErrorKind::Decode => GST_STREAM_ERROR_DECODE,
+			ErrorKind::Encode => GST_STREAM_ERROR_ENCODE,
+			ErrorKind::Decrypt => GST_STREAM_ERROR_DECRYPT,
+			ErrorKind::MissingPlugin => GST_CORE_ERROR_MISSING_PLUGIN,
+			ErrorKind::StateChange => GST_CORE_ERROR_STATE_CHANGE,
+			ErrorKind::Other => GST_CORE_ERROR_FAILED,
+		};
+		code as i32
+	}
+}
+
 impl Drop for Error{
 	fn drop(&mut self){
 		unsafe{
@@ -33,6 +133,11 @@ impl Error{
 		}
     }
     
+    /// An error of the domain and code that kind is mapped from
+    pub fn new_from_kind(kind: ErrorKind, message: &str) -> Error{
+		Error::new(kind.domain().quark(), kind.code(), message)
+    }
+
     pub unsafe fn new_from_g_error(err: *mut GError) -> Error{
 		Error{ error: err }
 	}
@@ -57,6 +162,49 @@ impl Error{
 		}
 	}
 	
+	/// The class of failure of the error, from its domain and code only.
+	///
+	/// Elements that stop streaming because of a flow error, as
+	/// not-negotiated, post STREAM/FAILED "Internal data stream error"
+	/// with the reason only in the debug details, which Error doesn't
+	/// keep, so those are classified as Other
+	pub fn kind(&self) -> ErrorKind{
+		let code = self.code() as u32;
+		match ErrorDomain::from_quark(self.domain()){
+			Some(ErrorDomain::Resource) => match code{
+				GST_RESOURCE_ERROR_NOT_FOUND => ErrorKind::NotFound,
+				GST_RESOURCE_ERROR_NOT_AUTHORIZED => ErrorKind::NotAuthorized,
+				GST_RESOURCE_ERROR_BUSY => ErrorKind::Busy,
+				GST_RESOURCE_ERROR_NO_SPACE_LEFT => ErrorKind::NoSpaceLeft,
+				_ => ErrorKind::Resource
+			},
+			Some(ErrorDomain::Stream) => match code{
+				GST_STREAM_ERROR_FORMAT => ErrorKind::NotNegotiated,
+				GST_STREAM_ERROR_DECODE | GST_STREAM_ERROR_DEMUX => ErrorKind::Decode,
+				GST_STREAM_ERROR_ENCODE | GST_STREAM_ERROR_MUX => ErrorKind::Encode,
+				GST_STREAM_ERROR_DECRYPT | GST_STREAM_ERROR_DECRYPT_NOKEY => ErrorKind::Decrypt,
+				GST_STREAM_ERROR_TYPE_NOT_FOUND | GST_STREAM_ERROR_CODEC_NOT_FOUND => ErrorKind::MissingPlugin,
+				_ => ErrorKind::Other
+			},
+			Some(ErrorDomain::Core) => match code{
+				GST_CORE_ERROR_NEGOTIATION | GST_CORE_ERROR_CAPS => ErrorKind::NotNegotiated,
+				GST_CORE_ERROR_MISSING_PLUGIN => ErrorKind::MissingPlugin,
+				GST_CORE_ERROR_STATE_CHANGE => ErrorKind::StateChange,
+				_ => ErrorKind::Other
+			},
+			Some(ErrorDomain::Library) => match code{
+				GST_LIBRARY_ERROR_ENCODE => ErrorKind::Encode,
+				_ => ErrorKind::Other
+			},
+			None => ErrorKind::Other
+		}
+	}
+
+	/// The domain of the error if it's one of the GStreamer domains
+	pub fn error_domain(&self) -> Option<ErrorDomain>{
+		ErrorDomain::from_quark(self.domain())
+	}
+
 	pub fn domain(&self) -> u32{
 		unsafe{
 			if self.error != ptr::null_mut(){
@@ -0,0 +1,278 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+use appsrc::AppSrc;
+use pad::Pad;
+use structure::Structure;
+
+use std::fmt::{self, Debug, Formatter};
+
+/// Types of the events an application can create with Event::new_custom
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum EventType{
+    /// Sent upstream, as from a sink towards the sources
+    CustomUpstream,
+    /// Sent downstream serialized with the data, it reaches each element
+    /// after the buffers pushed before it
+    CustomDownstream,
+    /// Sent downstream out of band, before the queued buffers
+    CustomDownstreamOob,
+    /// Sent downstream serialized and kept on the pads, so elements
+    /// linked later receive it too. Only the last one of each name is
+    /// kept
+    CustomDownstreamSticky,
+    CustomBoth,
+    CustomBothOob,
+}
+
+impl EventType{
+    pub fn gst_event_type(&self) -> GstEventType{
+        match *self{
+            EventType::CustomUpstream => GST_EVENT_CUSTOM_UPSTREAM,
+            EventType::CustomDownstream => GST_EVENT_CUSTOM_DOWNSTREAM,
+            EventType::CustomDownstreamOob => GST_EVENT_CUSTOM_DOWNSTREAM_OOB,
+            EventType::CustomDownstreamSticky => GST_EVENT_CUSTOM_DOWNSTREAM_STICKY,
+            EventType::CustomBoth => GST_EVENT_CUSTOM_BOTH,
+            EventType::CustomBothOob => GST_EVENT_CUSTOM_BOTH_OOB,
+        }
+    }
+
+    fn from_gst_event_type(event_type: GstEventType) -> Option<EventType>{
+        match event_type{
+            GST_EVENT_CUSTOM_UPSTREAM => Some(EventType::CustomUpstream),
+            GST_EVENT_CUSTOM_DOWNSTREAM => Some(EventType::CustomDownstream),
+            GST_EVENT_CUSTOM_DOWNSTREAM_OOB => Some(EventType::CustomDownstreamOob),
+            GST_EVENT_CUSTOM_DOWNSTREAM_STICKY => Some(EventType::CustomDownstreamSticky),
+            GST_EVENT_CUSTOM_BOTH => Some(EventType::CustomBoth),
+            GST_EVENT_CUSTOM_BOTH_OOB => Some(EventType::CustomBothOob),
+            _ => None
+        }
+    }
+}
+
+/// An event travelling through the pipeline, downstream with the data, as
+/// segments, EOS or gaps, or upstream, as seeks and QoS
+pub struct Event{
+    event: *mut GstEvent
+}
+
+unsafe impl Sync for Event {}
+unsafe impl Send for Event {}
+
+impl Drop for Event{
+    fn drop(&mut self){
+        unsafe{
+            gst_mini_object_unref(self.event as *mut GstMiniObject);
+        }
+    }
+}
+
+impl Clone for Event{
+    fn clone(&self) -> Event{
+        unsafe{
+            Event::new(self.event, false).unwrap()
+        }
+    }
+}
+
+impl Event{
+    /// Wraps a raw GstEvent, if owned is false a new reference is taken
+    pub unsafe fn new(event: *mut GstEvent, owned: bool) -> Option<Event>{
+        if event != ptr::null_mut(){
+            if !owned{
+                gst_mini_object_ref(event as *mut GstMiniObject);
+            }
+            Some(Event{ event: event })
+        }else{
+            None
+        }
+    }
+
+    /// A GAP event, telling downstream there's no data from timestamp for
+    /// duration nanoseconds, or until the next buffer if None. Sinks
+    /// preroll and keep the clock running with it instead of waiting for
+    /// data, audio sinks and mixers fill the gap with silence
+    pub fn new_gap(timestamp: u64, duration: Option<u64>) -> Event{
+        unsafe{
+            Event::new(gst_event_new_gap(timestamp, duration.unwrap_or(GST_CLOCK_TIME_NONE)), true).unwrap()
+        }
+    }
+
+    /// The timestamp and duration of a GAP event, None for other events
+    pub fn parse_gap(&self) -> Option<(u64, Option<u64>)>{
+        if !self.is_gap(){
+            return None;
+        }
+        unsafe{
+            let mut timestamp = 0;
+            let mut duration = 0;
+            gst_event_parse_gap(self.event, &mut timestamp, &mut duration);
+            Some((timestamp, if duration == GST_CLOCK_TIME_NONE{ None }else{ Some(duration) }))
+        }
+    }
+
+    /// An application event carrying structure, as a marker to insert at
+    /// this point of a recording. The name of the structure identifies
+    /// the event, to match it with custom_structure or in a probe added
+    /// with Pad::add_custom_event_probe
+    pub fn new_custom(event_type: EventType, structure: Structure) -> Event{
+        unsafe{
+            Event::new(gst_event_new_custom(event_type.gst_event_type(), ::Transfer::transfer(structure)), true).unwrap()
+        }
+    }
+
+    /// The type of a custom event, None for the other events
+    pub fn custom_type(&self) -> Option<EventType>{
+        EventType::from_gst_event_type(self.event_type())
+    }
+
+    /// A copy of the structure of the event, if it has one
+    pub fn structure(&self) -> Option<Structure>{
+        unsafe{
+            let structure = gst_event_get_structure(self.event);
+            if structure != ptr::null(){
+                Structure::new_from_gst_structure(gst_structure_copy(structure))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Whether the structure of the event is named name
+    pub fn has_name(&self, name: &str) -> bool{
+        unsafe{
+            gst_event_has_name(self.event, to_c_str!(name)) == 1
+        }
+    }
+
+    /// The structure of a custom event named name, None for any other
+    /// event
+    pub fn custom_structure(&self, name: &str) -> Option<Structure>{
+        if self.custom_type().is_some() && self.has_name(name){
+            self.structure()
+        }else{
+            None
+        }
+    }
+
+    pub fn is_gap(&self) -> bool{
+        self.event_type() == GST_EVENT_GAP
+    }
+
+    pub fn event_type(&self) -> GstEventType{
+        unsafe{
+            (*self.event)._type
+        }
+    }
+
+    /// Name of the type, as "gap" or "segment"
+    pub fn type_name(&self) -> String{
+        unsafe{
+            from_c_str!(gst_event_type_get_name(self.event_type())).to_string()
+        }
+    }
+
+    pub fn seqnum(&self) -> u32{
+        unsafe{
+            gst_event_get_seqnum(self.event)
+        }
+    }
+
+    pub unsafe fn gst_event(&self) -> *mut GstEvent{
+        self.event
+    }
+}
+
+impl ::Transfer<GstEvent> for Event{
+    unsafe fn transfer(self) -> *mut GstEvent{
+        let event = self.event;
+        mem::forget(self);
+        event
+    }
+}
+
+impl Debug for Event{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "Event {{ type: {}, seqnum: {} }}", self.type_name(), self.seqnum())
+    }
+}
+
+impl Pad{
+    /// Sends an event to the peer of a source pad, downstream events are
+    /// serialized with the buffers pushed from the same thread
+    pub fn push_event(&self, event: Event) -> bool{
+        unsafe{
+            gst_pad_push_event(self.gst_pad() as *mut GstPad, ::Transfer::transfer(event)) == 1
+        }
+    }
+
+    /// Sends an event to this pad, as if it came from its peer
+    pub fn send_event(&self, event: Event) -> bool{
+        unsafe{
+            gst_pad_send_event(self.gst_pad() as *mut GstPad, ::Transfer::transfer(event)) == 1
+        }
+    }
+
+    /// Calls f with every event that goes through the pad in either
+    /// direction, from the thread sending it. Returns the id of the probe
+    /// to remove it with remove_probe
+    pub fn add_event_probe<F: Fn(&Pad, &Event) -> GstPadProbeReturn + Send + Sync + 'static>(&self, f: F) -> u64{
+        unsafe{
+            gst_pad_add_probe(self.gst_pad() as *mut GstPad, GST_PAD_PROBE_TYPE_EVENT_BOTH, Some(event_probe::<F>),
+                Box::into_raw(Box::new(f)) as gpointer, Some(box_destroy_notify::<F>)) as u64
+        }
+    }
+
+    /// Calls f with the structure of the custom events named name that go
+    /// through the pad, as the ones sent by the application with
+    /// Event::new_custom. Other events pass untouched
+    pub fn add_custom_event_probe<F: Fn(&Pad, &Structure) -> GstPadProbeReturn + Send + Sync + 'static>(&self, name: &str, f: F) -> u64{
+        let name = name.to_string();
+        self.add_event_probe(move |pad, event| {
+            match event.custom_structure(&name){
+                Some(structure) => f(pad, &structure),
+                None => GST_PAD_PROBE_OK
+            }
+        })
+    }
+}
+
+extern "C" fn event_probe<F: Fn(&Pad, &Event) -> GstPadProbeReturn>(pad: *mut GstPad, info: *mut GstPadProbeInfo, data: gpointer) -> GstPadProbeReturn{
+    unsafe{
+        match (Pad::new(pad, false), Event::new(gst_pad_probe_info_get_event(info), false)){
+            (Some(pad), Some(event)) => (*(data as *const F))(&pad, &event),
+            _ => GST_PAD_PROBE_OK
+        }
+    }
+}
+
+impl AppSrc{
+    /// Tells downstream there's no data from timestamp for duration
+    /// nanoseconds, so the sinks of a sparse stream, as subtitles or KLV,
+    /// don't stall the pipeline waiting for the next buffer. The event is
+    /// sent after the buffers already pushed
+    pub fn push_gap(&mut self, timestamp: u64, duration: Option<u64>) -> bool{
+        unsafe{
+            self.send_event(::Transfer::transfer(Event::new_gap(timestamp, duration)))
+        }
+    }
+}
+
+/// Configures a sink fed by a sparse stream, as subtitles, metadata or
+/// intermittent audio, so the pipeline doesn't wait for it to preroll
+/// when changing state, and late buffers after a long gap are still
+/// rendered
+pub fn configure_sparse_sink<E: ElementT>(sink: &E){
+    sink.set("async", 0 as gboolean);
+    sink.set("max-lateness", -1 as i64);
+}
+
+/// A live audiotestsrc producing silence, to feed an input of a mixer so
+/// it keeps producing output while the other inputs have gaps
+pub fn new_silence_src(name: &str) -> Option<Element>{
+    let src = Element::new("audiotestsrc", name)?;
+    // GST_AUDIO_TEST_SRC_WAVE_SILENCE
+    src.set("wave", 4 as gint);
+    src.set("is-live", 1 as gboolean);
+    Some(src)
+}
@@ -0,0 +1,115 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+
+use std::fs::File;
+use std::os::unix::io::{RawFd, IntoRawFd, FromRawFd};
+
+// fdsrc and fdsink never close their fd, so an owned one is attached to
+// the element and closed when the element is destroyed, even after the
+// wrapper was transferred to a bin
+fn set_owned_fd<F: IntoRawFd>(element: &Element, f: F){
+    unsafe{
+        let file = File::from_raw_fd(f.into_raw_fd());
+        element.set("fd", ::std::os::unix::io::AsRawFd::as_raw_fd(&file) as gint);
+        g_object_set_data_full(element.gst_element() as *mut GObject, to_c_str!("gst-rs-owned-fd"),
+            Box::into_raw(Box::new(file)) as gpointer, Some(box_destroy_notify::<File>));
+    }
+}
+
+/// Wrapper around fdsrc, reading a stream from a file descriptor, as a
+/// pipe or socket connected to another process
+pub struct FdSrc{
+    fdsrc: Element
+}
+
+unsafe impl Sync for FdSrc {}
+unsafe impl Send for FdSrc {}
+
+impl FdSrc{
+    pub fn new(name: &str) -> Option<FdSrc>{
+        Element::new("fdsrc", name).map(|fdsrc| FdSrc{ fdsrc: fdsrc })
+    }
+
+    pub fn new_from_element(element: Element) -> FdSrc{
+        FdSrc{ fdsrc: element }
+    }
+
+    /// Reads from fd, which has to stay open while the element uses it
+    pub fn set_fd(&self, fd: RawFd){
+        self.fdsrc.set("fd", fd as gint);
+    }
+
+    /// Reads from a File, UnixStream, ChildStdout... The element takes
+    /// ownership of it and closes it when destroyed
+    pub fn set_file<F: IntoRawFd>(&self, f: F){
+        set_owned_fd(&self.fdsrc, f);
+    }
+
+    /// Time in microseconds without data after which an element message
+    /// named GstFdSrcTimeout is posted, 0 to disable
+    pub fn set_timeout(&self, timeout_us: u64){
+        self.fdsrc.set("timeout", timeout_us);
+    }
+}
+
+impl ElementT for FdSrc{
+    fn as_element(&self) -> &Element{
+        &self.fdsrc
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.fdsrc
+    }
+}
+
+impl ::Transfer for FdSrc{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.fdsrc.transfer()
+    }
+}
+
+/// Wrapper around fdsink, writing a stream to a file descriptor
+pub struct FdSink{
+    fdsink: Element
+}
+
+unsafe impl Sync for FdSink {}
+unsafe impl Send for FdSink {}
+
+impl FdSink{
+    pub fn new(name: &str) -> Option<FdSink>{
+        Element::new("fdsink", name).map(|fdsink| FdSink{ fdsink: fdsink })
+    }
+
+    pub fn new_from_element(element: Element) -> FdSink{
+        FdSink{ fdsink: element }
+    }
+
+    /// Writes to fd, which has to stay open while the element uses it
+    pub fn set_fd(&self, fd: RawFd){
+        self.fdsink.set("fd", fd as gint);
+    }
+
+    /// Writes to a File, UnixStream, ChildStdin... The element takes
+    /// ownership of it and closes it when destroyed
+    pub fn set_file<F: IntoRawFd>(&self, f: F){
+        set_owned_fd(&self.fdsink, f);
+    }
+}
+
+impl ElementT for FdSink{
+    fn as_element(&self) -> &Element{
+        &self.fdsink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.fdsink
+    }
+}
+
+impl ::Transfer for FdSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.fdsink.transfer()
+    }
+}
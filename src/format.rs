@@ -0,0 +1,69 @@
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+/// A position or duration in bytes, as returned by queries in
+/// GST_FORMAT_BYTES
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct Bytes(pub u64);
+
+/// A position or duration in frames for video or samples for audio, as
+/// returned by queries in GST_FORMAT_DEFAULT
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct Frames(pub u64);
+
+/// A signed difference between two clock times in nanoseconds, used for
+/// offsets that can delay or advance a stream
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct ClockTimeDiff(pub i64);
+
+impl ClockTimeDiff{
+    pub fn from_ns(ns: i64) -> ClockTimeDiff{
+        ClockTimeDiff(ns)
+    }
+
+    pub fn from_ms(ms: i64) -> ClockTimeDiff{
+        ClockTimeDiff(ms * 1000000)
+    }
+
+    /// Positive offset of the given duration
+    pub fn later(duration: Duration) -> ClockTimeDiff{
+        ClockTimeDiff(duration.as_secs() as i64 * 1000000000 + duration.subsec_nanos() as i64)
+    }
+
+    /// Negative offset of the given duration
+    pub fn earlier(duration: Duration) -> ClockTimeDiff{
+        ClockTimeDiff(-ClockTimeDiff::later(duration).0)
+    }
+
+    pub fn ns(&self) -> i64{
+        self.0
+    }
+
+    pub fn ms(&self) -> i64{
+        self.0 / 1000000
+    }
+}
+
+impl From<i64> for ClockTimeDiff{
+    fn from(ns: i64) -> ClockTimeDiff{
+        ClockTimeDiff(ns)
+    }
+}
+
+impl Display for ClockTimeDiff{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "{:+}ms", self.0 as f64 / 1000000.0)
+    }
+}
+
+impl Display for Bytes{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "{} bytes", self.0)
+    }
+}
+
+impl Display for Frames{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "{} frames", self.0)
+    }
+}
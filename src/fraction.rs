@@ -0,0 +1,314 @@
+use ffi::*;
+use value::Value;
+use videoinfo::VideoInfo;
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+const GST_SECOND: u64 = 1000000000;
+
+/// A rational number as used for frame rates and pixel aspect ratios.
+///
+/// Frame rates as 30000/1001 can't be represented exactly with floats,
+/// computing timestamps from them accumulates errors over long streams,
+/// so frame and time conversions are done with integer scaling. A frame
+/// rate of 0/1 means a variable frame rate.
+///
+/// Fractions are kept with a positive denominator and compare by value,
+/// so 2/4 == 1/2. The constructors reject a zero denominator, fractions
+/// built from the fields with one compare equal to each other and
+/// greater than any valid fraction. The arithmetic operators panic on
+/// overflow, use the checked_* methods to handle it.
+#[derive(Clone,Copy,Debug)]
+pub struct Fraction(pub i32, pub i32);
+
+impl Fraction{
+    /// None if denom is 0 or the sign can't be moved to the numerator,
+    /// as for i32::MIN/-1
+    pub fn new(numer: i32, denom: i32) -> Option<Fraction>{
+        if denom == 0{
+            None
+        }else if denom < 0{
+            Some(Fraction(numer.checked_neg()?, denom.checked_neg()?))
+        }else{
+            Some(Fraction(numer, denom))
+        }
+    }
+
+    /// Approximates a float as a fraction, as 29.97 to 2997/100
+    pub fn from_f64(value: f64) -> Option<Fraction>{
+        unsafe{
+            let mut numer = 0;
+            let mut denom = 1;
+            gst_util_double_to_fraction(value, &mut numer, &mut denom);
+            Fraction::new(numer, denom)
+        }
+    }
+
+    pub fn numer(&self) -> i32{
+        self.0
+    }
+
+    pub fn denom(&self) -> i32{
+        self.1
+    }
+
+    pub fn to_f64(&self) -> f64{
+        self.0 as f64 / self.1 as f64
+    }
+
+    /// Returns the fraction in its lowest terms, as 15/1 for 30/2
+    pub fn reduced(&self) -> Fraction{
+        unsafe{
+            if self.1 == 0{
+                return *self;
+            }
+            let gcd = gst_util_greatest_common_divisor(self.0, self.1).abs();
+            let gcd = if gcd > 1{ gcd }else{ 1 };
+            Fraction::new(self.0 / gcd, self.1 / gcd).unwrap_or(*self)
+        }
+    }
+
+    /// The inverse of the fraction, None for 0
+    pub fn recip(&self) -> Option<Fraction>{
+        Fraction::new(self.1, self.0)
+    }
+
+    pub fn checked_neg(&self) -> Option<Fraction>{
+        Some(Fraction(self.0.checked_neg()?, self.1))
+    }
+
+    pub fn checked_add(&self, other: Fraction) -> Option<Fraction>{
+        unsafe{
+            let (mut numer, mut denom) = (0, 1);
+            if gst_util_fraction_add(self.0, self.1, other.0, other.1, &mut numer, &mut denom) != 0{
+                Fraction::new(numer, denom)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn checked_sub(&self, other: Fraction) -> Option<Fraction>{
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, other: Fraction) -> Option<Fraction>{
+        unsafe{
+            let (mut numer, mut denom) = (0, 1);
+            if gst_util_fraction_multiply(self.0, self.1, other.0, other.1, &mut numer, &mut denom) != 0{
+                Fraction::new(numer, denom)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn checked_div(&self, other: Fraction) -> Option<Fraction>{
+        self.checked_mul(other.recip()?)
+    }
+
+    /// Duration of a frame in nanoseconds for this frame rate, or
+    /// GST_CLOCK_TIME_NONE for variable frame rates
+    pub fn frame_duration(&self) -> GstClockTime{
+        self.frames_to_time(1)
+    }
+
+    /// Time in nanoseconds at which the given frame starts for this frame
+    /// rate, or GST_CLOCK_TIME_NONE for variable frame rates
+    pub fn frames_to_time(&self, frames: u64) -> GstClockTime{
+        if self.0 <= 0 || self.1 <= 0{
+            return GST_CLOCK_TIME_NONE;
+        }
+        unsafe{
+            gst_util_uint64_scale(frames, GST_SECOND * self.1 as u64, self.0 as u64)
+        }
+    }
+
+    /// Number of the frame shown at time in nanoseconds for this frame
+    /// rate, or None for variable frame rates
+    pub fn time_to_frames(&self, time: GstClockTime) -> Option<u64>{
+        if self.0 <= 0 || self.1 <= 0 || time == GST_CLOCK_TIME_NONE{
+            return None;
+        }
+        unsafe{
+            Some(gst_util_uint64_scale(time, self.0 as u64, GST_SECOND * self.1 as u64))
+        }
+    }
+}
+
+impl PartialEq for Fraction{
+    fn eq(&self, other: &Fraction) -> bool{
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Fraction{}
+
+impl PartialOrd for Fraction{
+    fn partial_cmp(&self, other: &Fraction) -> Option<Ordering>{
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fraction{
+    fn cmp(&self, other: &Fraction) -> Ordering{
+        match (self.1 == 0, other.1 == 0){
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => {
+                // in i64 so negative denominators and the products
+                // can't overflow
+                let (a_n, a_d) = (self.0 as i64 * self.1.signum() as i64, (self.1 as i64).abs());
+                let (b_n, b_d) = (other.0 as i64 * other.1.signum() as i64, (other.1 as i64).abs());
+                (a_n * b_d).cmp(&(b_n * a_d))
+            }
+        }
+    }
+}
+
+impl Neg for Fraction{
+    type Output = Fraction;
+    fn neg(self) -> Fraction{
+        self.checked_neg().expect("fraction negation overflowed")
+    }
+}
+
+impl Add for Fraction{
+    type Output = Fraction;
+    fn add(self, other: Fraction) -> Fraction{
+        self.checked_add(other).expect("fraction addition overflowed")
+    }
+}
+
+impl Sub for Fraction{
+    type Output = Fraction;
+    fn sub(self, other: Fraction) -> Fraction{
+        self.checked_sub(other).expect("fraction subtraction overflowed")
+    }
+}
+
+impl Mul for Fraction{
+    type Output = Fraction;
+    fn mul(self, other: Fraction) -> Fraction{
+        self.checked_mul(other).expect("fraction multiplication overflowed")
+    }
+}
+
+impl Div for Fraction{
+    type Output = Fraction;
+    fn div(self, other: Fraction) -> Fraction{
+        self.checked_div(other).expect("fraction division by zero or overflow")
+    }
+}
+
+impl From<i32> for Fraction{
+    fn from(value: i32) -> Fraction{
+        Fraction(value, 1)
+    }
+}
+
+
+impl From<Fraction> for f64{
+    fn from(fraction: Fraction) -> f64{
+        fraction.to_f64()
+    }
+}
+
+impl Display for Fraction{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "{}/{}", self.0, self.1)
+    }
+}
+
+impl From<Fraction> for Value{
+    fn from(fraction: Fraction) -> Value{
+        Value::Fraction(fraction.0, fraction.1)
+    }
+}
+
+impl Value{
+    /// Returns the value as a Fraction if it's one
+    pub fn as_fraction(&self) -> Option<Fraction>{
+        match *self{
+            Value::Fraction(numer, denom) => Fraction::new(numer, denom),
+            _ => None
+        }
+    }
+}
+
+impl VideoInfo{
+    pub fn framerate(&self) -> Fraction{
+        Fraction(self.fps_n, self.fps_d)
+    }
+
+    pub fn pixel_aspect_ratio(&self) -> Fraction{
+        Fraction(self.par_n, self.par_d)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn new_moves_the_sign_to_the_numerator(){
+        assert_eq!(Fraction::new(1, -2).map(|f| (f.numer(), f.denom())), Some((-1, 2)));
+        assert_eq!(Fraction::new(1, 0), None);
+        assert_eq!(Fraction::new(i32::min_value(), -1), None);
+    }
+
+    #[test]
+    fn compares_by_value(){
+        assert_eq!(Fraction(2, 4), Fraction(1, 2));
+        assert!(Fraction(30000, 1001) < Fraction(30, 1));
+        assert!(Fraction(-1, 2) < Fraction(0, 1));
+        assert!(Fraction(1, 0) > Fraction(i32::max_value(), 1));
+        assert_eq!(Fraction(1, 0), Fraction(5, 0));
+    }
+
+    #[test]
+    fn reduced(){
+        let reduced = Fraction(30, 2).reduced();
+        assert_eq!((reduced.numer(), reduced.denom()), (15, 1));
+        let reduced = Fraction(-6, 4).reduced();
+        assert_eq!((reduced.numer(), reduced.denom()), (-3, 2));
+    }
+
+    #[test]
+    fn arithmetic(){
+        assert_eq!(Fraction(1, 2) + Fraction(1, 3), Fraction(5, 6));
+        assert_eq!(Fraction(1, 2) - Fraction(1, 3), Fraction(1, 6));
+        assert_eq!(Fraction(2, 3) * Fraction(3, 4), Fraction(1, 2));
+        assert_eq!(Fraction(1, 2) / Fraction(1, 4), Fraction(2, 1));
+        assert_eq!(-Fraction(1, 2), Fraction(-1, 2));
+        assert_eq!(Fraction::from(3) * Fraction(1, 3), Fraction(1, 1));
+    }
+
+    #[test]
+    fn checked_arithmetic(){
+        assert_eq!(Fraction(1, 2).checked_div(Fraction(0, 1)), None);
+        assert_eq!(Fraction(i32::max_value(), 1).checked_add(Fraction(1, 1)), None);
+        assert_eq!(Fraction(i32::max_value(), 1).checked_mul(Fraction(2, 1)), None);
+        assert_eq!(Fraction(i32::min_value(), 1).checked_neg(), None);
+        assert_eq!(Fraction(0, 1).recip(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn division_by_zero_panics(){
+        let _ = Fraction(1, 2) / Fraction(0, 1);
+    }
+
+    #[test]
+    fn frame_times(){
+        assert_eq!(Fraction(25, 1).frame_duration(), 40000000);
+        assert_eq!(Fraction(30000, 1001).frames_to_time(30000), 1001 * GST_SECOND);
+        assert_eq!(Fraction(30000, 1001).time_to_frames(1001 * GST_SECOND), Some(30000));
+        assert_eq!(Fraction(0, 1).frame_duration(), GST_CLOCK_TIME_NONE);
+        assert_eq!(Fraction(0, 1).time_to_frames(GST_SECOND), None);
+        assert_eq!(Fraction(25, 1).time_to_frames(GST_CLOCK_TIME_NONE), None);
+    }
+}
@@ -0,0 +1,89 @@
+use ffi::*;
+use util::*;
+use bin::BinT;
+use caps::Caps;
+use decode_util::{link_decoded_pads, missing_element};
+use element::{Element, ElementT};
+use pipeline::{Pipeline, PipelineT, PrerollError};
+use sample::Sample;
+use seek::{Seek, SeekFlags};
+use error::{Error, Result};
+
+// How long to wait for the pipeline to preroll after each seek, decoding
+// from the previous keyframe of long GOP streams can take a while
+const FRAME_TIMEOUT: GstClockTime = 10 * 1000000000;
+
+/// Decodes the frames of uri at the given timestamps in nanoseconds,
+/// converted to caps, as "video/x-raw, format=RGB, width=160,
+/// height=90" for the thumbnails of a filmstrip.
+///
+/// A single pipeline is used for all the frames and the timestamps are
+/// visited in order, so frames close to each other only decode from the
+/// same keyframe once. The samples are returned sorted by timestamp;
+/// timestamps after the end of the stream are skipped, the timestamp of
+/// each frame is the pts of its buffer.
+pub fn extract_frames(uri: &str, timestamps: &[u64], caps: &Caps) -> Result<Vec<Sample>>{
+    let mut pipeline = Pipeline::new("frame-extractor").ok_or_else(|| missing_element("pipeline"))?;
+    let decodebin = Element::new("uridecodebin", "").ok_or_else(|| missing_element("uridecodebin"))?;
+    let videoconvert = Element::new("videoconvert", "").ok_or_else(|| missing_element("videoconvert"))?;
+    let videoscale = Element::new("videoscale", "").ok_or_else(|| missing_element("videoscale"))?;
+    let appsink = Element::new("appsink", "").ok_or_else(|| missing_element("appsink"))?;
+    decodebin.set("uri", to_c_str!(uri));
+    decodebin.set("caps", unsafe{ Caps::from_string("video/x-raw").unwrap().gst_caps() });
+    appsink.set("caps", unsafe{ caps.gst_caps() });
+    appsink.set("sync", 0 as gboolean);
+
+    let mut decodebin_ref = decodebin.to_element();
+    let mut videoconvert_ref = videoconvert.to_element();
+    let mut videoscale_ref = videoscale.to_element();
+    let mut appsink_ref = appsink.to_element();
+    if !pipeline.add(decodebin) || !pipeline.add(videoconvert) || !pipeline.add(videoscale) || !pipeline.add(appsink) ||
+        !videoconvert_ref.link(&mut videoscale_ref) || !videoscale_ref.link(&mut appsink_ref){
+        return Err(Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_NEGOTIATION as i32,
+            "couldn't link the video converters"));
+    }
+    link_decoded_pads(&mut decodebin_ref, videoconvert_ref);
+
+    let frames = extract(&mut pipeline, &appsink_ref, timestamps);
+    pipeline.set_state(GST_STATE_NULL);
+    frames
+}
+
+fn extract(pipeline: &mut Pipeline, appsink: &Element, timestamps: &[u64]) -> Result<Vec<Sample>>{
+    match pipeline.preroll(FRAME_TIMEOUT){
+        Ok(()) => {}
+        Err(PrerollError::Error(err)) => return Err(err),
+        Err(err) => return Err(Error::new(unsafe{ gst_stream_error_quark() }, GST_STREAM_ERROR_FAILED as i32,
+            &format!("{}", err)))
+    }
+    let duration = pipeline.duration_ns();
+
+    let mut timestamps = timestamps.to_vec();
+    timestamps.sort();
+    timestamps.dedup();
+    let mut frames = Vec::with_capacity(timestamps.len());
+    for timestamp in timestamps{
+        if duration.map(|duration| timestamp >= duration as u64).unwrap_or(false){
+            break;
+        }
+        let seek = Seek::builder()
+            .flags(SeekFlags::FLUSH | SeekFlags::ACCURATE)
+            .start(timestamp)
+            .build();
+        if !seek.apply(pipeline){
+            continue;
+        }
+        match pipeline.wait_for_message(FRAME_TIMEOUT, GST_MESSAGE_ASYNC_DONE){
+            Some(Ok(_)) => {}
+            Some(Err(err)) => return Err(err),
+            None => return Err(Error::new(unsafe{ gst_stream_error_quark() }, GST_STREAM_ERROR_FAILED as i32,
+                &format!("timed out seeking to {}", timestamp)))
+        }
+        unsafe{
+            if let Some(sample) = Sample::new(gst_app_sink_pull_preroll(appsink.gst_element() as *mut GstAppSink), true){
+                frames.push(sample);
+            }
+        }
+    }
+    Ok(frames)
+}
@@ -0,0 +1,40 @@
+use ffi::*;
+use util::*;
+
+pub enum GSocket{}
+pub enum GSocketAddress{}
+pub enum GInetAddress{}
+
+// The socket API is part of gio, which isn't linked by default
+#[cfg_attr(not(target_os="macos"), link(name = "gio-2.0"))]
+extern "C"{
+    pub fn g_socket_new_from_fd(fd: gint, error: *mut *mut GError) -> *mut GSocket;
+    pub fn g_socket_get_remote_address(socket: *mut GSocket, error: *mut *mut GError) -> *mut GSocketAddress;
+    pub fn g_inet_socket_address_get_address(address: *mut GSocketAddress) -> *mut GInetAddress;
+    pub fn g_inet_socket_address_get_port(address: *mut GSocketAddress) -> guint16;
+    pub fn g_inet_address_to_string(address: *mut GInetAddress) -> *mut gchar;
+}
+
+/// Formats an inet socket address as host:port
+pub unsafe fn socket_address_to_string(address: *mut GSocketAddress) -> String{
+    if address == ptr::null_mut(){
+        return String::new();
+    }
+    let inet = g_inet_socket_address_get_address(address);
+    let c_str = g_inet_address_to_string(inet);
+    let host = from_c_str!(c_str).to_string();
+    g_free(c_str as gpointer);
+    format!("{}:{}", host, g_inet_socket_address_get_port(address))
+}
+
+/// The address of the remote end of a connected socket as host:port, empty
+/// if it's not connected anymore
+pub unsafe fn socket_remote_address(socket: *mut GSocket) -> String{
+    let address = g_socket_get_remote_address(socket, ptr::null_mut());
+    if address == ptr::null_mut(){
+        return String::new();
+    }
+    let string = socket_address_to_string(address);
+    g_object_unref(address as gpointer);
+    string
+}
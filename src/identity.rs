@@ -0,0 +1,99 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use element::{Element, ElementT};
+
+/// Wrapper around identity, which passes the data through unchanged. It's
+/// the usual way to look at the buffers flowing between two elements,
+/// calling a closure with each one, or to simulate problems as dropped or
+/// slow buffers while testing
+pub struct Identity{
+    identity: Element
+}
+
+unsafe impl Sync for Identity {}
+unsafe impl Send for Identity {}
+
+impl Identity{
+    pub fn new(name: &str) -> Option<Identity>{
+        Element::new("identity", name).map(|identity| Identity{ identity: identity })
+    }
+
+    pub fn new_from_element(element: Element) -> Identity{
+        Identity{ identity: element }
+    }
+
+    /// Calls f with every buffer before it's pushed downstream, from the
+    /// streaming thread. The buffer can't be modified, taking a
+    /// reference keeps it alive after it's pushed
+    pub fn on_handoff<F: Fn(&Buffer) + Send + Sync + 'static>(&self, f: F){
+        self.identity.set("signal-handoffs", 1 as gboolean);
+        unsafe{
+            connect_boxed(self.identity.gst_element() as gpointer, "handoff",
+                handoff::<F> as extern "C" fn(*mut GstElement, *mut GstBuffer, gpointer) as gpointer,
+                Box::new(f));
+        }
+    }
+
+    /// Whether to stop printing every buffer to the console with -v
+    pub fn set_silent(&self, silent: bool){
+        self.identity.set("silent", silent as gboolean);
+    }
+
+    /// Holds each buffer until its running time, as a sink would, to
+    /// throttle a source that produces data faster than real time
+    pub fn set_sync(&self, sync: bool){
+        self.identity.set("sync", sync as gboolean);
+    }
+
+    /// Microseconds to wait before pushing each buffer
+    pub fn set_sleep_time(&self, sleep_time: u32){
+        self.identity.set("sleep-time", sleep_time);
+    }
+
+    /// Probability of dropping each buffer, from 0 to 1
+    pub fn set_drop_probability(&self, probability: f32){
+        self.identity.set("drop-probability", probability as f64);
+    }
+
+    /// Posts an error after that many buffers, -1 to disable
+    pub fn set_error_after(&self, buffers: i32){
+        self.identity.set("error-after", buffers);
+    }
+
+    /// Drops the buffers that have any of flags, as
+    /// GST_BUFFER_FLAG_DELTA_UNIT to only let keyframes through
+    pub fn set_drop_buffer_flags(&self, flags: GstBufferFlags){
+        self.identity.set("drop-buffer-flags", flags);
+    }
+
+    /// Checks that the timestamps and offsets of the buffers are
+    /// contiguous, posting a warning when they aren't
+    pub fn set_check_imperfect_timestamp(&self, check: bool){
+        self.identity.set("check-imperfect-timestamp", check as gboolean);
+    }
+}
+
+extern "C" fn handoff<F: Fn(&Buffer)>(_identity: *mut GstElement, buffer: *mut GstBuffer, data: gpointer){
+    unsafe{
+        if let Some(buffer) = Buffer::new(buffer, false){
+            (*(data as *const F))(&buffer);
+        }
+    }
+}
+
+impl ElementT for Identity{
+    fn as_element(&self) -> &Element{
+        &self.identity
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.identity
+    }
+}
+
+impl ::Transfer for Identity{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.identity.transfer()
+    }
+}
@@ -0,0 +1,248 @@
+use ffi::*;
+use util::*;
+use bin::BinT;
+use decode_util::{discard_pad, gst_app_sink_try_pull_sample, missing_element};
+use decodebin::{DecodeBinT, UriDecodeBin};
+use element::{Element, ElementT};
+use pipeline::{Pipeline, PipelineT, PrerollError};
+use sample::Sample;
+use seek::{Seek, SeekFlags};
+use error::{Error, ErrorKind, Result};
+
+// How long to wait for a buffer before checking the bus for errors
+const POLL_INTERVAL: GstClockTime = 100 * 1000000;
+
+// How long to wait for the pipeline to preroll after each seek
+const SEEK_TIMEOUT: GstClockTime = 10 * 1000000000;
+
+/// How keyframes finds the keyframes of a stream
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum KeyframeScan{
+    /// Reads the whole video stream without decoding it, finds every
+    /// keyframe in any container but takes as long as reading the file
+    Full,
+    /// Seeks from keyframe to keyframe, using the index of the container
+    /// as the one of mp4 or the cues of mkv. Much faster for long files
+    /// with an index, the demuxer decides which frames are keyframes
+    Seek,
+}
+
+/// Which keyframe snap_to_keyframe picks
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Snap{
+    Before,
+    After,
+    Nearest,
+}
+
+/// The timestamps in nanoseconds of the keyframes of the first video
+/// stream of uri, sorted, to draw them on a timeline or to cut the
+/// stream where it can be copied without re-encoding. Fails if uri has
+/// no video stream
+pub fn keyframes(uri: &str, scan: KeyframeScan) -> Result<Vec<u64>>{
+    let mut pipeline = Pipeline::new("keyframes").ok_or_else(|| missing_element("pipeline"))?;
+    let decodebin = UriDecodeBin::new("").ok_or_else(|| missing_element("uridecodebin"))?;
+    let appsink = Element::new("appsink", "").ok_or_else(|| missing_element("appsink"))?;
+    decodebin.set_uri(uri);
+    decodebin.expose_parsed_streams();
+    appsink.set("sync", 0 as gboolean);
+    appsink.set("max-buffers", 16 as guint);
+
+    let appsink_ref = appsink.to_element();
+    unsafe{
+        connect_boxed(decodebin.gst_element() as gpointer, "pad-added",
+            pad_added as extern "C" fn(*mut GstElement, *mut GstPad, gpointer) as gpointer,
+            Box::new(appsink.to_element()));
+        connect_boxed(decodebin.gst_element() as gpointer, "no-more-pads",
+            no_more_pads as extern "C" fn(*mut GstElement, gpointer) as gpointer,
+            Box::new(appsink.to_element()));
+    }
+    pipeline.add(decodebin);
+    pipeline.add(appsink);
+
+    let result = match scan{
+        KeyframeScan::Full => scan_full(&mut pipeline, &appsink_ref),
+        KeyframeScan::Seek => scan_seeking(&mut pipeline, &appsink_ref),
+    };
+    pipeline.set_state(GST_STATE_NULL);
+    result
+}
+
+/// The keyframe before, after or nearest to position in keyframes, which
+/// has to be sorted as returned by the keyframes function. None if
+/// there's no keyframe in that direction
+pub fn snap_to_keyframe(keyframes: &[u64], position: u64, snap: Snap) -> Option<u64>{
+    let after = match keyframes.binary_search(&position){
+        Ok(_) => return Some(position),
+        Err(after) => after
+    };
+    let before = if after > 0{ Some(keyframes[after - 1]) }else{ None };
+    let after = keyframes.get(after).cloned();
+    match snap{
+        Snap::Before => before,
+        Snap::After => after,
+        Snap::Nearest => match (before, after){
+            (Some(before), Some(after)) => if position - before <= after - position{ Some(before) }else{ Some(after) },
+            (before, after) => before.or(after)
+        }
+    }
+}
+
+fn timestamp(sample: &Sample) -> Option<(u64, bool)>{
+    let buffer = sample.buffer()?;
+    let ts = if buffer.pts() != GST_CLOCK_TIME_NONE{ buffer.pts() }else{ buffer.dts() };
+    if ts == GST_CLOCK_TIME_NONE{
+        None
+    }else{
+        Some((ts, buffer.is_keyframe()))
+    }
+}
+
+fn scan_full(pipeline: &mut Pipeline, appsink: &Element) -> Result<Vec<u64>>{
+    if pipeline.set_state(GST_STATE_PLAYING) == GST_STATE_CHANGE_FAILURE{
+        return Err(state_change_error(pipeline));
+    }
+    let mut keyframes = Vec::new();
+    loop{
+        unsafe{
+            let appsink = appsink.gst_element() as *mut GstAppSink;
+            if let Some(sample) = Sample::new(gst_app_sink_try_pull_sample(appsink, POLL_INTERVAL), true){
+                if let Some((ts, true)) = timestamp(&sample){
+                    keyframes.push(ts);
+                }
+                continue;
+            }
+            if gst_app_sink_is_eos(appsink) == 1{
+                break;
+            }
+        }
+        if let Some(Err(err)) = pipeline.wait_for_message(0, 0){
+            return Err(err);
+        }
+    }
+    keyframes.sort();
+    keyframes.dedup();
+    Ok(keyframes)
+}
+
+fn scan_seeking(pipeline: &mut Pipeline, appsink: &Element) -> Result<Vec<u64>>{
+    match pipeline.preroll(SEEK_TIMEOUT){
+        Ok(()) => {}
+        Err(PrerollError::Error(err)) => return Err(err),
+        Err(err) => return Err(Error::new(unsafe{ gst_stream_error_quark() }, GST_STREAM_ERROR_FAILED as i32,
+            &format!("{}", err)))
+    }
+    let mut keyframes = Vec::new();
+    let mut sample = pull_preroll(appsink);
+    while let Some((ts, _)) = sample.as_ref().and_then(timestamp){
+        // the demuxer snapped back to a keyframe already found, there
+        // are no more after it
+        if keyframes.last().map(|last| ts <= *last).unwrap_or(false){
+            break;
+        }
+        keyframes.push(ts);
+        let seek = Seek::builder()
+            .flags(SeekFlags::FLUSH | SeekFlags::KEY_UNIT | SeekFlags::SNAP_AFTER)
+            .start(ts + 1)
+            .build();
+        if !seek.apply(pipeline){
+            break;
+        }
+        match pipeline.wait_for_message(SEEK_TIMEOUT, GST_MESSAGE_ASYNC_DONE){
+            Some(Ok(_)) => {}
+            Some(Err(err)) => return Err(err),
+            None => return Err(Error::new(unsafe{ gst_stream_error_quark() }, GST_STREAM_ERROR_FAILED as i32,
+                &format!("timed out seeking after {}", ts)))
+        }
+        sample = pull_preroll(appsink);
+    }
+    Ok(keyframes)
+}
+
+fn pull_preroll(appsink: &Element) -> Option<Sample>{
+    unsafe{
+        Sample::new(gst_app_sink_pull_preroll(appsink.gst_element() as *mut GstAppSink), true)
+    }
+}
+
+fn state_change_error(pipeline: &mut Pipeline) -> Error{
+    match pipeline.wait_for_message(0, 0){
+        Some(Err(err)) => err,
+        _ => Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_STATE_CHANGE as i32,
+            "couldn't start the pipeline")
+    }
+}
+
+// Links the first video stream to the appsink, the rest are discarded
+extern "C" fn pad_added(decodebin: *mut GstElement, pad: *mut GstPad, data: gpointer){
+    unsafe{
+        let appsink = &*(data as *const Element);
+        let sink = gst_element_get_static_pad(appsink.gst_element() as *mut GstElement, to_c_str!("sink"));
+        let caps = gst_pad_get_current_caps(pad);
+        let is_video = caps != ptr::null_mut() &&
+            from_c_str!(gst_structure_get_name(gst_caps_get_structure(caps, 0))).starts_with("video/");
+        if caps != ptr::null_mut(){
+            gst_mini_object_unref(caps as *mut GstMiniObject);
+        }
+        let target = if is_video && gst_pad_is_linked(sink) == 0{
+            gst_object_ref(sink as gpointer) as *mut GstPad
+        }else{
+            discard_pad(decodebin)
+        };
+        gst_object_unref(sink as gpointer);
+        if target != ptr::null_mut(){
+            gst_pad_link(pad, target);
+            gst_object_unref(target as gpointer);
+        }
+    }
+}
+
+// Without a video stream the appsink never gets data and the pipeline
+// never prerolls, fail instead of waiting forever
+extern "C" fn no_more_pads(decodebin: *mut GstElement, data: gpointer){
+    unsafe{
+        let appsink = &*(data as *const Element);
+        let sink = gst_element_get_static_pad(appsink.gst_element() as *mut GstElement, to_c_str!("sink"));
+        let linked = gst_pad_is_linked(sink) != 0;
+        gst_object_unref(sink as gpointer);
+        if !linked{
+            if let Some(decodebin) = Element::new_from_gst_element(gst_object_ref(decodebin as gpointer) as *mut GstElement){
+                decodebin.post_error(ErrorKind::Other, "the stream has no video", "");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    const KEYFRAMES: [u64; 3] = [0, 2000, 4000];
+
+    #[test]
+    fn exact_position(){
+        for snap in &[Snap::Before, Snap::After, Snap::Nearest]{
+            assert_eq!(snap_to_keyframe(&KEYFRAMES, 2000, *snap), Some(2000));
+        }
+    }
+
+    #[test]
+    fn between_keyframes(){
+        assert_eq!(snap_to_keyframe(&KEYFRAMES, 2500, Snap::Before), Some(2000));
+        assert_eq!(snap_to_keyframe(&KEYFRAMES, 2500, Snap::After), Some(4000));
+        assert_eq!(snap_to_keyframe(&KEYFRAMES, 2500, Snap::Nearest), Some(2000));
+        assert_eq!(snap_to_keyframe(&KEYFRAMES, 3500, Snap::Nearest), Some(4000));
+        // ties go to the keyframe before
+        assert_eq!(snap_to_keyframe(&KEYFRAMES, 3000, Snap::Nearest), Some(2000));
+    }
+
+    #[test]
+    fn outside_the_keyframes(){
+        assert_eq!(snap_to_keyframe(&KEYFRAMES, 5000, Snap::Before), Some(4000));
+        assert_eq!(snap_to_keyframe(&KEYFRAMES, 5000, Snap::After), None);
+        assert_eq!(snap_to_keyframe(&KEYFRAMES, 5000, Snap::Nearest), Some(4000));
+        assert_eq!(snap_to_keyframe(&KEYFRAMES[1..], 1000, Snap::Before), None);
+        assert_eq!(snap_to_keyframe(&KEYFRAMES[1..], 1000, Snap::Nearest), Some(2000));
+        assert_eq!(snap_to_keyframe(&[], 1000, Snap::Nearest), None);
+    }
+}
@@ -0,0 +1,280 @@
+use ffi::*;
+use appsink::{self, AppSink};
+use caps::Caps;
+use element::{Element, ElementT};
+use pad::Pad;
+
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+
+/// Caps of KLV metadata streams as output by tsdemux
+pub const KLV_CAPS: &'static str = "meta/x-klv";
+
+/// Universal key of the MISB ST 0601 UAS Datalink local set
+pub const MISB_0601_KEY: [u8; 16] = [0x06, 0x0e, 0x2b, 0x34, 0x02, 0x0b, 0x01, 0x01,
+                                     0x0e, 0x01, 0x03, 0x01, 0x01, 0x00, 0x00, 0x00];
+
+/// Checks if a pad, usually a new src pad of a demuxer, outputs KLV
+/// metadata
+pub fn is_klv_pad(pad: &Pad) -> bool{
+    let klv_caps = match Caps::from_string(KLV_CAPS){
+        Some(caps) => caps,
+        None => return false
+    };
+    pad.current_caps().or_else(|| pad.query_caps())
+        .map(|caps| caps.can_intersect(&klv_caps))
+        .unwrap_or(false)
+}
+
+/// A single key-length-value packet with a 16 byte universal key
+#[derive(Clone,Debug,PartialEq)]
+pub struct KlvPacket{
+    pub key: [u8; 16],
+    pub value: Vec<u8>,
+}
+
+impl KlvPacket{
+    /// Parses all the consecutive packets in data, stopping at the first
+    /// truncated or malformed one
+    pub fn parse_all(mut data: &[u8]) -> Vec<KlvPacket>{
+        let mut packets = Vec::new();
+        while let Some((packet, len)) = KlvPacket::parse(data){
+            packets.push(packet);
+            data = &data[len..];
+        }
+        packets
+    }
+
+    /// Parses a packet from the start of data, returning it and the
+    /// number of bytes it takes
+    pub fn parse(data: &[u8]) -> Option<(KlvPacket, usize)>{
+        if data.len() < 17{
+            return None;
+        }
+        let mut key = [0u8; 16];
+        key.copy_from_slice(&data[..16]);
+        let (len, len_size) = parse_ber_length(&data[16..])?;
+        let start = 16 + len_size;
+        let end = start.checked_add(len)?;
+        if end > data.len(){
+            return None;
+        }
+        Some((KlvPacket{ key: key, value: data[start..end].to_vec() }, end))
+    }
+
+    /// Checks if this is a MISB ST 0601 local set, the most common KLV
+    /// metadata in drone video, ignoring the version byte of the key
+    pub fn is_misb_0601(&self) -> bool{
+        self.key[..7] == MISB_0601_KEY[..7] && self.key[8..] == MISB_0601_KEY[8..]
+    }
+}
+
+/// Parses a BER encoded length, returning the length and the number of
+/// bytes used to encode it. None if the length doesn't fit in a usize
+fn parse_ber_length(data: &[u8]) -> Option<(usize, usize)>{
+    let first = *data.first()?;
+    if first & 0x80 == 0{
+        return Some((first as usize, 1));
+    }
+    let n = (first & 0x7f) as usize;
+    if n == 0 || n > 8 || data.len() < 1 + n{
+        return None;
+    }
+    let len = data[1..1 + n].iter().fold(0u64, |len, b| (len << 8) | *b as u64);
+    Some((usize::try_from(len).ok()?, 1 + n))
+}
+
+/// The KLV packets of a buffer and its timestamps in nanoseconds, which
+/// are in the same timeline as the video of the same stream
+#[derive(Clone,Debug)]
+pub struct KlvSample{
+    pub pts: GstClockTime,
+    pub dts: GstClockTime,
+    pub packets: Vec<KlvPacket>,
+}
+
+impl KlvSample{
+    /// The timestamp used to correlate the metadata with the video, the
+    /// pts or, if the muxer didn't set it, the dts
+    pub fn timestamp(&self) -> GstClockTime{
+        if self.pts != GST_CLOCK_TIME_NONE{
+            self.pts
+        }else{
+            self.dts
+        }
+    }
+}
+
+/// An appsink accepting KLV streams that returns the parsed packets, to
+/// be linked to the KLV pad of a demuxer
+pub struct KlvExtractor{
+    appsink: AppSink
+}
+
+unsafe impl Sync for KlvExtractor {}
+unsafe impl Send for KlvExtractor {}
+
+impl KlvExtractor{
+    /// Creates the extractor, it doesn't synchronize to the clock so
+    /// metadata is delivered as soon as it's demuxed
+    pub fn new(name: &str) -> Option<KlvExtractor>{
+        let mut appsink = AppSink::new(name)?;
+        appsink.set_caps(Caps::from_string(KLV_CAPS)?);
+        appsink.set("sync", 0 as gboolean);
+        Some(KlvExtractor{ appsink: appsink })
+    }
+
+    /// Waits for the next buffer of metadata, returns None on EOS
+    pub fn recv(&self) -> Option<KlvSample>{
+        loop{
+            match self.appsink.recv(){
+                Ok(appsink::Message::NewSample(sample)) => {
+                    if let Some(buffer) = sample.buffer(){
+                        let packets = buffer.map_read(|mapinfo| KlvPacket::parse_all(mapinfo.data::<u8>()))
+                            .unwrap_or_default();
+                        return Some(KlvSample{
+                            pts: buffer.pts(),
+                            dts: buffer.dts(),
+                            packets: packets,
+                        });
+                    }
+                }
+                Ok(appsink::Message::NewPreroll(_)) => (),
+                Ok(appsink::Message::Eos) | Err(_) => return None
+            }
+        }
+    }
+
+    pub fn appsink(&self) -> &AppSink{
+        &self.appsink
+    }
+}
+
+impl ElementT for KlvExtractor{
+    fn as_element(&self) -> &Element{
+        self.appsink.as_element()
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        self.appsink.as_element_mut()
+    }
+}
+
+impl ::Transfer for KlvExtractor{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.appsink.transfer()
+    }
+}
+
+/// Keeps the latest KlvSamples to find the metadata that applies to each
+/// video frame.
+///
+/// KLV is usually sent at a lower rate than the video, so the metadata of
+/// a frame is the last sample with a timestamp before or at the frame
+/// timestamp, as long as it's not older than max_age.
+pub struct KlvCorrelator{
+    samples: VecDeque<KlvSample>,
+    max_age: GstClockTime,
+}
+
+impl KlvCorrelator{
+    /// Creates a correlator that ignores metadata older than max_age
+    /// nanoseconds relative to the frame
+    pub fn new(max_age: GstClockTime) -> KlvCorrelator{
+        KlvCorrelator{
+            samples: VecDeque::new(),
+            max_age: max_age,
+        }
+    }
+
+    /// Adds a sample, samples have to be pushed in timestamp order
+    pub fn push(&mut self, sample: KlvSample){
+        if sample.timestamp() != GST_CLOCK_TIME_NONE{
+            self.samples.push_back(sample);
+        }
+    }
+
+    /// Returns the metadata for the video frame with timestamp pts and
+    /// drops the samples that can't apply to later frames
+    pub fn metadata_for(&mut self, pts: GstClockTime) -> Option<&KlvSample>{
+        while self.samples.len() > 1 && self.samples[1].timestamp() <= pts{
+            self.samples.pop_front();
+        }
+        let max_age = self.max_age;
+        self.samples.front().filter(|sample| {
+            let timestamp = sample.timestamp();
+            timestamp <= pts && pts - timestamp <= max_age
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn packet(len: &[u8], value: &[u8]) -> Vec<u8>{
+        let mut data = MISB_0601_KEY.to_vec();
+        data.extend_from_slice(len);
+        data.extend_from_slice(value);
+        data
+    }
+
+    #[test]
+    fn short_length(){
+        let data = packet(&[3], &[1, 2, 3]);
+        let (packet, len) = KlvPacket::parse(&data).unwrap();
+        assert_eq!(len, 20);
+        assert_eq!(packet.key, MISB_0601_KEY);
+        assert_eq!(packet.value, vec![1, 2, 3]);
+        assert!(packet.is_misb_0601());
+    }
+
+    #[test]
+    fn long_length(){
+        let value = vec![7u8; 300];
+        let data = packet(&[0x82, 0x01, 0x2c], &value);
+        let (packet, len) = KlvPacket::parse(&data).unwrap();
+        assert_eq!(len, 16 + 3 + 300);
+        assert_eq!(packet.value, value);
+    }
+
+    #[test]
+    fn truncated(){
+        assert_eq!(KlvPacket::parse(&MISB_0601_KEY), None);
+        assert_eq!(KlvPacket::parse(&packet(&[4], &[1, 2, 3])), None);
+        assert_eq!(KlvPacket::parse(&packet(&[0x82, 0x01], &[])), None);
+    }
+
+    #[test]
+    fn malformed_length(){
+        // indefinite length and more than 8 length bytes
+        assert_eq!(KlvPacket::parse(&packet(&[0x80], &[1])), None);
+        assert_eq!(KlvPacket::parse(&packet(&[0x89, 0, 0, 0, 0, 0, 0, 0, 0, 1], &[1])), None);
+        assert_eq!(KlvPacket::parse(&packet(&[0x88, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff], &[1])), None);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width="32")]
+    fn length_larger_than_usize(){
+        assert_eq!(KlvPacket::parse(&packet(&[0x85, 0x01, 0, 0, 0, 0], &[1])), None);
+    }
+
+    #[test]
+    fn parse_all_stops_at_the_first_bad_packet(){
+        let mut data = packet(&[1], &[1]);
+        data.extend(packet(&[2], &[2, 2]));
+        data.extend(packet(&[5], &[3]));
+        let packets = KlvPacket::parse_all(&data);
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[1].value, vec![2, 2]);
+    }
+
+    #[test]
+    fn version_byte_is_ignored(){
+        let mut data = packet(&[0], &[]);
+        data[7] = 0x03;
+        assert!(KlvPacket::parse(&data).unwrap().0.is_misb_0601());
+        data[8] = 0;
+        assert!(!KlvPacket::parse(&data).unwrap().0.is_misb_0601());
+    }
+}
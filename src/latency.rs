@@ -0,0 +1,129 @@
+use ffi::*;
+use bin::{Bin, BinT};
+use element::{Element, ElementT};
+use pad::Pad;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// Buffers an element can hold before its entry times are discarded, as the
+// ones dropped by the element or merged into a single output
+const MAX_PENDING: usize = 64;
+
+/// Processing latency of an element in nanoseconds, as returned by
+/// measure_latency
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct LatencyStats{
+    pub min: u64,
+    pub avg: u64,
+    pub max: u64,
+    /// Number of buffers measured
+    pub samples: u64,
+}
+
+#[derive(Default)]
+struct Measurement{
+    pending: VecDeque<u64>,
+    min: u64,
+    max: u64,
+    total: u64,
+    samples: u64,
+}
+
+impl Measurement{
+    fn stats(&self) -> Option<LatencyStats>{
+        if self.samples == 0{
+            return None;
+        }
+        Some(LatencyStats{
+            min: self.min,
+            avg: self.total / self.samples,
+            max: self.max,
+            samples: self.samples,
+        })
+    }
+}
+
+/// Measures for duration how long each element of a playing pipeline
+/// takes from receiving a buffer on a sink pad to pushing the result on
+/// a source pad, blocking the calling thread while measuring.
+///
+/// Returns the name of each element that processed buffers and its
+/// latency, slowest first. Buffers are matched in order, so the values
+/// are only meaningful for elements that output a buffer per input;
+/// encoders with lookahead and queues report the time buffers spend
+/// waiting in them, which is usually what's looked for when finding the
+/// element that makes a live pipeline late.
+pub fn measure_latency<B: BinT>(pipeline: &B, duration: Duration) -> Vec<(String, LatencyStats)>{
+    let mut elements = Vec::new();
+    leaf_elements(pipeline.as_bin(), &mut elements);
+
+    let mut probes: Vec<(Pad, u64)> = Vec::new();
+    let mut measurements = Vec::new();
+    for element in elements{
+        let pads = element.pads();
+        if !pads.iter().any(|pad| pad.is_sink()) || !pads.iter().any(|pad| pad.is_src()){
+            continue;
+        }
+        let measurement = Arc::new(Mutex::new(Measurement::default()));
+        for pad in pads{
+            let m = measurement.clone();
+            let id = if pad.is_sink(){
+                pad.add_buffer_probe(move |_, _| {
+                    let mut m = m.lock().unwrap();
+                    if m.pending.len() == MAX_PENDING{
+                        m.pending.pop_front();
+                    }
+                    m.pending.push_back(unsafe{ gst_util_get_timestamp() });
+                    GST_PAD_PROBE_OK
+                })
+            }else{
+                pad.add_buffer_probe(move |_, _| {
+                    let mut m = m.lock().unwrap();
+                    if let Some(start) = m.pending.pop_front(){
+                        let latency = unsafe{ gst_util_get_timestamp() }.saturating_sub(start);
+                        if m.samples == 0 || latency < m.min{
+                            m.min = latency;
+                        }
+                        if latency > m.max{
+                            m.max = latency;
+                        }
+                        m.total += latency;
+                        m.samples += 1;
+                    }
+                    GST_PAD_PROBE_OK
+                })
+            };
+            probes.push((pad, id));
+        }
+        measurements.push((element.name(), measurement));
+    }
+
+    thread::sleep(duration);
+
+    for (pad, id) in probes{
+        pad.remove_probe(id);
+    }
+    let mut latencies: Vec<(String, LatencyStats)> = measurements.into_iter()
+        .filter_map(|(name, measurement)| measurement.lock().unwrap().stats().map(|stats| (name, stats)))
+        .collect();
+    latencies.sort_by(|a, b| b.1.avg.cmp(&a.1.avg));
+    latencies
+}
+
+fn leaf_elements(bin: &Bin, elements: &mut Vec<Element>){
+    for child in bin.children(){
+        unsafe{
+            if g_type_check_instance_is_a(child.gst_element() as *mut GTypeInstance, gst_bin_get_type()) != 0{
+                gst_object_ref(child.gst_element() as gpointer);
+                if let Some(child_bin) = Bin::new_from_gst_bin(child.gst_element() as *mut GstBin){
+                    leaf_elements(&child_bin, elements);
+                }
+                continue;
+            }
+        }
+        elements.push(child);
+    }
+}
@@ -1,30 +1,135 @@
 #![crate_type = "lib"]
 #![crate_name = "gst"]
 
+#[macro_use]
+extern crate bitflags;
+#[cfg(feature="raw-window-handle")]
+extern crate raw_window_handle;
+#[cfg(feature="serde")]
+extern crate serde;
+#[cfg(feature="tokio")]
+extern crate tokio;
+#[cfg(feature="tokio")]
+extern crate futures_core;
+
 pub use self::appsink::AppSink;
 pub use self::appsrc::AppSrc;
 pub use self::sample::Sample;
 pub use self::caps::Caps;
-pub use self::buffer::Buffer;
+pub use self::structure::Structure;
+pub use self::taglist::TagList;
+pub use self::value::Value;
+pub use self::buffer::{Buffer, BufferFlags, GST_BUFFER_FLAG_SYNC_AFTER, GST_BUFFER_FLAG_NON_DROPPABLE};
+pub use self::format::{Bytes, Frames, ClockTimeDiff};
 pub use self::mapinfo::MapInfo;
 pub use self::mapinfo::Map;
 pub use self::element::Element;
 pub use self::element::ElementT;
+pub use self::element::{PositionError, PendingState, StateChangeError};
+pub use self::element_factory::ElementFactory;
 pub use self::bus::Bus;
+pub use self::pad::Pad;
+pub use self::event::{Event, EventType, configure_sparse_sink, new_silence_src};
+pub use self::seek::{Seek, SeekBuilder, SeekFlags, GST_SEEK_FLAG_TRICKMODE, GST_SEEK_FLAG_TRICKMODE_KEY_UNITS, GST_SEEK_FLAG_TRICKMODE_NO_AUDIO, GST_SEEK_FLAG_TRICKMODE_FORWARD_PREDICTED, GST_SEEK_FLAG_INSTANT_RATE_CHANGE};
 pub use self::bin::Bin;
 pub use self::bin::BinT;
 pub use self::pipeline::Pipeline;
 pub use self::pipeline::PipelineT;
-pub use self::playbin::PlayBin;
+pub use self::pipeline::PrerollError;
+pub use self::pipeline_builder::PipelineBuilder;
+pub use self::pipeline_spec::{PipelineSpec, ElementSpec, LinkSpec, SpecValue};
+pub use self::position_cache::CachedPosition;
+pub use self::playbin::{PlayBin, PlayFlags};
+pub use self::encoder_config::{EncoderConfig, H264Profile, load_preset};
+pub use self::encoding_profile::{EncodingProfile, GstEncodingProfile};
+pub use self::transcoder::{Transcoder, TranscodeProgress, TranscodeError, TranscodeCanceller};
+pub use self::audio_extract::{AudioExtractor, AudioBuffer, AudioSamples, AudioFormat, SampleFormat, WaveformBucket, extract_audio, waveform};
+pub use self::frame_extract::extract_frames;
+pub use self::remux::{Remuxer, remux};
+pub use self::keyframes::{KeyframeScan, Snap, keyframes, snap_to_keyframe};
+pub use self::parse::{ParseError, parse_launch, parse_bin};
+pub use self::properties::{Properties, PropertyValue, check_property, write_property, read_property};
+pub use self::identity::Identity;
+pub use self::base_sink::BaseSinkT;
+pub use self::decodebin::{DecodeBin, UriDecodeBin, DecodeBinT, AutoplugSelect};
+pub use self::source_setup::{SourceSetupT, HttpCredentials, HTTP_HEADERS_CONTEXT};
+pub use self::buffering::{BufferingStatus, QueueLevel, queue_level, GST_FORMAT_PERCENT_MAX};
 pub use self::message::Message;
+pub use self::stream_collection::{Stream, StreamCollection, GstStream, GstStreamCollection, GstStreamType};
+pub use self::stream_collection::{GST_STREAM_TYPE_UNKNOWN, GST_STREAM_TYPE_AUDIO, GST_STREAM_TYPE_VIDEO, GST_STREAM_TYPE_CONTAINER, GST_STREAM_TYPE_TEXT};
+pub use self::stream_collection::{GST_MESSAGE_STREAM_COLLECTION, GST_MESSAGE_STREAMS_SELECTED};
 pub use self::mainloop::MainLoop;
+pub use self::mainloop::MainContext;
 pub use self::error::Error;
+pub use self::error::ErrorKind;
+pub use self::error::ErrorDomain;
 pub use self::error::Result;
 pub use self::videoframe::VideoFrame;
 pub use self::videoframe::VideoPlane;
 pub use self::videoframe::VideoComponent;
 pub use self::videoinfo::VideoInfo;
+pub use self::video_converter::{VideoConverter, VideoConverterConfig, VideoResamplerMethod, VideoDitherMethod, GstVideoConverter};
+pub use self::videooverlay::VideoOverlay;
 pub use self::buffer_pool::BufferPool;
+pub use self::equalizer::Equalizer;
+pub use self::replaygain::{ReplayGain, RgVolume, RgAnalysis};
+pub use self::device::{Device, DeviceMonitor};
+pub use self::camera::CameraSource;
+pub use self::screen_capture::ScreenCapture;
+pub use self::audio_devices::AudioDevices;
+pub use self::v4l2::V4l2Src;
+pub use self::compositor::{Compositor, CompositorInput};
+pub use self::audiomixer::{AudioMixer, AudioMixerInput};
+pub use self::muxer::{Muxer, MuxerInput, StreamType};
+pub use self::audio_converter::{AudioConverter, AudioResampler, ResamplerMethod, GstAudioConverter, GstAudioResampler, DEFAULT_RESAMPLER_QUALITY};
+pub use self::audio_channels::{AudioChannelPosition, Interleave, Deinterleave, channel_mask, positions_from_mask, sort_positions};
+pub use self::text_overlay::{TextOverlayT, TextOverlay, TimeOverlay, ClockOverlay, HAlignment, VAlignment, TimeMode};
+pub use self::overlay_composition::{OverlayRectangle, OverlayComposition};
+pub use self::timecode::{VideoTimeCode, TimeCodeStamper, GstVideoTimeCode, GstVideoTimeCodeConfig, GstVideoTimeCodeMeta, GstVideoTimeCodeFlags};
+pub use self::timecode::{GST_VIDEO_TIME_CODE_FLAGS_NONE, GST_VIDEO_TIME_CODE_FLAGS_DROP_FRAME, GST_VIDEO_TIME_CODE_FLAGS_INTERLACED};
+pub use self::closed_caption::{CaptionType, VideoCaption, CcExtractor, CcCombiner, GstVideoCaptionMeta, GstVideoCaptionType};
+pub use self::closed_caption::{CEA608_RAW_CAPS, CEA608_S334_1A_CAPS, CEA708_RAW_CAPS, CEA708_CDP_CAPS};
+pub use self::closed_caption::{GST_VIDEO_CAPTION_TYPE_UNKNOWN, GST_VIDEO_CAPTION_TYPE_CEA608_RAW, GST_VIDEO_CAPTION_TYPE_CEA608_S334_1A, GST_VIDEO_CAPTION_TYPE_CEA708_RAW, GST_VIDEO_CAPTION_TYPE_CEA708_CDP};
+pub use self::klv::{KlvPacket, KlvSample, KlvExtractor, KlvCorrelator, KLV_CAPS, MISB_0601_KEY, is_klv_pad};
+pub use self::protection::{ProtectionEvent, ProtectionInfo, GstProtectionMeta, GST_EVENT_PROTECTION, select_protection_system, protection_system_id};
+pub use self::protection::{CLEARKEY_SYSTEM_ID, WIDEVINE_SYSTEM_ID, PLAYREADY_SYSTEM_ID, FAIRPLAY_SYSTEM_ID, MARLIN_SYSTEM_ID, PROTECTION_SYSTEM_ID_CAPS_FIELD};
+pub use self::multiview::{MultiviewMode, DownmixMode, GlViewConvert, GstVideoMultiviewMode, GstVideoMultiviewFlags, multiview_flags_from_caps, set_multiview_caps};
+pub use self::multiview::{GST_VIDEO_MULTIVIEW_MODE_NONE, GST_VIDEO_MULTIVIEW_MODE_MONO, GST_VIDEO_MULTIVIEW_MODE_LEFT, GST_VIDEO_MULTIVIEW_MODE_RIGHT, GST_VIDEO_MULTIVIEW_MODE_SIDE_BY_SIDE, GST_VIDEO_MULTIVIEW_MODE_SIDE_BY_SIDE_QUINCUNX, GST_VIDEO_MULTIVIEW_MODE_COLUMN_INTERLEAVED, GST_VIDEO_MULTIVIEW_MODE_ROW_INTERLEAVED, GST_VIDEO_MULTIVIEW_MODE_TOP_BOTTOM, GST_VIDEO_MULTIVIEW_MODE_CHECKERBOARD, GST_VIDEO_MULTIVIEW_MODE_FRAME_BY_FRAME, GST_VIDEO_MULTIVIEW_MODE_MULTIVIEW_FRAME_BY_FRAME, GST_VIDEO_MULTIVIEW_MODE_SEPARATED};
+pub use self::multiview::{GST_VIDEO_MULTIVIEW_FLAGS_NONE, GST_VIDEO_MULTIVIEW_FLAGS_RIGHT_VIEW_FIRST, GST_VIDEO_MULTIVIEW_FLAGS_LEFT_FLIPPED, GST_VIDEO_MULTIVIEW_FLAGS_LEFT_FLOPPED, GST_VIDEO_MULTIVIEW_FLAGS_RIGHT_FLIPPED, GST_VIDEO_MULTIVIEW_FLAGS_RIGHT_FLOPPED, GST_VIDEO_MULTIVIEW_FLAGS_HALF_ASPECT, GST_VIDEO_MULTIVIEW_FLAGS_MIXED_MONO};
+pub use self::context::Context;
+pub use self::vulkan::{VulkanSink, vulkan_upload, vulkan_download, vulkan_image_caps, vk_instance, vk_device};
+pub use self::vulkan::{CAPS_FEATURE_MEMORY_VULKAN_IMAGE, CAPS_FEATURE_MEMORY_VULKAN_BUFFER, VULKAN_INSTANCE_CONTEXT_TYPE, VULKAN_DEVICE_CONTEXT_TYPE};
+pub use self::meta::{MetaApi, MetaRef, MetaIter, VideoMeta, AudioMeta, ReferenceTimestampMeta, ParentBufferMeta, GstAudioInfo, GstAudioMeta, GstReferenceTimestampMeta, GstParentBufferMeta, register_custom_meta};
+pub use self::meta::{NTP_TIMESTAMP_REFERENCE, PTP_TIMESTAMP_REFERENCE, UNIX_TIMESTAMP_REFERENCE, ptp_timestamp_reference};
+pub use self::roi::VideoRegionOfInterestMeta;
+pub use self::tensor_meta::{Tensor, TensorData, TensorMeta};
+pub use self::byte_reader::{ByteReader, BitReader, ByteWriter, GstByteReader, GstBitReader, GstByteWriter};
+pub use self::uri::{Uri, UriType, UriHandlerT, GstUri, GST_URI_NO_PORT, uri_is_valid, uri_protocol_is_supported, uri_to_filename};
+pub use self::fraction::Fraction;
+pub use self::topology::{PipelineGraph, ElementNode, PadNode, Link};
+pub use self::tracer::{Tracer, TracerRecord, LatencyRecord, ElementLatencyRecord, register_tracer, enable_tracers, add_tracer_record_handler};
+pub use self::latency::{LatencyStats, measure_latency};
+pub use self::monitor::{PipelineMonitor, MonitorSnapshot, PadStats};
+pub use self::watchdog::{Watchdog, RestartPolicy, StallInfo, watchdog_element};
+pub use self::resilient_source::{ResilientSource, Backoff, SourceState};
+pub use self::udp::{UdpSrc, UdpSink, MultiUdpSink, UdpSinkT};
+pub use self::gio::{GSocket, GSocketAddress};
+pub use self::srt::{SrtSrc, SrtSink, SrtT, SrtMode, SrtKeyLength, SrtStats};
+pub use self::rist::{RistSrc, RistSink, RistT, RistStats};
+pub use self::tcp::{TcpServerSink, TcpClientSrc, TcpServerSrc};
+pub use self::subclass::{Metadata, PadTemplate, ElementImpl};
+pub use self::device_provider::{DeviceProvider, DeviceProviderImpl, register_device_provider};
+pub use self::bin_impl::{BinImpl, register_bin, register_pipeline};
+pub use self::aggregator::{Aggregator, AggregatorPad, AggregatorImpl, GstAggregator, GstAggregatorPad, register_aggregator};
+pub use self::video_codec::{VideoCodecState, VideoCodecFrame, VideoDecoder, VideoDecoderImpl, VideoEncoder, VideoEncoderImpl, register_video_decoder, register_video_encoder};
+pub use self::audio_codec::{AudioDecoder, AudioDecoderImpl, AudioEncoder, AudioEncoderImpl, GstAudioDecoder, GstAudioEncoder, register_audio_decoder, register_audio_encoder};
+pub use self::sink_impl::{AudioSink, AudioSinkImpl, AudioBaseSink, AudioBaseSinkImpl, AudioBaseSinkT, AudioRingBuffer, AudioRingBufferImpl, GstAudioRingBuffer, GstAudioRingBufferSpec, VideoSink, VideoSinkImpl, register_audio_sink, register_audio_base_sink, register_video_sink};
+pub use self::task::{Task, TaskPool, TaskPoolImpl, TaskPoolJob};
+pub use self::queue::{AtomicQueue, DataQueue, DataQueueLevel, GstDataQueue};
+#[cfg(unix)]
+pub use self::fd::{FdSrc, FdSink};
+#[cfg(unix)]
+pub use self::shm::{ShmSrc, ShmSink, shm_sender, shm_receiver};
 
 pub use ffi::*;
 use std::ptr;
@@ -39,19 +144,114 @@ pub mod appsink;
 pub mod appsrc;
 mod sample;
 mod caps;
+mod structure;
+mod taglist;
+mod value;
 mod buffer;
+mod format;
 mod element;
+mod element_factory;
+mod pad;
+mod event;
+mod seek;
 pub mod bus;
 mod bin;
 mod pipeline;
+mod pipeline_builder;
+mod pipeline_spec;
+mod position_cache;
 mod playbin;
+mod buffering;
+mod encoder_config;
+mod encoding_profile;
+mod transcoder;
+mod decode_util;
+mod audio_extract;
+mod frame_extract;
+mod remux;
+mod keyframes;
+mod parse;
+mod properties;
+mod identity;
+mod base_sink;
+mod source_setup;
+mod decodebin;
 mod message;
+mod stream_collection;
 pub mod mainloop;
+#[cfg(feature="tokio")]
+pub mod tokio_support;
+#[cfg(feature="mpegts")]
+pub mod mpegts;
+#[cfg(all(target_os="windows", feature="d3d11"))]
+pub mod d3d11;
+#[cfg(feature="cuda")]
+pub mod cuda;
+#[cfg(feature="rtsp-server")]
+pub mod rtsp_server;
+#[cfg(feature="validate")]
+pub mod validate;
+#[cfg(feature="test-support")]
+pub mod test_support;
 mod error;
 mod videoframe;
 mod videoinfo;
+mod videooverlay;
 mod mapinfo;
 mod buffer_pool;
+mod equalizer;
+mod replaygain;
+mod device;
+mod camera;
+mod screen_capture;
+mod audio_devices;
+mod v4l2;
+mod compositor;
+mod audiomixer;
+mod muxer;
+mod audio_channels;
+mod audio_converter;
+mod video_converter;
+mod text_overlay;
+mod overlay_composition;
+mod timecode;
+mod closed_caption;
+mod klv;
+mod protection;
+mod multiview;
+mod context;
+mod vulkan;
+mod meta;
+mod roi;
+mod tensor_meta;
+mod byte_reader;
+mod uri;
+mod fraction;
+mod topology;
+pub mod debug;
+mod tracer;
+mod latency;
+mod monitor;
+mod watchdog;
+mod resilient_source;
+mod gio;
+mod udp;
+mod srt;
+mod rist;
+mod tcp;
+mod subclass;
+mod device_provider;
+mod bin_impl;
+mod aggregator;
+mod video_codec;
+mod audio_codec;
+mod sink_impl;
+mod task;
+mod queue;
+#[cfg(unix)]
+mod fd;
+#[cfg(unix)]
+mod shm;
 #[cfg(target_os="linux")]
 mod link_linux;
 #[cfg(target_os="macos")]
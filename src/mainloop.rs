@@ -1,4 +1,5 @@
 use ffi::*;
+use util::box_destroy_notify;
 use std::ptr;
 use std::thread;
 use std::mem;
@@ -7,6 +8,118 @@ use std::os::raw::c_void;
 
 unsafe impl Sync for MainLoop {}
 unsafe impl Send for MainLoop {}
+unsafe impl Sync for MainContext {}
+unsafe impl Send for MainContext {}
+
+/// A set of sources to be dispatched by a main loop. Bus watches and
+/// signals are dispatched by the context that was the thread default when
+/// they were added, by default the global default context.
+pub struct MainContext{
+	context: *mut GMainContext
+}
+
+impl Drop for MainContext{
+	fn drop(&mut self){
+		unsafe{
+			g_main_context_unref(self.context);
+		}
+	}
+}
+
+impl Clone for MainContext{
+	fn clone(&self) -> MainContext{
+		unsafe{
+			MainContext{ context: g_main_context_ref(self.context) }
+		}
+	}
+}
+
+impl MainContext{
+	/// Creates a new context, to be run by a MainLoop created with
+	/// MainLoop::new_with_context or iterated manually
+	pub fn new() -> MainContext{
+		unsafe{
+			MainContext{ context: g_main_context_new() }
+		}
+	}
+
+	/// Returns the global default context, the one used by MainLoop::new()
+	pub fn default() -> MainContext{
+		unsafe{
+			MainContext{ context: g_main_context_ref(g_main_context_default()) }
+		}
+	}
+
+	/// Returns the context that is the default for the current thread
+	pub fn thread_default() -> MainContext{
+		unsafe{
+			MainContext{ context: g_main_context_ref_thread_default() }
+		}
+	}
+
+	/// Makes this context the default for the current thread until
+	/// pop_thread_default is called, so bus watches added in the meantime
+	/// are dispatched by it.
+	pub fn push_thread_default(&self){
+		unsafe{
+			g_main_context_push_thread_default(self.context);
+		}
+	}
+
+	/// Undoes a previous push_thread_default
+	pub fn pop_thread_default(&self){
+		unsafe{
+			g_main_context_pop_thread_default(self.context);
+		}
+	}
+
+	/// Runs a single iteration of the context, dispatching any ready
+	/// sources. If may_block is true it waits for a source to become ready.
+	///
+	/// Returns true if any source was dispatched.
+	pub fn iteration(&self, may_block: bool) -> bool{
+		unsafe{
+			g_main_context_iteration(self.context, may_block as gboolean) == 1
+		}
+	}
+
+	/// Returns true if any source has pending events
+	pub fn pending(&self) -> bool{
+		unsafe{
+			g_main_context_pending(self.context) == 1
+		}
+	}
+
+	/// Wakes up the context if it's blocked in iteration
+	pub fn wakeup(&self){
+		unsafe{
+			g_main_context_wakeup(self.context);
+		}
+	}
+
+	/// Calls f from the thread running this context. If the current thread
+	/// already owns the context f is called immediately.
+	pub fn invoke<F: FnOnce() + Send + 'static>(&self, f: F){
+		unsafe{
+			let f: Box<Option<F>> = Box::new(Some(f));
+			g_main_context_invoke_full(self.context, 0, Some(invoke_callback::<F>), Box::into_raw(f) as gpointer, Some(box_destroy_notify::<Option<F>>));
+		}
+	}
+
+	pub unsafe fn gst_main_context(&self) -> *mut GMainContext{
+		self.context
+	}
+}
+
+extern "C" fn invoke_callback<F: FnOnce()>(data: gpointer) -> gboolean{
+	unsafe{
+		let f = data as *mut Option<F>;
+		if let Some(f) = (*f).take(){
+			f();
+		}
+		0
+	}
+}
 
 pub struct MainLoop{
 	gst_loop: *mut GMainLoop,
@@ -31,6 +144,27 @@ impl MainLoop{
 		}
 	}
 
+	/// Creates a main loop that will dispatch the sources of context
+	pub fn new_with_context(context: &MainContext) -> MainLoop{
+		unsafe{
+			MainLoop{ gst_loop: g_main_loop_new(context.gst_main_context(), 0), running: false }
+		}
+	}
+
+	/// Returns the context this loop dispatches
+	pub fn context(&self) -> MainContext{
+		unsafe{
+			MainContext{ context: g_main_context_ref(g_main_loop_get_context(self.gst_loop)) }
+		}
+	}
+
+	/// Returns true while the loop is running
+	pub fn is_running(&self) -> bool{
+		unsafe{
+			g_main_loop_is_running(self.gst_loop) == 1
+		}
+	}
+
 	#[cfg(all(unix, target_pointer_width = "32"))]
 	pub fn spawn(&mut self){
 		if !self.running {
@@ -1,7 +1,11 @@
 use ffi::*;
 use util::*;
 use error::Error;
+use stream_collection::*;
+use device::Device;
+use context::Context;
 use std::os::raw;
+use std::fmt::{self, Debug, Display, Formatter};
 
 unsafe impl Send for GstMessage {}
 unsafe impl Send for GstTagList {}
@@ -52,10 +56,18 @@ pub enum Message{
     ResetTime(MessagePrivate),
     StreamStart(MessagePrivate),
     NeedContext(MessagePrivate),
+    NeedContextParsed{msg: MessagePrivate, context_type: String},
     HaveContext(MessagePrivate),
+    HaveContextParsed{msg: MessagePrivate, context: Context},
     Extended(MessagePrivate),
     DeviceAdded(MessagePrivate),
+    DeviceAddedParsed{msg: MessagePrivate, device: Device},
     DeviceRemoved(MessagePrivate),
+    DeviceRemovedParsed{msg: MessagePrivate, device: Device},
+    StreamCollection(MessagePrivate),
+    StreamCollectionParsed{msg: MessagePrivate, collection: StreamCollection},
+    StreamsSelected(MessagePrivate),
+    StreamsSelectedParsed{msg: MessagePrivate, collection: StreamCollection, streams: Vec<Stream>},
     Any(MessagePrivate),
 }
 
@@ -107,6 +119,8 @@ impl Message{
                  GST_MESSAGE_EXTENDED => Some(Message::Extended(gst_message)),
                  GST_MESSAGE_DEVICE_ADDED => Some(Message::DeviceAdded(gst_message)),
                  GST_MESSAGE_DEVICE_REMOVED => Some(Message::DeviceRemoved(gst_message)),
+                 GST_MESSAGE_STREAM_COLLECTION => Some(Message::StreamCollection(gst_message)),
+                 GST_MESSAGE_STREAMS_SELECTED => Some(Message::StreamsSelected(gst_message)),
                  GST_MESSAGE_ANY => Some(Message::Any(gst_message)),
                  _ => None
             }
@@ -218,10 +232,18 @@ impl Message{
             Message::ResetTime(msg) => msg,
             Message::StreamStart(msg) => msg,
             Message::NeedContext(msg) => msg,
+            Message::NeedContextParsed{msg, ref context_type} => msg,
             Message::HaveContext(msg) => msg,
+            Message::HaveContextParsed{msg, ref context} => msg,
             Message::Extended(msg) => msg,
             Message::DeviceAdded(msg) => msg,
+            Message::DeviceAddedParsed{msg, ref device} => msg,
             Message::DeviceRemoved(msg) => msg,
+            Message::DeviceRemovedParsed{msg, ref device} => msg,
+            Message::StreamCollection(msg) => msg,
+            Message::StreamCollectionParsed{msg, ref collection} => msg,
+            Message::StreamsSelected(msg) => msg,
+            Message::StreamsSelectedParsed{msg, ref collection, ref streams} => msg,
             Message::Any(msg) => msg,
         }
     }
@@ -266,10 +288,18 @@ impl Message{
             Message::ResetTime(msg) => msg,
             Message::StreamStart(msg) => msg,
             Message::NeedContext(msg) => msg,
+            Message::NeedContextParsed{msg, ref context_type} => msg,
             Message::HaveContext(msg) => msg,
+            Message::HaveContextParsed{msg, ref context} => msg,
             Message::Extended(msg) => msg,
             Message::DeviceAdded(msg) => msg,
+            Message::DeviceAddedParsed{msg, ref device} => msg,
             Message::DeviceRemoved(msg) => msg,
+            Message::DeviceRemovedParsed{msg, ref device} => msg,
+            Message::StreamCollection(msg) => msg,
+            Message::StreamCollectionParsed{msg, ref collection} => msg,
+            Message::StreamsSelected(msg) => msg,
+            Message::StreamsSelectedParsed{msg, ref collection, ref streams} => msg,
             Message::Any(msg) => msg,
         }
     }
@@ -314,6 +344,12 @@ impl Message{
         }
     }
 
+    fn has_src_name(&self) -> bool{
+        unsafe{
+            self.src() != ptr::null_mut() && (*self.src()).name != ptr::null_mut()
+        }
+    }
+
     pub unsafe fn structure(&self) -> *const GstStructure{
         gst_message_get_structure(mem::transmute(self.gst_message()))
     }
@@ -381,6 +417,73 @@ impl Message{
                     let message = gst_message_ref(message);
                     Message::StateChangedParsed{msg: message, old: old, new: new, pending: pending}
                 }
+                Message::NeedContext(message) => {
+                    let mut context_type: *const gchar = ptr::null();
+                    if gst_message_parse_context_type(message,&mut context_type) == 1{
+                        let message = gst_message_ref(message);
+                        Message::NeedContextParsed{msg: message, context_type: from_c_str!(context_type).to_string()}
+                    }else{
+                        ret
+                    }
+                }
+                Message::HaveContext(message) => {
+                    let mut context: *mut GstContext = ptr::null_mut();
+                    gst_message_parse_have_context(message,&mut context);
+                    match Context::new_from_gst_context(context, true){
+                        Some(context) => {
+                            let message = gst_message_ref(message);
+                            Message::HaveContextParsed{msg: message, context: context}
+                        }
+                        None => ret
+                    }
+                }
+                Message::DeviceAdded(message) => {
+                    let mut device: *mut GstDevice = ptr::null_mut();
+                    gst_message_parse_device_added(message,&mut device);
+                    match Device::new(device, true){
+                        Some(device) => {
+                            let message = gst_message_ref(message);
+                            Message::DeviceAddedParsed{msg: message, device: device}
+                        }
+                        None => ret
+                    }
+                }
+                Message::DeviceRemoved(message) => {
+                    let mut device: *mut GstDevice = ptr::null_mut();
+                    gst_message_parse_device_removed(message,&mut device);
+                    match Device::new(device, true){
+                        Some(device) => {
+                            let message = gst_message_ref(message);
+                            Message::DeviceRemovedParsed{msg: message, device: device}
+                        }
+                        None => ret
+                    }
+                }
+                Message::StreamCollection(message) => {
+                    let mut collection: *mut GstStreamCollection = ptr::null_mut();
+                    gst_message_parse_stream_collection(message,&mut collection);
+                    match StreamCollection::new(collection, true){
+                        Some(collection) => {
+                            let message = gst_message_ref(message);
+                            Message::StreamCollectionParsed{msg: message, collection: collection}
+                        }
+                        None => ret
+                    }
+                }
+                Message::StreamsSelected(message) => {
+                    let mut collection: *mut GstStreamCollection = ptr::null_mut();
+                    gst_message_parse_streams_selected(message,&mut collection);
+                    match StreamCollection::new(collection, true){
+                        Some(collection) => {
+                            let streams = (0..gst_message_streams_selected_get_size(message))
+                                .filter_map(|i| Stream::new(gst_message_streams_selected_get_stream(message, i), true))
+                                .collect();
+                            let message = gst_message_ref(message);
+                            Message::StreamsSelectedParsed{msg: message, collection: collection, streams: streams}
+                        }
+                        None => ret
+                    }
+                }
                 _ => {
                     ret
                 }
@@ -427,6 +530,38 @@ impl ::Transfer<GstMessage> for Message{
     }
 }
 
+/// Formats the message as its type followed by the name of the object
+/// that posted it, for example `eos from pipeline0`
+impl Display for Message{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        if self.has_src_name(){
+            write!(fmt, "{} from {}", self.type_name(), self.src_name())
+        }else{
+            write!(fmt, "{}", self.type_name())
+        }
+    }
+}
+
+impl Debug for Message{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "Message {{ type: {}", self.type_name())?;
+        if self.has_src_name(){
+            write!(fmt, ", src: \"{}\"", self.src_name())?;
+        }
+        write!(fmt, ", seqnum: {}", self.seqnum())?;
+        unsafe{
+            let structure = self.structure();
+            if structure != ptr::null(){
+                let c_str = gst_structure_to_string(structure);
+                let string = from_c_str!(c_str).to_string();
+                g_free(c_str as gpointer);
+                write!(fmt, ", structure: {}", string)?;
+            }
+        }
+        write!(fmt, " }}")
+    }
+}
+
 /*pub trait MessageT{
     unsafe fn gst_message(&self) -> *mut GstMessage;
 
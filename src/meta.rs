@@ -0,0 +1,418 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use caps::Caps;
+use structure::Structure;
+
+use std::marker::PhantomData;
+use std::os::raw::c_void;
+use std::slice;
+use std::fmt::{self, Debug, Formatter};
+
+/// Implemented by the structs of the metas that can be retrieved from a
+/// buffer with Buffer::meta or MetaRef::downcast.
+///
+/// Unsafe because the struct has to start with a GstMeta and match the
+/// layout of the meta registered for api_type
+pub unsafe trait MetaApi{
+    fn api_type() -> GType;
+}
+
+// The audio meta was added in GStreamer 1.16, the reference timestamp
+// meta in 1.14 and the parent buffer meta in 1.6, after ffi.rs was
+// generated
+#[repr(C)]
+pub struct GstAudioInfo{
+    pub finfo: *const c_void,
+    pub flags: guint,
+    pub layout: guint,
+    pub rate: gint,
+    pub channels: gint,
+    pub bpf: gint,
+    pub position: [gint; 64],
+    pub _gst_reserved: [gpointer; 4],
+}
+
+#[repr(C)]
+pub struct GstAudioMeta{
+    pub meta: GstMeta,
+    pub info: GstAudioInfo,
+    pub samples: gsize,
+    pub offsets: *mut gsize,
+    pub priv_offsets_arr: [gsize; 8],
+    pub _gst_reserved: [gpointer; 4],
+}
+
+#[repr(C)]
+pub struct GstReferenceTimestampMeta{
+    pub meta: GstMeta,
+    pub reference: *mut GstCaps,
+    pub timestamp: GstClockTime,
+    pub duration: GstClockTime,
+}
+
+#[repr(C)]
+pub struct GstParentBufferMeta{
+    pub meta: GstMeta,
+    pub buffer: *mut GstBuffer,
+}
+
+pub enum GstCustomMeta {}
+
+#[cfg_attr(not(target_os="macos"), link(name = "gstaudio-1.0"))]
+extern "C"{
+    fn gst_audio_meta_api_get_type() -> GType;
+}
+
+extern "C"{
+    fn gst_reference_timestamp_meta_api_get_type() -> GType;
+    fn gst_parent_buffer_meta_api_get_type() -> GType;
+    fn gst_buffer_add_reference_timestamp_meta(buffer: *mut GstBuffer, reference: *mut GstCaps,
+        timestamp: GstClockTime, duration: GstClockTime) -> *mut GstReferenceTimestampMeta;
+    fn gst_buffer_get_reference_timestamp_meta(buffer: *mut GstBuffer, reference: *mut GstCaps)
+        -> *mut GstReferenceTimestampMeta;
+    // Custom metas were added in GStreamer 1.20
+    fn gst_meta_register_custom(name: *const gchar, tags: *mut *const gchar, transform_func: gpointer,
+        user_data: gpointer, destroy_data: gpointer) -> *const GstMetaInfo;
+    fn gst_meta_info_is_custom(info: *const GstMetaInfo) -> gboolean;
+    fn gst_custom_meta_has_name(meta: *mut GstCustomMeta, name: *const gchar) -> gboolean;
+    fn gst_custom_meta_get_structure(meta: *mut GstCustomMeta) -> *mut GstStructure;
+    fn gst_buffer_add_custom_meta(buffer: *mut GstBuffer, name: *const gchar) -> *mut GstCustomMeta;
+}
+
+/// Describes the layout of a video buffer, attached by elements and
+/// pools whose strides or plane offsets differ from the default ones
+pub type VideoMeta = GstVideoMeta;
+
+unsafe impl MetaApi for GstVideoMeta{
+    fn api_type() -> GType{
+        unsafe{ gst_video_meta_api_get_type() }
+    }
+}
+
+impl VideoMeta{
+    pub fn format(&self) -> GstVideoFormat{
+        self.format
+    }
+
+    pub fn width(&self) -> u32{
+        self.width
+    }
+
+    pub fn height(&self) -> u32{
+        self.height
+    }
+
+    pub fn n_planes(&self) -> u32{
+        self.n_planes
+    }
+
+    pub fn plane_offset(&self, p: usize) -> u64{
+        self.offset[p] as u64
+    }
+
+    pub fn plane_stride(&self, p: usize) -> i32{
+        self.stride[p]
+    }
+}
+
+/// Describes the layout of an audio buffer, mostly used to find the
+/// planes of non interleaved audio
+pub type AudioMeta = GstAudioMeta;
+
+unsafe impl MetaApi for GstAudioMeta{
+    fn api_type() -> GType{
+        unsafe{ gst_audio_meta_api_get_type() }
+    }
+}
+
+impl AudioMeta{
+    pub fn rate(&self) -> i32{
+        self.info.rate
+    }
+
+    pub fn channels(&self) -> i32{
+        self.info.channels
+    }
+
+    /// Bytes per frame
+    pub fn bpf(&self) -> i32{
+        self.info.bpf
+    }
+
+    /// Number of samples per channel in the buffer
+    pub fn samples(&self) -> usize{
+        self.samples as usize
+    }
+
+    /// Offsets in bytes of every channel plane, empty for interleaved
+    /// audio
+    pub fn offsets(&self) -> &[usize]{
+        if self.offsets != ptr::null_mut(){
+            unsafe{ slice::from_raw_parts(self.offsets as *const usize, self.info.channels as usize) }
+        }else{
+            &[]
+        }
+    }
+}
+
+/// Reference caps of NTP timestamps, in nanoseconds since 1900, as
+/// added by rtpjitterbuffer with add-reference-timestamp-meta enabled
+pub const NTP_TIMESTAMP_REFERENCE: &'static str = "timestamp/x-ntp";
+
+/// Reference caps of PTP timestamps, see ptp_timestamp_reference to
+/// restrict them to a domain
+pub const PTP_TIMESTAMP_REFERENCE: &'static str = "timestamp/x-ptp";
+
+/// Reference caps of the system realtime clock, in nanoseconds since
+/// the unix epoch
+pub const UNIX_TIMESTAMP_REFERENCE: &'static str = "timestamp/x-unix";
+
+/// Returns the reference caps of an IEEE 1588-2008 PTP clock in the
+/// given domain
+pub fn ptp_timestamp_reference(domain: u32) -> Option<Caps>{
+    Caps::from_string(&format!("{},version=(string)IEEE1588-2008,domain=(int){}", PTP_TIMESTAMP_REFERENCE, domain))
+}
+
+/// A timestamp of the buffer in some other clock than the pipeline one,
+/// as NTP or PTP, identified by the reference caps
+pub type ReferenceTimestampMeta = GstReferenceTimestampMeta;
+
+unsafe impl MetaApi for GstReferenceTimestampMeta{
+    fn api_type() -> GType{
+        unsafe{ gst_reference_timestamp_meta_api_get_type() }
+    }
+}
+
+impl ReferenceTimestampMeta{
+    /// Caps identifying the clock, as in `timestamp/x-ntp`
+    pub fn reference(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(self.reference, false)
+        }
+    }
+
+    pub fn timestamp(&self) -> GstClockTime{
+        self.timestamp
+    }
+
+    pub fn duration(&self) -> GstClockTime{
+        self.duration
+    }
+}
+
+/// Keeps alive the buffer this one was created from, as with the sub
+/// buffers created by some elements to avoid copies
+pub type ParentBufferMeta = GstParentBufferMeta;
+
+unsafe impl MetaApi for GstParentBufferMeta{
+    fn api_type() -> GType{
+        unsafe{ gst_parent_buffer_meta_api_get_type() }
+    }
+}
+
+impl ParentBufferMeta{
+    pub fn parent(&self) -> Option<Buffer>{
+        unsafe{
+            Buffer::new(self.buffer, false)
+        }
+    }
+}
+
+/// A meta attached to a buffer, as returned by Buffer::iter_meta
+pub struct MetaRef<'a>{
+    meta: *mut GstMeta,
+    phantom: PhantomData<&'a Buffer>,
+}
+
+impl<'a> MetaRef<'a>{
+    /// The type of the meta API, compare with MetaApi::api_type
+    pub fn api(&self) -> GType{
+        unsafe{
+            (*(*self.meta).info).api
+        }
+    }
+
+    pub fn api_name(&self) -> String{
+        unsafe{
+            from_c_str!(g_type_name(self.api())).to_string()
+        }
+    }
+
+    /// True if the meta API was registered with the given tag as in
+    /// "video" or "orientation"
+    pub fn has_tag(&self, tag: &str) -> bool{
+        unsafe{
+            gst_meta_api_type_has_tag(self.api(), g_quark_from_string(to_c_str!(tag))) != 0
+        }
+    }
+
+    pub fn is<M: MetaApi>(&self) -> bool{
+        self.api() == M::api_type()
+    }
+
+    pub fn downcast<M: MetaApi>(&self) -> Option<&'a M>{
+        if self.is::<M>(){
+            unsafe{ Some(&*(self.meta as *const M)) }
+        }else{
+            None
+        }
+    }
+
+    /// Name of the meta if it's a custom meta
+    pub fn custom_meta_name(&self) -> Option<String>{
+        unsafe{
+            if gst_meta_info_is_custom((*self.meta).info) != 0{
+                let structure = gst_custom_meta_get_structure(self.meta as *mut GstCustomMeta);
+                Some(from_c_str!(gst_structure_get_name(structure)).to_string())
+            }else{
+                None
+            }
+        }
+    }
+
+    pub unsafe fn gst_meta(&self) -> *const GstMeta{
+        self.meta
+    }
+}
+
+impl<'a> Debug for MetaRef<'a>{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "Meta({})", self.api_name())
+    }
+}
+
+/// Iterator over the metas of a buffer
+pub struct MetaIter<'a>{
+    buffer: &'a Buffer,
+    state: gpointer,
+}
+
+impl<'a> Iterator for MetaIter<'a>{
+    type Item = MetaRef<'a>;
+
+    fn next(&mut self) -> Option<MetaRef<'a>>{
+        unsafe{
+            let meta = gst_buffer_iterate_meta(self.buffer.gst_buffer() as *mut GstBuffer, &mut self.state);
+            if meta != ptr::null_mut(){
+                Some(MetaRef{ meta: meta, phantom: PhantomData })
+            }else{
+                None
+            }
+        }
+    }
+}
+
+/// Registers a custom meta with the given name, whose content is a
+/// Structure with the same name. Custom metas are copied along with the
+/// buffer so application data, as detections of an analysis element,
+/// travels through the pipeline to an appsink.
+///
+/// Registering an already registered name does nothing and returns
+/// true. Needs GStreamer 1.20
+pub fn register_custom_meta(name: &str, tags: &[&str]) -> bool{
+    unsafe{
+        let c_name = CString::new(name).unwrap();
+        if gst_meta_get_info(c_name.as_ptr()) != ptr::null(){
+            return true;
+        }
+        let c_tags: Vec<CString> = tags.iter().map(|tag| CString::new(*tag).unwrap()).collect();
+        let mut tags_ptrs: Vec<*const gchar> = c_tags.iter().map(|tag| tag.as_ptr()).collect();
+        tags_ptrs.push(ptr::null());
+        gst_meta_register_custom(c_name.as_ptr(), tags_ptrs.as_mut_ptr(),
+            ptr::null_mut(), ptr::null_mut(), ptr::null_mut()) != ptr::null()
+    }
+}
+
+impl Buffer{
+    /// Iterates over all the metas attached to the buffer
+    pub fn iter_meta(&self) -> MetaIter{
+        MetaIter{ buffer: self, state: ptr::null_mut() }
+    }
+
+    /// Returns the first meta of type M, as in buffer.meta::<VideoMeta>()
+    pub fn meta<M: MetaApi>(&self) -> Option<&M>{
+        unsafe{
+            let meta = gst_buffer_get_meta(self.gst_buffer() as *mut GstBuffer, M::api_type());
+            if meta != ptr::null_mut(){
+                Some(&*(meta as *const M))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns all the metas of type M
+    pub fn metas_of<M: MetaApi>(&self) -> Vec<&M>{
+        self.iter_meta().filter_map(|meta| meta.downcast::<M>()).collect()
+    }
+
+    /// Stamps the buffer with a timestamp and duration in the clock
+    /// identified by reference, as the capture time of a hardware clock
+    /// or an NTP time, so consumers can correlate the buffer with data
+    /// timestamped in that clock
+    pub fn add_reference_timestamp(&mut self, reference: &Caps, timestamp: GstClockTime, duration: GstClockTime){
+        unsafe{
+            gst_buffer_add_reference_timestamp_meta(self.gst_buffer_mut(), reference.gst_caps() as *mut GstCaps,
+                timestamp, duration);
+        }
+    }
+
+    /// Returns the first reference timestamp whose reference caps are a
+    /// subset of reference, as in:
+    ///
+    /// ```ignore
+    /// let ntp = Caps::from_string(NTP_TIMESTAMP_REFERENCE).unwrap();
+    /// if let Some(meta) = buffer.reference_timestamp(&ntp){
+    ///     println!("captured at {} ns since 1900", meta.timestamp());
+    /// }
+    /// ```
+    pub fn reference_timestamp(&self, reference: &Caps) -> Option<&ReferenceTimestampMeta>{
+        unsafe{
+            let meta = gst_buffer_get_reference_timestamp_meta(self.gst_buffer() as *mut GstBuffer,
+                reference.gst_caps() as *mut GstCaps);
+            if meta != ptr::null_mut(){
+                Some(&*meta)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// All the reference timestamps of the buffer, in any clock
+    pub fn reference_timestamps(&self) -> Vec<&ReferenceTimestampMeta>{
+        self.metas_of::<ReferenceTimestampMeta>()
+    }
+
+    /// Attaches a custom meta registered with register_custom_meta,
+    /// holding a copy of the fields of content. Returns false if the
+    /// meta isn't registered
+    pub fn add_custom_meta(&mut self, name: &str, content: &Structure) -> bool{
+        unsafe{
+            let meta = gst_buffer_add_custom_meta(self.gst_buffer_mut(), to_c_str!(name));
+            if meta == ptr::null_mut(){
+                return false;
+            }
+            let structure = gst_custom_meta_get_structure(meta);
+            for (field, value) in content.fields(){
+                if let Some(mut gvalue) = value.to_gvalue(){
+                    gst_structure_take_value(structure, to_c_str!(field.as_str()), &mut gvalue);
+                }
+            }
+            true
+        }
+    }
+
+    /// Returns a copy of the content of the custom meta with the given
+    /// name
+    pub fn custom_meta(&self, name: &str) -> Option<Structure>{
+        unsafe{
+            let c_name = CString::new(name).unwrap();
+            self.iter_meta()
+                .find(|meta| gst_meta_info_is_custom((*meta.meta).info) != 0 &&
+                    gst_custom_meta_has_name(meta.meta as *mut GstCustomMeta, c_name.as_ptr()) != 0)
+                .and_then(|meta| Structure::new_from_gst_structure(
+                    gst_structure_copy(gst_custom_meta_get_structure(meta.meta as *mut GstCustomMeta))))
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use ffi::*;
+use element::ElementT;
+use pad::Pad;
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Period over which the frame rate and bitrate are averaged
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Statistics of the buffers that went through a monitored pad
+#[derive(Clone,Debug,PartialEq)]
+pub struct PadStats{
+    /// Label the pad was added with
+    pub label: String,
+    pub buffers: u64,
+    pub bytes: u64,
+    /// Buffers per second over the last second, the frame rate for
+    /// raw video
+    pub fps: f64,
+    /// Bits per second over the last second
+    pub bitrate: f64,
+    /// Timestamp of the last buffer
+    pub last_pts: GstClockTime,
+    /// Time since the last buffer, None if no buffer went through yet
+    pub since_last_buffer: Option<Duration>,
+}
+
+/// The statistics of all the pads of a PipelineMonitor at one point
+#[derive(Clone,Debug,PartialEq)]
+pub struct MonitorSnapshot{
+    pub pads: Vec<PadStats>,
+}
+
+impl MonitorSnapshot{
+    pub fn pad(&self, label: &str) -> Option<&PadStats>{
+        self.pads.iter().find(|pad| pad.label == label)
+    }
+
+    /// Labels of the pads that didn't get a buffer for longer than timeout,
+    /// including the ones that never got any
+    pub fn stalled(&self, timeout: Duration) -> Vec<&str>{
+        self.pads.iter()
+            .filter(|pad| pad.since_last_buffer.map(|since| since > timeout).unwrap_or(true))
+            .map(|pad| pad.label.as_str())
+            .collect()
+    }
+}
+
+struct Counters{
+    buffers: u64,
+    bytes: u64,
+    last_pts: GstClockTime,
+    last_buffer: Option<Instant>,
+    window_start: Instant,
+    window_buffers: u64,
+    window_bytes: u64,
+    fps: f64,
+    bitrate: f64,
+}
+
+struct MonitoredPad{
+    label: String,
+    pad: Pad,
+    probe: u64,
+    counters: Arc<Mutex<Counters>>,
+}
+
+/// Counts the buffers and bytes going through selected pads of a pipeline
+/// to show the frame rate and bitrate of a stream or detect when it stops
+/// flowing:
+///
+/// ```ignore
+/// let mut monitor = PipelineMonitor::new();
+/// monitor.watch(&encoder, "src", "video");
+/// monitor.start(Duration::from_secs(1), |snapshot| {
+///     for pad in snapshot.stalled(Duration::from_secs(5)){
+///         println!("no data on {} for 5s", pad);
+///     }
+/// });
+/// ```
+///
+/// The probes are removed when the monitor is dropped.
+pub struct PipelineMonitor{
+    pads: Vec<MonitoredPad>,
+    running: Arc<AtomicBool>,
+}
+
+impl PipelineMonitor{
+    pub fn new() -> PipelineMonitor{
+        PipelineMonitor{
+            pads: Vec::new(),
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Monitors the pad with the given label, used to identify it in the
+    /// snapshots
+    pub fn watch_pad(&mut self, pad: &Pad, label: &str){
+        let counters = Arc::new(Mutex::new(Counters{
+            buffers: 0,
+            bytes: 0,
+            last_pts: GST_CLOCK_TIME_NONE,
+            last_buffer: None,
+            window_start: Instant::now(),
+            window_buffers: 0,
+            window_bytes: 0,
+            fps: 0.,
+            bitrate: 0.,
+        }));
+        let probe_counters = counters.clone();
+        let probe = pad.add_buffer_probe(move |_, buffer| {
+            let now = Instant::now();
+            let mut counters = probe_counters.lock().unwrap();
+            let size = buffer.size();
+            counters.buffers += 1;
+            counters.bytes += size;
+            counters.last_pts = buffer.pts();
+            counters.last_buffer = Some(now);
+            counters.window_buffers += 1;
+            counters.window_bytes += size;
+            let elapsed = now.duration_since(counters.window_start);
+            if elapsed >= RATE_WINDOW{
+                let secs = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 / 1e9;
+                counters.fps = counters.window_buffers as f64 / secs;
+                counters.bitrate = counters.window_bytes as f64 * 8. / secs;
+                counters.window_start = now;
+                counters.window_buffers = 0;
+                counters.window_bytes = 0;
+            }
+            GST_PAD_PROBE_OK
+        });
+        self.pads.push(MonitoredPad{
+            label: label.to_string(),
+            pad: pad.clone(),
+            probe: probe,
+            counters: counters,
+        });
+    }
+
+    /// Monitors the static pad with the given name of an element, returns
+    /// false if the element doesn't have that pad
+    pub fn watch<E: ElementT>(&mut self, element: &E, pad_name: &str, label: &str) -> bool{
+        match element.static_pad(pad_name){
+            Some(pad) => {
+                self.watch_pad(&pad, label);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// The current statistics of all the monitored pads
+    pub fn snapshot(&self) -> MonitorSnapshot{
+        snapshot(&self.pads.iter().map(|pad| (pad.label.clone(), pad.counters.clone())).collect::<Vec<_>>())
+    }
+
+    /// Calls f with a snapshot every interval from a new thread, until the
+    /// monitor is dropped or stop is called. Pads watched after calling
+    /// start aren't included
+    pub fn start<F: Fn(&MonitorSnapshot) + Send + 'static>(&mut self, interval: Duration, f: F){
+        self.stop();
+        let running = Arc::new(AtomicBool::new(true));
+        self.running = running.clone();
+        let pads: Vec<(String, Arc<Mutex<Counters>>)> = self.pads.iter()
+            .map(|pad| (pad.label.clone(), pad.counters.clone()))
+            .collect();
+        thread::spawn(move || {
+            while running.load(Ordering::SeqCst){
+                thread::sleep(interval);
+                if running.load(Ordering::SeqCst){
+                    f(&snapshot(&pads));
+                }
+            }
+        });
+    }
+
+    /// Stops the periodic callback started with start
+    pub fn stop(&mut self){
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Drop for PipelineMonitor{
+    fn drop(&mut self){
+        self.stop();
+        for pad in self.pads.iter(){
+            pad.pad.remove_probe(pad.probe);
+        }
+    }
+}
+
+fn snapshot(pads: &[(String, Arc<Mutex<Counters>>)]) -> MonitorSnapshot{
+    let now = Instant::now();
+    MonitorSnapshot{
+        pads: pads.iter().map(|&(ref label, ref counters)| {
+            let counters = counters.lock().unwrap();
+            let since_last_buffer = counters.last_buffer.map(|last| now.duration_since(last));
+            // the rates are only updated when buffers arrive, a pad that
+            // stopped receiving them is reported at 0
+            let stale = since_last_buffer.map(|since| since > RATE_WINDOW).unwrap_or(true);
+            PadStats{
+                label: label.clone(),
+                buffers: counters.buffers,
+                bytes: counters.bytes,
+                fps: if stale{ 0. }else{ counters.fps },
+                bitrate: if stale{ 0. }else{ counters.bitrate },
+                last_pts: counters.last_pts,
+                since_last_buffer: since_last_buffer,
+            }
+        }).collect()
+    }
+}
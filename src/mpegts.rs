@@ -0,0 +1,448 @@
+//! Parsing of the MPEG-TS tables (PAT, PMT, SDT, EIT...) posted by tsdemux
+//! and tsparse as element messages, using the gstmpegts library.
+//!
+//! Needs the `mpegts` feature. Call `initialize()` once before parsing
+//! any message:
+//!
+//! ```ignore
+//! gst::mpegts::initialize();
+//! for message in bus.receiver().iter(){
+//!     if let Some(section) = MpegTsSection::from_message(&message){
+//!         if let SectionData::Sdt(sdt) = section.parse(){
+//!             for service in sdt.services{
+//!                 println!("{}: {:?}", service.service_id, service.name);
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+
+use ffi::*;
+use util::*;
+use message::Message;
+
+use std::os::raw::c_uint;
+
+pub type GstMpegtsSectionType = c_uint;
+pub const GST_MPEGTS_SECTION_UNKNOWN: GstMpegtsSectionType = 0;
+pub const GST_MPEGTS_SECTION_PAT: GstMpegtsSectionType = 1;
+pub const GST_MPEGTS_SECTION_PMT: GstMpegtsSectionType = 2;
+pub const GST_MPEGTS_SECTION_CAT: GstMpegtsSectionType = 3;
+pub const GST_MPEGTS_SECTION_TSDT: GstMpegtsSectionType = 4;
+pub const GST_MPEGTS_SECTION_EIT: GstMpegtsSectionType = 5;
+pub const GST_MPEGTS_SECTION_NIT: GstMpegtsSectionType = 6;
+pub const GST_MPEGTS_SECTION_BAT: GstMpegtsSectionType = 7;
+pub const GST_MPEGTS_SECTION_SDT: GstMpegtsSectionType = 8;
+pub const GST_MPEGTS_SECTION_TDT: GstMpegtsSectionType = 9;
+pub const GST_MPEGTS_SECTION_TOT: GstMpegtsSectionType = 10;
+
+const GST_MTS_DESC_DVB_SERVICE: guint8 = 0x48;
+const GST_MTS_DESC_DVB_SHORT_EVENT: guint8 = 0x4d;
+
+/// Only the public fields, sections are always used through pointers
+#[repr(C)]
+pub struct GstMpegtsSection{
+    pub parent: GstMiniObject,
+    pub section_type: GstMpegtsSectionType,
+    pub pid: guint16,
+    pub table_id: guint8,
+    pub subtable_extension: guint16,
+    pub version_number: guint8,
+    pub current_next_indicator: gboolean,
+    pub section_number: guint8,
+    pub last_section_number: guint8,
+    pub crc: guint32,
+}
+
+pub enum GstMpegtsDescriptor {}
+
+#[repr(C)]
+struct GstMpegtsPatProgram{
+    program_number: guint16,
+    network_or_program_map_pid: guint16,
+}
+
+#[repr(C)]
+struct GstMpegtsPMT{
+    pcr_pid: guint16,
+    program_number: guint16,
+    descriptors: *mut GPtrArray,
+    streams: *mut GPtrArray,
+}
+
+#[repr(C)]
+struct GstMpegtsPMTStream{
+    stream_type: guint8,
+    pid: guint16,
+    descriptors: *mut GPtrArray,
+}
+
+#[repr(C)]
+struct GstMpegtsSDT{
+    original_network_id: guint16,
+    actual_ts: gboolean,
+    transport_stream_id: guint16,
+    services: *mut GPtrArray,
+}
+
+#[repr(C)]
+struct GstMpegtsSDTService{
+    service_id: guint16,
+    eit_schedule_flag: gboolean,
+    eit_present_following_flag: gboolean,
+    running_status: c_uint,
+    free_ca_mode: gboolean,
+    descriptors: *mut GPtrArray,
+}
+
+#[repr(C)]
+struct GstMpegtsEIT{
+    transport_stream_id: guint16,
+    original_network_id: guint16,
+    segment_last_section_number: guint8,
+    last_table_id: guint8,
+    actual_stream: gboolean,
+    present_following: gboolean,
+    events: *mut GPtrArray,
+}
+
+#[repr(C)]
+struct GstMpegtsEITEvent{
+    event_id: guint16,
+    start_time: *mut GstDateTime,
+    duration: guint32,
+    running_status: c_uint,
+    free_ca_mode: gboolean,
+    descriptors: *mut GPtrArray,
+}
+
+#[cfg_attr(not(target_os="macos"), link(name = "gstmpegts-1.0"))]
+extern "C"{
+    fn gst_mpegts_initialize();
+    fn gst_message_parse_mpegts_section(message: *mut GstMessage) -> *mut GstMpegtsSection;
+    fn gst_mpegts_section_get_pat(section: *mut GstMpegtsSection) -> *mut GPtrArray;
+    fn gst_mpegts_section_get_pmt(section: *mut GstMpegtsSection) -> *const GstMpegtsPMT;
+    fn gst_mpegts_section_get_sdt(section: *mut GstMpegtsSection) -> *const GstMpegtsSDT;
+    fn gst_mpegts_section_get_eit(section: *mut GstMpegtsSection) -> *const GstMpegtsEIT;
+    fn gst_mpegts_find_descriptor(descriptors: *mut GPtrArray, tag: guint8) -> *const GstMpegtsDescriptor;
+    fn gst_mpegts_descriptor_parse_dvb_service(descriptor: *const GstMpegtsDescriptor, service_type: *mut c_uint,
+        service_name: *mut *mut gchar, provider_name: *mut *mut gchar) -> gboolean;
+    fn gst_mpegts_descriptor_parse_dvb_short_event(descriptor: *const GstMpegtsDescriptor, language_code: *mut *mut gchar,
+        event_name: *mut *mut gchar, text: *mut *mut gchar) -> gboolean;
+}
+
+/// Registers the mpegts types, has to be called once after gst::init and
+/// before parsing sections
+pub fn initialize(){
+    unsafe{
+        gst_mpegts_initialize();
+    }
+}
+
+/// A program of the PAT and the pid where its PMT is sent
+#[derive(Clone,Debug,PartialEq)]
+pub struct PatProgram{
+    pub program_number: u16,
+    pub pmt_pid: u16,
+}
+
+/// Program Association Table, the list of programs in the stream
+#[derive(Clone,Debug,PartialEq)]
+pub struct Pat{
+    pub programs: Vec<PatProgram>,
+}
+
+/// An elementary stream of a program, the stream type is the one from
+/// ISO 13818-1, as in 0x1b for H.264 or 0x0f for AAC
+#[derive(Clone,Debug,PartialEq)]
+pub struct PmtStream{
+    pub stream_type: u8,
+    pub pid: u16,
+}
+
+/// Program Map Table, the streams of a program
+#[derive(Clone,Debug,PartialEq)]
+pub struct Pmt{
+    pub program_number: u16,
+    pub pcr_pid: u16,
+    pub streams: Vec<PmtStream>,
+}
+
+/// A service (channel) of the SDT, the service id is the program number
+/// in the PAT
+#[derive(Clone,Debug,PartialEq)]
+pub struct SdtService{
+    pub service_id: u16,
+    pub running_status: u32,
+    pub free_ca_mode: bool,
+    pub service_type: Option<u8>,
+    pub name: Option<String>,
+    pub provider: Option<String>,
+}
+
+/// DVB Service Description Table, the names of the services
+#[derive(Clone,Debug,PartialEq)]
+pub struct Sdt{
+    pub transport_stream_id: u16,
+    pub original_network_id: u16,
+    /// If the table describes the current transport stream or another one
+    pub actual_ts: bool,
+    pub services: Vec<SdtService>,
+}
+
+/// A programme of the EIT
+#[derive(Clone,Debug,PartialEq)]
+pub struct EitEvent{
+    pub event_id: u16,
+    /// Start time in ISO 8601 format, in UTC
+    pub start_time: Option<String>,
+    /// Duration in seconds
+    pub duration: u32,
+    pub running_status: u32,
+    pub free_ca_mode: bool,
+    pub language: Option<String>,
+    pub name: Option<String>,
+    pub text: Option<String>,
+}
+
+/// DVB Event Information Table, the programme guide of a service
+#[derive(Clone,Debug,PartialEq)]
+pub struct Eit{
+    pub service_id: u16,
+    pub transport_stream_id: u16,
+    pub original_network_id: u16,
+    /// If the table has the present and following events or the schedule
+    pub present_following: bool,
+    pub events: Vec<EitEvent>,
+}
+
+/// The parsed contents of a section
+#[derive(Clone,Debug,PartialEq)]
+pub enum SectionData{
+    Pat(Pat),
+    Pmt(Pmt),
+    Sdt(Sdt),
+    Eit(Eit),
+    /// A table that isn't parsed yet or failed to parse, with its type
+    Other(GstMpegtsSectionType),
+}
+
+/// A section of an SI/PSI table as found in the transport stream
+pub struct MpegTsSection{
+    section: *mut GstMpegtsSection
+}
+
+unsafe impl Send for MpegTsSection {}
+
+impl Drop for MpegTsSection{
+    fn drop(&mut self){
+        unsafe{
+            gst_mini_object_unref(self.section as *mut GstMiniObject);
+        }
+    }
+}
+
+impl Clone for MpegTsSection{
+    fn clone(&self) -> MpegTsSection{
+        unsafe{
+            gst_mini_object_ref(self.section as *mut GstMiniObject);
+            MpegTsSection{ section: self.section }
+        }
+    }
+}
+
+impl MpegTsSection{
+    /// Wraps a raw GstMpegtsSection, if owned is false a new reference is
+    /// taken
+    pub unsafe fn new(section: *mut GstMpegtsSection, owned: bool) -> Option<MpegTsSection>{
+        if section != ptr::null_mut(){
+            if !owned{
+                gst_mini_object_ref(section as *mut GstMiniObject);
+            }
+            Some(MpegTsSection{ section: section })
+        }else{
+            None
+        }
+    }
+
+    /// Returns the section carried by an element message of tsdemux or
+    /// tsparse, None for any other message
+    pub fn from_message(message: &Message) -> Option<MpegTsSection>{
+        unsafe{
+            MpegTsSection::new(gst_message_parse_mpegts_section(message.gst_message() as *mut GstMessage), true)
+        }
+    }
+
+    pub fn section_type(&self) -> GstMpegtsSectionType{
+        unsafe{ (*self.section).section_type }
+    }
+
+    pub fn pid(&self) -> u16{
+        unsafe{ (*self.section).pid }
+    }
+
+    pub fn table_id(&self) -> u8{
+        unsafe{ (*self.section).table_id }
+    }
+
+    /// Program number for PMTs, service id for EITs
+    pub fn subtable_extension(&self) -> u16{
+        unsafe{ (*self.section).subtable_extension }
+    }
+
+    /// Incremented by the broadcaster each time the table changes
+    pub fn version_number(&self) -> u8{
+        unsafe{ (*self.section).version_number }
+    }
+
+    /// Parses the section into one of the supported tables
+    pub fn parse(&self) -> SectionData{
+        let section_type = self.section_type();
+        let data = match section_type{
+            GST_MPEGTS_SECTION_PAT => self.pat().map(SectionData::Pat),
+            GST_MPEGTS_SECTION_PMT => self.pmt().map(SectionData::Pmt),
+            GST_MPEGTS_SECTION_SDT => self.sdt().map(SectionData::Sdt),
+            GST_MPEGTS_SECTION_EIT => self.eit().map(SectionData::Eit),
+            _ => None
+        };
+        data.unwrap_or(SectionData::Other(section_type))
+    }
+
+    fn pat(&self) -> Option<Pat>{
+        unsafe{
+            let programs = gst_mpegts_section_get_pat(self.section);
+            if programs == ptr::null_mut(){
+                return None;
+            }
+            let pat = Pat{
+                programs: ptr_array::<GstMpegtsPatProgram>(programs).into_iter().map(|program| PatProgram{
+                    program_number: (*program).program_number,
+                    pmt_pid: (*program).network_or_program_map_pid,
+                }).collect()
+            };
+            g_ptr_array_unref(programs);
+            Some(pat)
+        }
+    }
+
+    fn pmt(&self) -> Option<Pmt>{
+        unsafe{
+            let pmt = gst_mpegts_section_get_pmt(self.section);
+            if pmt == ptr::null(){
+                return None;
+            }
+            Some(Pmt{
+                program_number: (*pmt).program_number,
+                pcr_pid: (*pmt).pcr_pid,
+                streams: ptr_array::<GstMpegtsPMTStream>((*pmt).streams).into_iter().map(|stream| PmtStream{
+                    stream_type: (*stream).stream_type,
+                    pid: (*stream).pid,
+                }).collect()
+            })
+        }
+    }
+
+    fn sdt(&self) -> Option<Sdt>{
+        unsafe{
+            let sdt = gst_mpegts_section_get_sdt(self.section);
+            if sdt == ptr::null(){
+                return None;
+            }
+            Some(Sdt{
+                transport_stream_id: (*sdt).transport_stream_id,
+                original_network_id: (*sdt).original_network_id,
+                actual_ts: (*sdt).actual_ts != 0,
+                services: ptr_array::<GstMpegtsSDTService>((*sdt).services).into_iter().map(|service| {
+                    let mut sdt_service = SdtService{
+                        service_id: (*service).service_id,
+                        running_status: (*service).running_status,
+                        free_ca_mode: (*service).free_ca_mode != 0,
+                        service_type: None,
+                        name: None,
+                        provider: None,
+                    };
+                    let descriptor = gst_mpegts_find_descriptor((*service).descriptors, GST_MTS_DESC_DVB_SERVICE);
+                    let mut service_type = 0;
+                    let mut name = ptr::null_mut();
+                    let mut provider = ptr::null_mut();
+                    if descriptor != ptr::null() &&
+                      gst_mpegts_descriptor_parse_dvb_service(descriptor, &mut service_type, &mut name, &mut provider) == 1{
+                        sdt_service.service_type = Some(service_type as u8);
+                        sdt_service.name = take_string(name);
+                        sdt_service.provider = take_string(provider);
+                    }
+                    sdt_service
+                }).collect()
+            })
+        }
+    }
+
+    fn eit(&self) -> Option<Eit>{
+        unsafe{
+            let eit = gst_mpegts_section_get_eit(self.section);
+            if eit == ptr::null(){
+                return None;
+            }
+            Some(Eit{
+                service_id: self.subtable_extension(),
+                transport_stream_id: (*eit).transport_stream_id,
+                original_network_id: (*eit).original_network_id,
+                present_following: (*eit).present_following != 0,
+                events: ptr_array::<GstMpegtsEITEvent>((*eit).events).into_iter().map(|event| {
+                    let start_time = if (*event).start_time != ptr::null_mut(){
+                        take_string(gst_date_time_to_iso8601_string((*event).start_time))
+                    }else{
+                        None
+                    };
+                    let mut eit_event = EitEvent{
+                        event_id: (*event).event_id,
+                        start_time: start_time,
+                        duration: (*event).duration,
+                        running_status: (*event).running_status,
+                        free_ca_mode: (*event).free_ca_mode != 0,
+                        language: None,
+                        name: None,
+                        text: None,
+                    };
+                    let descriptor = gst_mpegts_find_descriptor((*event).descriptors, GST_MTS_DESC_DVB_SHORT_EVENT);
+                    let mut language = ptr::null_mut();
+                    let mut name = ptr::null_mut();
+                    let mut text = ptr::null_mut();
+                    if descriptor != ptr::null() &&
+                      gst_mpegts_descriptor_parse_dvb_short_event(descriptor, &mut language, &mut name, &mut text) == 1{
+                        eit_event.language = take_string(language);
+                        eit_event.name = take_string(name);
+                        eit_event.text = take_string(text);
+                    }
+                    eit_event
+                }).collect()
+            })
+        }
+    }
+
+    pub unsafe fn gst_mpegts_section(&self) -> *const GstMpegtsSection{
+        self.section
+    }
+
+    pub unsafe fn gst_mpegts_section_mut(&mut self) -> *mut GstMpegtsSection{
+        self.section
+    }
+}
+
+unsafe fn ptr_array<T>(array: *mut GPtrArray) -> Vec<*mut T>{
+    if array == ptr::null_mut(){
+        return Vec::new();
+    }
+    (0..(*array).len as isize).map(|i| *(*array).pdata.offset(i) as *mut T).collect()
+}
+
+/// Converts a string returned with transfer full and frees it
+unsafe fn take_string(c_str: *mut gchar) -> Option<String>{
+    if c_str != ptr::null_mut(){
+        let string = from_c_str!(c_str).to_string();
+        g_free(c_str as gpointer);
+        Some(string)
+    }else{
+        None
+    }
+}
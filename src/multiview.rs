@@ -0,0 +1,215 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use element::{Element, ElementT};
+use value::Value;
+use videoinfo::VideoInfo;
+
+use std::os::raw::{c_int, c_uint};
+
+// Multiview support was added in GStreamer 1.6, after ffi.rs was
+// generated
+pub type GstVideoMultiviewMode = c_int;
+pub const GST_VIDEO_MULTIVIEW_MODE_NONE: GstVideoMultiviewMode = -1;
+pub const GST_VIDEO_MULTIVIEW_MODE_MONO: GstVideoMultiviewMode = 0;
+pub const GST_VIDEO_MULTIVIEW_MODE_LEFT: GstVideoMultiviewMode = 1;
+pub const GST_VIDEO_MULTIVIEW_MODE_RIGHT: GstVideoMultiviewMode = 2;
+pub const GST_VIDEO_MULTIVIEW_MODE_SIDE_BY_SIDE: GstVideoMultiviewMode = 3;
+pub const GST_VIDEO_MULTIVIEW_MODE_SIDE_BY_SIDE_QUINCUNX: GstVideoMultiviewMode = 4;
+pub const GST_VIDEO_MULTIVIEW_MODE_COLUMN_INTERLEAVED: GstVideoMultiviewMode = 5;
+pub const GST_VIDEO_MULTIVIEW_MODE_ROW_INTERLEAVED: GstVideoMultiviewMode = 6;
+pub const GST_VIDEO_MULTIVIEW_MODE_TOP_BOTTOM: GstVideoMultiviewMode = 7;
+pub const GST_VIDEO_MULTIVIEW_MODE_CHECKERBOARD: GstVideoMultiviewMode = 8;
+pub const GST_VIDEO_MULTIVIEW_MODE_FRAME_BY_FRAME: GstVideoMultiviewMode = 32;
+pub const GST_VIDEO_MULTIVIEW_MODE_MULTIVIEW_FRAME_BY_FRAME: GstVideoMultiviewMode = 33;
+pub const GST_VIDEO_MULTIVIEW_MODE_SEPARATED: GstVideoMultiviewMode = 34;
+
+pub type GstVideoMultiviewFlags = c_uint;
+pub const GST_VIDEO_MULTIVIEW_FLAGS_NONE: GstVideoMultiviewFlags = 0;
+pub const GST_VIDEO_MULTIVIEW_FLAGS_RIGHT_VIEW_FIRST: GstVideoMultiviewFlags = 1 << 0;
+pub const GST_VIDEO_MULTIVIEW_FLAGS_LEFT_FLIPPED: GstVideoMultiviewFlags = 1 << 1;
+pub const GST_VIDEO_MULTIVIEW_FLAGS_LEFT_FLOPPED: GstVideoMultiviewFlags = 1 << 2;
+pub const GST_VIDEO_MULTIVIEW_FLAGS_RIGHT_FLIPPED: GstVideoMultiviewFlags = 1 << 3;
+pub const GST_VIDEO_MULTIVIEW_FLAGS_RIGHT_FLOPPED: GstVideoMultiviewFlags = 1 << 4;
+pub const GST_VIDEO_MULTIVIEW_FLAGS_HALF_ASPECT: GstVideoMultiviewFlags = 1 << 14;
+pub const GST_VIDEO_MULTIVIEW_FLAGS_MIXED_MONO: GstVideoMultiviewFlags = 1 << 15;
+
+extern "C"{
+    fn gst_video_multiview_flagset_get_type() -> GType;
+}
+
+/// How the views of stereoscopic or multiview video are packed
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum MultiviewMode{
+    Mono,
+    /// Only the left view of a stereo stream
+    Left,
+    /// Only the right view of a stereo stream
+    Right,
+    SideBySide,
+    SideBySideQuincunx,
+    ColumnInterleaved,
+    RowInterleaved,
+    TopBottom,
+    Checkerboard,
+    /// Left and right views in alternating frames
+    FrameByFrame,
+    MultiviewFrameByFrame,
+    /// Each view in a separate memory of the buffer
+    Separated,
+}
+
+const MODES: &'static [(MultiviewMode, GstVideoMultiviewMode, &'static str)] = &[
+    (MultiviewMode::Mono, GST_VIDEO_MULTIVIEW_MODE_MONO, "mono"),
+    (MultiviewMode::Left, GST_VIDEO_MULTIVIEW_MODE_LEFT, "left"),
+    (MultiviewMode::Right, GST_VIDEO_MULTIVIEW_MODE_RIGHT, "right"),
+    (MultiviewMode::SideBySide, GST_VIDEO_MULTIVIEW_MODE_SIDE_BY_SIDE, "side-by-side"),
+    (MultiviewMode::SideBySideQuincunx, GST_VIDEO_MULTIVIEW_MODE_SIDE_BY_SIDE_QUINCUNX, "side-by-side-quincunx"),
+    (MultiviewMode::ColumnInterleaved, GST_VIDEO_MULTIVIEW_MODE_COLUMN_INTERLEAVED, "column-interleaved"),
+    (MultiviewMode::RowInterleaved, GST_VIDEO_MULTIVIEW_MODE_ROW_INTERLEAVED, "row-interleaved"),
+    (MultiviewMode::TopBottom, GST_VIDEO_MULTIVIEW_MODE_TOP_BOTTOM, "top-bottom"),
+    (MultiviewMode::Checkerboard, GST_VIDEO_MULTIVIEW_MODE_CHECKERBOARD, "checkerboard"),
+    (MultiviewMode::FrameByFrame, GST_VIDEO_MULTIVIEW_MODE_FRAME_BY_FRAME, "frame-by-frame"),
+    (MultiviewMode::MultiviewFrameByFrame, GST_VIDEO_MULTIVIEW_MODE_MULTIVIEW_FRAME_BY_FRAME, "multiview-frame-by-frame"),
+    (MultiviewMode::Separated, GST_VIDEO_MULTIVIEW_MODE_SEPARATED, "separated"),
+];
+
+impl MultiviewMode{
+    pub fn from_gst(mode: GstVideoMultiviewMode) -> Option<MultiviewMode>{
+        MODES.iter().find(|m| m.1 == mode).map(|m| m.0)
+    }
+
+    pub fn to_gst(&self) -> GstVideoMultiviewMode{
+        MODES.iter().find(|m| m.0 == *self).map(|m| m.1).unwrap()
+    }
+
+    /// Parses the value of the multiview-mode caps field
+    pub fn from_caps_str(mode: &str) -> Option<MultiviewMode>{
+        MODES.iter().find(|m| m.2 == mode).map(|m| m.0)
+    }
+
+    /// The value of the multiview-mode caps field, also the nick used by
+    /// the mode properties of the elements
+    pub fn to_caps_str(&self) -> &'static str{
+        MODES.iter().find(|m| m.0 == *self).map(|m| m.2).unwrap()
+    }
+
+    /// Returns the mode of the video with these caps, mono if the caps
+    /// don't specify one
+    pub fn from_caps(caps: &Caps) -> Option<MultiviewMode>{
+        match caps.structure(0)?.get("multiview-mode"){
+            Some(Value::String(mode)) => MultiviewMode::from_caps_str(&mode),
+            _ => Some(MultiviewMode::Mono)
+        }
+    }
+
+    /// Checks if both views are packed in each frame
+    pub fn is_frame_packed(&self) -> bool{
+        match *self{
+            MultiviewMode::SideBySide | MultiviewMode::SideBySideQuincunx |
+            MultiviewMode::ColumnInterleaved | MultiviewMode::RowInterleaved |
+            MultiviewMode::TopBottom | MultiviewMode::Checkerboard => true,
+            _ => false
+        }
+    }
+}
+
+/// Returns the GST_VIDEO_MULTIVIEW_FLAGS_* of the video with these caps
+pub fn multiview_flags_from_caps(caps: &Caps) -> GstVideoMultiviewFlags{
+    match caps.structure(0).and_then(|structure| structure.get("multiview-flags")){
+        Some(Value::FlagSet{flags, ..}) => flags,
+        _ => GST_VIDEO_MULTIVIEW_FLAGS_NONE
+    }
+}
+
+/// Sets the multiview-mode and multiview-flags fields of video caps, as
+/// needed to tell downstream elements how a stream that doesn't signal it
+/// is packed
+pub fn set_multiview_caps(caps: &mut Caps, mode: MultiviewMode, flags: GstVideoMultiviewFlags) -> bool{
+    if !caps.set_field("multiview-mode", mode.to_caps_str()){
+        return false;
+    }
+    unsafe{
+        caps.set_field("multiview-flags", Value::FlagSet{
+            type_name: from_c_str!(g_type_name(gst_video_multiview_flagset_get_type())).to_string(),
+            flags: flags,
+            mask: 0xffffffff,
+        })
+    }
+}
+
+impl VideoInfo{
+    /// Packing of the views, only valid with GStreamer 1.6 or later
+    pub fn multiview_mode(&self) -> Option<MultiviewMode>{
+        unsafe{
+            MultiviewMode::from_gst(*(self._gst_reserved.as_ptr() as *const GstVideoMultiviewMode))
+        }
+    }
+
+    pub fn multiview_flags(&self) -> GstVideoMultiviewFlags{
+        unsafe{
+            *(self._gst_reserved.as_ptr() as *const GstVideoMultiviewMode).offset(1) as GstVideoMultiviewFlags
+        }
+    }
+}
+
+/// Anaglyph used by GlViewConvert when converting stereo to mono
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum DownmixMode{
+    GreenMagentaDubois = 0,
+    RedCyanDubois = 1,
+    AmberBlueDubois = 2,
+}
+
+/// Wrapper around glviewconvert, which converts between the multiview
+/// packings, for example from side by side to frame by frame for a 3D
+/// display or to an anaglyph for mono displays
+pub struct GlViewConvert{
+    glviewconvert: Element
+}
+
+unsafe impl Sync for GlViewConvert {}
+unsafe impl Send for GlViewConvert {}
+
+impl GlViewConvert{
+    pub fn new(name: &str) -> Option<GlViewConvert>{
+        Element::new("glviewconvert", name).map(|glviewconvert| GlViewConvert{ glviewconvert: glviewconvert })
+    }
+
+    pub fn new_from_element(element: Element) -> GlViewConvert{
+        GlViewConvert{ glviewconvert: element }
+    }
+
+    /// Overrides the packing of the input for streams that don't signal
+    /// it, or signal it wrong
+    pub fn set_input_mode(&mut self, mode: MultiviewMode, flags: GstVideoMultiviewFlags) -> bool{
+        self.glviewconvert.set("input-flags-override", flags);
+        self.glviewconvert.set_property("input-mode-override", &mode.to_caps_str().into()).is_ok()
+    }
+
+    /// Packing to convert to, by default the one negotiated downstream
+    pub fn set_output_mode(&mut self, mode: MultiviewMode, flags: GstVideoMultiviewFlags) -> bool{
+        self.glviewconvert.set("output-flags-override", flags);
+        self.glviewconvert.set_property("output-mode-override", &mode.to_caps_str().into()).is_ok()
+    }
+
+    pub fn set_downmix_mode(&self, mode: DownmixMode){
+        self.glviewconvert.set("downmix-mode", mode as i32);
+    }
+}
+
+impl ElementT for GlViewConvert{
+    fn as_element(&self) -> &Element{
+        &self.glviewconvert
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.glviewconvert
+    }
+}
+
+impl ::Transfer for GlViewConvert{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.glviewconvert.transfer()
+    }
+}
@@ -0,0 +1,238 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+use pad::Pad;
+use pipeline::PipelineT;
+use taglist::TagList;
+use error::{Error, Result};
+use ::Transfer;
+
+use std::cmp;
+use std::sync::Mutex;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+// How often Muxer::finish checks if the muxer pushed EOS while waiting
+// for errors in the bus
+const FINISH_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Kind of stream fed to a Muxer input
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum StreamType{
+    Audio,
+    Video,
+    Subtitle,
+}
+
+impl StreamType{
+    // Names of the request pad templates for the stream type in the
+    // muxers, mpegtsmux uses the same template for all of them
+    fn templates(&self) -> &'static [&'static str]{
+        match *self{
+            StreamType::Audio => &["audio_%u", "sink_%d", "sink_%u"],
+            StreamType::Video => &["video_%u", "sink_%d", "sink_%u"],
+            StreamType::Subtitle => &["subtitle_%u", "text_%u", "sink_%d", "sink_%u"],
+        }
+    }
+}
+
+/// Wrapper around a muxer as mp4mux, matroskamux or mpegtsmux, which
+/// requests the right pad for each kind of stream.
+///
+/// Muxers only write the headers and index of the file once every input
+/// receives EOS, so recordings have to be stopped with finish instead of
+/// setting the pipeline to NULL or the file won't be playable.
+pub struct Muxer{
+    muxer: Element
+}
+
+unsafe impl Sync for Muxer {}
+unsafe impl Send for Muxer {}
+
+/// An input of a Muxer
+pub struct MuxerInput{
+    pad: Pad
+}
+
+unsafe impl Sync for MuxerInput {}
+unsafe impl Send for MuxerInput {}
+
+impl Muxer{
+    /// A muxer from any factory, as "qtmux" or "webmmux"
+    pub fn new(factory: &str, name: &str) -> Option<Muxer>{
+        Element::new(factory, name).map(|muxer| Muxer{ muxer: muxer })
+    }
+
+    pub fn mp4(name: &str) -> Option<Muxer>{
+        Muxer::new("mp4mux", name)
+    }
+
+    pub fn matroska(name: &str) -> Option<Muxer>{
+        Muxer::new("matroskamux", name)
+    }
+
+    pub fn mpegts(name: &str) -> Option<Muxer>{
+        Muxer::new("mpegtsmux", name)
+    }
+
+    pub fn new_from_element(element: Element) -> Muxer{
+        Muxer{ muxer: element }
+    }
+
+    /// Requests a new input for a stream of the given type without
+    /// linking it, None if the muxer doesn't support that type
+    pub fn request_input(&mut self, stream_type: StreamType) -> Option<MuxerInput>{
+        unsafe{
+            let class = (*(self.muxer.gst_element() as *mut GTypeInstance)).g_class as *mut GstElementClass;
+            let template = stream_type.templates().iter()
+                .find(|template| gst_element_class_get_pad_template(class, to_c_str!(**template)) != ptr::null_mut())?;
+            let pad = gst_element_get_request_pad(self.muxer.gst_element_mut(), to_c_str!(*template));
+            Pad::new(pad, true).map(|pad| MuxerInput{ pad: pad })
+        }
+    }
+
+    /// Requests a new input for the stream type and links the src pad of
+    /// source to it, both elements have to be in the same bin
+    pub fn add_input(&mut self, source: &ElementT, stream_type: StreamType) -> Option<MuxerInput>{
+        let mut src = source.static_pad("src")?;
+        let mut input = self.request_input(stream_type)?;
+        if src.link(&mut input.pad) == GST_PAD_LINK_OK{
+            Some(input)
+        }else{
+            self.remove_input(input);
+            None
+        }
+    }
+
+    /// Unlinks and releases the input, before the pipeline starts
+    pub fn remove_input(&mut self, mut input: MuxerInput){
+        if let Some(mut peer) = input.pad.peer(){
+            peer.unlink(&mut input.pad);
+        }
+        unsafe{
+            gst_element_release_request_pad(self.muxer.gst_element_mut(), input.pad.gst_pad_mut());
+        }
+    }
+
+    /// mp4mux: writes a fragment every duration milliseconds, so a
+    /// recording interrupted by a crash is still playable up to the last
+    /// fragment
+    pub fn set_fragment_duration(&self, duration: u32){
+        self.muxer.set("fragment-duration", duration);
+    }
+
+    /// mp4mux: moves the index to the start of the file when finishing,
+    /// so it can be played while downloading
+    pub fn set_faststart(&self, faststart: bool){
+        self.muxer.set("faststart", faststart as gboolean);
+    }
+
+    /// matroskamux: writes a file that doesn't need seeking back to the
+    /// headers, to stream it while it's written
+    pub fn set_streamable(&self, streamable: bool){
+        self.muxer.set("streamable", streamable as gboolean);
+    }
+
+    /// Sends EOS to every input of the muxer and waits up to timeout
+    /// nanoseconds for the file to be finished, then sets pipeline to
+    /// NULL. Unlike PipelineT::send_eos_and_wait it only waits for the
+    /// muxer to push EOS, so it works with sources that don't handle EOS,
+    /// as most live sources, and with other branches still running
+    pub fn finish<P: PipelineT>(&mut self, pipeline: &mut P, timeout: GstClockTime) -> Result<()>{
+        let src = self.muxer.static_pad("src")
+            .ok_or_else(|| Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_PAD as i32,
+                &format!("{} has no src pad", self.muxer.name())))?;
+        // the muxer writes the headers and index before pushing EOS
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let probe = src.add_event_probe(move |_, event| {
+            if event.event_type() == GST_EVENT_EOS{
+                sender.lock().unwrap().send(()).ok();
+            }
+            GST_PAD_PROBE_OK
+        });
+        unsafe{
+            for pad in iterator_objects(gst_element_iterate_sink_pads(self.muxer.gst_element_mut())){
+                gst_pad_send_event(pad as *mut GstPad, gst_event_new_eos());
+                gst_object_unref(pad);
+            }
+        }
+        let deadline = if timeout == GST_CLOCK_TIME_NONE{
+            None
+        }else{
+            Some(Instant::now() + Duration::from_nanos(timeout))
+        };
+        let result = loop{
+            if receiver.try_recv().is_ok(){
+                break Ok(());
+            }
+            let mut wait = FINISH_POLL_INTERVAL;
+            if let Some(deadline) = deadline{
+                let now = Instant::now();
+                if now >= deadline{
+                    break Err(Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_FAILED as i32,
+                        &format!("timed out waiting for {} to finish", self.muxer.name())));
+                }
+                wait = cmp::min(wait, deadline - now);
+            }
+            // errors of the muxer or its sink are posted to the bus
+            if let Some(Err(err)) = pipeline.as_pipeline_mut().wait_for_message(wait.as_nanos() as GstClockTime, GST_MESSAGE_ERROR){
+                break Err(err);
+            }
+        };
+        src.remove_probe(probe);
+        pipeline.set_state(GST_STATE_NULL);
+        result
+    }
+}
+
+impl MuxerInput{
+    /// Tags of the stream written in the container, as the language or
+    /// title of an audio track. Has to be set before the data starts
+    /// flowing
+    pub fn set_tags(&self, tags: TagList){
+        unsafe{
+            gst_pad_send_event(self.pad.gst_pad() as *mut GstPad, gst_event_new_tag(tags.transfer()));
+        }
+    }
+
+    /// ISO 639 code of the language of the stream, as "en" or "spa"
+    pub fn set_language(&self, language: &str){
+        let mut tags = TagList::new();
+        tags.add("language-code", language);
+        self.set_tags(tags);
+    }
+
+    pub fn set_title(&self, title: &str){
+        let mut tags = TagList::new();
+        tags.add("title", title);
+        self.set_tags(tags);
+    }
+
+    /// Sets a property of the pad, as trak-timescale in mp4mux
+    pub fn set<T>(&self, name: &str, value: T){
+        unsafe{
+            g_object_set(self.pad.gst_pad() as gpointer, to_c_str!(name), value, ptr::null::<gchar>());
+        }
+    }
+
+    pub fn pad(&self) -> &Pad{
+        &self.pad
+    }
+}
+
+impl ElementT for Muxer{
+    fn as_element(&self) -> &Element{
+        &self.muxer
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.muxer
+    }
+}
+
+impl ::Transfer for Muxer{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.muxer.transfer()
+    }
+}
@@ -0,0 +1,267 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+
+/// Format of the pixels passed to OverlayRectangle::new_argb, ARGB in
+/// native endianness which is BGRA in memory on little endian machines
+#[cfg(target_endian="little")]
+const OVERLAY_FORMAT: GstVideoFormat = GST_VIDEO_FORMAT_BGRA;
+#[cfg(target_endian="big")]
+const OVERLAY_FORMAT: GstVideoFormat = GST_VIDEO_FORMAT_ARGB;
+
+/// An image rendered by the application, like a subtitle or a HUD, and
+/// the area of the video where it should be drawn
+pub struct OverlayRectangle{
+    rectangle: *mut GstVideoOverlayRectangle
+}
+
+unsafe impl Send for OverlayRectangle {}
+
+impl Drop for OverlayRectangle{
+    fn drop(&mut self){
+        unsafe{
+            gst_mini_object_unref(self.rectangle as *mut GstMiniObject);
+        }
+    }
+}
+
+impl Clone for OverlayRectangle{
+    fn clone(&self) -> OverlayRectangle{
+        unsafe{
+            gst_mini_object_ref(self.rectangle as *mut GstMiniObject);
+            OverlayRectangle{ rectangle: self.rectangle }
+        }
+    }
+}
+
+impl OverlayRectangle{
+    /// Wraps a raw GstVideoOverlayRectangle, if owned is false a new
+    /// reference is taken
+    pub unsafe fn new(rectangle: *mut GstVideoOverlayRectangle, owned: bool) -> Option<OverlayRectangle>{
+        if rectangle != ptr::null_mut(){
+            if !owned{
+                gst_mini_object_ref(rectangle as *mut GstMiniObject);
+            }
+            Some(OverlayRectangle{ rectangle: rectangle })
+        }else{
+            None
+        }
+    }
+
+    /// Creates a rectangle from width x height pixels in ARGB with
+    /// straight (not premultiplied) alpha, 4 bytes per pixel without
+    /// padding, drawn at x, y in the video scaled to render_width x
+    /// render_height
+    pub fn new_argb(pixels: &[u8], width: u32, height: u32, x: i32, y: i32, render_width: u32, render_height: u32) -> Option<OverlayRectangle>{
+        let size = width as usize * height as usize * 4;
+        if pixels.len() < size{
+            return None;
+        }
+        unsafe{
+            let buffer = gst_buffer_new_allocate(ptr::null_mut(), size as gsize, ptr::null_mut());
+            if buffer == ptr::null_mut(){
+                return None;
+            }
+            gst_buffer_fill(buffer, 0, pixels.as_ptr() as gconstpointer, size as gsize);
+            gst_buffer_add_video_meta(buffer, GST_VIDEO_FRAME_FLAG_NONE, OVERLAY_FORMAT, width, height);
+            let rectangle = gst_video_overlay_rectangle_new_raw(buffer, x, y, render_width, render_height,
+                GST_VIDEO_OVERLAY_FORMAT_FLAG_NONE);
+            gst_mini_object_unref(buffer as *mut GstMiniObject);
+            OverlayRectangle::new(rectangle, true)
+        }
+    }
+
+    /// Returns the area of the video where the rectangle is drawn as
+    /// (x, y, width, height)
+    pub fn render_rectangle(&self) -> (i32, i32, u32, u32){
+        unsafe{
+            let (mut x, mut y, mut width, mut height) = (0, 0, 0, 0);
+            gst_video_overlay_rectangle_get_render_rectangle(self.rectangle, &mut x, &mut y, &mut width, &mut height);
+            (x, y, width, height)
+        }
+    }
+
+    /// Moves or scales the rectangle, for example to follow a change in
+    /// the video size, without copying the pixels
+    pub fn set_render_rectangle(&mut self, x: i32, y: i32, width: u32, height: u32){
+        unsafe{
+            gst_video_overlay_rectangle_set_render_rectangle(self.rectangle, x, y, width, height);
+        }
+    }
+
+    /// Opacity applied to the whole rectangle, from 0.0 to 1.0
+    pub fn global_alpha(&self) -> f32{
+        unsafe{
+            gst_video_overlay_rectangle_get_global_alpha(self.rectangle)
+        }
+    }
+
+    pub fn set_global_alpha(&mut self, alpha: f32){
+        unsafe{
+            gst_video_overlay_rectangle_set_global_alpha(self.rectangle, alpha);
+        }
+    }
+
+    /// Changes every time the rectangle is modified, sinks use it to know
+    /// when to upload the overlay again
+    pub fn seqnum(&self) -> u32{
+        unsafe{
+            gst_video_overlay_rectangle_get_seqnum(self.rectangle)
+        }
+    }
+
+    /// Returns the pixels in ARGB at their original size
+    pub fn pixels_argb(&self) -> Option<Buffer>{
+        unsafe{
+            Buffer::new(gst_video_overlay_rectangle_get_pixels_unscaled_argb(self.rectangle,
+                GST_VIDEO_OVERLAY_FORMAT_FLAG_NONE), false)
+        }
+    }
+
+    pub unsafe fn gst_video_overlay_rectangle(&self) -> *const GstVideoOverlayRectangle{
+        self.rectangle
+    }
+
+    pub unsafe fn gst_video_overlay_rectangle_mut(&mut self) -> *mut GstVideoOverlayRectangle{
+        self.rectangle
+    }
+}
+
+/// A set of OverlayRectangles attached to a video buffer with
+/// Buffer::add_overlay_composition. Sinks and elements that support the
+/// composition meta, including the GL sinks, blend the rectangles when
+/// rendering, otherwise they are blended into the frame by an
+/// overlaycomposition element or dropped.
+pub struct OverlayComposition{
+    composition: *mut GstVideoOverlayComposition
+}
+
+unsafe impl Send for OverlayComposition {}
+
+impl Drop for OverlayComposition{
+    fn drop(&mut self){
+        unsafe{
+            gst_mini_object_unref(self.composition as *mut GstMiniObject);
+        }
+    }
+}
+
+impl Clone for OverlayComposition{
+    fn clone(&self) -> OverlayComposition{
+        unsafe{
+            gst_mini_object_ref(self.composition as *mut GstMiniObject);
+            OverlayComposition{ composition: self.composition }
+        }
+    }
+}
+
+impl OverlayComposition{
+    /// Wraps a raw GstVideoOverlayComposition, if owned is false a new
+    /// reference is taken
+    pub unsafe fn new(composition: *mut GstVideoOverlayComposition, owned: bool) -> Option<OverlayComposition>{
+        if composition != ptr::null_mut(){
+            if !owned{
+                gst_mini_object_ref(composition as *mut GstMiniObject);
+            }
+            Some(OverlayComposition{ composition: composition })
+        }else{
+            None
+        }
+    }
+
+    /// Creates a composition with a first rectangle
+    pub fn new_from_rectangle(rectangle: &OverlayRectangle) -> Option<OverlayComposition>{
+        unsafe{
+            OverlayComposition::new(gst_video_overlay_composition_new(rectangle.rectangle), true)
+        }
+    }
+
+    /// Creates a composition with all the rectangles, drawn in order
+    pub fn new_from_rectangles(rectangles: &[OverlayRectangle]) -> Option<OverlayComposition>{
+        let (first, rest) = rectangles.split_first()?;
+        let mut composition = OverlayComposition::new_from_rectangle(first)?;
+        for rectangle in rest{
+            composition.add_rectangle(rectangle);
+        }
+        Some(composition)
+    }
+
+    /// Adds a rectangle on top of the previous ones, the composition is
+    /// copied if it's shared
+    pub fn add_rectangle(&mut self, rectangle: &OverlayRectangle){
+        unsafe{
+            self.composition = gst_video_overlay_composition_make_writable(self.composition);
+            gst_video_overlay_composition_add_rectangle(self.composition, rectangle.rectangle);
+        }
+    }
+
+    pub fn len(&self) -> u32{
+        unsafe{
+            gst_video_overlay_composition_n_rectangles(self.composition)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+
+    pub fn rectangle(&self, index: u32) -> Option<OverlayRectangle>{
+        unsafe{
+            OverlayRectangle::new(gst_video_overlay_composition_get_rectangle(self.composition, index), false)
+        }
+    }
+
+    pub fn rectangles(&self) -> Vec<OverlayRectangle>{
+        (0..self.len()).filter_map(|i| self.rectangle(i)).collect()
+    }
+
+    pub fn seqnum(&self) -> u32{
+        unsafe{
+            gst_video_overlay_composition_get_seqnum(self.composition)
+        }
+    }
+
+    pub unsafe fn gst_video_overlay_composition(&self) -> *const GstVideoOverlayComposition{
+        self.composition
+    }
+
+    pub unsafe fn gst_video_overlay_composition_mut(&mut self) -> *mut GstVideoOverlayComposition{
+        self.composition
+    }
+}
+
+impl Buffer{
+    /// Attaches the overlays to this video buffer, replacing any previous
+    /// composition
+    pub fn add_overlay_composition(&mut self, composition: &OverlayComposition){
+        unsafe{
+            let buffer = self.gst_buffer_mut();
+            let meta = gst_buffer_get_meta(buffer, gst_video_overlay_composition_meta_api_get_type());
+            if meta != ptr::null_mut(){
+                gst_buffer_remove_meta(buffer, meta);
+            }
+            gst_buffer_add_video_overlay_composition_meta(buffer, composition.composition);
+        }
+    }
+
+    /// Returns the overlays attached to this buffer if any
+    pub fn overlay_composition(&self) -> Option<OverlayComposition>{
+        unsafe{
+            let meta = gst_buffer_get_meta(self.gst_buffer() as *mut GstBuffer,
+                gst_video_overlay_composition_meta_api_get_type()) as *mut GstVideoOverlayCompositionMeta;
+            if meta != ptr::null_mut(){
+                OverlayComposition::new((*meta).overlay, false)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn remove_overlay_composition(&mut self) -> bool{
+        unsafe{
+            let buffer = self.gst_buffer_mut();
+            let meta = gst_buffer_get_meta(buffer, gst_video_overlay_composition_meta_api_get_type());
+            meta != ptr::null_mut() && gst_buffer_remove_meta(buffer, meta) == 1
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use ffi::*;
+use util::*;
+use debug::{track_new, track_release};
+use caps::Caps;
+use buffer::Buffer;
+use element::Element;
+use format::ClockTimeDiff;
+
+use std::os::raw::c_void;
+use std::fmt::{self, Debug, Display, Formatter};
+
+unsafe impl Sync for Pad {}
+unsafe impl Send for Pad {}
+
+/// A pad is the connection point of an element, data flows from the source
+/// pads of an element to the sink pads of the next one.
+pub struct Pad{
+    pad: *mut GstPad
+}
+
+impl Drop for Pad{
+    fn drop(&mut self){
+        unsafe{
+            track_release(self.pad);
+            gst_object_unref(self.pad as *mut c_void);
+        }
+    }
+}
+
+impl Clone for Pad{
+    fn clone(&self) -> Pad{
+        unsafe{
+            gst_object_ref(self.pad as *mut c_void);
+            track_new("Pad", self.pad, false);
+            Pad{ pad: self.pad }
+        }
+    }
+}
+
+impl Pad{
+    /// Wraps a raw GstPad, if owned is false a new reference is taken
+    pub unsafe fn new(pad: *mut GstPad, owned: bool) -> Option<Pad>{
+        if pad != ptr::null_mut(){
+            if !owned{
+                gst_object_ref(pad as *mut c_void);
+            }
+            track_new("Pad", pad, false);
+            Some(Pad{ pad: pad })
+        }else{
+            None
+        }
+    }
+
+    pub fn name(&self) -> String{
+        unsafe{
+            let c_str_name = gst_object_get_name(self.pad as *mut GstObject);
+            let name = from_c_str!(c_str_name).to_string();
+            g_free(c_str_name as gpointer);
+            name
+        }
+    }
+
+    /// Returns GST_PAD_SRC, GST_PAD_SINK or GST_PAD_UNKNOWN
+    pub fn direction(&self) -> GstPadDirection{
+        unsafe{
+            gst_pad_get_direction(self.pad)
+        }
+    }
+
+    pub fn is_src(&self) -> bool{
+        self.direction() == GST_PAD_SRC
+    }
+
+    pub fn is_sink(&self) -> bool{
+        self.direction() == GST_PAD_SINK
+    }
+
+    /// Returns the caps negotiated on this pad, or None if the pad hasn't
+    /// negotiated yet
+    pub fn current_caps(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(gst_pad_get_current_caps(self.pad), true)
+        }
+    }
+
+    /// Returns all the caps this pad can produce or accept in its current
+    /// state
+    pub fn query_caps(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(gst_pad_query_caps(self.pad, ptr::null_mut()), true)
+        }
+    }
+
+    pub fn is_linked(&self) -> bool{
+        unsafe{
+            gst_pad_is_linked(self.pad) == 1
+        }
+    }
+
+    /// Returns the pad this pad is linked to
+    pub fn peer(&self) -> Option<Pad>{
+        unsafe{
+            Pad::new(gst_pad_get_peer(self.pad), true)
+        }
+    }
+
+    /// Returns the element this pad belongs to
+    pub fn parent_element(&self) -> Option<Element>{
+        unsafe{
+            Element::new_from_gst_element(gst_pad_get_parent_element(self.pad))
+        }
+    }
+
+    /// Links this source pad to a sink pad
+    pub fn link(&mut self, sink: &mut Pad) -> GstPadLinkReturn{
+        unsafe{
+            gst_pad_link(self.pad, sink.gst_pad_mut())
+        }
+    }
+
+    pub fn unlink(&mut self, sink: &mut Pad) -> bool{
+        unsafe{
+            gst_pad_unlink(self.pad, sink.gst_pad_mut()) == 1
+        }
+    }
+
+    /// Running time offset added to the data going through the pad,
+    /// positive values delay it. Used to align branches with
+    /// different processing delays, as an audio path processed outside
+    /// the pipeline, without changing the timestamps of the buffers
+    pub fn set_offset<T: Into<ClockTimeDiff>>(&self, offset: T){
+        unsafe{
+            gst_pad_set_offset(self.pad, offset.into().ns());
+        }
+    }
+
+    pub fn offset(&self) -> ClockTimeDiff{
+        unsafe{
+            ClockTimeDiff(gst_pad_get_offset(self.pad))
+        }
+    }
+
+    /// Whether this is a ghost pad exposing the pad of a child of a bin
+    pub fn is_ghost_pad(&self) -> bool{
+        unsafe{
+            g_type_check_instance_is_a(self.pad as *mut GTypeInstance, gst_ghost_pad_get_type()) != 0
+        }
+    }
+
+    /// Whether this is a proxy pad, as ghost pads and their internal pads
+    pub fn is_proxy_pad(&self) -> bool{
+        unsafe{
+            g_type_check_instance_is_a(self.pad as *mut GTypeInstance, gst_proxy_pad_get_type()) != 0
+        }
+    }
+
+    /// The other side of a proxy pad. For a ghost pad it's the pad inside
+    /// the bin linked to the target, with the opposite direction, where
+    /// probes see the data after it crossed the bin boundary
+    pub fn internal(&self) -> Option<Pad>{
+        unsafe{
+            if !self.is_proxy_pad(){
+                return None;
+            }
+            Pad::new(gst_proxy_pad_get_internal(self.pad as *mut GstProxyPad) as *mut GstPad, true)
+        }
+    }
+
+    /// The pad of the child a ghost pad exposes
+    pub fn ghost_target(&self) -> Option<Pad>{
+        unsafe{
+            if !self.is_ghost_pad(){
+                return None;
+            }
+            Pad::new(gst_ghost_pad_get_target(self.pad as *mut GstGhostPad), true)
+        }
+    }
+
+    /// The pad of an element that a ghost pad ends up exposing, following
+    /// the targets through nested bins. Returns the pad itself if it's
+    /// not a ghost pad
+    pub fn resolve_target(&self) -> Option<Pad>{
+        let mut pad = self.clone();
+        while pad.is_ghost_pad(){
+            pad = pad.ghost_target()?;
+        }
+        Some(pad)
+    }
+
+    /// The pads on the other side of the element the data from this pad
+    /// goes to, or comes from, as the source pads of a demuxer for its
+    /// sink pad. For a ghost pad it's its internal pad
+    pub fn internal_links(&self) -> Vec<Pad>{
+        unsafe{
+            iterator_objects(gst_pad_iterate_internal_links(self.pad)).into_iter()
+                .filter_map(|pad| Pad::new(pad as *mut GstPad, true))
+                .collect()
+        }
+    }
+
+    /// Calls f for every pad the data would be forwarded to, going
+    /// through ghost pads to the pads of the elements outside the bins,
+    /// until f returns true. Returns whether f returned true for any pad
+    pub fn forward<F: FnMut(&Pad) -> bool>(&self, mut f: F) -> bool{
+        unsafe{
+            gst_pad_forward(self.pad, Some(forward_func::<F>), &mut f as *mut F as gpointer) == 1
+        }
+    }
+
+    /// Calls f with every buffer that goes through the pad, from the
+    /// streaming thread. f decides what happens to the buffer, usually
+    /// GST_PAD_PROBE_OK to let it pass. Returns the id of the probe to
+    /// remove it with remove_probe
+    pub fn add_buffer_probe<F: Fn(&Pad, &Buffer) -> GstPadProbeReturn + Send + Sync + 'static>(&self, f: F) -> u64{
+        unsafe{
+            gst_pad_add_probe(self.pad, GST_PAD_PROBE_TYPE_BUFFER, Some(buffer_probe::<F>),
+                Box::into_raw(Box::new(f)) as gpointer, Some(box_destroy_notify::<F>)) as u64
+        }
+    }
+
+    pub fn remove_probe(&self, id: u64){
+        unsafe{
+            gst_pad_remove_probe(self.pad, id as gulong);
+        }
+    }
+
+    pub unsafe fn gst_pad(&self) -> *const GstPad{
+        self.pad
+    }
+
+    pub unsafe fn gst_pad_mut(&mut self) -> *mut GstPad{
+        self.pad
+    }
+}
+
+extern "C" fn buffer_probe<F: Fn(&Pad, &Buffer) -> GstPadProbeReturn>(pad: *mut GstPad, info: *mut GstPadProbeInfo, data: gpointer) -> GstPadProbeReturn{
+    unsafe{
+        match (Pad::new(pad, false), Buffer::new(gst_pad_probe_info_get_buffer(info), false)){
+            (Some(pad), Some(buffer)) => (*(data as *const F))(&pad, &buffer),
+            _ => GST_PAD_PROBE_OK
+        }
+    }
+}
+
+extern "C" fn forward_func<F: FnMut(&Pad) -> bool>(pad: *mut GstPad, data: gpointer) -> gboolean{
+    unsafe{
+        match Pad::new(pad, false){
+            Some(pad) => (*(data as *mut F))(&pad) as gboolean,
+            None => 0
+        }
+    }
+}
+
+impl ::Transfer<GstPad> for Pad{
+    unsafe fn transfer(self) -> *mut GstPad{
+        let pad = self.pad;
+        track_release(pad);
+        mem::forget(self);
+        pad
+    }
+}
+
+impl Display for Pad{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        match self.parent_element(){
+            Some(parent) => write!(fmt, "{}:{}", ::ElementT::name(&parent), self.name()),
+            None => write!(fmt, "{}", self.name())
+        }
+    }
+}
+
+impl Debug for Pad{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        let direction = match self.direction(){
+            GST_PAD_SRC => "src",
+            GST_PAD_SINK => "sink",
+            _ => "unknown"
+        };
+        write!(fmt, "Pad {{ name: \"{}\", direction: {}", self, direction)?;
+        if let Some(peer) = self.peer(){
+            write!(fmt, ", peer: \"{}\"", peer)?;
+        }
+        if let Some(caps) = self.current_caps(){
+            write!(fmt, ", caps: \"{}\"", caps)?;
+        }
+        write!(fmt, " }}")
+    }
+}
@@ -0,0 +1,169 @@
+use ffi::*;
+use util::*;
+use bin::{Bin, BinT};
+use element::Element;
+use pipeline::Pipeline;
+use error::Error;
+
+use std::result;
+use std::fmt;
+
+/// Reasons why parsing a pipeline description can fail
+#[derive(Debug)]
+pub enum ParseError{
+    /// The description isn't valid gst-launch syntax
+    Syntax(Error),
+    /// The description uses elements that aren't installed, with the
+    /// names of all of them so the user can be asked to install the
+    /// plugins providing them
+    MissingElements(Vec<String>),
+    /// An element doesn't have a property set in the description
+    NoSuchProperty(Error),
+    /// A property has a value that can't be converted to its type
+    CouldNotSetProperty(Error),
+    /// Two elements couldn't be linked
+    Link(Error),
+    /// The description, or a bin in it, has no elements
+    Empty,
+    /// Any other error
+    Other(Error),
+}
+
+impl ParseError{
+    /// The names of the elements that aren't installed, empty for other
+    /// errors
+    pub fn missing_elements(&self) -> &[String]{
+        match *self{
+            ParseError::MissingElements(ref elements) => elements,
+            _ => &[]
+        }
+    }
+
+    unsafe fn new(error: *mut GError, context: *mut GstParseContext) -> ParseError{
+        // a missing element can make parsing fail with other errors, as
+        // failing to link it, report it anyway since installing it is what
+        // the user has to do
+        let missing = missing_elements(context);
+        let error = Error::new_from_g_error(error);
+        if !missing.is_empty(){
+            return ParseError::MissingElements(missing);
+        }
+        if error.domain() != gst_parse_error_quark(){
+            return ParseError::Other(error);
+        }
+        match error.code() as guint{
+            GST_PARSE_ERROR_SYNTAX => ParseError::Syntax(error),
+            GST_PARSE_ERROR_NO_SUCH_PROPERTY => ParseError::NoSuchProperty(error),
+            GST_PARSE_ERROR_COULD_NOT_SET_PROPERTY => ParseError::CouldNotSetProperty(error),
+            GST_PARSE_ERROR_LINK => ParseError::Link(error),
+            GST_PARSE_ERROR_EMPTY_BIN | GST_PARSE_ERROR_EMPTY => ParseError::Empty,
+            _ => ParseError::Other(error),
+        }
+    }
+}
+
+impl fmt::Display for ParseError{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result{
+        match *self{
+            ParseError::Syntax(ref err) => write!(fmt, "syntax error: {}", err.message()),
+            ParseError::MissingElements(ref elements) => write!(fmt, "missing elements: {}", elements.join(", ")),
+            ParseError::NoSuchProperty(ref err) => write!(fmt, "no such property: {}", err.message()),
+            ParseError::CouldNotSetProperty(ref err) => write!(fmt, "couldn't set property: {}", err.message()),
+            ParseError::Link(ref err) => write!(fmt, "couldn't link: {}", err.message()),
+            ParseError::Empty => write!(fmt, "empty pipeline description"),
+            ParseError::Other(ref err) => write!(fmt, "{}", err.message()),
+        }
+    }
+}
+
+/// Keeps the error of the parser, missing elements are reported as a core
+/// MISSING_PLUGIN error naming them
+impl From<ParseError> for Error{
+    fn from(err: ParseError) -> Error{
+        match err{
+            ParseError::Syntax(err) | ParseError::NoSuchProperty(err) | ParseError::CouldNotSetProperty(err) |
+                ParseError::Link(err) | ParseError::Other(err) => err,
+            ParseError::MissingElements(elements) => Error::new(unsafe{ gst_core_error_quark() },
+                GST_CORE_ERROR_MISSING_PLUGIN as i32, &format!("missing elements: {}", elements.join(", "))),
+            ParseError::Empty => Error::new(unsafe{ gst_parse_error_quark() }, GST_PARSE_ERROR_EMPTY as i32,
+                "empty pipeline description"),
+        }
+    }
+}
+
+unsafe fn missing_elements(context: *mut GstParseContext) -> Vec<String>{
+    let names = gst_parse_context_get_missing_elements(context);
+    if names == ptr::null_mut(){
+        return vec![];
+    }
+    let mut missing = Vec::new();
+    let mut name = names;
+    while *name != ptr::null_mut(){
+        missing.push(from_c_str!(*name as *const gchar).to_string());
+        name = name.offset(1);
+    }
+    g_strfreev(names);
+    missing
+}
+
+// Parses with a GstParseContext to collect the missing elements, ghost is
+// Some to parse into a bin ghosting its unlinked pads or not
+unsafe fn parse(description: &str, ghost: Option<bool>) -> result::Result<*mut GstElement, ParseError>{
+    let context = gst_parse_context_new();
+    let mut error = ptr::null_mut::<GError>();
+    let element = match ghost{
+        Some(ghost) => gst_parse_bin_from_description_full(to_c_str!(description), ghost as gboolean, context,
+            GST_PARSE_FLAG_FATAL_ERRORS, &mut error),
+        None => gst_parse_launch_full(to_c_str!(description), context, GST_PARSE_FLAG_FATAL_ERRORS, &mut error),
+    };
+    let result = if error != ptr::null_mut(){
+        if element != ptr::null_mut(){
+            gst_object_unref(element as gpointer);
+        }
+        Err(ParseError::new(error, context))
+    }else if element == ptr::null_mut(){
+        Err(ParseError::Empty)
+    }else{
+        gst_object_ref_sink(element as gpointer);
+        Ok(element)
+    };
+    gst_parse_context_free(context);
+    result
+}
+
+/// Creates the elements of a gst-launch description, a Pipeline if it has
+/// more than one element or the only element otherwise
+pub fn parse_launch(description: &str) -> result::Result<Element, ParseError>{
+    unsafe{
+        let element = parse(description, None)?;
+        Element::new_from_gst_element(element).ok_or(ParseError::Empty)
+    }
+}
+
+/// Creates a bin with the elements of description, ghosting the unlinked
+/// pads if ghost_unlinked_pads so it can be linked as a single element
+pub fn parse_bin(description: &str, ghost_unlinked_pads: bool) -> result::Result<Bin, ParseError>{
+    unsafe{
+        let bin = parse(description, Some(ghost_unlinked_pads))?;
+        Bin::new_from_gst_bin(bin as *mut GstBin).ok_or(ParseError::Empty)
+    }
+}
+
+impl Pipeline{
+    /// Creates a pipeline from a gst-launch description, telling apart
+    /// syntax errors from elements that aren't installed. A description of
+    /// a single element is put into a new pipeline
+    pub fn parse(description: &str) -> result::Result<Pipeline, ParseError>{
+        unsafe{
+            let element = parse(description, None)?;
+            if g_type_check_instance_is_a(element as *mut GTypeInstance, gst_pipeline_get_type()) != 0{
+                return Pipeline::new_from_gst_pipeline(element as *mut GstPipeline).ok_or(ParseError::Empty);
+            }
+            let element = Element::new_from_gst_element(element).ok_or(ParseError::Empty)?;
+            let mut pipeline = Pipeline::new("").ok_or_else(|| ParseError::Other(
+                Error::new(gst_core_error_quark(), GST_CORE_ERROR_FAILED as i32, "couldn't create pipeline")))?;
+            pipeline.add(element);
+            Ok(pipeline)
+        }
+    }
+}
@@ -6,6 +6,10 @@ use error::Error;
 use error::Result;
 use util::*;
 
+use std::os::raw::c_void;
+use std::result;
+use std::fmt;
+
 
 /** A GstPipeline is a special GstBin used as the toplevel container for the filter graph. The GstPipeline will manage the selection and distribution of a global GstClock as well as provide a GstBus to the application.
 
@@ -46,23 +50,33 @@ impl Pipeline{
         }
     }
     
-    /// Creates a new pipeline using gst_parse_launch
+    /// Creates a new pipeline from a gst-launch description, as
+    /// Pipeline::parse but without telling apart the kind of error
     pub fn new_from_str(string: &str) -> Result<Pipeline>{
-        let mut error = ptr::null_mut::<GError>();
+        Pipeline::parse(string).map_err(Error::from)
+    }
+    
+    /// Creates a pipeline from a registered factory, as the ones
+    /// registered with register_pipeline. Returns None if the factory
+    /// doesn't exist or doesn't create pipelines
+    pub fn new_from_factory(factory_name: &str, name: &str) -> Option<Pipeline>{
         unsafe{
-            let pipeline = gst_parse_launch (to_c_str!(string), &mut error);
-            if error == ptr::null_mut(){
-	            gst_object_ref_sink(mem::transmute(pipeline));
-				match Bin::new_from_gst_bin(pipeline as *mut GstBin){
-					Some(bin) => Ok(Pipeline{ pipeline: bin }),
-					None => Err(Error::new(0,0,"Couldn't create bin"))
-				}
-			}else{
-				Err(Error::new_from_g_error(error))
-			}
+            // NULL lets the factory pick a unique name, as Element::new
+            let name = if name != ""{ Some(CString::new(name).ok()?) }else{ None };
+            let name = name.as_ref().map(|name| name.as_ptr()).unwrap_or(ptr::null());
+            let pipeline = gst_element_factory_make(to_c_str!(factory_name), name);
+            if pipeline == ptr::null_mut(){
+                return None;
+            }
+            gst_object_ref_sink(pipeline as gpointer);
+            if g_type_check_instance_is_a(pipeline as *mut GTypeInstance, gst_pipeline_get_type()) == 0{
+                gst_object_unref(pipeline as gpointer);
+                return None;
+            }
+            Pipeline::new_from_gst_pipeline(pipeline as *mut GstPipeline)
         }
     }
-    
+
     pub unsafe fn new_from_gst_pipeline(pipeline: *mut GstPipeline) -> Option<Pipeline>{
         match Bin::new_from_gst_bin(pipeline as *mut GstBin){
             Some(pipeline) => Some( Pipeline{ pipeline: pipeline } ),
@@ -103,6 +117,31 @@ pub trait PipelineT: BinT{
         self.as_pipeline_mut().set_delay(delay)
     }
     
+    /// Sends an EOS event to the pipeline and blocks until the EOS message
+    /// arrives on the bus, an error is posted or timeout expires. Use
+    /// GST_CLOCK_TIME_NONE to wait forever.
+    ///
+    /// Muxers only write their headers and indexes when they receive EOS,
+    /// so this has to be called before setting the state to NULL when
+    /// recording to files like mp4 or mkv, otherwise the file will be
+    /// unplayable.
+    ///
+    /// The messages are popped from the bus so this shouldn't be used
+    /// while a bus watch is dispatching messages from another thread.
+    fn send_eos_and_wait(&mut self, timeout: GstClockTime) -> Result<()>{
+        self.as_pipeline_mut().send_eos_and_wait(timeout)
+    }
+
+    /// Sets the pipeline to PAUSED and waits up to timeout for it to
+    /// preroll, that is, until every sink has received its first buffer,
+    /// so for example the first frame of a video is shown.
+    ///
+    /// Live pipelines can't preroll and return PrerollError::Live right
+    /// away, they are left in PAUSED and can be set to PLAYING.
+    fn preroll(&mut self, timeout: GstClockTime) -> result::Result<(), PrerollError>{
+        self.as_pipeline_mut().preroll(timeout)
+    }
+
     /// Returns a const raw pointer to the internal GstElement
     unsafe fn gst_pipeline(&self) -> *const GstPipeline{
         self.as_pipeline().gst_pipeline()
@@ -135,6 +174,37 @@ impl PipelineT for Pipeline{
         }
     }
     
+    fn send_eos_and_wait(&mut self, timeout: GstClockTime) -> Result<()>{
+        unsafe{
+            if gst_element_send_event(self.gst_element_mut(), gst_event_new_eos()) != 1{
+                return Err(Error::new(gst_core_error_quark(), GST_CORE_ERROR_EVENT as i32,
+                    &format!("{} didn't accept the EOS event", self.name())));
+            }
+        }
+        match self.wait_for_message(timeout, GST_MESSAGE_EOS){
+            Some(Ok(_)) => Ok(()),
+            Some(Err(err)) => Err(err),
+            None => Err(Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_FAILED as i32,
+                &format!("timed out waiting for EOS on {}", self.name())))
+        }
+    }
+
+    fn preroll(&mut self, timeout: GstClockTime) -> result::Result<(), PrerollError>{
+        match self.set_state(GST_STATE_PAUSED){
+            GST_STATE_CHANGE_SUCCESS => Ok(()),
+            GST_STATE_CHANGE_NO_PREROLL => Err(PrerollError::Live),
+            GST_STATE_CHANGE_ASYNC => match self.wait_for_message(timeout, GST_MESSAGE_ASYNC_DONE){
+                Some(Ok(_)) => Ok(()),
+                Some(Err(err)) => Err(PrerollError::Error(err)),
+                None => Err(PrerollError::Timeout)
+            },
+            _ => match self.wait_for_message(0, 0){
+                Some(Err(err)) => Err(PrerollError::Error(err)),
+                _ => Err(PrerollError::StateChangeFailed)
+            }
+        }
+    }
+
     unsafe fn gst_pipeline(&self) -> *const GstPipeline{
         self.pipeline.gst_element() as *const GstPipeline
     }
@@ -154,6 +224,60 @@ impl<P:PipelineT> BinT for P{
     }
 }
 
+impl Pipeline{
+    /// Pops messages of the passed types or errors from the bus waiting
+    /// up to timeout, error messages are returned as Err and None is
+    /// returned if the timeout expires
+    pub(crate) fn wait_for_message(&mut self, timeout: GstClockTime, types: GstMessageType) -> Option<Result<GstMessageType>>{
+        unsafe{
+            let bus = gst_pipeline_get_bus(self.gst_pipeline_mut());
+            let message = gst_bus_timed_pop_filtered(bus, timeout, types | GST_MESSAGE_ERROR);
+            gst_object_unref(bus as *mut c_void);
+            if message == ptr::null_mut(){
+                return None;
+            }
+            let ty = (*message)._type;
+            let ret = if ty == GST_MESSAGE_ERROR{
+                let mut error = ptr::null_mut::<GError>();
+                let mut debug = ptr::null_mut::<gchar>();
+                gst_message_parse_error(message, &mut error, &mut debug);
+                g_free(debug as gpointer);
+                Err(Error::new_from_g_error(error))
+            }else{
+                Ok(ty)
+            };
+            gst_mini_object_unref(message as *mut GstMiniObject);
+            Some(ret)
+        }
+    }
+}
+
+/// Reasons why Pipeline::preroll can fail
+#[derive(Debug)]
+pub enum PrerollError{
+    /// The pipeline has live sources that only produce data in PLAYING,
+    /// so it can't preroll. The pipeline is still in PAUSED and can be
+    /// set to PLAYING normally.
+    Live,
+    /// The pipeline didn't preroll before the timeout
+    Timeout,
+    /// The state change failed without posting an error
+    StateChangeFailed,
+    /// An element posted an error while prerolling
+    Error(Error),
+}
+
+impl fmt::Display for PrerollError{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result{
+        match *self{
+            PrerollError::Live => write!(fmt, "live pipelines can't preroll"),
+            PrerollError::Timeout => write!(fmt, "timed out waiting for preroll"),
+            PrerollError::StateChangeFailed => write!(fmt, "state change to PAUSED failed"),
+            PrerollError::Error(ref err) => write!(fmt, "error while prerolling: {}", err.message()),
+        }
+    }
+}
+
 impl ::Transfer for Pipeline{
     unsafe fn transfer(self) -> *mut GstElement{
         self.pipeline.transfer()
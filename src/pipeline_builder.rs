@@ -0,0 +1,165 @@
+use ffi::*;
+use element::{Element, ElementT};
+use bin::BinT;
+use pipeline::Pipeline;
+use value::Value;
+use error::{Error, Result};
+
+struct ElementSpec{
+    factory: String,
+    name: Option<String>,
+    properties: Vec<(String, Value)>,
+}
+
+/// Builds a linear pipeline element by element, a typed alternative to
+/// Pipeline::new_from_str:
+///
+/// ```ignore
+/// let pipeline = PipelineBuilder::new()
+///     .src("videotestsrc").prop("is-live", true)
+///     .pipe("videoconvert")
+///     .sink("autovideosink")
+///     .build()?;
+/// ```
+///
+/// Every element is created, configured, added to the pipeline and linked
+/// to the previous one when calling build, which returns an error naming
+/// the element that failed.
+pub struct PipelineBuilder{
+    name: String,
+    elements: Vec<ElementSpec>,
+    has_sink: bool,
+    error: Option<String>,
+}
+
+impl PipelineBuilder{
+    pub fn new() -> PipelineBuilder{
+        PipelineBuilder{
+            name: "pipeline".to_string(),
+            elements: Vec::new(),
+            has_sink: false,
+            error: None,
+        }
+    }
+
+    /// Sets the name of the pipeline
+    pub fn name(mut self, name: &str) -> PipelineBuilder{
+        self.name = name.to_string();
+        self
+    }
+
+    /// Adds the source element, it has to be the first element
+    pub fn src(mut self, factory: &str) -> PipelineBuilder{
+        if !self.elements.is_empty(){
+            self.fail(format!("source {} has to be the first element", factory));
+        }
+        self.push(factory)
+    }
+
+    /// Adds an element linked to the previous one
+    pub fn pipe(self, factory: &str) -> PipelineBuilder{
+        self.push(factory)
+    }
+
+    /// Adds the sink element, no elements can be added after it
+    pub fn sink(self, factory: &str) -> PipelineBuilder{
+        let mut builder = self.push(factory);
+        builder.has_sink = true;
+        builder
+    }
+
+    /// Adds a capsfilter restricting the format between the previous
+    /// and next elements, caps are in the usual string format as in
+    /// `video/x-raw,width=640,height=480`
+    pub fn caps(self, caps: &str) -> PipelineBuilder{
+        self.push("capsfilter").prop("caps", caps)
+    }
+
+    /// Names the last added element so it can be retrieved later with
+    /// get_by_name
+    pub fn named(mut self, name: &str) -> PipelineBuilder{
+        match self.elements.last_mut(){
+            Some(spec) => spec.name = Some(name.to_string()),
+            None => self.fail(format!("named({}) called before adding any element", name)),
+        }
+        self
+    }
+
+    /// Sets a property on the last added element
+    pub fn prop<V: Into<Value>>(mut self, name: &str, value: V) -> PipelineBuilder{
+        match self.elements.last_mut(){
+            Some(spec) => spec.properties.push((name.to_string(), value.into())),
+            None => self.fail(format!("property {} set before adding any element", name)),
+        }
+        self
+    }
+
+    /// Creates the pipeline with all its elements added and linked
+    pub fn build(self) -> Result<Pipeline>{
+        if let Some(error) = self.error{
+            return Err(builder_error(GST_CORE_ERROR_FAILED, &error));
+        }
+        if self.elements.is_empty(){
+            return Err(builder_error(GST_CORE_ERROR_FAILED, "the pipeline has no elements"));
+        }
+
+        let mut pipeline = match Pipeline::new(&self.name){
+            Some(pipeline) => pipeline,
+            None => return Err(builder_error(GST_CORE_ERROR_FAILED, &format!("couldn't create pipeline {}", self.name)))
+        };
+
+        let mut prev: Option<Element> = None;
+        for spec in self.elements{
+            let name = spec.name.as_ref().map(|name| name.as_str()).unwrap_or("");
+            let mut element = match Element::new(&spec.factory, name){
+                Some(element) => element,
+                None => return Err(builder_error(GST_CORE_ERROR_MISSING_PLUGIN,
+                    &format!("couldn't create element {}, is the plugin installed?", spec.factory)))
+            };
+            for (property, value) in spec.properties{
+                if let Err(err) = element.set_property(&property, &value){
+                    return Err(builder_error(GST_CORE_ERROR_FAILED, &err.message()));
+                }
+            }
+
+            let mut next = element.to_element();
+            if !pipeline.add(element){
+                return Err(builder_error(GST_CORE_ERROR_FAILED,
+                    &format!("couldn't add {} to {}", next.name(), self.name)));
+            }
+            if let Some(mut prev) = prev{
+                if !prev.link(&mut next){
+                    return Err(builder_error(GST_CORE_ERROR_NEGOTIATION,
+                        &format!("couldn't link {} to {}", prev.name(), next.name())));
+                }
+            }
+            prev = Some(next);
+        }
+
+        Ok(pipeline)
+    }
+
+    fn push(mut self, factory: &str) -> PipelineBuilder{
+        if self.has_sink{
+            self.fail(format!("{} added after the sink", factory));
+        }
+        self.elements.push(ElementSpec{
+            factory: factory.to_string(),
+            name: None,
+            properties: Vec::new(),
+        });
+        self
+    }
+
+    fn fail(&mut self, error: String){
+        if self.error.is_none(){
+            self.error = Some(error);
+        }
+    }
+}
+
+fn builder_error(code: u32, message: &str) -> Error{
+    unsafe{
+        Error::new(gst_core_error_quark(), code as i32, message)
+    }
+}
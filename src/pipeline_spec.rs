@@ -0,0 +1,227 @@
+use ffi::*;
+use util::*;
+use bin::BinT;
+use caps::Caps;
+use element::{Element, ElementT};
+use pipeline::Pipeline;
+use value::Value;
+use error::{Error, Result};
+
+use std::collections::BTreeMap;
+
+#[cfg(feature="serde")]
+use serde::{Serialize, Deserialize};
+
+/// The value of a property in a PipelineSpec. It's converted to the type
+/// of the property when set, strings are deserialized so enums and flags
+/// can be given by their nick and fractions or caps in their usual string
+/// format
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature="serde", derive(Serialize,Deserialize))]
+#[cfg_attr(feature="serde", serde(untagged))]
+pub enum SpecValue{
+    Bool(bool),
+    Int(i64),
+    Double(f64),
+    String(String),
+}
+
+impl SpecValue{
+    fn to_value(&self) -> Value{
+        match *self{
+            SpecValue::Bool(v) => Value::Bool(v),
+            SpecValue::Int(v) => Value::Int64(v),
+            SpecValue::Double(v) => Value::Double(v),
+            SpecValue::String(ref v) => Value::String(v.clone()),
+        }
+    }
+}
+
+/// An element of a PipelineSpec
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature="serde", derive(Serialize,Deserialize))]
+pub struct ElementSpec{
+    /// Name of the element in the pipeline, used in the links
+    pub name: String,
+    pub factory: String,
+    #[cfg_attr(feature="serde", serde(default))]
+    pub properties: BTreeMap<String, SpecValue>,
+}
+
+/// A link between two elements of a PipelineSpec. src and sink are the
+/// name of an element or element.pad to link a specific pad, as in
+/// gst-launch
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature="serde", derive(Serialize,Deserialize))]
+pub struct LinkSpec{
+    pub src: String,
+    pub sink: String,
+    /// Caps to restrict the format between both elements, as
+    /// `video/x-raw,width=640,height=480`
+    #[cfg_attr(feature="serde", serde(default))]
+    pub caps: Option<String>,
+}
+
+/// The topology of a pipeline as data, so it can be loaded from a
+/// configuration file with any serde format when the serde feature is
+/// enabled and changed without recompiling:
+///
+/// ```ignore
+/// name = "camera"
+///
+/// [[elements]]
+/// name = "src"
+/// factory = "v4l2src"
+/// properties = { device = "/dev/video0" }
+///
+/// [[elements]]
+/// name = "sink"
+/// factory = "autovideosink"
+///
+/// [[links]]
+/// src = "src"
+/// sink = "sink"
+/// caps = "video/x-raw,width=1280,height=720"
+/// ```
+///
+/// ```ignore
+/// let spec: PipelineSpec = toml::from_str(&config)?;
+/// let pipeline = spec.build()?;
+/// ```
+#[derive(Clone,Debug,Default,PartialEq)]
+#[cfg_attr(feature="serde", derive(Serialize,Deserialize))]
+pub struct PipelineSpec{
+    #[cfg_attr(feature="serde", serde(default))]
+    pub name: Option<String>,
+    pub elements: Vec<ElementSpec>,
+    #[cfg_attr(feature="serde", serde(default))]
+    pub links: Vec<LinkSpec>,
+}
+
+impl PipelineSpec{
+    /// Creates the pipeline with all the elements added, configured and
+    /// linked. Links from pads that don't exist yet, as the ones of
+    /// decodebin, are made when the pad is added.
+    ///
+    /// Returns an error naming the element, property or link that failed
+    pub fn build(&self) -> Result<Pipeline>{
+        let name = self.name.as_ref().map(|name| name.as_str()).unwrap_or("");
+        let mut pipeline = Pipeline::new(name)
+            .ok_or_else(|| spec_error(GST_CORE_ERROR_FAILED, "couldn't create pipeline".to_string()))?;
+        for spec in &self.elements{
+            let mut element = Element::new(&spec.factory, &spec.name)
+                .ok_or_else(|| spec_error(GST_CORE_ERROR_MISSING_PLUGIN,
+                    format!("couldn't create element {}, is the plugin installed?", spec.factory)))?;
+            for (property, value) in &spec.properties{
+                element.set_property(property, &value.to_value())
+                    .map_err(|err| spec_error(GST_CORE_ERROR_FAILED, err.message()))?;
+            }
+            if !pipeline.add(element){
+                return Err(spec_error(GST_CORE_ERROR_FAILED, format!("couldn't add {}, is the name repeated?", spec.name)));
+            }
+        }
+        for link in &self.links{
+            link.link(&pipeline)?;
+        }
+        Ok(pipeline)
+    }
+}
+
+impl LinkSpec{
+    fn link(&self, pipeline: &Pipeline) -> Result<()>{
+        let (src_name, src_pad) = split_pad(&self.src);
+        let (sink_name, sink_pad) = split_pad(&self.sink);
+        let mut src = pipeline.get_by_name(src_name)
+            .ok_or_else(|| spec_error(GST_CORE_ERROR_FAILED, format!("link from unknown element {}", src_name)))?;
+        let sink = pipeline.get_by_name(sink_name)
+            .ok_or_else(|| spec_error(GST_CORE_ERROR_FAILED, format!("link to unknown element {}", sink_name)))?;
+        let caps = match self.caps{
+            Some(ref caps) => Some(Caps::from_string(caps)
+                .ok_or_else(|| spec_error(GST_CORE_ERROR_FAILED, format!("invalid caps {}", caps)))?),
+            None => None
+        };
+        let link = DelayedLink{
+            sink: sink,
+            src_pad: src_pad.map(|pad| pad.to_string()),
+            sink_pad: sink_pad.map(|pad| pad.to_string()),
+            caps: caps,
+        };
+        unsafe{
+            if link.link(src.gst_element_mut()){
+                return Ok(());
+            }
+            if !has_sometimes_pads(src.gst_element()){
+                return Err(spec_error(GST_CORE_ERROR_NEGOTIATION, format!("couldn't link {} to {}", self.src, self.sink)));
+            }
+            connect_boxed(src.gst_element() as gpointer, "pad-added",
+                pad_added as extern "C" fn(*mut GstElement, *mut GstPad, gpointer) as gpointer,
+                Box::new(link));
+        }
+        Ok(())
+    }
+}
+
+// element.pad to (element, Some(pad)), element to (element, None)
+fn split_pad(name: &str) -> (&str, Option<&str>){
+    match name.find('.'){
+        Some(dot) => (&name[..dot], Some(&name[dot + 1..])),
+        None => (name, None)
+    }
+}
+
+// A link that can be retried when the source adds a pad
+struct DelayedLink{
+    sink: Element,
+    src_pad: Option<String>,
+    sink_pad: Option<String>,
+    caps: Option<Caps>,
+}
+
+impl DelayedLink{
+    unsafe fn link(&self, src: *mut GstElement) -> bool{
+        let src_pad = self.src_pad.as_ref().map(|pad| CString::new(pad.as_str()).unwrap());
+        let sink_pad = self.sink_pad.as_ref().map(|pad| CString::new(pad.as_str()).unwrap());
+        gst_element_link_pads_filtered(src,
+            src_pad.as_ref().map(|pad| pad.as_ptr()).unwrap_or(ptr::null()),
+            self.sink.gst_element() as *mut GstElement,
+            sink_pad.as_ref().map(|pad| pad.as_ptr()).unwrap_or(ptr::null()),
+            self.caps.as_ref().map(|caps| caps.gst_caps() as *mut GstCaps).unwrap_or(ptr::null_mut())) == 1
+    }
+}
+
+unsafe fn has_sometimes_pads(element: *const GstElement) -> bool{
+    let class = (*(element as *mut GTypeInstance)).g_class as *mut GstElementClass;
+    let mut templates = gst_element_class_get_pad_template_list(class);
+    while templates != ptr::null_mut(){
+        let template = (*templates).data as *mut GstPadTemplate;
+        if (*template).direction == GST_PAD_SRC && (*template).presence == GST_PAD_SOMETIMES{
+            return true;
+        }
+        templates = (*templates).next;
+    }
+    false
+}
+
+extern "C" fn pad_added(src: *mut GstElement, pad: *mut GstPad, data: gpointer){
+    unsafe{
+        let link = &*(data as *const DelayedLink);
+        if gst_pad_get_direction(pad) != GST_PAD_SRC || gst_pad_is_linked(pad) != 0{
+            return;
+        }
+        if let Some(ref name) = link.src_pad{
+            let pad_name = gst_object_get_name(pad as *mut GstObject);
+            let matches = from_c_str!(pad_name) == name.as_str();
+            g_free(pad_name as gpointer);
+            if !matches{
+                return;
+            }
+        }
+        link.link(src);
+    }
+}
+
+fn spec_error(code: u32, message: String) -> Error{
+    unsafe{
+        Error::new(gst_core_error_quark(), code as i32, &message)
+    }
+}
@@ -4,9 +4,64 @@ use pipeline::Pipeline;
 use pipeline::PipelineT;
 use element::Element;
 use element::ElementT;
-use std::ffi::CString;
+use taglist::TagList;
+use element_factory::ElementFactory;
+use device::Device;
+use format::ClockTimeDiff;
+use util::*;
 use ::Transfer;
 
+use std::os::raw::c_int;
+
+// Values of the GstPlayFlags enum in playbin, which isn't in the headers
+const GST_PLAY_FLAG_VIDEO: i32 = 1 << 0;
+const GST_PLAY_FLAG_AUDIO: i32 = 1 << 1;
+const GST_PLAY_FLAG_TEXT: i32 = 1 << 2;
+const GST_PLAY_FLAG_VIS: i32 = 1 << 3;
+const GST_PLAY_FLAG_SOFT_VOLUME: i32 = 1 << 4;
+const GST_PLAY_FLAG_NATIVE_AUDIO: i32 = 1 << 5;
+const GST_PLAY_FLAG_NATIVE_VIDEO: i32 = 1 << 6;
+const GST_PLAY_FLAG_DOWNLOAD: i32 = 1 << 7;
+const GST_PLAY_FLAG_BUFFERING: i32 = 1 << 8;
+const GST_PLAY_FLAG_DEINTERLACE: i32 = 1 << 9;
+const GST_PLAY_FLAG_SOFT_COLORBALANCE: i32 = 1 << 10;
+const GST_PLAY_FLAG_FORCE_FILTERS: i32 = 1 << 11;
+const GST_PLAY_FLAG_FORCE_SW_DECODERS: i32 = 1 << 12;
+
+bitflags!{
+    /// The flags property of playbin, see PlayBin::set_play_flags
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct PlayFlags: u32{
+        /// Renders the video stream
+        const VIDEO = GST_PLAY_FLAG_VIDEO as u32;
+        /// Renders the audio stream
+        const AUDIO = GST_PLAY_FLAG_AUDIO as u32;
+        /// Renders the subtitles
+        const TEXT = GST_PLAY_FLAG_TEXT as u32;
+        /// Renders a visualization for audio only streams
+        const VIS = GST_PLAY_FLAG_VIS as u32;
+        /// Applies the volume in software instead of in the sink
+        const SOFT_VOLUME = GST_PLAY_FLAG_SOFT_VOLUME as u32;
+        /// Only uses native audio formats, without conversion
+        const NATIVE_AUDIO = GST_PLAY_FLAG_NATIVE_AUDIO as u32;
+        /// Only uses native video formats, without conversion
+        const NATIVE_VIDEO = GST_PLAY_FLAG_NATIVE_VIDEO as u32;
+        /// Downloads progressive streams to disk while playing, so the
+        /// downloaded parts can be played again without the network
+        const DOWNLOAD = GST_PLAY_FLAG_DOWNLOAD as u32;
+        /// Buffers the demuxed streams, to play network streams smoothly
+        const BUFFERING = GST_PLAY_FLAG_BUFFERING as u32;
+        /// Deinterlaces interlaced video
+        const DEINTERLACE = GST_PLAY_FLAG_DEINTERLACE as u32;
+        /// Applies color balance in software if the sink doesn't
+        const SOFT_COLORBALANCE = GST_PLAY_FLAG_SOFT_COLORBALANCE as u32;
+        /// Applies the audio and video filters even to native formats
+        const FORCE_FILTERS = GST_PLAY_FLAG_FORCE_FILTERS as u32;
+        /// Only uses software decoders
+        const FORCE_SW_DECODERS = GST_PLAY_FLAG_FORCE_SW_DECODERS as u32;
+    }
+}
+
 unsafe impl Sync for PlayBin {}
 unsafe impl Send for PlayBin {}
 
@@ -32,6 +87,21 @@ impl PlayBin{
         self.set("audio-sink", unsafe{ audio_sink.gst_element() });
     }
     
+    /// Plays the audio through one of the devices returned by
+    /// AudioDevices::outputs(). Has to be called before the pipeline goes
+    /// to PAUSED.
+    ///
+    /// Returns false if no sink could be created for the device
+    pub fn set_audio_output(&self, device: &Device) -> bool{
+        match device.create_element(""){
+            Some(sink) => {
+                self.set_audio_sink(&sink);
+                true
+            }
+            None => false
+        }
+    }
+    
     /*pub fn frame(&self) -> GBuffer{
         GBuffer::new(playbin.get<GstBuffer*>("frame"))
     }*/
@@ -44,10 +114,83 @@ impl PlayBin{
         self.set("video-sink", unsafe{ video_sink.gst_element() });
     }
     
+    /// Sets the visualization element used for audio only streams, it's
+    /// only shown if the VIS flag is set, see set_visualization_enabled
     pub fn set_vis_plugin(&self, vis_plugin: &ElementT){
-        self.set("vis-plugin", vis_plugin);
+        self.set("vis-plugin", unsafe{ vis_plugin.gst_element() });
+    }
+
+    /// Returns the factories of all the visualization elements available
+    /// in the registry, like goom or wavescope
+    pub fn visualizers() -> Vec<ElementFactory>{
+        ElementFactory::list_by_klass("Visualization")
+    }
+
+    /// Creates the visualization element from the factory with the given
+    /// name, sets it as vis-plugin and enables visualizations.
+    ///
+    /// Returns false if the element couldn't be created
+    pub fn set_visualizer(&self, factory_name: &str) -> bool{
+        match Element::new(factory_name, ""){
+            Some(vis_plugin) => {
+                self.set_vis_plugin(&vis_plugin);
+                self.set_visualization_enabled(true);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Enables or disables showing a visualization for audio only
+    /// streams by toggling the VIS playbin flag
+    pub fn set_visualization_enabled(&self, enabled: bool){
+        self.set_play_flag(PlayFlags::VIS, enabled);
     }
     
+    /// Keeps the pitch of the audio natural when playing at rates other
+    /// than 1.0 with set_speed, by using scaletempo as audio-filter.
+    /// Has to be called before the pipeline goes to PAUSED.
+    ///
+    /// Returns false if scaletempo is not available
+    pub fn set_preserve_pitch(&self, preserve: bool) -> bool{
+        if preserve{
+            match Element::new("scaletempo", ""){
+                Some(scaletempo) => {
+                    self.set_audio_filter(&scaletempo);
+                    true
+                }
+                None => false
+            }
+        }else{
+            self.unset_audio_filter();
+            true
+        }
+    }
+
+    /// Inserts filter in the audio path right before the audio sink, it
+    /// can be a single element or a bin with ghost pads as created by
+    /// Bin::new_from_description. Has to be set before the pipeline goes
+    /// to PAUSED.
+    pub fn set_audio_filter(&self, filter: &ElementT){
+        self.set("audio-filter", unsafe{ filter.gst_element() });
+    }
+
+    pub fn unset_audio_filter(&self){
+        self.set("audio-filter", ptr::null::<GstElement>());
+    }
+
+    /// Inserts filter in the video path right before the video sink, it
+    /// can be a single element or a bin with ghost pads as created by
+    /// Bin::new_from_description. Has to be set before the pipeline goes
+    /// to PAUSED.
+    pub fn set_video_filter(&self, filter: &ElementT){
+        self.set("video-filter", unsafe{ filter.gst_element() });
+    }
+
+    pub fn unset_video_filter(&self){
+        self.set("video-filter", ptr::null::<GstElement>());
+    }
+
     pub fn set_volume(&self, volume: f64){
         self.set("volume", volume);
     }
@@ -56,13 +199,41 @@ impl PlayBin{
         self.set("connection-speed",connection_speed);
     }
     
-    pub fn set_av_offset(&self, av_offset: i64){
-        self.set("av-offset", av_offset);
+    /// Delays the audio relative to the video by offset, negative values
+    /// make the audio play earlier. Can be changed while playing to fix
+    /// lipsync, e.g. for bluetooth headphones.
+    pub fn set_av_offset<T: Into<ClockTimeDiff>>(&self, av_offset: T){
+        self.set("av-offset", av_offset.into().ns());
+    }
+
+    pub fn av_offset(&self) -> ClockTimeDiff{
+        unsafe{
+            ClockTimeDiff(self.get::<i64>("av-offset"))
+        }
     }
     
+    /// Duration in nanoseconds of the data buffered for network streams,
+    /// -1 for the default
     pub fn set_buffer_duration(&self, buffer_duration: i64){
         self.set("buffer-duration",buffer_duration);
     }
+
+    pub fn buffer_duration(&self) -> i64{
+        unsafe{
+            self.get::<i64>("buffer-duration")
+        }
+    }
+
+    /// Bytes of data buffered for network streams, -1 for the default
+    pub fn set_buffer_size(&self, buffer_size: i32){
+        self.set("buffer-size", buffer_size);
+    }
+
+    pub fn buffer_size(&self) -> i32{
+        unsafe{
+            self.get::<c_int>("buffer-size")
+        }
+    }
     
     pub fn set_current_audio(&self, current_audio: i32){
         self.set("current-audio",current_audio);
@@ -84,9 +255,18 @@ impl PlayBin{
         self.set("mute", 0 as gboolean);
     }
     
+    /// With the DOWNLOAD flag, downloads to a ring buffer of this size in
+    /// bytes instead of the whole stream to a file, so long streams don't
+    /// fill the disk. 0 downloads the whole stream
     pub fn set_ring_buffer_max_size(&self, ring_buffer_max_size: u64){
         self.set("ring-buffer-max-size", ring_buffer_max_size);
     }
+
+    pub fn ring_buffer_max_size(&self) -> u64{
+        unsafe{
+            self.get::<u64>("ring-buffer-max-size")
+        }
+    }
     
     pub fn set_source(&self, source: &ElementT){
         self.set("source", unsafe{ source.gst_element() });
@@ -96,9 +276,58 @@ impl PlayBin{
         self.set("subtitle-encoding", to_c_str!(encoding));
     }
     
+    /// Sets the uri of an external subtitle file, it has to be set
+    /// before the pipeline goes to PAUSED or after setting a new uri
     pub fn set_suburi(&self, suburi: &str){
         self.set("suburi", to_c_str!(suburi));
     }
+
+    pub fn suburi(&self) -> Option<String>{
+        unsafe{
+            let c_str = self.get::<*mut gchar>("suburi");
+            if c_str != ptr::null_mut(){
+                let suburi = from_c_str!(c_str).to_string();
+                g_free(c_str as gpointer);
+                Some(suburi)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Number of subtitle streams, including the one in suburi
+    pub fn n_text(&self) -> i32{
+        unsafe{
+            self.get::<c_int>("n-text")
+        }
+    }
+
+    /// Index of the subtitle stream being rendered or -1 if none
+    pub fn current_text(&self) -> i32{
+        unsafe{
+            self.get::<c_int>("current-text")
+        }
+    }
+
+    /// Tags of the subtitle stream at index, usually containing its
+    /// language
+    pub fn text_tags(&self, index: i32) -> Option<TagList>{
+        unsafe{
+            let mut tags: *mut GstTagList = ptr::null_mut();
+            g_signal_emit_by_name(self.gst_element() as gpointer, to_c_str!("get-text-tags"), index as c_int, &mut tags);
+            TagList::new_from_gst_taglist(tags, true)
+        }
+    }
+
+    /// Enables or disables rendering of subtitles by toggling the TEXT
+    /// playbin flag
+    pub fn set_subtitles_visible(&self, visible: bool){
+        self.set_play_flag(PlayFlags::TEXT, visible);
+    }
+
+    pub fn subtitles_visible(&self) -> bool{
+        self.play_flags().contains(PlayFlags::TEXT)
+    }
     
     pub fn set_text_sink(&self, textsink: &ElementT){
         self.set("text-sink", unsafe{ textsink.gst_element() });
@@ -120,9 +349,33 @@ impl PlayBin{
         self.set("vide-stream-combiner", unsafe{ video_stream_combiner.gst_element() });
     }
     
+    /// Sets the flags as a raw integer, see set_play_flags
     pub fn set_flags(&self, flags: i32){
         self.set("flags", flags);
     }
+
+    pub fn flags(&self) -> i32{
+        unsafe{
+            self.get::<c_int>("flags")
+        }
+    }
+
+    /// Replaces all the flags, most of them only take effect before the
+    /// pipeline goes to PAUSED
+    pub fn set_play_flags(&self, flags: PlayFlags){
+        self.set_flags(flags.bits() as i32);
+    }
+
+    pub fn play_flags(&self) -> PlayFlags{
+        PlayFlags::from_bits_retain(self.flags() as u32)
+    }
+
+    /// Enables or disables some flags keeping the others
+    pub fn set_play_flag(&self, flag: PlayFlags, enabled: bool){
+        let mut flags = self.play_flags();
+        flags.set(flag, enabled);
+        self.set_play_flags(flags);
+    }
 }
 
 impl PipelineT for PlayBin{    
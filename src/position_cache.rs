@@ -0,0 +1,188 @@
+use ffi::*;
+use element::{Element, ElementT, PositionError, position_pct};
+use util::*;
+
+use std::cell::Cell;
+use std::result;
+use std::os::raw::c_void;
+use std::time::Duration;
+
+#[derive(Clone,Copy)]
+struct LastPosition{
+    position: i64,
+    time: GstClockTime,
+}
+
+/// Wraps an element, usually a pipeline or playbin, limiting how often
+/// the position is actually queried.
+///
+/// Between queries the position is interpolated from the element clock
+/// and the playback rate, so calling position_ns() on every frame of a UI
+/// only queries the pipeline once per interval. The rate is queried along
+/// with the position, so it follows seeks done by other components. Seeks
+/// and speed changes done through the wrapper invalidate the cached
+/// position, call invalidate() when seeking or flushing the element by
+/// other means.
+pub struct CachedPosition<E: ElementT>{
+    element: E,
+    interval: GstClockTime,
+    rate: Cell<f64>,
+    last: Cell<Option<LastPosition>>,
+}
+
+impl<E: ElementT> CachedPosition<E>{
+    /// Creates a new cached position wrapper that queries the element at
+    /// most once every interval
+    pub fn new(element: E, interval: Duration) -> CachedPosition<E>{
+        CachedPosition{
+            element: element,
+            interval: interval.as_secs() * 1000000000 + interval.subsec_nanos() as u64,
+            rate: Cell::new(1.0),
+            last: Cell::new(None),
+        }
+    }
+
+    pub fn set_interval(&mut self, interval: Duration){
+        self.interval = interval.as_secs() * 1000000000 + interval.subsec_nanos() as u64;
+    }
+
+    /// Sets the playback rate used to interpolate the position until the
+    /// next query, which takes it from the sinks again
+    pub fn set_rate(&mut self, rate: f64){
+        self.rate.set(rate);
+        self.invalidate();
+    }
+
+    pub fn rate(&self) -> f64{
+        self.rate.get()
+    }
+
+    /// Forces the next position query to go to the element
+    pub fn invalidate(&self){
+        self.last.set(None);
+    }
+
+    pub fn inner(&self) -> &E{
+        &self.element
+    }
+
+    pub fn inner_mut(&mut self) -> &mut E{
+        &mut self.element
+    }
+
+    pub fn into_inner(self) -> E{
+        self.element
+    }
+
+    fn now(&self) -> GstClockTime{
+        unsafe{
+            let clock = gst_element_get_clock(self.element.gst_element() as *mut GstElement);
+            if clock != ptr::null_mut(){
+                let now = gst_clock_get_time(clock);
+                gst_object_unref(clock as *mut c_void);
+                now
+            }else{
+                gst_util_get_timestamp()
+            }
+        }
+    }
+
+    fn is_running(&self) -> bool{
+        match self.element.get_state(0){
+            (GST_STATE_PLAYING, GST_STATE_VOID_PENDING, _) => true,
+            _ => false
+        }
+    }
+}
+
+impl<E: ElementT> ElementT for CachedPosition<E>{
+    fn as_element(&self) -> &Element{
+        self.element.as_element()
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        self.element.as_element_mut()
+    }
+
+    fn position_ns(&self) -> Option<i64>{
+        let now = self.now();
+        if let Some(last) = self.last.get(){
+            if now >= last.time && now - last.time < self.interval{
+                if self.is_running(){
+                    return Some(last.position + ((now - last.time) as f64 * self.rate.get()) as i64);
+                }else{
+                    return Some(last.position);
+                }
+            }
+        }
+        let position = self.element.position_ns();
+        if let Some(speed) = self.element.speed(){
+            let applied_rate = self.element.applied_rate().unwrap_or(1.0);
+            self.rate.set(speed * applied_rate);
+        }
+        self.last.set(position.map(|position| LastPosition{ position: position, time: now }));
+        position
+    }
+
+    fn position_s(&self) -> Option<f64>{
+        self.position_ns().map(|ns| ns_to_s(ns as u64))
+    }
+
+    fn position_pct(&self) -> result::Result<f64, PositionError>{
+        position_pct(self.position_ns(), self.duration_ns())
+    }
+
+    unsafe fn send_event(&mut self, event: *mut GstEvent) -> bool{
+        self.invalidate();
+        self.element.send_event(event)
+    }
+
+    fn flush(&mut self, timeout: GstClockTime) -> bool{
+        self.invalidate();
+        self.element.flush(timeout)
+    }
+
+    fn seek_simple(&mut self, format: GstFormat, flags: GstSeekFlags, pos: i64) -> bool{
+        self.invalidate();
+        self.element.seek_simple(format, flags, pos)
+    }
+
+    fn seek(&mut self, rate: f64, format: GstFormat, flags: GstSeekFlags, start_type: GstSeekType, start: i64, stop_type: GstSeekType, stop: i64) -> bool{
+        self.invalidate();
+        let ret = self.element.seek(rate, format, flags, start_type, start, stop_type, stop);
+        if ret{
+            self.rate.set(rate);
+        }
+        ret
+    }
+
+    fn set_position_ns(&mut self, ns: i64) -> bool{
+        self.invalidate();
+        self.element.set_position_ns(ns)
+    }
+
+    fn set_position_s(&mut self, s: f64) -> bool{
+        self.invalidate();
+        self.element.set_position_s(s)
+    }
+
+    fn set_position_pct(&mut self, pct: f64) -> result::Result<(), PositionError>{
+        self.invalidate();
+        self.element.set_position_pct(pct)
+    }
+
+    fn set_speed(&mut self, speed: f64) -> bool{
+        self.invalidate();
+        let ret = self.element.set_speed(speed);
+        if ret && speed != 0.0{
+            self.rate.set(speed);
+        }
+        ret
+    }
+}
+
+impl<E: ElementT> ::Transfer for CachedPosition<E>{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.element.transfer()
+    }
+}
@@ -0,0 +1,208 @@
+use ffi::*;
+use util::*;
+use element::ElementT;
+use value::{Value, G_TYPE_BOOLEAN, G_TYPE_INT, G_TYPE_UINT, G_TYPE_INT64, G_TYPE_UINT64, G_TYPE_FLOAT,
+    G_TYPE_DOUBLE, G_TYPE_STRING};
+use error::{Error, Result};
+
+/// A struct whose fields are properties of an element, implemented by
+/// the gst_properties! macro
+pub trait Properties: Sized{
+    /// Sets every field as a property of element. Fails without setting
+    /// anything if a property doesn't exist, isn't writable or can't be
+    /// set from the type of its field
+    fn apply_to(&self, element: &ElementT) -> Result<()>;
+
+    /// Reads every field from the properties of element. Fails if a
+    /// property doesn't exist, isn't readable or can't be converted to the
+    /// type of its field
+    fn read_from(element: &ElementT) -> Result<Self>;
+}
+
+/// Types that can be used as fields of a gst_properties! struct
+pub trait PropertyValue: Sized{
+    /// The GType the field is converted from or to
+    fn gtype() -> GType;
+    fn to_value(&self) -> Value;
+    fn from_value(value: Value) -> Option<Self>;
+}
+
+macro_rules! property_value{
+    ($ty: ty, $gtype: expr, $variant: ident) => (
+        impl PropertyValue for $ty{
+            fn gtype() -> GType{
+                $gtype
+            }
+
+            fn to_value(&self) -> Value{
+                Value::$variant(*self)
+            }
+
+            fn from_value(value: Value) -> Option<$ty>{
+                match value{
+                    Value::$variant(v) => Some(v),
+                    _ => None
+                }
+            }
+        }
+    );
+}
+
+property_value!(bool, G_TYPE_BOOLEAN, Bool);
+property_value!(i32, G_TYPE_INT, Int);
+property_value!(u32, G_TYPE_UINT, UInt);
+property_value!(i64, G_TYPE_INT64, Int64);
+property_value!(u64, G_TYPE_UINT64, UInt64);
+property_value!(f32, G_TYPE_FLOAT, Float);
+property_value!(f64, G_TYPE_DOUBLE, Double);
+
+/// Strings can be used for any property that can be serialized, values
+/// are read and written in the format of gst-launch, enums and flags by
+/// their nick
+impl PropertyValue for String{
+    fn gtype() -> GType{
+        G_TYPE_STRING
+    }
+
+    fn to_value(&self) -> Value{
+        Value::String(self.clone())
+    }
+
+    fn from_value(value: Value) -> Option<String>{
+        match value{
+            Value::String(v) => Some(v),
+            _ => None
+        }
+    }
+}
+
+fn property_error(element: &ElementT, message: String) -> Error{
+    Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_FAILED as i32,
+        &format!("{}: {}", element.name(), message))
+}
+
+/// Checks that element has a property name that can be written, if
+/// write, or read from a field of type T. Used by gst_properties!
+#[doc(hidden)]
+pub fn check_property<T: PropertyValue>(element: &ElementT, name: &str, write: bool) -> Result<()>{
+    unsafe{
+        let pspec = element.as_element().find_property(name);
+        if pspec == ptr::null_mut(){
+            return Err(property_error(element, format!("no property {}", name)));
+        }
+        let flags = (*pspec).flags as i32;
+        let (flag, src, dest) = if write{
+            (G_PARAM_WRITABLE, T::gtype(), (*pspec).value_type)
+        }else{
+            (G_PARAM_READABLE, (*pspec).value_type, T::gtype())
+        };
+        if flags & flag == 0{
+            return Err(property_error(element, format!("property {} isn't {}", name,
+                if write{ "writable" }else{ "readable" })));
+        }
+        // strings are deserialized instead of transformed when writing,
+        // every type that can be serialized can be transformed to a string
+        if !(write && T::gtype() == G_TYPE_STRING) && g_value_type_transformable(src, dest) == 0{
+            return Err(property_error(element, format!("property {} of type {} can't be converted {} {}", name,
+                from_c_str!(g_type_name((*pspec).value_type)), if write{ "from" }else{ "to" },
+                from_c_str!(g_type_name(T::gtype())))));
+        }
+        Ok(())
+    }
+}
+
+/// Sets the property name of element to value. Used by gst_properties!
+#[doc(hidden)]
+pub fn write_property<T: PropertyValue>(element: &ElementT, name: &str, value: &T) -> Result<()>{
+    element.to_element().set_property(name, &value.to_value())
+}
+
+/// Reads the property name of element into a T. Used by gst_properties!
+#[doc(hidden)]
+pub fn read_property<T: PropertyValue>(element: &ElementT, name: &str) -> Result<T>{
+    let value = unsafe{
+        let pspec = element.as_element().find_property(name);
+        if pspec == ptr::null_mut(){
+            return Err(property_error(element, format!("no property {}", name)));
+        }
+        let mut gvalue: GValue = mem::zeroed();
+        g_value_init(&mut gvalue, (*pspec).value_type);
+        g_object_get_property(element.gst_element() as *mut GObject, to_c_str!(name), &mut gvalue);
+        let value = if T::gtype() == G_TYPE_STRING && (*pspec).value_type != G_TYPE_STRING{
+            // serialized as in gst-launch, enums and flags by their nick
+            let serialized = gst_value_serialize(&gvalue);
+            if serialized != ptr::null_mut(){
+                let value = Value::String(from_c_str!(serialized).to_string());
+                g_free(serialized as gpointer);
+                Some(value)
+            }else{
+                None
+            }
+        }else{
+            let mut converted: GValue = mem::zeroed();
+            g_value_init(&mut converted, T::gtype());
+            let value = if g_value_transform(&gvalue, &mut converted) == 1{
+                Value::from_gvalue(&converted)
+            }else{
+                None
+            };
+            g_value_unset(&mut converted);
+            value
+        };
+        g_value_unset(&mut gvalue);
+        value
+    };
+    value.and_then(T::from_value)
+        .ok_or_else(|| property_error(element, format!("couldn't read property {}", name)))
+}
+
+/// Declares a struct whose fields are properties of an element and
+/// implements Properties for it, to keep the configuration of encoders or
+/// sources in a typed struct instead of setting properties by name:
+///
+/// ```ignore
+/// gst_properties!{
+///     #[derive(Clone, Debug)]
+///     pub struct X264Settings{
+///         pub bitrate: u32,
+///         pub tune: String,
+///         pub key_int_max: u32 => "key-int-max",
+///     }
+/// }
+///
+/// let settings = X264Settings{ bitrate: 2048, tune: "zerolatency".to_string(), key_int_max: 60 };
+/// settings.apply_to(&encoder)?;
+/// ```
+///
+/// The property of each field is its name, underscores work as dashes,
+/// or the name after =>. Fields can be bool, i32, u32, i64, u64, f32, f64
+/// or String. Names and types are checked against the element when the
+/// struct is applied or read, before setting or reading any property.
+#[macro_export]
+macro_rules! gst_properties{
+    (@name $field: ident) => (stringify!($field));
+    (@name $field: ident $property: expr) => ($property);
+    ($(#[$attr: meta])* $vis: vis struct $name: ident{
+        $($(#[$field_attr: meta])* $field_vis: vis $field: ident: $ty: ty $(=> $property: expr)*),+ $(,)*
+    }) => (
+        $(#[$attr])*
+        $vis struct $name{
+            $($(#[$field_attr])* $field_vis $field: $ty),+
+        }
+
+        impl $crate::Properties for $name{
+            fn apply_to(&self, element: &$crate::ElementT) -> $crate::Result<()>{
+                $($crate::check_property::<$ty>(element, $crate::gst_properties!(@name $field $($property)*), true)?;)+
+                $($crate::write_property(element, $crate::gst_properties!(@name $field $($property)*), &self.$field)?;)+
+                Ok(())
+            }
+
+            fn read_from(element: &$crate::ElementT) -> $crate::Result<$name>{
+                $($crate::check_property::<$ty>(element, $crate::gst_properties!(@name $field $($property)*), false)?;)+
+                Ok($name{
+                    $($field: $crate::read_property(element, $crate::gst_properties!(@name $field $($property)*))?),+
+                })
+            }
+        }
+    );
+}
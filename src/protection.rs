@@ -0,0 +1,211 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use caps::Caps;
+use structure::Structure;
+
+// The protection API was added in GStreamer 1.6, after ffi.rs was
+// generated
+pub const GST_EVENT_PROTECTION: GstEventType = (58 << 8) | GST_EVENT_TYPE_DOWNSTREAM |
+    GST_EVENT_TYPE_SERIALIZED | GST_EVENT_TYPE_STICKY | GST_EVENT_TYPE_STICKY_MULTI;
+
+#[repr(C)]
+pub struct GstProtectionMeta{
+    pub meta: GstMeta,
+    pub info: *mut GstStructure,
+}
+
+extern "C"{
+    fn gst_protection_meta_api_get_type() -> GType;
+    fn gst_buffer_add_protection_meta(buffer: *mut GstBuffer, info: *mut GstStructure) -> *mut GstProtectionMeta;
+    fn gst_protection_select_system(system_identifiers: *mut *const gchar) -> *const gchar;
+    fn gst_event_new_protection(system_id: *const gchar, data: *mut GstBuffer, origin: *const gchar) -> *mut GstEvent;
+    fn gst_event_parse_protection(event: *mut GstEvent, system_id: *mut *const gchar, data: *mut *mut GstBuffer,
+        origin: *mut *const gchar);
+}
+
+/// UUIDs of the common DRM systems, as used in PSSH boxes, protection
+/// events and the protection-system caps field
+pub const CLEARKEY_SYSTEM_ID: &'static str = "1077efec-c0b2-4d02-ace3-3c1e52e2fb4b";
+pub const WIDEVINE_SYSTEM_ID: &'static str = "edef8ba9-79d6-4ace-a3c8-27dcd51d21ed";
+pub const PLAYREADY_SYSTEM_ID: &'static str = "9a04f079-9840-4286-ab92-e65be0885f95";
+pub const FAIRPLAY_SYSTEM_ID: &'static str = "94ce86fb-07ff-4f43-adb8-93d2fa968ca2";
+pub const MARLIN_SYSTEM_ID: &'static str = "5e629af5-38da-4063-8977-97ffbd9902d4";
+
+/// Name of the caps field with the system id of encrypted streams, whose
+/// caps are application/x-cenc with the original caps in original-media-type
+pub const PROTECTION_SYSTEM_ID_CAPS_FIELD: &'static str = "protection-system";
+
+/// Returns the first of the system ids for which a decryptor element is
+/// installed
+pub fn select_protection_system(system_ids: &[&str]) -> Option<String>{
+    let ids: Vec<CString> = system_ids.iter().map(|id| CString::new(*id).unwrap()).collect();
+    let mut ptrs: Vec<*const gchar> = ids.iter().map(|id| id.as_ptr()).collect();
+    ptrs.push(ptr::null());
+    unsafe{
+        let selected = gst_protection_select_system(ptrs.as_mut_ptr());
+        if selected != ptr::null(){
+            Some(from_c_str!(selected).to_string())
+        }else{
+            None
+        }
+    }
+}
+
+/// Returns the DRM system id of an encrypted stream from its caps
+pub fn protection_system_id(caps: &Caps) -> Option<String>{
+    match caps.structure(0)?.get(PROTECTION_SYSTEM_ID_CAPS_FIELD)?{
+        ::Value::String(id) => Some(id),
+        _ => None
+    }
+}
+
+/// The DRM initialization data found by a demuxer, usually the contents
+/// of a PSSH box, that has to be passed to the CDM of the system to get
+/// the decryption keys
+#[derive(Clone,Debug,PartialEq)]
+pub struct ProtectionEvent{
+    pub system_id: String,
+    pub data: Vec<u8>,
+    /// Where the data was found, as in "isobmff/moov" or "dash/mpd"
+    pub origin: Option<String>,
+}
+
+impl ProtectionEvent{
+    /// Parses a PROTECTION event, returns None for any other event type
+    pub unsafe fn parse(event: *mut GstEvent) -> Option<ProtectionEvent>{
+        if event == ptr::null_mut() || (*event)._type != GST_EVENT_PROTECTION{
+            return None;
+        }
+        let mut system_id = ptr::null();
+        let mut data = ptr::null_mut();
+        let mut origin = ptr::null();
+        gst_event_parse_protection(event, &mut system_id, &mut data, &mut origin);
+        if system_id == ptr::null(){
+            return None;
+        }
+        let data = Buffer::new(data, false)
+            .and_then(|buffer| buffer.map_read(|mapinfo| mapinfo.data::<u8>().to_vec()).ok())
+            .unwrap_or_default();
+        Some(ProtectionEvent{
+            system_id: from_c_str!(system_id).to_string(),
+            data: data,
+            origin: if origin != ptr::null(){ Some(from_c_str!(origin).to_string()) } else { None },
+        })
+    }
+
+    /// Creates a PROTECTION event to be sent downstream to a decryptor
+    pub fn to_event(&self) -> *mut GstEvent{
+        unsafe{
+            let data = gst_buffer_new_allocate(ptr::null_mut(), self.data.len() as gsize, ptr::null_mut());
+            gst_buffer_fill(data, 0, self.data.as_ptr() as gconstpointer, self.data.len() as gsize);
+            let origin = self.origin.as_ref().map(|origin| CString::new(origin.as_str()).unwrap());
+            let event = gst_event_new_protection(to_c_str!(self.system_id.as_str()), data,
+                origin.as_ref().map(|origin| origin.as_ptr()).unwrap_or(ptr::null()));
+            gst_mini_object_unref(data as *mut GstMiniObject);
+            event
+        }
+    }
+}
+
+/// The encryption parameters of a sample as attached by demuxers to
+/// encrypted buffers, read by decryptors
+pub struct ProtectionInfo{
+    info: Structure
+}
+
+impl ProtectionInfo{
+    /// Wraps the structure of a protection meta, usually named
+    /// application/x-cenc
+    pub fn new(info: Structure) -> ProtectionInfo{
+        ProtectionInfo{ info: info }
+    }
+
+    /// If the sample is encrypted at all
+    pub fn is_encrypted(&self) -> bool{
+        match self.info.get("encrypted"){
+            Some(::Value::Bool(encrypted)) => encrypted,
+            _ => false
+        }
+    }
+
+    /// Id of the key needed to decrypt the sample
+    pub fn key_id(&self) -> Option<Vec<u8>>{
+        self.buffer_field("kid")
+    }
+
+    /// Initialization vector of the sample
+    pub fn iv(&self) -> Option<Vec<u8>>{
+        self.buffer_field("iv")
+    }
+
+    /// Encryption scheme as in "cenc" or "cbcs"
+    pub fn cipher_mode(&self) -> Option<String>{
+        match self.info.get("cipher-mode"){
+            Some(::Value::String(mode)) => Some(mode),
+            _ => None
+        }
+    }
+
+    /// Subsamples as (clear bytes, encrypted bytes) pairs, empty if the
+    /// whole sample is encrypted
+    pub fn subsamples(&self) -> Vec<(u16, u32)>{
+        let data = self.buffer_field("subsamples").unwrap_or_default();
+        data.chunks(6).filter(|chunk| chunk.len() == 6).map(|chunk| {
+            let clear = (chunk[0] as u16) << 8 | chunk[1] as u16;
+            let encrypted = (chunk[2] as u32) << 24 | (chunk[3] as u32) << 16 | (chunk[4] as u32) << 8 | chunk[5] as u32;
+            (clear, encrypted)
+        }).collect()
+    }
+
+    pub fn structure(&self) -> &Structure{
+        &self.info
+    }
+
+    pub fn into_structure(self) -> Structure{
+        self.info
+    }
+
+    fn buffer_field(&self, field: &str) -> Option<Vec<u8>>{
+        unsafe{
+            let value = gst_structure_get_value(self.info.gst_structure(), to_c_str!(field));
+            if value == ptr::null() || (*value).g_type != gst_buffer_get_type(){
+                return None;
+            }
+            let buffer = g_value_get_boxed(value) as *mut GstBuffer;
+            if buffer == ptr::null_mut(){
+                return None;
+            }
+            let mut mapinfo = ::MapInfo::new();
+            if gst_buffer_map(buffer, &mut mapinfo, GST_MAP_READ) != 0{
+                let data = mapinfo.data::<u8>().to_vec();
+                gst_buffer_unmap(buffer, &mut mapinfo);
+                Some(data)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+impl Buffer{
+    /// Returns the encryption parameters of this buffer if it's encrypted
+    pub fn protection_info(&self) -> Option<ProtectionInfo>{
+        unsafe{
+            let meta = gst_buffer_get_meta(self.gst_buffer() as *mut GstBuffer,
+                gst_protection_meta_api_get_type()) as *mut GstProtectionMeta;
+            if meta != ptr::null_mut() && (*meta).info != ptr::null_mut(){
+                Structure::new_from_gst_structure(gst_structure_copy((*meta).info)).map(ProtectionInfo::new)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Attaches encryption parameters to this buffer
+    pub fn add_protection_info(&mut self, info: ProtectionInfo){
+        unsafe{
+            gst_buffer_add_protection_meta(self.gst_buffer_mut(), ::Transfer::transfer(info.info));
+        }
+    }
+}
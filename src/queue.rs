@@ -0,0 +1,317 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+/// A lock-free queue of values sent between threads, as between a
+/// streaming thread and the application, without the allocation of a
+/// node per value of std channels. It doesn't block, pop returns None
+/// when empty.
+pub struct AtomicQueue<T: Send>{
+    queue: *mut GstAtomicQueue,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Sync for AtomicQueue<T> {}
+unsafe impl<T: Send> Send for AtomicQueue<T> {}
+
+impl<T: Send> AtomicQueue<T>{
+    /// Creates a queue with room for initial_size values before it grows
+    pub fn new(initial_size: u32) -> AtomicQueue<T>{
+        unsafe{
+            AtomicQueue{ queue: gst_atomic_queue_new(initial_size), _marker: PhantomData }
+        }
+    }
+
+    pub fn push(&self, value: T){
+        unsafe{
+            gst_atomic_queue_push(self.queue, Box::into_raw(Box::new(value)) as gpointer);
+        }
+    }
+
+    /// Removes the oldest value, None if the queue is empty
+    pub fn pop(&self) -> Option<T>{
+        unsafe{
+            let value = gst_atomic_queue_pop(self.queue);
+            if value != ptr::null_mut(){
+                Some(*Box::from_raw(value as *mut T))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize{
+        unsafe{
+            gst_atomic_queue_length(self.queue) as usize
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+}
+
+impl<T: Send> Drop for AtomicQueue<T>{
+    fn drop(&mut self){
+        while self.pop().is_some(){}
+        unsafe{
+            gst_atomic_queue_unref(self.queue);
+        }
+    }
+}
+
+pub enum GstDataQueue{}
+
+#[repr(C)]
+struct GstDataQueueItem{
+    object: *mut GstMiniObject,
+    size: guint,
+    duration: guint64,
+    visible: gboolean,
+    destroy: GDestroyNotify,
+    _gst_reserved: [gpointer; 4],
+}
+
+#[repr(C)]
+#[derive(Clone,Copy,Debug,Default)]
+pub struct DataQueueLevel{
+    /// Number of buffers
+    pub visible: u32,
+    pub bytes: u32,
+    /// Sum of the durations of the buffers in nanoseconds
+    pub time: u64,
+}
+
+// GstDataQueue is part of gstbase, which ffi.rs doesn't include
+extern "C"{
+    fn gst_data_queue_new(checkfull: Option<extern "C" fn(*mut GstDataQueue, guint, guint, guint64, gpointer) -> gboolean>,
+        fullcallback: Option<extern "C" fn(*mut GstDataQueue, gpointer)>,
+        emptycallback: Option<extern "C" fn(*mut GstDataQueue, gpointer)>,
+        checkdata: gpointer) -> *mut GstDataQueue;
+    fn gst_data_queue_push(queue: *mut GstDataQueue, item: *mut GstDataQueueItem) -> gboolean;
+    fn gst_data_queue_push_force(queue: *mut GstDataQueue, item: *mut GstDataQueueItem) -> gboolean;
+    fn gst_data_queue_pop(queue: *mut GstDataQueue, item: *mut *mut GstDataQueueItem) -> gboolean;
+    fn gst_data_queue_peek(queue: *mut GstDataQueue, item: *mut *mut GstDataQueueItem) -> gboolean;
+    fn gst_data_queue_flush(queue: *mut GstDataQueue);
+    fn gst_data_queue_set_flushing(queue: *mut GstDataQueue, flushing: gboolean);
+    fn gst_data_queue_is_empty(queue: *mut GstDataQueue) -> gboolean;
+    fn gst_data_queue_is_full(queue: *mut GstDataQueue) -> gboolean;
+    fn gst_data_queue_get_level(queue: *mut GstDataQueue, level: *mut DataQueueLevel);
+    fn gst_data_queue_limits_changed(queue: *mut GstDataQueue);
+}
+
+struct Callbacks{
+    is_full: Box<dyn Fn(&DataQueueLevel) -> bool + Send + Sync>,
+    full: Mutex<Option<Box<dyn Fn() + Send + Sync>>>,
+    empty: Mutex<Option<Box<dyn Fn() + Send + Sync>>>,
+}
+
+extern "C" fn data_queue_check_full(_queue: *mut GstDataQueue, visible: guint, bytes: guint, time: guint64, data: gpointer) -> gboolean{
+    unsafe{
+        let level = DataQueueLevel{ visible: visible, bytes: bytes, time: time };
+        ((*(data as *const Callbacks)).is_full)(&level) as gboolean
+    }
+}
+
+extern "C" fn data_queue_full(_queue: *mut GstDataQueue, data: gpointer){
+    unsafe{
+        if let Some(ref full) = *(*(data as *const Callbacks)).full.lock().unwrap(){
+            full();
+        }
+    }
+}
+
+extern "C" fn data_queue_empty(_queue: *mut GstDataQueue, data: gpointer){
+    unsafe{
+        if let Some(ref empty) = *(*(data as *const Callbacks)).empty.lock().unwrap(){
+            empty();
+        }
+    }
+}
+
+extern "C" fn free_item(item: gpointer){
+    unsafe{
+        let item = Box::from_raw(item as *mut GstDataQueueItem);
+        if item.object != ptr::null_mut(){
+            gst_mini_object_unref(item.object);
+        }
+    }
+}
+
+/// A queue of buffers with blocking push and pop and a limit decided by
+/// the application from the level of the queue, as the one of the queue
+/// element. Used to hand buffers between the application and a
+/// streaming thread with backpressure, or by elements with their own
+/// streaming thread.
+pub struct DataQueue{
+    queue: *mut GstDataQueue
+}
+
+unsafe impl Sync for DataQueue {}
+unsafe impl Send for DataQueue {}
+
+impl DataQueue{
+    /// Creates a queue that's full when is_full returns true for its
+    /// level. push blocks while the queue is full and pop while it's
+    /// empty
+    pub fn new<F: Fn(&DataQueueLevel) -> bool + Send + Sync + 'static>(is_full: F) -> DataQueue{
+        unsafe{
+            let callbacks = Box::into_raw(Box::new(Callbacks{
+                is_full: Box::new(is_full),
+                full: Mutex::new(None),
+                empty: Mutex::new(None),
+            }));
+            let queue = gst_data_queue_new(Some(data_queue_check_full), Some(data_queue_full),
+                Some(data_queue_empty), callbacks as gpointer);
+            g_object_set_data_full(queue as *mut GObject, to_c_str!("gst-rs-data-queue-callbacks"),
+                callbacks as gpointer, Some(box_destroy_notify::<Callbacks>));
+            DataQueue{ queue: queue }
+        }
+    }
+
+    /// Calls f when push finds the queue full, before blocking, from the
+    /// thread pushing
+    pub fn on_full<F: Fn() + Send + Sync + 'static>(&self, f: F){
+        unsafe{
+            *(*self.callbacks()).full.lock().unwrap() = Some(Box::new(f));
+        }
+    }
+
+    /// Calls f when pop finds the queue empty, before blocking, from the
+    /// thread popping
+    pub fn on_empty<F: Fn() + Send + Sync + 'static>(&self, f: F){
+        unsafe{
+            *(*self.callbacks()).empty.lock().unwrap() = Some(Box::new(f));
+        }
+    }
+
+    unsafe fn callbacks(&self) -> *const Callbacks{
+        g_object_get_data(self.queue as *mut GObject, to_c_str!("gst-rs-data-queue-callbacks")) as *const Callbacks
+    }
+
+    fn new_item(buffer: Buffer) -> *mut GstDataQueueItem{
+        let size = buffer.size() as guint;
+        let duration = buffer.duration();
+        Box::into_raw(Box::new(GstDataQueueItem{
+            object: unsafe{ ::Transfer::transfer(buffer) } as *mut GstMiniObject,
+            size: size,
+            duration: if duration == GST_CLOCK_TIME_NONE{ 0 }else{ duration },
+            visible: 1,
+            destroy: Some(free_item),
+            _gst_reserved: [ptr::null_mut(); 4],
+        }))
+    }
+
+    unsafe fn take_item(item: *mut GstDataQueueItem) -> Option<Buffer>{
+        let buffer = Buffer::new((*item).object as *mut GstBuffer, true);
+        (*item).object = ptr::null_mut();
+        free_item(item as gpointer);
+        buffer
+    }
+
+    /// Adds a buffer, blocking while the queue is full. Returns the
+    /// buffer back if the queue is flushing
+    pub fn push(&self, buffer: Buffer) -> Result<(), Buffer>{
+        unsafe{
+            let item = DataQueue::new_item(buffer);
+            if gst_data_queue_push(self.queue, item) == 1{
+                Ok(())
+            }else{
+                Err(DataQueue::take_item(item).unwrap())
+            }
+        }
+    }
+
+    /// Adds a buffer even if the queue is full, as for the last buffers
+    /// before EOS. Returns the buffer back if the queue is flushing
+    pub fn push_force(&self, buffer: Buffer) -> Result<(), Buffer>{
+        unsafe{
+            let item = DataQueue::new_item(buffer);
+            if gst_data_queue_push_force(self.queue, item) == 1{
+                Ok(())
+            }else{
+                Err(DataQueue::take_item(item).unwrap())
+            }
+        }
+    }
+
+    /// Removes the oldest buffer, blocking while the queue is empty.
+    /// Returns None if the queue is flushing
+    pub fn pop(&self) -> Option<Buffer>{
+        unsafe{
+            let mut item = ptr::null_mut();
+            if gst_data_queue_pop(self.queue, &mut item) == 1 && item != ptr::null_mut(){
+                DataQueue::take_item(item)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// The oldest buffer without removing it, blocking while the queue
+    /// is empty. Returns None if the queue is flushing
+    pub fn peek(&self) -> Option<Buffer>{
+        unsafe{
+            let mut item = ptr::null_mut();
+            if gst_data_queue_peek(self.queue, &mut item) == 1 && item != ptr::null_mut(){
+                Buffer::new((*item).object as *mut GstBuffer, false)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Drops all the buffers
+    pub fn flush(&self){
+        unsafe{
+            gst_data_queue_flush(self.queue);
+        }
+    }
+
+    /// While flushing push and pop fail without blocking, and the ones
+    /// blocked return. Used to unblock the threads before stopping them
+    pub fn set_flushing(&self, flushing: bool){
+        unsafe{
+            gst_data_queue_set_flushing(self.queue, flushing as gboolean);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        unsafe{
+            gst_data_queue_is_empty(self.queue) == 1
+        }
+    }
+
+    pub fn is_full(&self) -> bool{
+        unsafe{
+            gst_data_queue_is_full(self.queue) == 1
+        }
+    }
+
+    pub fn level(&self) -> DataQueueLevel{
+        unsafe{
+            let mut level = DataQueueLevel::default();
+            gst_data_queue_get_level(self.queue, &mut level);
+            level
+        }
+    }
+
+    /// Wakes up the threads blocked in push to check again if the queue
+    /// is full, after the limits used by is_full changed
+    pub fn limits_changed(&self){
+        unsafe{
+            gst_data_queue_limits_changed(self.queue);
+        }
+    }
+}
+
+impl Drop for DataQueue{
+    fn drop(&mut self){
+        unsafe{
+            g_object_unref(self.queue as gpointer);
+        }
+    }
+}
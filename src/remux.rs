@@ -0,0 +1,125 @@
+use ffi::*;
+use util::*;
+use bin::BinT;
+use caps::Caps;
+use decode_util::{discard_pad, missing_element};
+use decodebin::{DecodeBinT, UriDecodeBin};
+use element::{Element, ElementT};
+use error::{Error, ErrorKind, Result};
+use pipeline::Pipeline;
+
+type StreamFilter = Box<dyn Fn(&Caps) -> bool + Send + Sync>;
+
+/// Copies the streams of a file or network stream into a new container
+/// without decoding them, as converting a mkv to mp4. Much faster than
+/// transcoding and without quality loss, but the container has to
+/// support the codecs of the streams.
+///
+/// ```ignore
+/// Remuxer::new("file:///videos/input.mkv", "/videos/output.mp4", "mp4mux")
+///     .select_streams(|caps| caps.structure(0).map(|s| !s.name().starts_with("text/")).unwrap_or(false))
+///     .run()?;
+/// ```
+pub struct Remuxer{
+    input_uri: String,
+    output_path: String,
+    muxer: String,
+    filter: Option<StreamFilter>,
+}
+
+impl Remuxer{
+    /// muxer is the factory of the container, as "mp4mux", "matroskamux"
+    /// or "mpegtsmux"
+    pub fn new(input_uri: &str, output_path: &str, muxer: &str) -> Remuxer{
+        Remuxer{
+            input_uri: input_uri.to_string(),
+            output_path: output_path.to_string(),
+            muxer: muxer.to_string(),
+            filter: None,
+        }
+    }
+
+    /// Only copies the streams for which f returns true, called with the
+    /// caps of each parsed stream. All the streams are copied by default
+    pub fn select_streams<F: Fn(&Caps) -> bool + Send + Sync + 'static>(mut self, f: F) -> Remuxer{
+        self.filter = Some(Box::new(f));
+        self
+    }
+
+    /// Remuxes the whole input, blocking until it's written. Fails with
+    /// an error of kind NotNegotiated if the container doesn't support
+    /// one of the selected streams
+    pub fn run(self) -> Result<()>{
+        let mut pipeline = Pipeline::new("remuxer").ok_or_else(|| missing_element("pipeline"))?;
+        let decodebin = UriDecodeBin::new("").ok_or_else(|| missing_element("uridecodebin"))?;
+        let muxer = Element::new(&self.muxer, "").ok_or_else(|| missing_element(&self.muxer))?;
+        let filesink = Element::new("filesink", "").ok_or_else(|| missing_element("filesink"))?;
+        decodebin.set_uri(&self.input_uri);
+        decodebin.expose_parsed_streams();
+        filesink.set("location", to_c_str!(self.output_path.as_str()));
+
+        let mut muxer_ref = muxer.to_element();
+        let mut filesink_ref = filesink.to_element();
+        unsafe{
+            let streams = Box::new(Streams{ muxer: muxer.to_element(), filter: self.filter });
+            connect_boxed(decodebin.gst_element() as gpointer, "pad-added",
+                pad_added as extern "C" fn(*mut GstElement, *mut GstPad, gpointer) as gpointer,
+                streams);
+        }
+        if !pipeline.add(decodebin) || !pipeline.add(muxer) || !pipeline.add(filesink) ||
+            !muxer_ref.link(&mut filesink_ref){
+            return Err(Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_NEGOTIATION as i32,
+                &format!("couldn't link {} to the filesink", self.muxer)));
+        }
+
+        pipeline.set_state(GST_STATE_PLAYING);
+        let result = match pipeline.wait_for_message(GST_CLOCK_TIME_NONE, GST_MESSAGE_EOS){
+            Some(Err(err)) => Err(err),
+            _ => Ok(())
+        };
+        pipeline.set_state(GST_STATE_NULL);
+        result
+    }
+}
+
+/// Copies all the streams of input_uri into a new file at output_path,
+/// with the container of the muxer factory, see Remuxer
+pub fn remux(input_uri: &str, output_path: &str, muxer: &str) -> Result<()>{
+    Remuxer::new(input_uri, output_path, muxer).run()
+}
+
+struct Streams{
+    muxer: Element,
+    filter: Option<StreamFilter>,
+}
+
+// Links each selected stream to a new pad of the muxer, the rest go to
+// a fakesink so they don't stop the demuxer
+extern "C" fn pad_added(decodebin: *mut GstElement, pad: *mut GstPad, data: gpointer){
+    unsafe{
+        let streams = &*(data as *const Streams);
+        let caps = match Caps::new(gst_pad_get_current_caps(pad), true){
+            Some(caps) => caps,
+            None => return
+        };
+        let selected = streams.filter.as_ref().map(|filter| filter(&caps)).unwrap_or(true);
+        let sink = if selected{
+            // no filter, so the pad is matched with all the caps it can
+            // produce, parsers can convert the stream-format or alignment
+            // to the one the muxer needs as h264 byte-stream to avc
+            gst_element_get_compatible_pad(streams.muxer.gst_element() as *mut GstElement, pad, ptr::null_mut())
+        }else{
+            discard_pad(decodebin)
+        };
+        if sink == ptr::null_mut() || gst_pad_link(pad, sink) != GST_PAD_LINK_OK{
+            if let Some(decodebin) = Element::new_from_gst_element(gst_object_ref(decodebin as gpointer) as *mut GstElement){
+                decodebin.post_error(ErrorKind::NotNegotiated,
+                    &format!("{} doesn't support the stream {}", streams.muxer.factory_name().unwrap_or_default(), caps),
+                    "");
+            }
+        }
+        if sink != ptr::null_mut(){
+            gst_object_unref(sink as gpointer);
+        }
+    }
+}
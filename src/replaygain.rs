@@ -0,0 +1,166 @@
+use ffi::*;
+use element::{Element, ElementT};
+use taglist::TagList;
+use value::Value;
+
+/// ReplayGain values as found in the tags of a stream or computed by
+/// RgAnalysis, gains in dB and peaks normalized to 1.0
+#[derive(Clone,Copy,Debug,PartialEq,Default)]
+pub struct ReplayGain{
+    pub track_gain: Option<f64>,
+    pub track_peak: Option<f64>,
+    pub album_gain: Option<f64>,
+    pub album_peak: Option<f64>,
+    pub reference_level: Option<f64>,
+}
+
+impl ReplayGain{
+    /// Reads the replaygain-* tags from a tag list, as posted in TAG
+    /// messages by demuxers or by rganalysis. Returns None if the list
+    /// has no ReplayGain information.
+    pub fn from_tags(tags: &TagList) -> Option<ReplayGain>{
+        let get = |tag: &str| match tags.get(tag){
+            Some(Value::Double(v)) => Some(v),
+            Some(Value::List(ref values)) => match values.first(){
+                Some(&Value::Double(v)) => Some(v),
+                _ => None
+            },
+            _ => None
+        };
+        let replaygain = ReplayGain{
+            track_gain: get("replaygain-track-gain"),
+            track_peak: get("replaygain-track-peak"),
+            album_gain: get("replaygain-album-gain"),
+            album_peak: get("replaygain-album-peak"),
+            reference_level: get("replaygain-reference-level"),
+        };
+        if replaygain == ReplayGain::default(){
+            None
+        }else{
+            Some(replaygain)
+        }
+    }
+}
+
+/// Wrapper around rgvolume, which applies the ReplayGain found in the
+/// stream tags so all tracks play at the same loudness
+pub struct RgVolume{
+    rgvolume: Element
+}
+
+unsafe impl Sync for RgVolume {}
+unsafe impl Send for RgVolume {}
+
+impl RgVolume{
+    pub fn new(name: &str) -> Option<RgVolume>{
+        Element::new("rgvolume", name).map(|rgvolume| RgVolume{ rgvolume: rgvolume })
+    }
+
+    pub fn new_from_element(element: Element) -> RgVolume{
+        RgVolume{ rgvolume: element }
+    }
+
+    /// Prefer the album gain over the track gain, the default
+    pub fn set_album_mode(&self, album_mode: bool){
+        self.set("album-mode", album_mode as gboolean);
+    }
+
+    /// Extra gain in dB applied to streams with ReplayGain information
+    pub fn set_pre_amp(&self, pre_amp: f64){
+        self.set("pre-amp", pre_amp);
+    }
+
+    /// Gain in dB applied to streams without ReplayGain information
+    pub fn set_fallback_gain(&self, fallback_gain: f64){
+        self.set("fallback-gain", fallback_gain);
+    }
+
+    /// Allowed gain in dB above 0dB, clipping is avoided with the peak
+    /// values if this is 0
+    pub fn set_headroom(&self, headroom: f64){
+        self.set("headroom", headroom);
+    }
+
+    /// Gain in dB that should be applied to the current stream
+    pub fn target_gain(&self) -> f64{
+        unsafe{
+            self.get::<f64>("target-gain")
+        }
+    }
+
+    /// Gain in dB actually applied to the current stream, can be lower
+    /// than target_gain to avoid clipping
+    pub fn result_gain(&self) -> f64{
+        unsafe{
+            self.get::<f64>("result-gain")
+        }
+    }
+}
+
+impl ElementT for RgVolume{
+    fn as_element(&self) -> &Element{
+        &self.rgvolume
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.rgvolume
+    }
+}
+
+impl ::Transfer for RgVolume{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.rgvolume.transfer()
+    }
+}
+
+/// Wrapper around rganalysis, which computes the ReplayGain of the
+/// audio going through it and posts it as TAG messages, read them with
+/// ReplayGain::from_tags
+pub struct RgAnalysis{
+    rganalysis: Element
+}
+
+unsafe impl Sync for RgAnalysis {}
+unsafe impl Send for RgAnalysis {}
+
+impl RgAnalysis{
+    pub fn new(name: &str) -> Option<RgAnalysis>{
+        Element::new("rganalysis", name).map(|rganalysis| RgAnalysis{ rganalysis: rganalysis })
+    }
+
+    pub fn new_from_element(element: Element) -> RgAnalysis{
+        RgAnalysis{ rganalysis: element }
+    }
+
+    /// Number of tracks that make up the album, the album gain is posted
+    /// after the last one
+    pub fn set_num_tracks(&self, num_tracks: i32){
+        self.set("num-tracks", num_tracks);
+    }
+
+    /// Analyze even streams that already have ReplayGain tags
+    pub fn set_forced(&self, forced: bool){
+        self.set("forced", forced as gboolean);
+    }
+
+    /// Reference level in dB, 89 by default
+    pub fn set_reference_level(&self, reference_level: f64){
+        self.set("reference-level", reference_level);
+    }
+}
+
+impl ElementT for RgAnalysis{
+    fn as_element(&self) -> &Element{
+        &self.rganalysis
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.rganalysis
+    }
+}
+
+impl ::Transfer for RgAnalysis{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.rganalysis.transfer()
+    }
+}
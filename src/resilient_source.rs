@@ -0,0 +1,362 @@
+use ffi::*;
+use util::*;
+use bin::{Bin, BinT};
+use element::{Element, ElementT};
+use message::Message;
+use pad::Pad;
+use value::Value;
+use error::Result;
+
+use std::cmp;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Delays between the reconnection attempts of a ResilientSource, each
+/// attempt waits multiplier times longer than the previous one, up to max
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct Backoff{
+    pub initial: Duration,
+    pub max: Duration,
+    pub multiplier: u32,
+    /// Attempts before giving up, None to retry forever
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Backoff{
+    fn default() -> Backoff{
+        Backoff{
+            initial: Duration::from_millis(500),
+            max: Duration::from_secs(30),
+            multiplier: 2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl Backoff{
+    /// Delay before the given attempt, starting at 1
+    pub fn delay(&self, attempt: u32) -> Duration{
+        let mut delay = self.initial;
+        for _ in 1..attempt{
+            delay = match delay.checked_mul(self.multiplier){
+                Some(delay) if delay < self.max => delay,
+                _ => return self.max
+            };
+        }
+        cmp::min(delay, self.max)
+    }
+}
+
+/// Changes in the connection of a ResilientSource, passed to the
+/// callbacks added with on_state_change
+#[derive(Clone,Debug,PartialEq)]
+pub enum SourceState{
+    /// Data started flowing from the source
+    Connected,
+    /// The source posted an error and was torn down
+    Disconnected{ error: String },
+    /// A new source will be created after delay
+    Reconnecting{ attempt: u32, delay: Duration },
+    /// The maximum number of attempts was reached, the source stays down
+    GaveUp{ attempts: u32 },
+}
+
+struct Connection{
+    bin: Bin,
+    source: Option<Element>,
+    attempt: u32,
+    reconnecting: bool,
+    connected: bool,
+}
+
+struct Inner{
+    factory: String,
+    properties: Vec<(String, Value)>,
+    backoff: Backoff,
+    ghost: Pad,
+    connection: Mutex<Connection>,
+    listeners: Mutex<Vec<Box<dyn Fn(&SourceState) + Send + Sync>>>,
+}
+
+/// A network source, as rtspsrc, souphttpsrc or udpsrc, in a bin that
+/// replaces it with a new one when it fails, so a camera or stream that
+/// goes down for a while doesn't stop the rest of the pipeline.
+///
+/// The bin has a single src pad, linked to the first pad of the source.
+/// GStreamer has no way to catch the errors of an element before they
+/// reach the bus, so the application has to pass the messages of the
+/// pipeline bus to handle_message, which handles the errors posted by the
+/// source and returns true for them:
+///
+/// ```ignore
+/// let camera = ResilientSource::rtsp("camera", "rtsp://10.0.0.2/stream", Backoff::default())?;
+/// pipeline.add(camera.clone());
+/// ...
+/// for message in bus.receiver().iter(){
+///     if camera.handle_message(&message){
+///         continue;
+///     }
+///     ...
+/// }
+/// ```
+///
+/// Clones refer to the same source.
+pub struct ResilientSource{
+    bin: Bin,
+    inner: Arc<Inner>,
+}
+
+impl Clone for ResilientSource{
+    fn clone(&self) -> ResilientSource{
+        ResilientSource{ bin: self.bin.to_bin(), inner: self.inner.clone() }
+    }
+}
+
+unsafe impl Sync for ResilientSource {}
+unsafe impl Send for ResilientSource {}
+
+impl ResilientSource{
+    /// Creates the bin with a source of the given factory, the properties
+    /// are set on every source created
+    pub fn new(name: &str, factory: &str, properties: &[(&str, Value)], backoff: Backoff) -> Result<ResilientSource>{
+        let source = Element::with_properties(factory, properties)?;
+        let bin = Bin::new(name).ok_or_else(|| ::Error::new(unsafe{ gst_core_error_quark() },
+            GST_CORE_ERROR_FAILED as i32, "couldn't create bin"))?;
+        let ghost = unsafe{
+            let ghost = gst_ghost_pad_new_no_target(to_c_str!("src"), GST_PAD_SRC);
+            gst_element_add_pad(bin.gst_element() as *mut GstElement, ghost);
+            Pad::new(ghost, false).unwrap()
+        };
+        let inner = Arc::new(Inner{
+            factory: factory.to_string(),
+            properties: properties.iter().map(|&(name, ref value)| (name.to_string(), value.clone())).collect(),
+            backoff: backoff,
+            ghost: ghost,
+            connection: Mutex::new(Connection{
+                bin: bin.to_bin(),
+                source: None,
+                attempt: 0,
+                reconnecting: false,
+                connected: false,
+            }),
+            listeners: Mutex::new(Vec::new()),
+        });
+        let weak = Arc::downgrade(&inner);
+        inner.ghost.add_buffer_probe(move |_, _| {
+            if let Some(inner) = weak.upgrade(){
+                inner.buffer_received();
+            }
+            GST_PAD_PROBE_OK
+        });
+        if !inner.install_source(source) || !inner.start_source(){
+            return Err(::Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_FAILED as i32,
+                &format!("couldn't add {} to the bin", factory)));
+        }
+        Ok(ResilientSource{ bin: bin, inner: inner })
+    }
+
+    /// An rtspsrc for location
+    pub fn rtsp(name: &str, location: &str, backoff: Backoff) -> Result<ResilientSource>{
+        ResilientSource::new(name, "rtspsrc", &[("location", Value::String(location.to_string()))], backoff)
+    }
+
+    /// A souphttpsrc for location
+    pub fn http(name: &str, location: &str, backoff: Backoff) -> Result<ResilientSource>{
+        ResilientSource::new(name, "souphttpsrc", &[
+            ("location", Value::String(location.to_string())),
+            ("is-live", Value::Bool(true)),
+        ], backoff)
+    }
+
+    /// A udpsrc for uri, as udp://0.0.0.0:5000
+    pub fn udp(name: &str, uri: &str, backoff: Backoff) -> Result<ResilientSource>{
+        ResilientSource::new(name, "udpsrc", &[("uri", Value::String(uri.to_string()))], backoff)
+    }
+
+    /// Calls f when the connection state of the source changes, from the
+    /// thread that detected the change
+    pub fn on_state_change<F: Fn(&SourceState) + Send + Sync + 'static>(&self, f: F){
+        self.inner.listeners.lock().unwrap().push(Box::new(f));
+    }
+
+    /// True once data flows from the current source
+    pub fn is_connected(&self) -> bool{
+        self.inner.connection.lock().unwrap().connected
+    }
+
+    /// Handles the error messages posted by the source or its children,
+    /// tearing it down and scheduling a new one. Returns true if the
+    /// message was handled and shouldn't be treated as a pipeline error
+    pub fn handle_message(&self, message: &Message) -> bool{
+        unsafe{
+            if message.ty() != GST_MESSAGE_ERROR{
+                return false;
+            }
+            let src = message.src();
+            if src == ptr::null_mut() || gst_object_has_ancestor(src, self.bin.gst_element() as *mut GstObject) == 0{
+                return false;
+            }
+        }
+        let error = match message.parse(){
+            Message::ErrorParsed{ref error, ..} => error.message(),
+            _ => String::new()
+        };
+        Inner::source_failed(&self.inner, error);
+        true
+    }
+}
+
+impl Inner{
+    fn notify(&self, state: SourceState){
+        for listener in self.listeners.lock().unwrap().iter(){
+            listener(&state);
+        }
+    }
+
+    fn buffer_received(&self){
+        let newly_connected = {
+            let mut connection = self.connection.lock().unwrap();
+            let newly_connected = !connection.connected;
+            connection.connected = true;
+            connection.attempt = 0;
+            newly_connected
+        };
+        if newly_connected{
+            self.notify(SourceState::Connected);
+        }
+    }
+
+    fn source_failed(inner: &Arc<Inner>, error: String){
+        {
+            let mut connection = inner.connection.lock().unwrap();
+            if connection.reconnecting{
+                return;
+            }
+            connection.reconnecting = true;
+            connection.connected = false;
+        }
+        inner.notify(SourceState::Disconnected{ error: error });
+        let inner = inner.clone();
+        // the source can't be set to NULL from the thread that posted the
+        // error, which could be its own streaming thread
+        thread::spawn(move || {
+            inner.remove_source();
+            inner.reconnect();
+        });
+    }
+
+    fn remove_source(&self){
+        unsafe{
+            gst_ghost_pad_set_target(self.ghost.gst_pad() as *mut GstGhostPad, ptr::null_mut());
+        }
+        // the lock can't be held while stopping the source, its streaming
+        // thread could be waiting for it in the ghost pad probe
+        let source = self.connection.lock().unwrap().source.take();
+        if let Some(mut source) = source{
+            source.set_state(GST_STATE_NULL);
+            self.connection.lock().unwrap().bin.remove(&source);
+        }
+    }
+
+    fn reconnect(&self){
+        loop{
+            let attempt = {
+                let mut connection = self.connection.lock().unwrap();
+                connection.attempt += 1;
+                connection.attempt
+            };
+            if self.backoff.max_attempts.map(|max| attempt > max).unwrap_or(false){
+                self.notify(SourceState::GaveUp{ attempts: attempt - 1 });
+                return;
+            }
+            let delay = self.backoff.delay(attempt);
+            self.notify(SourceState::Reconnecting{ attempt: attempt, delay: delay });
+            thread::sleep(delay);
+            let properties: Vec<(&str, Value)> = self.properties.iter()
+                .map(|&(ref name, ref value)| (name.as_str(), value.clone()))
+                .collect();
+            if let Ok(source) = Element::with_properties(&self.factory, &properties){
+                if self.install_source(source){
+                    // cleared before the state change, errors the new
+                    // source posts meanwhile start a new reconnection
+                    self.connection.lock().unwrap().reconnecting = false;
+                    if self.start_source(){
+                        return;
+                    }
+                    let mut connection = self.connection.lock().unwrap();
+                    if connection.reconnecting{
+                        return;
+                    }
+                    connection.reconnecting = true;
+                }
+                self.remove_source();
+            }
+        }
+    }
+
+    // Adds the source to the bin and targets the ghost pad to its src pad
+    // or to its first pad if it has dynamic pads
+    fn install_source(&self, source: Element) -> bool{
+        let mut source_ref = source.to_element();
+        {
+            let mut connection = self.connection.lock().unwrap();
+            if !connection.bin.add(source){
+                return false;
+            }
+            connection.source = Some(source_ref.to_element());
+        }
+        unsafe{
+            match source_ref.static_pad("src"){
+                Some(pad) => {
+                    gst_ghost_pad_set_target(self.ghost.gst_pad() as *mut GstGhostPad, pad.gst_pad() as *mut GstPad);
+                }
+                None => {
+                    let ghost = Box::new(self.ghost.clone());
+                    connect_boxed(source_ref.gst_element_mut() as gpointer, "pad-added",
+                        pad_added as extern "C" fn(*mut GstElement, *mut GstPad, gpointer) as gpointer,
+                        ghost);
+                }
+            }
+        }
+        true
+    }
+
+    // Syncs the state of the current source with the bin
+    fn start_source(&self) -> bool{
+        let source = self.connection.lock().unwrap().source.as_ref().map(|source| source.to_element());
+        match source{
+            Some(mut source) => unsafe{ gst_element_sync_state_with_parent(source.gst_element_mut()) != 0 },
+            None => false
+        }
+    }
+}
+
+extern "C" fn pad_added(_element: *mut GstElement, pad: *mut GstPad, data: gpointer){
+    unsafe{
+        let ghost = &*(data as *const Pad);
+        let ghost_pad = ghost.gst_pad() as *mut GstGhostPad;
+        let target = gst_ghost_pad_get_target(ghost_pad);
+        if target == ptr::null_mut(){
+            gst_ghost_pad_set_target(ghost_pad, pad);
+        }else{
+            gst_object_unref(target as gpointer);
+        }
+    }
+}
+
+impl BinT for ResilientSource{
+    fn as_bin(&self) -> &Bin{
+        &self.bin
+    }
+
+    fn as_bin_mut(&mut self) -> &mut Bin{
+        &mut self.bin
+    }
+}
+
+impl ::Transfer for ResilientSource{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.bin.transfer()
+    }
+}
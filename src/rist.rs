@@ -0,0 +1,187 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+use structure::Structure;
+use value::Value;
+
+/// Statistics of a RIST session, from the stats property. The sender
+/// counters are only set by ristsink and the receiver ones by ristsrc
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct RistStats{
+    pub sent_original_packets: u64,
+    pub sent_retransmitted_packets: u64,
+    pub received: u64,
+    pub dropped: u64,
+    pub recovered: u64,
+    pub permanently_lost: u64,
+    pub duplicates: u64,
+    pub retransmission_requests_sent: u64,
+    /// Round trip time of the retransmissions in nanoseconds
+    pub rtx_roundtrip_time: u64,
+}
+
+impl RistStats{
+    pub fn from_structure(structure: &Structure) -> RistStats{
+        let uint = |field: &str| match structure.get(field){
+            Some(Value::UInt64(value)) => value,
+            Some(Value::Int64(value)) => value as u64,
+            Some(Value::UInt(value)) => value as u64,
+            Some(Value::Int(value)) => value as u64,
+            _ => 0
+        };
+        RistStats{
+            sent_original_packets: uint("sent-original-packets"),
+            sent_retransmitted_packets: uint("sent-retransmitted-packets"),
+            received: uint("received"),
+            dropped: uint("dropped"),
+            recovered: uint("recovered"),
+            permanently_lost: uint("permanently-lost"),
+            duplicates: uint("duplicates"),
+            retransmission_requests_sent: uint("retransmission-requests-sent"),
+            rtx_roundtrip_time: uint("rtx-roundtrip-time"),
+        }
+    }
+}
+
+/// Properties shared by ristsrc and ristsink
+pub trait RistT: ElementT{
+    /// Address to send to or receive from, unicast or multicast
+    fn set_address(&self, address: &str){
+        self.as_element().set("address", to_c_str!(address));
+    }
+
+    /// RTP port, RTCP uses the next one so it has to be even
+    fn set_port(&self, port: u16){
+        self.as_element().set("port", port as guint);
+    }
+
+    /// Network interface used for multicast, as "eth0"
+    fn set_multicast_iface(&self, iface: &str){
+        self.as_element().set("multicast-iface", to_c_str!(iface));
+    }
+
+    fn set_multicast_ttl(&self, ttl: i32){
+        self.as_element().set("multicast-ttl", ttl);
+    }
+
+    /// Interval in milliseconds at which the stats property is updated
+    /// and posted as an element message, 0 to disable
+    fn set_stats_update_interval(&self, interval_ms: u32){
+        self.as_element().set("stats-update-interval", interval_ms);
+    }
+
+    /// The structure of the stats property as is
+    fn stats_structure(&self) -> Option<Structure>{
+        unsafe{
+            Structure::new_from_gst_structure(self.as_element().get::<*mut GstStructure>("stats"))
+        }
+    }
+
+    fn stats(&self) -> RistStats{
+        self.stats_structure().map(|stats| RistStats::from_structure(&stats)).unwrap_or_default()
+    }
+}
+
+/// Wrapper around ristsrc, receiving a stream with the RIST simple profile
+pub struct RistSrc{
+    ristsrc: Element
+}
+
+unsafe impl Sync for RistSrc {}
+unsafe impl Send for RistSrc {}
+
+impl RistSrc{
+    pub fn new(name: &str) -> Option<RistSrc>{
+        Element::new("ristsrc", name).map(|ristsrc| RistSrc{ ristsrc: ristsrc })
+    }
+
+    pub fn new_from_element(element: Element) -> RistSrc{
+        RistSrc{ ristsrc: element }
+    }
+
+    /// Time in milliseconds packets are buffered waiting for
+    /// retransmissions, the receive latency
+    pub fn set_receiver_buffer(&self, buffer_ms: u32){
+        self.ristsrc.set("receiver-buffer", buffer_ms);
+    }
+
+    /// Time in milliseconds to wait for a missing packet before asking
+    /// for its retransmission
+    pub fn set_reorder_section(&self, section_ms: u32){
+        self.ristsrc.set("reorder-section", section_ms);
+    }
+
+    pub fn set_max_rtx_retries(&self, retries: u32){
+        self.ristsrc.set("max-rtx-retries", retries);
+    }
+}
+
+impl RistT for RistSrc{}
+
+impl ElementT for RistSrc{
+    fn as_element(&self) -> &Element{
+        &self.ristsrc
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.ristsrc
+    }
+}
+
+impl ::Transfer for RistSrc{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.ristsrc.transfer()
+    }
+}
+
+/// Wrapper around ristsink, sending a stream with the RIST simple profile
+pub struct RistSink{
+    ristsink: Element
+}
+
+unsafe impl Sync for RistSink {}
+unsafe impl Send for RistSink {}
+
+impl RistSink{
+    pub fn new(name: &str) -> Option<RistSink>{
+        Element::new("ristsink", name).map(|ristsink| RistSink{ ristsink: ristsink })
+    }
+
+    pub fn new_from_element(element: Element) -> RistSink{
+        RistSink{ ristsink: element }
+    }
+
+    /// Time in milliseconds sent packets are kept to answer
+    /// retransmission requests
+    pub fn set_sender_buffer(&self, buffer_ms: u32){
+        self.ristsink.set("sender-buffer", buffer_ms);
+    }
+
+    /// Sends the stream to several receivers or through several links at
+    /// once, as pairs of address and port
+    pub fn set_bonding_addresses(&self, addresses: &[(&str, u16)]){
+        let addresses = addresses.iter()
+            .map(|&(address, port)| format!("{}:{}", address, port))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.ristsink.set("bonding-addresses", to_c_str!(addresses));
+    }
+}
+
+impl RistT for RistSink{}
+
+impl ElementT for RistSink{
+    fn as_element(&self) -> &Element{
+        &self.ristsink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.ristsink
+    }
+}
+
+impl ::Transfer for RistSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.ristsink.transfer()
+    }
+}
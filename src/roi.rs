@@ -0,0 +1,143 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use meta::MetaApi;
+use structure::Structure;
+
+// The params list of GstVideoRegionOfInterestMeta and its functions were
+// added in GStreamer 1.14, after ffi.rs was generated
+#[repr(C)]
+struct GstVideoRegionOfInterestMetaParams{
+    roi: GstVideoRegionOfInterestMeta,
+    params: *mut GList,
+}
+
+extern "C"{
+    fn gst_video_region_of_interest_meta_add_param(meta: *mut GstVideoRegionOfInterestMeta, s: *mut GstStructure);
+    fn gst_video_region_of_interest_meta_get_param(meta: *mut GstVideoRegionOfInterestMeta, name: *const gchar)
+        -> *mut GstStructure;
+}
+
+/// A region of a video frame, usually a detection of an analysis
+/// element as a face or an object, with an optional list of params
+/// structures holding the details of the detection. Encoders as x264enc
+/// also use them to set a quality for the region, with a
+/// `roi/<encoder>` param as in `roi/x264enc, delta-qp=(int)-10`
+pub type VideoRegionOfInterestMeta = GstVideoRegionOfInterestMeta;
+
+unsafe impl MetaApi for GstVideoRegionOfInterestMeta{
+    fn api_type() -> GType{
+        unsafe{ gst_video_region_of_interest_meta_api_get_type() }
+    }
+}
+
+impl VideoRegionOfInterestMeta{
+    /// Type of the region, as in "face" or "car"
+    pub fn roi_type(&self) -> String{
+        unsafe{
+            from_c_str!(g_quark_to_string(self.roi_type)).to_string()
+        }
+    }
+
+    /// Identifier of the region, unique per buffer
+    pub fn id(&self) -> i32{
+        self.id
+    }
+
+    /// The id of the region containing this one or -1
+    pub fn parent_id(&self) -> i32{
+        self.parent_id
+    }
+
+    pub fn set_parent_id(&mut self, parent_id: i32){
+        self.parent_id = parent_id;
+    }
+
+    /// Returns x, y, width and height of the region in pixels
+    pub fn rect(&self) -> (u32, u32, u32, u32){
+        (self.x, self.y, self.w, self.h)
+    }
+
+    pub fn set_rect(&mut self, x: u32, y: u32, w: u32, h: u32){
+        self.x = x;
+        self.y = y;
+        self.w = w;
+        self.h = h;
+    }
+
+    /// Returns a copy of the param with the given name
+    pub fn param(&self, name: &str) -> Option<Structure>{
+        unsafe{
+            let param = gst_video_region_of_interest_meta_get_param(self as *const Self as *mut Self, to_c_str!(name));
+            if param != ptr::null_mut(){
+                Structure::new_from_gst_structure(gst_structure_copy(param))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns a copy of all the params of the region
+    pub fn params(&self) -> Vec<Structure>{
+        unsafe{
+            let mut params = Vec::new();
+            let mut node = (*(self as *const Self as *const GstVideoRegionOfInterestMetaParams)).params;
+            while node != ptr::null_mut(){
+                if let Some(param) = Structure::new_from_gst_structure(gst_structure_copy((*node).data as *mut GstStructure)){
+                    params.push(param);
+                }
+                node = (*node).next;
+            }
+            params
+        }
+    }
+
+    /// Adds a param to the region, as the label and confidence of a
+    /// detection in a `detection, label=(string)person, confidence=(double)0.9`
+    /// structure
+    pub fn add_param(&mut self, param: Structure){
+        unsafe{
+            gst_video_region_of_interest_meta_add_param(self, ::Transfer::transfer(param));
+        }
+    }
+}
+
+impl Buffer{
+    /// Adds a region of interest of the given type to the buffer and
+    /// returns it to set its params
+    pub fn add_region_of_interest(&mut self, roi_type: &str, x: u32, y: u32, w: u32, h: u32) -> &mut VideoRegionOfInterestMeta{
+        unsafe{
+            &mut *gst_buffer_add_video_region_of_interest_meta(self.gst_buffer_mut(), to_c_str!(roi_type), x, y, w, h)
+        }
+    }
+
+    /// All the regions of interest of the buffer
+    pub fn regions_of_interest(&self) -> Vec<&VideoRegionOfInterestMeta>{
+        self.metas_of::<VideoRegionOfInterestMeta>()
+    }
+
+    /// Returns the region of interest with the given id
+    pub fn region_of_interest(&self, id: i32) -> Option<&VideoRegionOfInterestMeta>{
+        unsafe{
+            let meta = gst_buffer_get_video_region_of_interest_meta_id(self.gst_buffer() as *mut GstBuffer, id);
+            if meta != ptr::null_mut(){
+                Some(&*meta)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns the region of interest with the given id to modify it,
+    /// the buffer has to be writable
+    pub fn region_of_interest_mut(&mut self, id: i32) -> Option<&mut VideoRegionOfInterestMeta>{
+        unsafe{
+            let meta = gst_buffer_get_video_region_of_interest_meta_id(self.gst_buffer_mut(), id);
+            if meta != ptr::null_mut(){
+                Some(&mut *meta)
+            }else{
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,478 @@
+//! Bindings for the gst-rtsp-server library, to serve pipelines to RTSP
+//! clients as a camera or gateway would.
+//!
+//! Needs the `rtsp-server` feature. The server handles the clients from a
+//! glib main loop, so one has to be running on the context it's attached
+//! to:
+//!
+//! ```ignore
+//! let mut server = RtspServer::new();
+//! server.set_port(8554);
+//! let factory = RtspMediaFactory::new_from_launch(
+//!     "( videotestsrc is-live=true ! x264enc tune=zerolatency ! rtph264pay name=pay0 pt=96 )");
+//! factory.set_shared(true);
+//! server.mount("/test", factory);
+//! server.attach(None);
+//! gst::mainloop::run();
+//! ```
+//!
+//! The pipeline of each media has to contain the payloaders of the
+//! streams to serve named pay0, pay1...
+
+use ffi::*;
+use util::*;
+use bin::Bin;
+use element::Element;
+use mainloop::MainContext;
+use value::{G_TYPE_BOOLEAN, G_TYPE_STRING};
+
+use std::sync::Arc;
+
+pub enum GstRTSPServer{}
+pub enum GstRTSPMountPoints{}
+pub enum GstRTSPAuth{}
+pub enum GstRTSPToken{}
+pub enum GstRTSPClient{}
+pub enum GstRTSPConnection{}
+pub enum GstRTSPMedia{}
+
+#[repr(C)]
+pub struct GstRTSPMediaFactory{
+    pub parent: GObject,
+    _priv: gpointer,
+    _gst_reserved: [gpointer; 4],
+}
+
+#[repr(C)]
+struct GstRTSPMediaFactoryClass{
+    parent_class: GObjectClass,
+    gen_key: gpointer,
+    create_element: Option<extern "C" fn(*mut GstRTSPMediaFactory, *const GstRTSPUrl) -> *mut GstElement>,
+    construct: gpointer,
+    create_pipeline: gpointer,
+    configure: gpointer,
+    media_constructed: gpointer,
+    media_configure: gpointer,
+    _gst_reserved: [gpointer; 20],
+}
+
+#[repr(C)]
+pub struct GstRTSPUrl{
+    pub transports: guint,
+    pub family: guint,
+    pub user: *mut gchar,
+    pub passwd: *mut gchar,
+    pub host: *mut gchar,
+    pub port: guint16,
+    pub abspath: *mut gchar,
+    pub query: *mut gchar,
+}
+
+#[cfg_attr(not(target_os="macos"), link(name = "gstrtspserver-1.0"))]
+extern "C"{
+    fn gst_rtsp_server_new() -> *mut GstRTSPServer;
+    fn gst_rtsp_server_set_address(server: *mut GstRTSPServer, address: *const gchar);
+    fn gst_rtsp_server_set_service(server: *mut GstRTSPServer, service: *const gchar);
+    fn gst_rtsp_server_get_bound_port(server: *mut GstRTSPServer) -> gint;
+    fn gst_rtsp_server_get_mount_points(server: *mut GstRTSPServer) -> *mut GstRTSPMountPoints;
+    fn gst_rtsp_server_set_auth(server: *mut GstRTSPServer, auth: *mut GstRTSPAuth);
+    fn gst_rtsp_server_attach(server: *mut GstRTSPServer, context: *mut GMainContext) -> guint;
+    fn gst_rtsp_mount_points_add_factory(mounts: *mut GstRTSPMountPoints, path: *const gchar, factory: *mut GstRTSPMediaFactory);
+    fn gst_rtsp_mount_points_remove_factory(mounts: *mut GstRTSPMountPoints, path: *const gchar);
+    fn gst_rtsp_media_factory_get_type() -> GType;
+    fn gst_rtsp_media_factory_new() -> *mut GstRTSPMediaFactory;
+    fn gst_rtsp_media_factory_set_launch(factory: *mut GstRTSPMediaFactory, launch: *const gchar);
+    fn gst_rtsp_media_factory_set_shared(factory: *mut GstRTSPMediaFactory, shared: gboolean);
+    fn gst_rtsp_media_factory_set_latency(factory: *mut GstRTSPMediaFactory, latency: guint);
+    fn gst_rtsp_media_factory_set_eos_shutdown(factory: *mut GstRTSPMediaFactory, eos_shutdown: gboolean);
+    fn gst_rtsp_media_factory_add_role(factory: *mut GstRTSPMediaFactory, role: *const gchar, fieldname: *const gchar, ...);
+    fn gst_rtsp_media_get_element(media: *mut GstRTSPMedia) -> *mut GstElement;
+    fn gst_rtsp_auth_new() -> *mut GstRTSPAuth;
+    fn gst_rtsp_auth_make_basic(user: *const gchar, pass: *const gchar) -> *mut gchar;
+    fn gst_rtsp_auth_add_basic(auth: *mut GstRTSPAuth, basic: *const gchar, token: *mut GstRTSPToken);
+    fn gst_rtsp_token_new(firstfield: *const gchar, ...) -> *mut GstRTSPToken;
+    fn gst_rtsp_client_get_connection(client: *mut GstRTSPClient) -> *mut GstRTSPConnection;
+}
+
+#[cfg_attr(not(target_os="macos"), link(name = "gstrtsp-1.0"))]
+extern "C"{
+    fn gst_rtsp_connection_get_ip(conn: *const GstRTSPConnection) -> *const gchar;
+}
+
+type Callback<T> = Arc<dyn Fn(T) + Send + Sync>;
+
+unsafe fn client_ip(client: *mut GstRTSPClient) -> String{
+    let connection = gst_rtsp_client_get_connection(client);
+    if connection == ptr::null_mut(){
+        return String::new();
+    }
+    let ip = gst_rtsp_connection_get_ip(connection);
+    if ip == ptr::null(){
+        String::new()
+    }else{
+        from_c_str!(ip).to_string()
+    }
+}
+
+/// An RTSP server listening on a port, serving the factories mounted on
+/// its paths
+pub struct RtspServer{
+    server: *mut GstRTSPServer,
+    source: guint,
+    // the context the source is attached to, its id is only valid there
+    context: Option<MainContext>,
+}
+
+unsafe impl Sync for RtspServer {}
+unsafe impl Send for RtspServer {}
+
+impl Drop for RtspServer{
+    fn drop(&mut self){
+        unsafe{
+            self.detach();
+            g_object_unref(self.server as gpointer);
+        }
+    }
+}
+
+impl RtspServer{
+    pub fn new() -> RtspServer{
+        unsafe{
+            RtspServer{ server: gst_rtsp_server_new(), source: 0, context: None }
+        }
+    }
+
+    /// Address to listen on, by default all the interfaces
+    pub fn set_address(&self, address: &str){
+        unsafe{
+            gst_rtsp_server_set_address(self.server, to_c_str!(address));
+        }
+    }
+
+    /// Port to listen on, 8554 by default, 0 to let the system pick one
+    pub fn set_port(&self, port: u16){
+        unsafe{
+            gst_rtsp_server_set_service(self.server, to_c_str!(port.to_string()));
+        }
+    }
+
+    /// The port the server listens on once attached, -1 before
+    pub fn bound_port(&self) -> i32{
+        unsafe{
+            gst_rtsp_server_get_bound_port(self.server)
+        }
+    }
+
+    /// Serves the media created by factory at path, as "/camera"
+    pub fn mount(&self, path: &str, factory: RtspMediaFactory){
+        unsafe{
+            let mounts = gst_rtsp_server_get_mount_points(self.server);
+            gst_rtsp_mount_points_add_factory(mounts, to_c_str!(path), factory.transfer());
+            g_object_unref(mounts as gpointer);
+        }
+    }
+
+    pub fn unmount(&self, path: &str){
+        unsafe{
+            let mounts = gst_rtsp_server_get_mount_points(self.server);
+            gst_rtsp_mount_points_remove_factory(mounts, to_c_str!(path));
+            g_object_unref(mounts as gpointer);
+        }
+    }
+
+    /// Requires clients to authenticate, only the roles allowed with
+    /// RtspMediaFactory::allow_role can then access each factory
+    pub fn set_auth(&self, auth: &RtspAuth){
+        unsafe{
+            gst_rtsp_server_set_auth(self.server, auth.auth);
+        }
+    }
+
+    /// Starts listening, handling the clients from the passed context or
+    /// the default one. Returns false if the server couldn't listen on
+    /// the address and port. Attaching again stops listening on the
+    /// previous context. The server stops when dropped
+    pub fn attach(&mut self, context: Option<&MainContext>) -> bool{
+        unsafe{
+            self.detach();
+            let context = context.cloned().unwrap_or_else(MainContext::default);
+            self.source = gst_rtsp_server_attach(self.server, context.gst_main_context());
+            self.context = Some(context);
+            self.source != 0
+        }
+    }
+
+    unsafe fn detach(&mut self){
+        if let Some(context) = self.context.take(){
+            let source = g_main_context_find_source_by_id(context.gst_main_context(), self.source);
+            if source != ptr::null_mut(){
+                g_source_destroy(source);
+            }
+        }
+        self.source = 0;
+    }
+
+    /// Calls f with the ip of each client that connects
+    pub fn on_client_connected<F: Fn(&str) + Send + Sync + 'static>(&self, f: F){
+        unsafe{
+            let callback: Callback<&str> = Arc::new(f);
+            connect_boxed(self.server as gpointer, "client-connected",
+                client_connected as extern "C" fn(*mut GstRTSPServer, *mut GstRTSPClient, gpointer) as gpointer,
+                Box::new(callback));
+        }
+    }
+
+    /// Calls f with the ip of each client that disconnects
+    pub fn on_client_disconnected<F: Fn(&str) + Send + Sync + 'static>(&self, f: F){
+        unsafe{
+            let callback: Callback<&str> = Arc::new(f);
+            connect_boxed(self.server as gpointer, "client-connected",
+                client_connected_watch_close as extern "C" fn(*mut GstRTSPServer, *mut GstRTSPClient, gpointer) as gpointer,
+                Box::new(callback));
+        }
+    }
+
+    pub unsafe fn gst_rtsp_server(&self) -> *mut GstRTSPServer{
+        self.server
+    }
+}
+
+extern "C" fn client_connected(_server: *mut GstRTSPServer, client: *mut GstRTSPClient, data: gpointer){
+    unsafe{
+        let callback = &*(data as *const Callback<&str>);
+        callback(&client_ip(client));
+    }
+}
+
+// The ip can't be read anymore once the client is closed, so it's kept
+// from the connection
+extern "C" fn client_connected_watch_close(_server: *mut GstRTSPServer, client: *mut GstRTSPClient, data: gpointer){
+    unsafe{
+        let callback = (*(data as *const Callback<&str>)).clone();
+        let closed = Box::new((callback, client_ip(client)));
+        connect_boxed(client as gpointer, "closed",
+            client_closed as extern "C" fn(*mut GstRTSPClient, gpointer) as gpointer, closed);
+    }
+}
+
+extern "C" fn client_closed(_client: *mut GstRTSPClient, data: gpointer){
+    unsafe{
+        let &(ref callback, ref ip) = &*(data as *const (Callback<&str>, String));
+        callback(ip);
+    }
+}
+
+/// Creates the pipelines served for a path of an RtspServer, from a launch
+/// line or from a closure building it
+pub struct RtspMediaFactory{
+    factory: *mut GstRTSPMediaFactory
+}
+
+unsafe impl Sync for RtspMediaFactory {}
+unsafe impl Send for RtspMediaFactory {}
+
+impl Drop for RtspMediaFactory{
+    fn drop(&mut self){
+        unsafe{
+            g_object_unref(self.factory as gpointer);
+        }
+    }
+}
+
+type CreatePipeline = Box<dyn Fn(&str) -> Option<Bin> + Send + Sync>;
+
+#[repr(C)]
+struct RsMediaFactory{
+    parent: GstRTSPMediaFactory,
+    create: *mut CreatePipeline,
+}
+
+static REGISTER: ::std::sync::Once = ::std::sync::Once::new();
+static mut RS_MEDIA_FACTORY_TYPE: GType = 0;
+static mut PARENT_CLASS: *mut GObjectClass = 0 as *mut GObjectClass;
+
+fn rs_media_factory_type() -> GType{
+    unsafe{
+        REGISTER.call_once(|| {
+            let mut info: GTypeInfo = mem::zeroed();
+            info.class_size = mem::size_of::<GstRTSPMediaFactoryClass>() as guint16;
+            info.class_init = Some(mem::transmute(rs_media_factory_class_init as extern "C" fn(gpointer, gpointer)));
+            info.instance_size = mem::size_of::<RsMediaFactory>() as guint16;
+            RS_MEDIA_FACTORY_TYPE = g_type_register_static(gst_rtsp_media_factory_get_type(),
+                to_c_str!("GstRsRTSPMediaFactory"), &info, 0);
+        });
+        RS_MEDIA_FACTORY_TYPE
+    }
+}
+
+extern "C" fn rs_media_factory_class_init(class: gpointer, _data: gpointer){
+    unsafe{
+        PARENT_CLASS = g_type_class_peek_parent(class) as *mut GObjectClass;
+        let factory_class = class as *mut GstRTSPMediaFactoryClass;
+        (*factory_class).create_element = Some(rs_media_factory_create_element);
+        (*factory_class).parent_class.finalize = Some(rs_media_factory_finalize);
+    }
+}
+
+extern "C" fn rs_media_factory_create_element(factory: *mut GstRTSPMediaFactory, url: *const GstRTSPUrl) -> *mut GstElement{
+    unsafe{
+        let create = (*(factory as *mut RsMediaFactory)).create;
+        let path = if url != ptr::null() && (*url).abspath != ptr::null_mut(){
+            from_c_str!((*url).abspath).to_string()
+        }else{
+            String::new()
+        };
+        match (*create)(&path){
+            Some(bin) => {
+                let element = ::Transfer::transfer(bin);
+                // the factory is expected to return a floating reference
+                g_object_force_floating(element as *mut GObject);
+                element
+            }
+            None => ptr::null_mut()
+        }
+    }
+}
+
+extern "C" fn rs_media_factory_finalize(object: *mut GObject){
+    unsafe{
+        let factory = object as *mut RsMediaFactory;
+        if (*factory).create != ptr::null_mut(){
+            drop(Box::from_raw((*factory).create));
+            (*factory).create = ptr::null_mut();
+        }
+        if let Some(finalize) = (*PARENT_CLASS).finalize{
+            finalize(object);
+        }
+    }
+}
+
+impl RtspMediaFactory{
+    /// Creates the pipelines from a launch line in parentheses, as
+    /// "( videotestsrc ! x264enc ! rtph264pay name=pay0 pt=96 )"
+    pub fn new_from_launch(launch: &str) -> RtspMediaFactory{
+        unsafe{
+            let factory = gst_rtsp_media_factory_new();
+            gst_rtsp_media_factory_set_launch(factory, to_c_str!(launch));
+            RtspMediaFactory{ factory: factory }
+        }
+    }
+
+    /// Creates the pipelines calling create with the path requested by
+    /// the client, for pipelines that can't be described with a launch
+    /// line, as ones with an appsrc fed by the application. The bin
+    /// returned has to contain the payloaders named pay0, pay1...
+    pub fn new_with_pipeline<F: Fn(&str) -> Option<Bin> + Send + Sync + 'static>(create: F) -> RtspMediaFactory{
+        unsafe{
+            let factory = g_object_new(rs_media_factory_type(), ptr::null::<gchar>()) as *mut RsMediaFactory;
+            let create: CreatePipeline = Box::new(create);
+            (*factory).create = Box::into_raw(Box::new(create));
+            RtspMediaFactory{ factory: factory as *mut GstRTSPMediaFactory }
+        }
+    }
+
+    /// Whether all the clients share the same pipeline instead of
+    /// creating one per client
+    pub fn set_shared(&self, shared: bool){
+        unsafe{
+            gst_rtsp_media_factory_set_shared(self.factory, shared as gboolean);
+        }
+    }
+
+    /// Latency in milliseconds of the rtpbin of the media
+    pub fn set_latency(&self, latency_ms: u32){
+        unsafe{
+            gst_rtsp_media_factory_set_latency(self.factory, latency_ms);
+        }
+    }
+
+    /// Whether an EOS is sent to the pipeline before shutting it down, so
+    /// recordings inside it are finalized
+    pub fn set_eos_shutdown(&self, eos_shutdown: bool){
+        unsafe{
+            gst_rtsp_media_factory_set_eos_shutdown(self.factory, eos_shutdown as gboolean);
+        }
+    }
+
+    /// Allows the users with role, see RtspAuth::add_user, to access and
+    /// create the media of this factory
+    pub fn allow_role(&self, role: &str){
+        unsafe{
+            gst_rtsp_media_factory_add_role(self.factory, to_c_str!(role),
+                to_c_str!("media.factory.access"), G_TYPE_BOOLEAN, 1 as gboolean,
+                to_c_str!("media.factory.construct"), G_TYPE_BOOLEAN, 1 as gboolean,
+                ptr::null::<gchar>());
+        }
+    }
+
+    /// Calls f with the pipeline of each media once created, before it
+    /// starts, to configure its elements
+    pub fn on_media_configure<F: Fn(&Element) + Send + Sync + 'static>(&self, f: F){
+        unsafe{
+            let callback: Callback<&Element> = Arc::new(f);
+            connect_boxed(self.factory as gpointer, "media-configure",
+                media_configure as extern "C" fn(*mut GstRTSPMediaFactory, *mut GstRTSPMedia, gpointer) as gpointer,
+                Box::new(callback));
+        }
+    }
+
+    pub unsafe fn gst_rtsp_media_factory(&self) -> *mut GstRTSPMediaFactory{
+        self.factory
+    }
+
+    unsafe fn transfer(self) -> *mut GstRTSPMediaFactory{
+        let factory = self.factory;
+        mem::forget(self);
+        factory
+    }
+}
+
+extern "C" fn media_configure(_factory: *mut GstRTSPMediaFactory, media: *mut GstRTSPMedia, data: gpointer){
+    unsafe{
+        let callback = &*(data as *const Callback<&Element>);
+        if let Some(element) = Element::new_from_gst_element(gst_rtsp_media_get_element(media)){
+            callback(&element);
+        }
+    }
+}
+
+/// Users allowed to connect to an RtspServer
+pub struct RtspAuth{
+    auth: *mut GstRTSPAuth
+}
+
+unsafe impl Sync for RtspAuth {}
+unsafe impl Send for RtspAuth {}
+
+impl Drop for RtspAuth{
+    fn drop(&mut self){
+        unsafe{
+            g_object_unref(self.auth as gpointer);
+        }
+    }
+}
+
+impl RtspAuth{
+    pub fn new() -> RtspAuth{
+        unsafe{
+            RtspAuth{ auth: gst_rtsp_auth_new() }
+        }
+    }
+
+    /// Adds a user that authenticates with basic authentication, with
+    /// the role given access to the factories with allow_role
+    pub fn add_user(&self, user: &str, password: &str, role: &str){
+        unsafe{
+            let token = gst_rtsp_token_new(to_c_str!("media.factory.role"), G_TYPE_STRING, to_c_str!(role),
+                ptr::null::<gchar>());
+            let basic = gst_rtsp_auth_make_basic(to_c_str!(user), to_c_str!(password));
+            gst_rtsp_auth_add_basic(self.auth, basic, token);
+            g_free(basic as gpointer);
+            gst_mini_object_unref(token as *mut GstMiniObject);
+        }
+    }
+
+    pub unsafe fn gst_rtsp_auth(&self) -> *mut GstRTSPAuth{
+        self.auth
+    }
+}
@@ -0,0 +1,148 @@
+use ffi::*;
+use bin::{Bin, BinT};
+use element::{Element, ElementT};
+use element_factory::ElementFactory;
+use value::Value;
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+enum Backend{
+    XImage,
+    D3D11,
+    DX9,
+    AVFoundation,
+}
+
+#[cfg(target_os="linux")]
+const SCREEN_SOURCES: &'static [(&'static str, Backend)] = &[("ximagesrc", Backend::XImage)];
+#[cfg(target_os="macos")]
+const SCREEN_SOURCES: &'static [(&'static str, Backend)] = &[("avfvideosrc", Backend::AVFoundation)];
+#[cfg(target_os="windows")]
+const SCREEN_SOURCES: &'static [(&'static str, Backend)] = &[("d3d11screencapturesrc", Backend::D3D11), ("dx9screencapsrc", Backend::DX9)];
+#[cfg(not(any(target_os="linux", target_os="macos", target_os="windows")))]
+const SCREEN_SOURCES: &'static [(&'static str, Backend)] = &[];
+
+/// A screen capture as a bin with a single raw video src pad.
+///
+/// It wraps the screen capture element of the platform, ximagesrc,
+/// d3d11screencapturesrc, dx9screencapsrc or avfvideosrc with
+/// capture-screen, followed by a capsfilter for the framerate and a
+/// videoconvert, so it can be linked like any other element.
+pub struct ScreenCapture{
+    bin: Bin,
+    source: Element,
+    capsfilter: Element,
+    backend: Backend,
+}
+
+unsafe impl Sync for ScreenCapture {}
+unsafe impl Send for ScreenCapture {}
+
+impl ScreenCapture{
+    /// Creates a screen capture of the main screen with the first
+    /// available screen capture element for this platform
+    pub fn new(name: &str) -> Option<ScreenCapture>{
+        let &(factory, backend) = SCREEN_SOURCES.iter()
+            .find(|&&(factory, _)| ElementFactory::find(factory).is_some())?;
+        let source = Element::new(factory, "")?;
+        match backend{
+            Backend::XImage => source.set("use-damage", 0 as gboolean),
+            Backend::AVFoundation => source.set("capture-screen", 1 as gboolean),
+            _ => ()
+        }
+
+        let mut bin = Bin::new(name)?;
+        let capsfilter = Element::new("capsfilter", "")?;
+        let convert = Element::new("videoconvert", "")?;
+        let mut source_ref = source.to_element();
+        let mut capsfilter_ref = capsfilter.to_element();
+        let mut convert_ref = convert.to_element();
+        if !bin.add(source) || !bin.add(capsfilter) || !bin.add(convert){
+            return None;
+        }
+        if !source_ref.link(&mut capsfilter_ref) || !capsfilter_ref.link(&mut convert_ref){
+            return None;
+        }
+        let src = convert_ref.static_pad("src")?;
+        if !bin.add_ghost_pad("src", &src){
+            return None;
+        }
+        Some(ScreenCapture{
+            bin: bin,
+            source: source_ref,
+            capsfilter: capsfilter_ref,
+            backend: backend,
+        })
+    }
+
+    /// Name of the element used for the capture
+    pub fn source_factory(&self) -> Option<String>{
+        self.source.factory_name()
+    }
+
+    /// Captures only the given region of the screen, in pixels.
+    ///
+    /// Returns false for an empty region or if the capture element doesn't
+    /// support regions, as is the case on macOS
+    pub fn set_region(&mut self, x: u32, y: u32, width: u32, height: u32) -> bool{
+        if width == 0 || height == 0{
+            return false;
+        }
+        match self.backend{
+            Backend::XImage => {
+                self.source.set("startx", x);
+                self.source.set("starty", y);
+                self.source.set("endx", x + width - 1);
+                self.source.set("endy", y + height - 1);
+                true
+            }
+            Backend::D3D11 => {
+                self.source.set("crop-x", x);
+                self.source.set("crop-y", y);
+                self.source.set("crop-width", width);
+                self.source.set("crop-height", height);
+                true
+            }
+            Backend::DX9 => {
+                self.source.set("x", x as i32);
+                self.source.set("y", y as i32);
+                self.source.set("width", width as i32);
+                self.source.set("height", height as i32);
+                true
+            }
+            Backend::AVFoundation => false
+        }
+    }
+
+    /// Shows or hides the mouse cursor in the capture
+    pub fn set_show_cursor(&mut self, show: bool){
+        let property = match self.backend{
+            Backend::XImage => "show-pointer",
+            Backend::D3D11 => "show-cursor",
+            Backend::DX9 => "cursor",
+            Backend::AVFoundation => "capture-screen-cursor",
+        };
+        self.source.set(property, show as gboolean);
+    }
+
+    /// Sets the framerate of the capture as a fraction, e.g. 30/1
+    pub fn set_framerate(&mut self, numerator: i32, denominator: i32) -> bool{
+        let caps = format!("video/x-raw,framerate={}/{}", numerator, denominator);
+        self.capsfilter.set_property("caps", &Value::String(caps)).is_ok()
+    }
+}
+
+impl BinT for ScreenCapture{
+    fn as_bin(&self) -> &Bin{
+        &self.bin
+    }
+
+    fn as_bin_mut(&mut self) -> &mut Bin{
+        &mut self.bin
+    }
+}
+
+impl ::Transfer for ScreenCapture{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.bin.transfer()
+    }
+}
@@ -0,0 +1,166 @@
+use ffi::*;
+use element::ElementT;
+use event::Event;
+
+// The trickmode flags were added in GStreamer 1.6 and INSTANT_RATE_CHANGE
+// and TRICKMODE_FORWARD_PREDICTED in 1.18, after ffi.rs was generated
+pub const GST_SEEK_FLAG_TRICKMODE: GstSeekFlags = GST_SEEK_FLAG_SKIP;
+pub const GST_SEEK_FLAG_TRICKMODE_KEY_UNITS: GstSeekFlags = 128;
+pub const GST_SEEK_FLAG_TRICKMODE_NO_AUDIO: GstSeekFlags = 256;
+pub const GST_SEEK_FLAG_TRICKMODE_FORWARD_PREDICTED: GstSeekFlags = 512;
+pub const GST_SEEK_FLAG_INSTANT_RATE_CHANGE: GstSeekFlags = 1024;
+
+bitflags!{
+    /// Flags of a seek, see Seek::builder
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct SeekFlags: u32{
+        /// Drops the queued data so the seek takes effect immediately
+        const FLUSH = GST_SEEK_FLAG_FLUSH;
+        /// Goes to the exact position, decoding from the previous
+        /// keyframe, instead of the closest position that's fast to reach
+        const ACCURATE = GST_SEEK_FLAG_ACCURATE;
+        /// Goes to the keyframe closest to the position
+        const KEY_UNIT = GST_SEEK_FLAG_KEY_UNIT;
+        /// Posts SEGMENT_DONE instead of EOS at the stop position, to
+        /// loop seamlessly with another segment seek
+        const SEGMENT = GST_SEEK_FLAG_SEGMENT;
+        /// Allows skipping frames to play at fast rates
+        const TRICKMODE = GST_SEEK_FLAG_TRICKMODE;
+        /// With KEY_UNIT, goes to the keyframe before the position
+        const SNAP_BEFORE = GST_SEEK_FLAG_SNAP_BEFORE;
+        /// With KEY_UNIT, goes to the keyframe after the position
+        const SNAP_AFTER = GST_SEEK_FLAG_SNAP_AFTER;
+        /// With KEY_UNIT, goes to the nearest keyframe
+        const SNAP_NEAREST = GST_SEEK_FLAG_SNAP_NEAREST;
+        /// With TRICKMODE, only decodes keyframes
+        const TRICKMODE_KEY_UNITS = GST_SEEK_FLAG_TRICKMODE_KEY_UNITS;
+        /// With TRICKMODE, doesn't decode audio
+        const TRICKMODE_NO_AUDIO = GST_SEEK_FLAG_TRICKMODE_NO_AUDIO;
+        /// With TRICKMODE, skips the frames no other frame depends on
+        const TRICKMODE_FORWARD_PREDICTED = GST_SEEK_FLAG_TRICKMODE_FORWARD_PREDICTED;
+        /// Changes the rate without flushing or changing the position,
+        /// can't be combined with FLUSH or a start or stop position
+        const INSTANT_RATE_CHANGE = GST_SEEK_FLAG_INSTANT_RATE_CHANGE;
+    }
+}
+
+/// The parameters of a seek, created with Seek::builder
+///
+/// ```ignore
+/// let seek = Seek::builder()
+///     .rate(2.0)
+///     .flags(SeekFlags::FLUSH | SeekFlags::ACCURATE)
+///     .start(10 * 1000000000)
+///     .build();
+/// seek.apply(&mut pipeline);
+/// ```
+#[derive(Clone,Copy,Debug)]
+pub struct Seek{
+    pub rate: f64,
+    pub format: GstFormat,
+    pub flags: SeekFlags,
+    pub start_type: GstSeekType,
+    pub start: i64,
+    pub stop_type: GstSeekType,
+    pub stop: i64,
+}
+
+impl Seek{
+    /// A seek in time at normal rate, flushing, that doesn't change the
+    /// start or stop positions until set
+    pub fn builder() -> SeekBuilder{
+        SeekBuilder{
+            seek: Seek{
+                rate: 1.0,
+                format: GST_FORMAT_TIME,
+                flags: SeekFlags::FLUSH,
+                start_type: GST_SEEK_TYPE_NONE,
+                start: -1,
+                stop_type: GST_SEEK_TYPE_NONE,
+                stop: -1,
+            }
+        }
+    }
+
+    /// A SEEK event with these parameters, to send it to a pad
+    pub fn to_event(&self) -> Event{
+        unsafe{
+            Event::new(gst_event_new_seek(self.rate, self.format, self.flags.bits(),
+                self.start_type, self.start, self.stop_type, self.stop), true).unwrap()
+        }
+    }
+
+    /// Sends the seek to element, usually the pipeline. Returns false if
+    /// the seek couldn't be done, as in streams that aren't seekable
+    pub fn apply<E: ElementT>(&self, element: &mut E) -> bool{
+        element.seek(self.rate, self.format, self.flags.bits(), self.start_type, self.start,
+            self.stop_type, self.stop)
+    }
+}
+
+pub struct SeekBuilder{
+    seek: Seek
+}
+
+impl SeekBuilder{
+    /// Playback rate, negative to play backwards. When playing backwards
+    /// the playback starts from the stop position
+    pub fn rate(mut self, rate: f64) -> SeekBuilder{
+        self.seek.rate = rate;
+        self
+    }
+
+    /// Format of the positions, GST_FORMAT_TIME by default
+    pub fn format(mut self, format: GstFormat) -> SeekBuilder{
+        self.seek.format = format;
+        self
+    }
+
+    /// Replaces the flags, FLUSH by default
+    pub fn flags(mut self, flags: SeekFlags) -> SeekBuilder{
+        self.seek.flags = flags;
+        self
+    }
+
+    /// Start position, in nanoseconds for GST_FORMAT_TIME
+    pub fn start(mut self, start: u64) -> SeekBuilder{
+        self.seek.start_type = GST_SEEK_TYPE_SET;
+        self.seek.start = start as i64;
+        self
+    }
+
+    /// Stop position, in nanoseconds for GST_FORMAT_TIME
+    pub fn stop(mut self, stop: u64) -> SeekBuilder{
+        self.seek.stop_type = GST_SEEK_TYPE_SET;
+        self.seek.stop = stop as i64;
+        self
+    }
+
+    /// Start position relative to the end of the stream, usually
+    /// negative
+    pub fn start_from_end(mut self, offset: i64) -> SeekBuilder{
+        self.seek.start_type = GST_SEEK_TYPE_END;
+        self.seek.start = offset;
+        self
+    }
+
+    /// Stop position relative to the end of the stream, usually
+    /// negative
+    pub fn stop_from_end(mut self, offset: i64) -> SeekBuilder{
+        self.seek.stop_type = GST_SEEK_TYPE_END;
+        self.seek.stop = offset;
+        self
+    }
+
+    /// Plays until the end of the stream, instead of keeping the current
+    /// stop position
+    pub fn until_end(mut self) -> SeekBuilder{
+        self.seek.stop_type = GST_SEEK_TYPE_SET;
+        self.seek.stop = -1;
+        self
+    }
+
+    pub fn build(self) -> Seek{
+        self.seek
+    }
+}
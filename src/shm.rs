@@ -0,0 +1,169 @@
+use ffi::*;
+use util::*;
+use bin::Bin;
+use element::{Element, ElementT};
+use error::Result;
+use value::Value;
+
+extern "C" fn client_callback<F: Fn(i32) + Send + Sync + 'static>(_element: *mut GstElement, client: gint, data: gpointer){
+    unsafe{
+        (*(data as *const F))(client);
+    }
+}
+
+/// Wrapper around shmsink, which writes the buffers to shared memory and
+/// announces them through a unix socket to the shmsrc of other processes.
+///
+/// Only the data goes through, not the caps or timestamps, see
+/// shm_sender to send the whole stream
+pub struct ShmSink{
+    shmsink: Element
+}
+
+unsafe impl Sync for ShmSink {}
+unsafe impl Send for ShmSink {}
+
+impl ShmSink{
+    pub fn new(name: &str) -> Option<ShmSink>{
+        Element::new("shmsink", name).map(|shmsink| ShmSink{ shmsink: shmsink })
+    }
+
+    pub fn new_from_element(element: Element) -> ShmSink{
+        ShmSink{ shmsink: element }
+    }
+
+    /// Path of the control socket the sink creates and the sources
+    /// connect to, as "/tmp/camera0"
+    pub fn set_socket_path(&self, path: &str){
+        self.shmsink.set("socket-path", to_c_str!(path));
+    }
+
+    /// Size in bytes of the shared memory area, it has to fit the buffers
+    /// not yet released by all the sources
+    pub fn set_shm_size(&self, size: u32){
+        self.shmsink.set("shm-size", size);
+    }
+
+    /// Permissions of the shared memory area, as 0o660
+    pub fn set_perms(&self, perms: u32){
+        self.shmsink.set("perms", perms);
+    }
+
+    /// Whether the sink blocks until a source connects instead of
+    /// dropping the buffers
+    pub fn set_wait_for_connection(&self, wait: bool){
+        self.shmsink.set("wait-for-connection", wait as gboolean);
+    }
+
+    /// Maximum time in nanoseconds of the buffers kept in the shared
+    /// memory, -1 for no limit
+    pub fn set_buffer_time(&self, buffer_time: i64){
+        self.shmsink.set("buffer-time", buffer_time);
+    }
+
+    /// Calls f with the id of each source that connects
+    pub fn on_client_connected<F: Fn(i32) + Send + Sync + 'static>(&self, f: F){
+        self.connect_client_signal("client-connected", f);
+    }
+
+    /// Calls f with the id of each source that disconnects
+    pub fn on_client_disconnected<F: Fn(i32) + Send + Sync + 'static>(&self, f: F){
+        self.connect_client_signal("client-disconnected", f);
+    }
+
+    fn connect_client_signal<F: Fn(i32) + Send + Sync + 'static>(&self, signal: &str, f: F){
+        unsafe{
+            connect_boxed(self.shmsink.gst_element() as gpointer, signal,
+                client_callback::<F> as extern "C" fn(*mut GstElement, gint, gpointer) as gpointer,
+                Box::new(f));
+        }
+    }
+}
+
+impl ElementT for ShmSink{
+    fn as_element(&self) -> &Element{
+        &self.shmsink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.shmsink
+    }
+}
+
+impl ::Transfer for ShmSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.shmsink.transfer()
+    }
+}
+
+/// Wrapper around shmsrc, which receives the buffers written by a shmsink
+/// in another process. The buffers have no caps, they have to be set
+/// with a capsfilter or, with shm_receiver, sent along the stream
+pub struct ShmSrc{
+    shmsrc: Element
+}
+
+unsafe impl Sync for ShmSrc {}
+unsafe impl Send for ShmSrc {}
+
+impl ShmSrc{
+    pub fn new(name: &str) -> Option<ShmSrc>{
+        Element::new("shmsrc", name).map(|shmsrc| ShmSrc{ shmsrc: shmsrc })
+    }
+
+    pub fn new_from_element(element: Element) -> ShmSrc{
+        ShmSrc{ shmsrc: element }
+    }
+
+    /// Path of the control socket of the shmsink to connect to
+    pub fn set_socket_path(&self, path: &str){
+        self.shmsrc.set("socket-path", to_c_str!(path));
+    }
+
+    /// Whether the source timestamps the buffers with the running time
+    /// they are received at, as a live source
+    pub fn set_is_live(&self, is_live: bool){
+        self.shmsrc.set("is-live", is_live as gboolean);
+    }
+
+    /// Name of the shared memory area once connected
+    pub fn shm_area_name(&self) -> Option<String>{
+        match self.shmsrc.property("shm-area-name"){
+            Some(Value::String(name)) => Some(name),
+            _ => None
+        }
+    }
+}
+
+impl ElementT for ShmSrc{
+    fn as_element(&self) -> &Element{
+        &self.shmsrc
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.shmsrc
+    }
+}
+
+impl ::Transfer for ShmSrc{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.shmsrc.transfer()
+    }
+}
+
+/// A bin with a sink pad sending the stream, caps and timestamps
+/// included, to the shm_receiver of other processes connected to
+/// socket_path. Buffers are dropped while no receiver is connected
+pub fn shm_sender(socket_path: &str, shm_size: u32) -> Result<Bin>{
+    Bin::new_from_description(&format!(
+        "gdppay ! shmsink socket-path=\"{}\" shm-size={} wait-for-connection=false sync=false",
+        socket_path, shm_size))
+}
+
+/// A bin with a src pad outputting the stream of the shm_sender at
+/// socket_path, with the caps it was sent with
+pub fn shm_receiver(socket_path: &str) -> Result<Bin>{
+    Bin::new_from_description(&format!(
+        "shmsrc socket-path=\"{}\" is-live=true ! gdpdepay",
+        socket_path))
+}
@@ -0,0 +1,722 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use caps::Caps;
+use event::Event;
+use element::{Element, ElementT};
+use meta::GstAudioInfo;
+use subclass::*;
+
+use std::any;
+use std::os::raw::c_void;
+use std::slice;
+
+// The audio sink base classes aren't in ffi.rs, which was generated
+// without gstaudio
+#[repr(C)]
+struct GstAudioBaseSinkClass{
+    parent_class: GstBaseSinkClass,
+    create_ringbuffer: Option<extern "C" fn(*mut GstElement) -> *mut GstAudioRingBuffer>,
+    payload: gpointer,
+    _gst_reserved: [gpointer; 4],
+}
+
+// Only the fields up to the memory of the segments, which subclasses
+// allocate in acquire
+#[repr(C)]
+pub struct GstAudioRingBuffer{
+    object: GstObject,
+    cond: GCond,
+    open: gboolean,
+    acquired: gboolean,
+    memory: *mut guint8,
+    size: gsize,
+}
+
+#[repr(C)]
+struct GstAudioRingBufferClass{
+    parent_class: GstObjectClass,
+    open_device: Option<extern "C" fn(*mut GstAudioRingBuffer) -> gboolean>,
+    acquire: Option<extern "C" fn(*mut GstAudioRingBuffer, *mut GstAudioRingBufferSpec) -> gboolean>,
+    release: Option<extern "C" fn(*mut GstAudioRingBuffer) -> gboolean>,
+    close_device: Option<extern "C" fn(*mut GstAudioRingBuffer) -> gboolean>,
+    start: Option<extern "C" fn(*mut GstAudioRingBuffer) -> gboolean>,
+    pause: Option<extern "C" fn(*mut GstAudioRingBuffer) -> gboolean>,
+    resume: Option<extern "C" fn(*mut GstAudioRingBuffer) -> gboolean>,
+    stop: Option<extern "C" fn(*mut GstAudioRingBuffer) -> gboolean>,
+    delay: Option<extern "C" fn(*mut GstAudioRingBuffer) -> guint>,
+    activate: gpointer,
+    commit: gpointer,
+    clear_all: gpointer,
+    _gst_reserved: [gpointer; 4],
+}
+
+#[repr(C)]
+struct GstAudioSinkClass{
+    parent_class: GstAudioBaseSinkClass,
+    open: Option<extern "C" fn(*mut GstElement) -> gboolean>,
+    prepare: Option<extern "C" fn(*mut GstElement, *mut GstAudioRingBufferSpec) -> gboolean>,
+    unprepare: Option<extern "C" fn(*mut GstElement) -> gboolean>,
+    close: Option<extern "C" fn(*mut GstElement) -> gboolean>,
+    write: Option<extern "C" fn(*mut GstElement, gpointer, guint) -> gint>,
+    delay: Option<extern "C" fn(*mut GstElement) -> guint>,
+    reset: Option<extern "C" fn(*mut GstElement)>,
+}
+
+/// The format the device has to be opened with and the size of the ring
+/// buffer between the sink and the device, in segments of segsize bytes
+#[repr(C)]
+pub struct GstAudioRingBufferSpec{
+    pub caps: *mut GstCaps,
+    pub _type: gint,
+    pub info: GstAudioInfo,
+    /// Duration of a segment in microseconds
+    pub latency_time: guint64,
+    /// Duration of the whole buffer in microseconds
+    pub buffer_time: guint64,
+    pub segsize: gint,
+    pub segtotal: gint,
+    pub seglatency: gint,
+    _gst_reserved: [gpointer; 4],
+}
+
+#[cfg_attr(not(target_os="macos"), link(name = "gstaudio-1.0"))]
+extern "C"{
+    fn gst_audio_sink_get_type() -> GType;
+    fn gst_audio_base_sink_get_type() -> GType;
+    fn gst_audio_ring_buffer_get_type() -> GType;
+    fn gst_audio_ring_buffer_prepare_read(buf: *mut GstAudioRingBuffer, segment: *mut gint, readptr: *mut *mut guint8,
+        len: *mut gint) -> gboolean;
+    fn gst_audio_ring_buffer_clear(buf: *mut GstAudioRingBuffer, segment: gint);
+    fn gst_audio_ring_buffer_advance(buf: *mut GstAudioRingBuffer, advance: guint);
+    fn gst_audio_format_fill_silence(info: *const c_void, dest: gpointer, length: gsize);
+}
+
+/// Properties of the sinks deriving from GstAudioBaseSink, as the ones
+/// implemented with AudioSinkImpl, pulsesink or alsasink
+pub trait AudioBaseSinkT: ElementT{
+    /// Size of the buffer to the device in microseconds
+    fn set_buffer_time(&self, buffer_time_us: i64) where Self: Sized{
+        self.set("buffer-time", buffer_time_us);
+    }
+
+    /// Duration of each write to the device in microseconds, the latency
+    /// of the sink
+    fn set_latency_time(&self, latency_time_us: i64) where Self: Sized{
+        self.set("latency-time", latency_time_us);
+    }
+
+    /// Whether the sink provides a clock driven by the device, so the
+    /// pipeline follows the audio hardware instead of drifting from it
+    fn set_provide_clock(&self, provide: bool) where Self: Sized{
+        self.set("provide-clock", provide as gboolean);
+    }
+
+    /// Tolerance in microseconds before the sink resyncs to the pipeline
+    /// clock when it doesn't provide it
+    fn set_drift_tolerance(&self, tolerance_us: i64) where Self: Sized{
+        self.set("drift-tolerance", tolerance_us);
+    }
+
+    /// Timestamp difference in nanoseconds after which buffers are
+    /// considered discontinuous
+    fn set_alignment_threshold(&self, threshold: u64) where Self: Sized{
+        self.set("alignment-threshold", threshold);
+    }
+}
+
+/// An audio output implemented in Rust, as a cpal stream, on top of
+/// GstAudioSink which synchronizes to the clock, provides a clock from
+/// the device and handles the latency. The device is fed from a ring
+/// buffer in a thread of the base class. Register it with
+/// register_audio_sink.
+///
+/// The pad template has to be named "sink" with audio/x-raw caps
+pub trait AudioSinkImpl: ElementImpl{
+    /// Opens the device, to query its capabilities
+    fn open(&self, _sink: &AudioSink) -> bool{
+        true
+    }
+
+    /// Configures the device for the format in spec, adjusting segsize
+    /// and segtotal to what the device supports
+    fn prepare(&self, sink: &AudioSink, spec: &mut GstAudioRingBufferSpec) -> bool;
+
+    /// Undoes prepare
+    fn unprepare(&self, _sink: &AudioSink) -> bool{
+        true
+    }
+
+    fn close(&self, _sink: &AudioSink) -> bool{
+        true
+    }
+
+    /// Writes samples to the device, blocking until there's room for
+    /// them. Returns the number of bytes written or -1 on error
+    fn write(&self, sink: &AudioSink, data: &[u8]) -> i32;
+
+    /// Number of frames written but not played yet by the device
+    fn delay(&self, _sink: &AudioSink) -> u32{
+        0
+    }
+
+    /// Discards the samples queued in the device and unblocks write, on
+    /// flushes and pauses
+    fn reset(&self, _sink: &AudioSink){}
+
+    /// Called with each event the sink receives before the base class
+    /// handles it, as the custom events sent by the application
+    fn event(&self, _sink: &AudioSink, _event: &Event){}
+}
+
+/// An audio sink as passed to the methods of an AudioSinkImpl
+pub struct AudioSink{
+    sink: Element
+}
+
+unsafe impl Sync for AudioSink {}
+unsafe impl Send for AudioSink {}
+
+impl AudioSink{
+    unsafe fn new(sink: *mut GstElement) -> AudioSink{
+        gst_object_ref(sink as gpointer);
+        AudioSink{ sink: Element::new_from_gst_element(sink).unwrap() }
+    }
+}
+
+impl AudioBaseSinkT for AudioSink{}
+
+impl ElementT for AudioSink{
+    fn as_element(&self) -> &Element{
+        &self.sink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.sink
+    }
+}
+
+impl ::Transfer for AudioSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.sink.transfer()
+    }
+}
+
+extern "C" fn audio_sink_class_init<T: AudioSinkImpl>(class: gpointer, _data: gpointer){
+    unsafe{
+        element_class_init::<T>(class);
+        let sink_class = class as *mut GstAudioSinkClass;
+        (*sink_class).open = Some(audio_sink_open::<T>);
+        (*sink_class).prepare = Some(audio_sink_prepare::<T>);
+        (*sink_class).unprepare = Some(audio_sink_unprepare::<T>);
+        (*sink_class).close = Some(audio_sink_close::<T>);
+        (*sink_class).write = Some(audio_sink_write::<T>);
+        (*sink_class).delay = Some(audio_sink_delay::<T>);
+        (*sink_class).reset = Some(audio_sink_reset::<T>);
+        (*(class as *mut GstBaseSinkClass)).event = Some(audio_sink_event::<T>);
+    }
+}
+
+extern "C" fn audio_sink_open<T: AudioSinkImpl>(sink: *mut GstElement) -> gboolean{
+    unsafe{
+        imp::<T>(sink as gpointer).open(&AudioSink::new(sink)) as gboolean
+    }
+}
+
+extern "C" fn audio_sink_prepare<T: AudioSinkImpl>(sink: *mut GstElement, spec: *mut GstAudioRingBufferSpec) -> gboolean{
+    unsafe{
+        if spec == ptr::null_mut(){
+            return 0;
+        }
+        imp::<T>(sink as gpointer).prepare(&AudioSink::new(sink), &mut *spec) as gboolean
+    }
+}
+
+extern "C" fn audio_sink_unprepare<T: AudioSinkImpl>(sink: *mut GstElement) -> gboolean{
+    unsafe{
+        imp::<T>(sink as gpointer).unprepare(&AudioSink::new(sink)) as gboolean
+    }
+}
+
+extern "C" fn audio_sink_close<T: AudioSinkImpl>(sink: *mut GstElement) -> gboolean{
+    unsafe{
+        imp::<T>(sink as gpointer).close(&AudioSink::new(sink)) as gboolean
+    }
+}
+
+extern "C" fn audio_sink_write<T: AudioSinkImpl>(sink: *mut GstElement, data: gpointer, length: guint) -> gint{
+    unsafe{
+        let data = slice::from_raw_parts(data as *const u8, length as usize);
+        imp::<T>(sink as gpointer).write(&AudioSink::new(sink), data)
+    }
+}
+
+extern "C" fn audio_sink_delay<T: AudioSinkImpl>(sink: *mut GstElement) -> guint{
+    unsafe{
+        imp::<T>(sink as gpointer).delay(&AudioSink::new(sink))
+    }
+}
+
+extern "C" fn audio_sink_reset<T: AudioSinkImpl>(sink: *mut GstElement){
+    unsafe{
+        imp::<T>(sink as gpointer).reset(&AudioSink::new(sink));
+    }
+}
+
+extern "C" fn audio_sink_event<T: AudioSinkImpl>(sink: *mut GstBaseSink, event: *mut GstEvent) -> gboolean{
+    unsafe{
+        if let Some(wrapper) = Event::new(event, false){
+            imp::<T>(sink as gpointer).event(&AudioSink::new(sink as *mut GstElement), &wrapper);
+        }
+        match (*parent_class::<GstBaseSinkClass>(sink as gpointer)).event{
+            Some(event_func) => event_func(sink, event),
+            None => {
+                gst_mini_object_unref(event as *mut GstMiniObject);
+                0
+            }
+        }
+    }
+}
+
+/// Registers an audio sink implemented in Rust so it can be created by
+/// name with Element::new, in launch lines, and picked by autoaudiosink
+/// if the rank is GST_RANK_PRIMARY or higher and the classification
+/// contains "Sink/Audio"
+pub fn register_audio_sink<T: AudioSinkImpl>(name: &str, rank: u32) -> bool{
+    unsafe{
+        register_element(gst_audio_sink_get_type(), name, rank, audio_sink_class_init::<T>, instance_init::<T>)
+    }
+}
+
+/// The ring buffer of an AudioBaseSinkImpl, for outputs whose device
+/// pulls the samples from its own thread or callback, as a cpal stream,
+/// instead of being written to as with AudioSinkImpl. The device reads
+/// each segment with AudioRingBuffer::read_segment while started
+pub trait AudioRingBufferImpl: Default + Send + Sync + 'static{
+    fn open_device(&self, _ring_buffer: &AudioRingBuffer) -> bool{
+        true
+    }
+
+    fn close_device(&self, _ring_buffer: &AudioRingBuffer) -> bool{
+        true
+    }
+
+    /// Configures the device for the format in spec, adjusting segsize
+    /// and segtotal to what the device supports. The memory for the
+    /// segments is allocated once it returns true
+    fn acquire(&self, ring_buffer: &AudioRingBuffer, spec: &mut GstAudioRingBufferSpec) -> bool;
+
+    /// Undoes acquire, the memory of the segments is freed afterwards
+    fn release(&self, _ring_buffer: &AudioRingBuffer) -> bool{
+        true
+    }
+
+    /// Starts the device reading segments
+    fn start(&self, ring_buffer: &AudioRingBuffer) -> bool;
+
+    fn pause(&self, _ring_buffer: &AudioRingBuffer) -> bool{
+        true
+    }
+
+    fn resume(&self, ring_buffer: &AudioRingBuffer) -> bool{
+        self.start(ring_buffer)
+    }
+
+    /// Stops the device, read_segment can't be called afterwards
+    fn stop(&self, ring_buffer: &AudioRingBuffer) -> bool;
+
+    /// Number of frames read but not played yet by the device
+    fn delay(&self, _ring_buffer: &AudioRingBuffer) -> u32{
+        0
+    }
+}
+
+/// A ring buffer implemented in Rust, created with new for an
+/// AudioBaseSinkImpl or passed to the methods of an AudioRingBufferImpl
+pub struct AudioRingBuffer{
+    ring_buffer: *mut GstAudioRingBuffer
+}
+
+unsafe impl Sync for AudioRingBuffer {}
+unsafe impl Send for AudioRingBuffer {}
+
+impl Drop for AudioRingBuffer{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.ring_buffer as gpointer);
+        }
+    }
+}
+
+impl AudioRingBuffer{
+    /// Creates a ring buffer implemented by R
+    pub fn new<R: AudioRingBufferImpl>() -> Option<AudioRingBuffer>{
+        unsafe{
+            let ring_buffer_type = register_type(gst_audio_ring_buffer_get_type(),
+                &type_name(&format!("ringbuffer-{}", any::type_name::<R>())),
+                ring_buffer_class_init::<R>, instance_init::<R>);
+            if ring_buffer_type == 0{
+                return None;
+            }
+            let ring_buffer = g_object_new(ring_buffer_type, ptr::null::<gchar>());
+            gst_object_ref_sink(ring_buffer);
+            Some(AudioRingBuffer{ ring_buffer: ring_buffer as *mut GstAudioRingBuffer })
+        }
+    }
+
+    unsafe fn new_from_gst_ring_buffer(ring_buffer: *mut GstAudioRingBuffer) -> AudioRingBuffer{
+        gst_object_ref(ring_buffer as gpointer);
+        AudioRingBuffer{ ring_buffer: ring_buffer }
+    }
+
+    /// Calls f with the next segment to play, of spec.segsize bytes,
+    /// which is then cleared so it plays silence if the sink doesn't
+    /// fill it again in time. Returns false without calling f if the
+    /// ring buffer isn't started
+    pub fn read_segment<F: FnOnce(&[u8])>(&self, f: F) -> bool{
+        unsafe{
+            let mut segment: gint = 0;
+            let mut data: *mut guint8 = ptr::null_mut();
+            let mut len: gint = 0;
+            if gst_audio_ring_buffer_prepare_read(self.ring_buffer, &mut segment, &mut data, &mut len) == 0{
+                return false;
+            }
+            f(slice::from_raw_parts(data, len as usize));
+            gst_audio_ring_buffer_clear(self.ring_buffer, segment);
+            gst_audio_ring_buffer_advance(self.ring_buffer, 1);
+            true
+        }
+    }
+
+    pub unsafe fn gst_audio_ring_buffer(&self) -> *mut GstAudioRingBuffer{
+        self.ring_buffer
+    }
+}
+
+extern "C" fn ring_buffer_class_init<R: AudioRingBufferImpl>(class: gpointer, _data: gpointer){
+    unsafe{
+        install_finalize::<R>(class);
+        let ring_buffer_class = class as *mut GstAudioRingBufferClass;
+        (*ring_buffer_class).open_device = Some(ring_buffer_open_device::<R>);
+        (*ring_buffer_class).close_device = Some(ring_buffer_close_device::<R>);
+        (*ring_buffer_class).acquire = Some(ring_buffer_acquire::<R>);
+        (*ring_buffer_class).release = Some(ring_buffer_release::<R>);
+        (*ring_buffer_class).start = Some(ring_buffer_start::<R>);
+        (*ring_buffer_class).pause = Some(ring_buffer_pause::<R>);
+        (*ring_buffer_class).resume = Some(ring_buffer_resume::<R>);
+        (*ring_buffer_class).stop = Some(ring_buffer_stop::<R>);
+        (*ring_buffer_class).delay = Some(ring_buffer_delay::<R>);
+    }
+}
+
+extern "C" fn ring_buffer_open_device<R: AudioRingBufferImpl>(ring_buffer: *mut GstAudioRingBuffer) -> gboolean{
+    unsafe{
+        imp::<R>(ring_buffer as gpointer).open_device(&AudioRingBuffer::new_from_gst_ring_buffer(ring_buffer)) as gboolean
+    }
+}
+
+extern "C" fn ring_buffer_close_device<R: AudioRingBufferImpl>(ring_buffer: *mut GstAudioRingBuffer) -> gboolean{
+    unsafe{
+        imp::<R>(ring_buffer as gpointer).close_device(&AudioRingBuffer::new_from_gst_ring_buffer(ring_buffer)) as gboolean
+    }
+}
+
+extern "C" fn ring_buffer_acquire<R: AudioRingBufferImpl>(ring_buffer: *mut GstAudioRingBuffer, spec: *mut GstAudioRingBufferSpec) -> gboolean{
+    unsafe{
+        if spec == ptr::null_mut() ||
+            !imp::<R>(ring_buffer as gpointer).acquire(&AudioRingBuffer::new_from_gst_ring_buffer(ring_buffer), &mut *spec){
+            return 0;
+        }
+        if (*spec).segsize <= 0 || (*spec).segtotal <= 0{
+            return 0;
+        }
+        let size = (*spec).segsize as gsize * (*spec).segtotal as gsize;
+        (*ring_buffer).memory = g_malloc(size) as *mut guint8;
+        (*ring_buffer).size = size;
+        gst_audio_format_fill_silence((*spec).info.finfo, (*ring_buffer).memory as gpointer, size);
+        1
+    }
+}
+
+extern "C" fn ring_buffer_release<R: AudioRingBufferImpl>(ring_buffer: *mut GstAudioRingBuffer) -> gboolean{
+    unsafe{
+        let released = imp::<R>(ring_buffer as gpointer).release(&AudioRingBuffer::new_from_gst_ring_buffer(ring_buffer));
+        g_free((*ring_buffer).memory as gpointer);
+        (*ring_buffer).memory = ptr::null_mut();
+        (*ring_buffer).size = 0;
+        released as gboolean
+    }
+}
+
+extern "C" fn ring_buffer_start<R: AudioRingBufferImpl>(ring_buffer: *mut GstAudioRingBuffer) -> gboolean{
+    unsafe{
+        imp::<R>(ring_buffer as gpointer).start(&AudioRingBuffer::new_from_gst_ring_buffer(ring_buffer)) as gboolean
+    }
+}
+
+extern "C" fn ring_buffer_pause<R: AudioRingBufferImpl>(ring_buffer: *mut GstAudioRingBuffer) -> gboolean{
+    unsafe{
+        imp::<R>(ring_buffer as gpointer).pause(&AudioRingBuffer::new_from_gst_ring_buffer(ring_buffer)) as gboolean
+    }
+}
+
+extern "C" fn ring_buffer_resume<R: AudioRingBufferImpl>(ring_buffer: *mut GstAudioRingBuffer) -> gboolean{
+    unsafe{
+        imp::<R>(ring_buffer as gpointer).resume(&AudioRingBuffer::new_from_gst_ring_buffer(ring_buffer)) as gboolean
+    }
+}
+
+extern "C" fn ring_buffer_stop<R: AudioRingBufferImpl>(ring_buffer: *mut GstAudioRingBuffer) -> gboolean{
+    unsafe{
+        imp::<R>(ring_buffer as gpointer).stop(&AudioRingBuffer::new_from_gst_ring_buffer(ring_buffer)) as gboolean
+    }
+}
+
+extern "C" fn ring_buffer_delay<R: AudioRingBufferImpl>(ring_buffer: *mut GstAudioRingBuffer) -> guint{
+    unsafe{
+        imp::<R>(ring_buffer as gpointer).delay(&AudioRingBuffer::new_from_gst_ring_buffer(ring_buffer))
+    }
+}
+
+/// An audio output implemented in Rust on top of GstAudioBaseSink, which
+/// synchronizes to the clock and provides one from the device as
+/// GstAudioSink does, but lets the implementation create the ring buffer
+/// the device reads from, see AudioRingBufferImpl. Register it with
+/// register_audio_base_sink.
+///
+/// The pad template has to be named "sink" with audio/x-raw caps
+pub trait AudioBaseSinkImpl: ElementImpl{
+    /// Creates the ring buffer, when the sink goes to READY
+    fn create_ringbuffer(&self, sink: &AudioBaseSink) -> Option<AudioRingBuffer>;
+}
+
+/// An audio sink as passed to the methods of an AudioBaseSinkImpl
+pub struct AudioBaseSink{
+    sink: Element
+}
+
+unsafe impl Sync for AudioBaseSink {}
+unsafe impl Send for AudioBaseSink {}
+
+impl AudioBaseSink{
+    unsafe fn new(sink: *mut GstElement) -> AudioBaseSink{
+        gst_object_ref(sink as gpointer);
+        AudioBaseSink{ sink: Element::new_from_gst_element(sink).unwrap() }
+    }
+}
+
+impl AudioBaseSinkT for AudioBaseSink{}
+
+impl ElementT for AudioBaseSink{
+    fn as_element(&self) -> &Element{
+        &self.sink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.sink
+    }
+}
+
+impl ::Transfer for AudioBaseSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.sink.transfer()
+    }
+}
+
+extern "C" fn audio_base_sink_class_init<T: AudioBaseSinkImpl>(class: gpointer, _data: gpointer){
+    unsafe{
+        element_class_init::<T>(class);
+        (*(class as *mut GstAudioBaseSinkClass)).create_ringbuffer = Some(audio_base_sink_create_ringbuffer::<T>);
+    }
+}
+
+extern "C" fn audio_base_sink_create_ringbuffer<T: AudioBaseSinkImpl>(sink: *mut GstElement) -> *mut GstAudioRingBuffer{
+    unsafe{
+        match imp::<T>(sink as gpointer).create_ringbuffer(&AudioBaseSink::new(sink)){
+            Some(ring_buffer) => {
+                let gst_ring_buffer = ring_buffer.ring_buffer;
+                mem::forget(ring_buffer);
+                // the sink takes the ring buffer with gst_object_set_parent,
+                // which expects a floating reference
+                g_object_force_floating(gst_ring_buffer as *mut GObject);
+                gst_ring_buffer
+            }
+            None => ptr::null_mut()
+        }
+    }
+}
+
+/// Registers an audio sink implemented in Rust with its own ring buffer,
+/// see register_audio_sink
+pub fn register_audio_base_sink<T: AudioBaseSinkImpl>(name: &str, rank: u32) -> bool{
+    unsafe{
+        register_element(gst_audio_base_sink_get_type(), name, rank, audio_base_sink_class_init::<T>, instance_init::<T>)
+    }
+}
+
+/// A video output implemented in Rust, as a wgpu surface, on top of
+/// GstVideoSink which shows each frame at its time on the clock, drops
+/// late frames and reports the render delay as latency. Register it with
+/// register_video_sink.
+///
+/// The pad template has to be named "sink" with video/x-raw caps
+pub trait VideoSinkImpl: ElementImpl{
+    /// Called when the element starts, to create the window or surface
+    fn start(&self, _sink: &VideoSink) -> bool{
+        true
+    }
+
+    fn stop(&self, _sink: &VideoSink) -> bool{
+        true
+    }
+
+    /// Called with the format of the frames when it's known or changes
+    fn set_caps(&self, _sink: &VideoSink, _caps: &Caps) -> bool{
+        true
+    }
+
+    /// Presents a frame, called at its time on the clock. To show the
+    /// last frame again, as when redrawing the window, keep the buffer
+    fn show_frame(&self, sink: &VideoSink, buffer: &Buffer) -> GstFlowReturn;
+
+    /// Called with each event the sink receives before the base class
+    /// handles it, as the custom events sent by the application
+    fn event(&self, _sink: &VideoSink, _event: &Event){}
+}
+
+/// A video sink as passed to the methods of a VideoSinkImpl
+pub struct VideoSink{
+    sink: Element
+}
+
+unsafe impl Sync for VideoSink {}
+unsafe impl Send for VideoSink {}
+
+impl VideoSink{
+    unsafe fn new(sink: *mut GstVideoSink) -> VideoSink{
+        gst_object_ref(sink as gpointer);
+        VideoSink{ sink: Element::new_from_gst_element(sink as *mut GstElement).unwrap() }
+    }
+
+    /// Time in nanoseconds between show_frame being called and the frame
+    /// being visible, so frames are shown earlier to compensate
+    pub fn set_render_delay(&self, delay: u64){
+        unsafe{
+            gst_base_sink_set_render_delay(self.sink.gst_element() as *mut GstBaseSink, delay);
+        }
+    }
+
+    /// Whether late frames are dropped and reported upstream so decoders
+    /// can skip work
+    pub fn set_qos_enabled(&self, enabled: bool){
+        unsafe{
+            gst_base_sink_set_qos_enabled(self.sink.gst_element() as *mut GstBaseSink, enabled as gboolean);
+        }
+    }
+
+    /// Time in nanoseconds a frame can be late and still be shown, -1 to
+    /// show all of them
+    pub fn set_max_lateness(&self, max_lateness: i64){
+        unsafe{
+            gst_base_sink_set_max_lateness(self.sink.gst_element() as *mut GstBaseSink, max_lateness);
+        }
+    }
+
+    /// Size of the video, used by the overlay interface and to center it
+    pub fn set_size(&self, width: i32, height: i32){
+        unsafe{
+            let sink = self.sink.gst_element() as *mut GstVideoSink;
+            (*sink).width = width;
+            (*sink).height = height;
+        }
+    }
+}
+
+impl ElementT for VideoSink{
+    fn as_element(&self) -> &Element{
+        &self.sink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.sink
+    }
+}
+
+impl ::Transfer for VideoSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.sink.transfer()
+    }
+}
+
+extern "C" fn video_sink_class_init<T: VideoSinkImpl>(class: gpointer, _data: gpointer){
+    unsafe{
+        element_class_init::<T>(class);
+        let base_sink_class = class as *mut GstBaseSinkClass;
+        (*base_sink_class).start = Some(video_sink_start::<T>);
+        (*base_sink_class).stop = Some(video_sink_stop::<T>);
+        (*base_sink_class).set_caps = Some(video_sink_set_caps::<T>);
+        (*base_sink_class).event = Some(video_sink_event::<T>);
+        (*(class as *mut GstVideoSinkClass)).show_frame = Some(video_sink_show_frame::<T>);
+    }
+}
+
+extern "C" fn video_sink_start<T: VideoSinkImpl>(sink: *mut GstBaseSink) -> gboolean{
+    unsafe{
+        imp::<T>(sink as gpointer).start(&VideoSink::new(sink as *mut GstVideoSink)) as gboolean
+    }
+}
+
+extern "C" fn video_sink_stop<T: VideoSinkImpl>(sink: *mut GstBaseSink) -> gboolean{
+    unsafe{
+        imp::<T>(sink as gpointer).stop(&VideoSink::new(sink as *mut GstVideoSink)) as gboolean
+    }
+}
+
+extern "C" fn video_sink_set_caps<T: VideoSinkImpl>(sink: *mut GstBaseSink, caps: *mut GstCaps) -> gboolean{
+    unsafe{
+        // GstVideoSink parses the caps in its own set_caps since 1.20
+        if let Some(set_caps) = (*parent_class::<GstBaseSinkClass>(sink as gpointer)).set_caps{
+            if set_caps(sink, caps) == 0{
+                return 0;
+            }
+        }
+        match Caps::new(caps, false){
+            Some(caps) => imp::<T>(sink as gpointer).set_caps(&VideoSink::new(sink as *mut GstVideoSink), &caps) as gboolean,
+            None => 0
+        }
+    }
+}
+
+extern "C" fn video_sink_show_frame<T: VideoSinkImpl>(sink: *mut GstVideoSink, buffer: *mut GstBuffer) -> GstFlowReturn{
+    unsafe{
+        match Buffer::new(buffer, false){
+            Some(buffer) => imp::<T>(sink as gpointer).show_frame(&VideoSink::new(sink), &buffer),
+            None => GST_FLOW_ERROR
+        }
+    }
+}
+
+extern "C" fn video_sink_event<T: VideoSinkImpl>(sink: *mut GstBaseSink, event: *mut GstEvent) -> gboolean{
+    unsafe{
+        if let Some(wrapper) = Event::new(event, false){
+            imp::<T>(sink as gpointer).event(&VideoSink::new(sink as *mut GstVideoSink), &wrapper);
+        }
+        match (*parent_class::<GstBaseSinkClass>(sink as gpointer)).event{
+            Some(event_func) => event_func(sink, event),
+            None => {
+                gst_mini_object_unref(event as *mut GstMiniObject);
+                0
+            }
+        }
+    }
+}
+
+/// Registers a video sink implemented in Rust so it can be created by
+/// name with Element::new, in launch lines, and picked by autovideosink
+/// if the rank is GST_RANK_PRIMARY or higher and the classification
+/// contains "Sink/Video"
+pub fn register_video_sink<T: VideoSinkImpl>(name: &str, rank: u32) -> bool{
+    unsafe{
+        register_element(gst_video_sink_get_type(), name, rank, video_sink_class_init::<T>, instance_init::<T>)
+    }
+}
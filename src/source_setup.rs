@@ -0,0 +1,234 @@
+//! Credentials and settings for the elements created inside playbin,
+//! uridecodebin or a demuxer.
+//!
+//! The source of playbin is only created once the uri is resolved, and
+//! adaptive demuxers as hlsdemux or dashdemux download the fragments with
+//! sources of their own, so none of them exist when the pipeline is
+//! built. There are two ways to reach them:
+//!
+//! - source-setup, element-setup and deep-element-added call a closure
+//!   with each element as it's created, see SourceSetupT.
+//! - souphttpsrc takes its cookies from an "http-headers" Context. Bins
+//!   keep the contexts set on them and pass them to the elements added
+//!   later, so setting it once on the pipeline reaches every HTTP source,
+//!   including those created by the demuxers.
+//!
+//! HttpCredentials uses both:
+//!
+//! ```ignore
+//! let mut playbin = PlayBin::new("player").unwrap();
+//! playbin.set_uri("https://example.com/live/index.m3u8");
+//! playbin.set_http_credentials(HttpCredentials::new()
+//!     .cookie("session=abc123; Path=/")
+//!     .header("Authorization", "Bearer token"));
+//! playbin.play();
+//! ```
+
+use ffi::*;
+use util::*;
+use bin::Bin;
+use context::Context;
+use decodebin::UriDecodeBin;
+use element::{Element, ElementT};
+use pipeline::Pipeline;
+use playbin::PlayBin;
+use value::G_TYPE_STRING;
+
+/// Context type souphttpsrc queries for cookies
+pub const HTTP_HEADERS_CONTEXT: &'static str = "http-headers";
+
+/// Credentials and headers for the HTTP and RTSP sources of a pipeline,
+/// applied to every element that has the corresponding properties
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct HttpCredentials{
+    user_id: Option<String>,
+    password: Option<String>,
+    cookies: Vec<String>,
+    user_agent: Option<String>,
+    headers: Vec<(String, String)>,
+}
+
+impl HttpCredentials{
+    pub fn new() -> HttpCredentials{
+        HttpCredentials::default()
+    }
+
+    /// User and password for basic or digest authentication, set as the
+    /// user-id and user-pw properties of souphttpsrc and rtspsrc
+    pub fn user(mut self, user_id: &str, password: &str) -> HttpCredentials{
+        self.user_id = Some(user_id.to_string());
+        self.password = Some(password.to_string());
+        self
+    }
+
+    /// A cookie in the format of a Set-Cookie header, as
+    /// "session=abc123; Path=/"
+    pub fn cookie(mut self, cookie: &str) -> HttpCredentials{
+        self.cookies.push(cookie.to_string());
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> HttpCredentials{
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// An extra header sent with every request, as an Authorization
+    /// header with a bearer token
+    pub fn header(mut self, name: &str, value: &str) -> HttpCredentials{
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// The "http-headers" context with the cookies, to set on the
+    /// pipeline with ElementT::set_context
+    pub fn context(&self) -> Option<Context>{
+        unsafe{
+            let context = gst_context_new(to_c_str!(HTTP_HEADERS_CONTEXT), 1);
+            if !self.cookies.is_empty(){
+                let mut cookies = strv_gvalue(&self.cookies);
+                gst_structure_take_value(gst_context_writable_structure(context), to_c_str!("cookies"), &mut cookies);
+            }
+            Context::new_from_gst_context(context, true)
+        }
+    }
+
+    /// Sets the credentials on element if it has the properties for them,
+    /// returns whether any was set. Usually called from the closures of
+    /// SourceSetupT
+    pub fn apply<E: ElementT + ?Sized>(&self, element: &E) -> bool{
+        unsafe{
+            let has_property = |name| element.as_element().find_property(name) != ptr::null_mut();
+            let element = element.gst_element() as *mut GstElement;
+            let mut applied = false;
+            if let (Some(user_id), Some(password)) = (self.user_id.as_ref(), self.password.as_ref()){
+                if has_property("user-id") && has_property("user-pw"){
+                    g_object_set(element as gpointer, to_c_str!("user-id"), to_c_str!(user_id.as_str()),
+                        to_c_str!("user-pw"), to_c_str!(password.as_str()), ptr::null::<gchar>());
+                    applied = true;
+                }
+            }
+            if let Some(user_agent) = self.user_agent.as_ref(){
+                if has_property("user-agent"){
+                    g_object_set(element as gpointer, to_c_str!("user-agent"), to_c_str!(user_agent.as_str()), ptr::null::<gchar>());
+                    applied = true;
+                }
+            }
+            if !self.cookies.is_empty() && has_property("cookies"){
+                let mut cookies = strv_gvalue(&self.cookies);
+                g_object_set_property(element as *mut GObject, to_c_str!("cookies"), &cookies);
+                g_value_unset(&mut cookies);
+                applied = true;
+            }
+            if !self.headers.is_empty() && has_property("extra-headers"){
+                let headers = gst_structure_new_empty(to_c_str!("extra-headers"));
+                for &(ref name, ref value) in &self.headers{
+                    gst_structure_set(headers, to_c_str!(name.as_str()), G_TYPE_STRING,
+                        to_c_str!(value.as_str()), ptr::null::<gchar>());
+                }
+                g_object_set(element as gpointer, to_c_str!("extra-headers"), headers, ptr::null::<gchar>());
+                gst_structure_free(headers);
+                applied = true;
+            }
+            applied
+        }
+    }
+}
+
+/// Closures called with the elements created inside playbin,
+/// uridecodebin and bins in general, to configure them before they start
+pub trait SourceSetupT: ElementT{
+    /// Calls f with the source playbin or uridecodebin creates for the
+    /// uri, before it's set to READY. Returns false if the element
+    /// doesn't have the source-setup signal
+    fn on_source_setup<F: Fn(&Element) + Send + Sync + 'static>(&self, f: F) -> bool{
+        unsafe{
+            connect_element_signal(self.gst_element() as *mut GstElement, "source-setup", Box::new(f))
+        }
+    }
+
+    /// Calls f with every element playbin creates, as decoders and
+    /// sinks, since GStreamer 1.10. Returns false if the element doesn't
+    /// have the element-setup signal
+    fn on_element_setup<F: Fn(&Element) + Send + Sync + 'static>(&self, f: F) -> bool{
+        unsafe{
+            connect_element_signal(self.gst_element() as *mut GstElement, "element-setup", Box::new(f))
+        }
+    }
+
+    /// Calls f with every element added to the bin or to any bin inside
+    /// it, as the sources of a demuxer, since GStreamer 1.10. Returns
+    /// false if the element isn't a bin
+    fn on_deep_element_added<F: Fn(&Element) + Send + Sync + 'static>(&self, f: F) -> bool{
+        unsafe{
+            let element = self.gst_element() as *mut GstElement;
+            if signal_id(element, "deep-element-added") == 0{
+                return false;
+            }
+            let f: ElementCallback = Box::new(f);
+            connect_boxed(element as gpointer, "deep-element-added",
+                deep_element_added as extern "C" fn(*mut GstBin, *mut GstBin, *mut GstElement, gpointer) as gpointer,
+                Box::new(f));
+            true
+        }
+    }
+
+    /// Sets the "http-headers" context of credentials on the element and
+    /// applies them to every element added to it from now on
+    fn set_http_credentials(&mut self, credentials: HttpCredentials){
+        if let Some(context) = credentials.context(){
+            self.set_context(&context);
+        }
+        self.on_deep_element_added(move |element| { credentials.apply(element); });
+    }
+}
+
+impl SourceSetupT for Element{}
+impl SourceSetupT for Bin{}
+impl SourceSetupT for Pipeline{}
+impl SourceSetupT for PlayBin{}
+impl SourceSetupT for UriDecodeBin{}
+
+type ElementCallback = Box<dyn Fn(&Element) + Send + Sync>;
+
+unsafe fn signal_id(element: *mut GstElement, signal: &str) -> guint{
+    let g_type = (*(*(element as *mut GTypeInstance)).g_class).g_type;
+    g_signal_lookup(to_c_str!(signal), g_type)
+}
+
+// A GValue holding a copy of strings as a G_TYPE_STRV
+unsafe fn strv_gvalue(strings: &[String]) -> GValue{
+    let c_strings = strings.iter().map(|string| CString::new(string.as_str()).unwrap()).collect::<Vec<_>>();
+    let mut strv = c_strings.iter().map(|string| string.as_ptr() as *mut gchar).collect::<Vec<_>>();
+    strv.push(ptr::null_mut());
+    let mut gvalue: GValue = mem::zeroed();
+    g_value_init(&mut gvalue, g_strv_get_type());
+    g_value_take_boxed(&mut gvalue, g_strdupv(strv.as_mut_ptr()) as gconstpointer);
+    gvalue
+}
+
+// Connects f to a signal with the signature of source-setup and
+// element-setup, which pass the new element after the emitter
+unsafe fn connect_element_signal(element: *mut GstElement, signal: &str, f: ElementCallback) -> bool{
+    if signal_id(element, signal) == 0{
+        return false;
+    }
+    connect_boxed(element as gpointer, signal,
+        element_setup as extern "C" fn(*mut GstElement, *mut GstElement, gpointer) as gpointer,
+        Box::new(f));
+    true
+}
+
+extern "C" fn element_setup(_emitter: *mut GstElement, element: *mut GstElement, data: gpointer){
+    unsafe{
+        let f = &*(data as *const ElementCallback);
+        gst_object_ref(element as gpointer);
+        if let Some(element) = Element::new_from_gst_element(element){
+            f(&element);
+        }
+    }
+}
+
+extern "C" fn deep_element_added(_bin: *mut GstBin, _sub_bin: *mut GstBin, element: *mut GstElement, data: gpointer){
+    element_setup(ptr::null_mut(), element, data);
+}
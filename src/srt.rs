@@ -0,0 +1,272 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+use structure::Structure;
+use value::Value;
+use gio::*;
+
+/// Connection mode of srtsrc and srtsink, the mode property
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum SrtMode{
+    None = 0,
+    /// Connects to a listener
+    Caller = 1,
+    /// Waits for callers to connect
+    Listener = 2,
+    /// Both ends connect to each other, to traverse firewalls
+    Rendezvous = 3,
+}
+
+impl SrtMode{
+    fn from_i32(mode: i32) -> SrtMode{
+        match mode{
+            1 => SrtMode::Caller,
+            2 => SrtMode::Listener,
+            3 => SrtMode::Rendezvous,
+            _ => SrtMode::None,
+        }
+    }
+}
+
+/// Length of the AES key used to encrypt the stream, the pbkeylen property
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum SrtKeyLength{
+    NoKey = 0,
+    Aes128 = 16,
+    Aes192 = 24,
+    Aes256 = 32,
+}
+
+/// Statistics of an SRT connection, from the stats property. For a
+/// listener sink with several callers the totals are empty and each
+/// caller is in callers
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct SrtStats{
+    pub packets_sent: i64,
+    pub packets_sent_lost: i64,
+    pub packets_retransmitted: i64,
+    pub packets_received: i64,
+    pub packets_received_lost: i64,
+    pub packets_received_retransmitted: i64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub send_rate_mbps: f64,
+    pub receive_rate_mbps: f64,
+    pub bandwidth_mbps: f64,
+    pub rtt_ms: f64,
+    pub callers: Vec<SrtStats>,
+}
+
+impl SrtStats{
+    /// Reads the stats from the structure of the stats property
+    pub fn from_structure(structure: &Structure) -> SrtStats{
+        let int = |field: &str| match structure.get(field){
+            Some(Value::Int(value)) => value as i64,
+            Some(Value::Int64(value)) => value,
+            Some(Value::UInt(value)) => value as i64,
+            Some(Value::UInt64(value)) => value as i64,
+            _ => 0
+        };
+        let double = |field: &str| match structure.get(field){
+            Some(Value::Double(value)) => value,
+            Some(Value::Float(value)) => value as f64,
+            _ => 0.
+        };
+        SrtStats{
+            packets_sent: int("packets-sent"),
+            packets_sent_lost: int("packets-sent-lost"),
+            packets_retransmitted: int("packets-retransmitted"),
+            packets_received: int("packets-received"),
+            packets_received_lost: int("packets-received-lost"),
+            packets_received_retransmitted: int("packets-received-retransmitted"),
+            bytes_sent: int("bytes-sent") as u64,
+            bytes_received: int("bytes-received") as u64,
+            send_rate_mbps: double("send-rate-mbps"),
+            receive_rate_mbps: double("receive-rate-mbps"),
+            bandwidth_mbps: double("bandwidth-mbps"),
+            rtt_ms: double("rtt-ms"),
+            callers: unsafe{ callers(structure) },
+        }
+    }
+}
+
+// The callers field is a GValueArray of structures, which Value doesn't
+// represent
+unsafe fn callers(structure: &Structure) -> Vec<SrtStats>{
+    let value = gst_structure_get_value(structure.gst_structure(), to_c_str!("callers"));
+    if value == ptr::null() || (*value).g_type != g_value_array_get_type(){
+        return Vec::new();
+    }
+    let array = g_value_get_boxed(value) as *const GValueArray;
+    if array == ptr::null(){
+        return Vec::new();
+    }
+    (0..(*array).n_values as isize)
+        .filter_map(|i| {
+            let caller = g_value_get_boxed((*array).values.offset(i)) as *mut GstStructure;
+            if caller != ptr::null_mut(){
+                Structure::new_from_gst_structure(gst_structure_copy(caller))
+            }else{
+                None
+            }
+        })
+        .map(|caller| SrtStats::from_structure(&caller))
+        .collect()
+}
+
+extern "C" fn caller_callback<F: Fn(i32, &str) + Send + Sync + 'static>(_element: *mut GstElement, socket: gint,
+        address: *mut GSocketAddress, data: gpointer){
+    unsafe{
+        (*(data as *const F))(socket, &socket_address_to_string(address));
+    }
+}
+
+/// Properties and signals shared by srtsrc and srtsink. The remote or
+/// local address and the options are set as an uri with
+/// UriHandlerT::set_uri, as srt://192.168.1.10:7001?mode=caller&latency=200
+pub trait SrtT: ElementT{
+    fn set_mode(&self, mode: SrtMode){
+        self.as_element().set("mode", mode as gint);
+    }
+
+    fn mode(&self) -> SrtMode{
+        unsafe{ SrtMode::from_i32(self.as_element().get::<gint>("mode")) }
+    }
+
+    /// Local address and port to bind to in listener and rendezvous modes
+    fn set_local_address(&self, address: &str, port: u16){
+        self.as_element().set("localaddress", to_c_str!(address));
+        self.as_element().set("localport", port as guint);
+    }
+
+    /// Receive latency in milliseconds, the time lost packets have to be
+    /// retransmitted. Should be a few times the round trip time
+    fn set_latency(&self, latency_ms: i32){
+        self.as_element().set("latency", latency_ms);
+    }
+
+    fn latency(&self) -> i32{
+        unsafe{ self.as_element().get::<gint>("latency") }
+    }
+
+    /// Encrypts the stream with a key derived from the passphrase, which
+    /// has to be between 10 and 79 characters and the same on both ends
+    fn set_passphrase(&self, passphrase: &str, key_length: SrtKeyLength){
+        self.as_element().set("passphrase", to_c_str!(passphrase));
+        self.as_element().set("pbkeylen", key_length as gint);
+    }
+
+    /// Stream id sent by callers, so a listener can tell streams apart
+    fn set_stream_id(&self, stream_id: &str){
+        self.as_element().set("streamid", to_c_str!(stream_id));
+    }
+
+    /// Whether a caller blocks until it connects instead of failing
+    fn set_wait_for_connection(&self, wait: bool){
+        self.as_element().set("wait-for-connection", wait as gboolean);
+    }
+
+    /// The structure of the stats property as is
+    fn stats_structure(&self) -> Option<Structure>{
+        unsafe{
+            Structure::new_from_gst_structure(self.as_element().get::<*mut GstStructure>("stats"))
+        }
+    }
+
+    fn stats(&self) -> SrtStats{
+        self.stats_structure().map(|stats| SrtStats::from_structure(&stats)).unwrap_or_default()
+    }
+
+    /// Calls f with the socket id and the address of each caller that
+    /// connects to a listener
+    fn on_caller_added<F: Fn(i32, &str) + Send + Sync + 'static>(&self, f: F) where Self: Sized{
+        connect_caller_signal(self.as_element(), "caller-added", f);
+    }
+
+    /// Calls f with the socket id and the address of each caller that
+    /// disconnects from a listener
+    fn on_caller_removed<F: Fn(i32, &str) + Send + Sync + 'static>(&self, f: F) where Self: Sized{
+        connect_caller_signal(self.as_element(), "caller-removed", f);
+    }
+}
+
+fn connect_caller_signal<F: Fn(i32, &str) + Send + Sync + 'static>(element: &Element, signal: &str, f: F){
+    unsafe{
+        connect_boxed(element.gst_element() as gpointer, signal,
+            caller_callback::<F> as extern "C" fn(*mut GstElement, gint, *mut GSocketAddress, gpointer) as gpointer,
+            Box::new(f));
+    }
+}
+
+/// Wrapper around srtsrc, receiving a stream over SRT
+pub struct SrtSrc{
+    srtsrc: Element
+}
+
+unsafe impl Sync for SrtSrc {}
+unsafe impl Send for SrtSrc {}
+
+impl SrtSrc{
+    pub fn new(name: &str) -> Option<SrtSrc>{
+        Element::new("srtsrc", name).map(|srtsrc| SrtSrc{ srtsrc: srtsrc })
+    }
+
+    pub fn new_from_element(element: Element) -> SrtSrc{
+        SrtSrc{ srtsrc: element }
+    }
+}
+
+impl SrtT for SrtSrc{}
+
+impl ElementT for SrtSrc{
+    fn as_element(&self) -> &Element{
+        &self.srtsrc
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.srtsrc
+    }
+}
+
+impl ::Transfer for SrtSrc{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.srtsrc.transfer()
+    }
+}
+
+/// Wrapper around srtsink, sending a stream over SRT, as listener it
+/// sends it to every connected caller
+pub struct SrtSink{
+    srtsink: Element
+}
+
+unsafe impl Sync for SrtSink {}
+unsafe impl Send for SrtSink {}
+
+impl SrtSink{
+    pub fn new(name: &str) -> Option<SrtSink>{
+        Element::new("srtsink", name).map(|srtsink| SrtSink{ srtsink: srtsink })
+    }
+
+    pub fn new_from_element(element: Element) -> SrtSink{
+        SrtSink{ srtsink: element }
+    }
+}
+
+impl SrtT for SrtSink{}
+
+impl ElementT for SrtSink{
+    fn as_element(&self) -> &Element{
+        &self.srtsink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.srtsink
+    }
+}
+
+impl ::Transfer for SrtSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.srtsink.transfer()
+    }
+}
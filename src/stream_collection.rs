@@ -0,0 +1,245 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use taglist::TagList;
+
+use std::os::raw::{c_uint, c_void};
+use std::fmt::{self, Debug, Formatter};
+
+// GstStream and GstStreamCollection were added in GStreamer 1.10, after
+// ffi.rs was generated
+pub enum GstStream {}
+pub enum GstStreamCollection {}
+
+pub type GstStreamType = c_uint;
+pub const GST_STREAM_TYPE_UNKNOWN: GstStreamType = 1 << 0;
+pub const GST_STREAM_TYPE_AUDIO: GstStreamType = 1 << 1;
+pub const GST_STREAM_TYPE_VIDEO: GstStreamType = 1 << 2;
+pub const GST_STREAM_TYPE_CONTAINER: GstStreamType = 1 << 3;
+pub const GST_STREAM_TYPE_TEXT: GstStreamType = 1 << 4;
+
+pub const GST_MESSAGE_STREAM_COLLECTION: GstMessageType = GST_MESSAGE_EXTENDED + 4;
+pub const GST_MESSAGE_STREAMS_SELECTED: GstMessageType = GST_MESSAGE_EXTENDED + 5;
+
+extern "C"{
+    fn gst_stream_get_stream_id(stream: *mut GstStream) -> *const gchar;
+    fn gst_stream_get_stream_type(stream: *mut GstStream) -> GstStreamType;
+    fn gst_stream_get_caps(stream: *mut GstStream) -> *mut GstCaps;
+    fn gst_stream_get_tags(stream: *mut GstStream) -> *mut GstTagList;
+    fn gst_stream_collection_get_size(collection: *mut GstStreamCollection) -> c_uint;
+    fn gst_stream_collection_get_stream(collection: *mut GstStreamCollection, index: c_uint) -> *mut GstStream;
+    fn gst_stream_collection_get_upstream_id(collection: *mut GstStreamCollection) -> *const gchar;
+    pub fn gst_message_parse_stream_collection(message: *mut GstMessage, collection: *mut *mut GstStreamCollection);
+    pub fn gst_message_parse_streams_selected(message: *mut GstMessage, collection: *mut *mut GstStreamCollection);
+    pub fn gst_message_streams_selected_get_size(message: *mut GstMessage) -> c_uint;
+    pub fn gst_message_streams_selected_get_stream(message: *mut GstMessage, index: c_uint) -> *mut GstStream;
+    fn gst_event_new_select_streams(streams: *mut GList) -> *mut GstEvent;
+}
+
+/// A single stream (audio, video, subtitles...) of a StreamCollection as
+/// announced by playbin3, uridecodebin3 or decodebin3
+pub struct Stream{
+    stream: *mut GstStream
+}
+
+unsafe impl Send for Stream {}
+
+impl Drop for Stream{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.stream as *mut c_void);
+        }
+    }
+}
+
+impl Clone for Stream{
+    fn clone(&self) -> Stream{
+        unsafe{
+            gst_object_ref(self.stream as *mut c_void);
+            Stream{ stream: self.stream }
+        }
+    }
+}
+
+impl Stream{
+    /// Wraps a raw GstStream, if owned is false a new reference is taken
+    pub unsafe fn new(stream: *mut GstStream, owned: bool) -> Option<Stream>{
+        if stream != ptr::null_mut(){
+            if !owned{
+                gst_object_ref(stream as *mut c_void);
+            }
+            Some(Stream{ stream: stream })
+        }else{
+            None
+        }
+    }
+
+    /// The id used to select this stream with ElementT::select_streams
+    pub fn stream_id(&self) -> Option<String>{
+        unsafe{
+            let id = gst_stream_get_stream_id(self.stream);
+            if id != ptr::null(){
+                Some(from_c_str!(id).to_string())
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns a combination of the GST_STREAM_TYPE_* flags
+    pub fn stream_type(&self) -> GstStreamType{
+        unsafe{
+            gst_stream_get_stream_type(self.stream)
+        }
+    }
+
+    pub fn is_audio(&self) -> bool{
+        self.stream_type() & GST_STREAM_TYPE_AUDIO != 0
+    }
+
+    pub fn is_video(&self) -> bool{
+        self.stream_type() & GST_STREAM_TYPE_VIDEO != 0
+    }
+
+    pub fn is_text(&self) -> bool{
+        self.stream_type() & GST_STREAM_TYPE_TEXT != 0
+    }
+
+    pub fn caps(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(gst_stream_get_caps(self.stream), true)
+        }
+    }
+
+    /// Tags of the stream, usually containing the language and codec
+    pub fn tags(&self) -> Option<TagList>{
+        unsafe{
+            TagList::new_from_gst_taglist(gst_stream_get_tags(self.stream), true)
+        }
+    }
+
+    pub unsafe fn gst_stream(&self) -> *const GstStream{
+        self.stream
+    }
+
+    pub unsafe fn gst_stream_mut(&mut self) -> *mut GstStream{
+        self.stream
+    }
+}
+
+impl Debug for Stream{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        let ty = if self.is_video(){
+            "video"
+        }else if self.is_audio(){
+            "audio"
+        }else if self.is_text(){
+            "text"
+        }else{
+            "unknown"
+        };
+        write!(fmt, "Stream {{ id: {:?}, type: {}", self.stream_id(), ty)?;
+        if let Some(caps) = self.caps(){
+            write!(fmt, ", caps: \"{}\"", caps)?;
+        }
+        write!(fmt, " }}")
+    }
+}
+
+/// The set of streams available from a source, posted on the bus in
+/// STREAM_COLLECTION messages
+pub struct StreamCollection{
+    collection: *mut GstStreamCollection
+}
+
+unsafe impl Send for StreamCollection {}
+
+impl Drop for StreamCollection{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.collection as *mut c_void);
+        }
+    }
+}
+
+impl Clone for StreamCollection{
+    fn clone(&self) -> StreamCollection{
+        unsafe{
+            gst_object_ref(self.collection as *mut c_void);
+            StreamCollection{ collection: self.collection }
+        }
+    }
+}
+
+impl StreamCollection{
+    /// Wraps a raw GstStreamCollection, if owned is false a new reference
+    /// is taken
+    pub unsafe fn new(collection: *mut GstStreamCollection, owned: bool) -> Option<StreamCollection>{
+        if collection != ptr::null_mut(){
+            if !owned{
+                gst_object_ref(collection as *mut c_void);
+            }
+            Some(StreamCollection{ collection: collection })
+        }else{
+            None
+        }
+    }
+
+    pub fn len(&self) -> u32{
+        unsafe{
+            gst_stream_collection_get_size(self.collection)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+
+    pub fn stream(&self, index: u32) -> Option<Stream>{
+        unsafe{
+            Stream::new(gst_stream_collection_get_stream(self.collection, index), false)
+        }
+    }
+
+    pub fn streams(&self) -> Vec<Stream>{
+        (0..self.len()).filter_map(|i| self.stream(i)).collect()
+    }
+
+    pub fn upstream_id(&self) -> Option<String>{
+        unsafe{
+            let id = gst_stream_collection_get_upstream_id(self.collection);
+            if id != ptr::null(){
+                Some(from_c_str!(id).to_string())
+            }else{
+                None
+            }
+        }
+    }
+
+    pub unsafe fn gst_stream_collection(&self) -> *const GstStreamCollection{
+        self.collection
+    }
+
+    pub unsafe fn gst_stream_collection_mut(&mut self) -> *mut GstStreamCollection{
+        self.collection
+    }
+}
+
+impl Debug for StreamCollection{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        fmt.debug_list().entries(self.streams().iter()).finish()
+    }
+}
+
+/// Creates a SELECT_STREAMS event activating the streams with the passed
+/// ids, any stream not in the list is deactivated
+pub unsafe fn new_select_streams_event(stream_ids: &[&str]) -> *mut GstEvent{
+    let ids: Vec<CString> = stream_ids.iter().map(|id| CString::new(*id).unwrap()).collect();
+    let mut list = ptr::null_mut::<GList>();
+    for id in ids.iter(){
+        list = g_list_append(list, id.as_ptr() as gpointer);
+    }
+    let event = gst_event_new_select_streams(list);
+    g_list_free(list);
+    event
+}
@@ -0,0 +1,212 @@
+use ffi::*;
+use util::*;
+use value::Value;
+use std::fmt::{self, Debug, Display, Formatter};
+
+#[cfg(feature="serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+#[cfg(feature="serde")]
+use serde::de::Error as SerdeError;
+#[cfg(feature="serde")]
+use std::collections::BTreeMap;
+
+/// A named collection of key/value pairs, used in caps, messages, events
+/// and queries among others.
+pub struct Structure{
+    structure: *mut GstStructure
+}
+
+unsafe impl Send for Structure {}
+
+impl Drop for Structure{
+    fn drop(&mut self){
+        unsafe{
+            gst_structure_free(self.structure);
+        }
+    }
+}
+
+impl Clone for Structure{
+    fn clone(&self) -> Structure{
+        unsafe{
+            Structure{ structure: gst_structure_copy(self.structure) }
+        }
+    }
+}
+
+impl PartialEq for Structure{
+    fn eq(&self, other: &Structure) -> bool{
+        unsafe{
+            gst_structure_is_equal(self.structure, other.structure) == 1
+        }
+    }
+}
+
+impl Structure{
+    /// Creates a new empty structure with the given name
+    pub fn new(name: &str) -> Option<Structure>{
+        unsafe{
+            Structure::new_from_gst_structure(gst_structure_new_empty(to_c_str!(name)))
+        }
+    }
+
+    /// Parses a structure from its string representation as in
+    /// `video/x-raw, width=(int)640, framerate=(fraction)30/1`
+    pub fn from_string(desc: &str) -> Option<Structure>{
+        unsafe{
+            Structure::new_from_gst_structure(gst_structure_from_string(to_c_str!(desc), ptr::null_mut()))
+        }
+    }
+
+    /// Takes ownership of a raw GstStructure, the structure must not have
+    /// a parent
+    pub unsafe fn new_from_gst_structure(structure: *mut GstStructure) -> Option<Structure>{
+        if structure != ptr::null_mut(){
+            Some(Structure{ structure: structure })
+        }else{
+            None
+        }
+    }
+
+    pub fn name(&self) -> String{
+        unsafe{
+            from_c_str!(gst_structure_get_name(self.structure)).to_string()
+        }
+    }
+
+    pub fn set_name(&mut self, name: &str){
+        unsafe{
+            gst_structure_set_name(self.structure, to_c_str!(name));
+        }
+    }
+
+    pub fn has_name(&self, name: &str) -> bool{
+        unsafe{
+            gst_structure_has_name(self.structure, to_c_str!(name)) == 1
+        }
+    }
+
+    pub fn has_field(&self, field: &str) -> bool{
+        unsafe{
+            gst_structure_has_field(self.structure, to_c_str!(field)) == 1
+        }
+    }
+
+    /// Returns the value of field or None if it doesn't exist
+    pub fn get(&self, field: &str) -> Option<Value>{
+        unsafe{
+            Value::from_gvalue(gst_structure_get_value(self.structure, to_c_str!(field)))
+        }
+    }
+
+    /// Sets field to value, replacing any previous value.
+    ///
+    /// Returns false if the value couldn't be converted
+    pub fn set<V: Into<Value>>(&mut self, field: &str, value: V) -> bool{
+        unsafe{
+            match value.into().to_gvalue(){
+                Some(mut gvalue) => {
+                    gst_structure_take_value(self.structure, to_c_str!(field), &mut gvalue);
+                    true
+                }
+                None => false
+            }
+        }
+    }
+
+    pub fn remove_field(&mut self, field: &str){
+        unsafe{
+            gst_structure_remove_field(self.structure, to_c_str!(field));
+        }
+    }
+
+    pub fn n_fields(&self) -> u32{
+        unsafe{
+            gst_structure_n_fields(self.structure) as u32
+        }
+    }
+
+    pub fn nth_field_name(&self, index: u32) -> Option<String>{
+        unsafe{
+            if index < self.n_fields(){
+                Some(from_c_str!(gst_structure_nth_field_name(self.structure, index)).to_string())
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns all the fields in the structure in order
+    pub fn fields(&self) -> Vec<(String, Value)>{
+        (0..self.n_fields())
+            .filter_map(|i| self.nth_field_name(i))
+            .filter_map(|name| self.get(&name).map(|value| (name, value)))
+            .collect()
+    }
+
+    pub unsafe fn gst_structure(&self) -> *const GstStructure{
+        self.structure
+    }
+
+    pub unsafe fn gst_structure_mut(&mut self) -> *mut GstStructure{
+        self.structure
+    }
+}
+
+impl ::Transfer<GstStructure> for Structure{
+    unsafe fn transfer(self) -> *mut GstStructure{
+        let structure = self.structure;
+        mem::forget(self);
+        structure
+    }
+}
+
+/// Formats the structure in the same format accepted by from_string
+impl Display for Structure{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        unsafe{
+            let c_str = gst_structure_to_string(self.structure);
+            let ret = fmt.write_str(from_c_str!(c_str));
+            g_free(c_str as gpointer);
+            ret
+        }
+    }
+}
+
+impl Debug for Structure{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "Structure({})", self)
+    }
+}
+
+#[cfg(feature="serde")]
+#[derive(Serialize,Deserialize)]
+struct SerdeStructure{
+    name: String,
+    fields: BTreeMap<String, Value>
+}
+
+#[cfg(feature="serde")]
+impl Serialize for Structure{
+    fn serialize<S: Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>{
+        SerdeStructure{
+            name: self.name(),
+            fields: self.fields().into_iter().collect()
+        }.serialize(serializer)
+    }
+}
+
+#[cfg(feature="serde")]
+impl<'de> Deserialize<'de> for Structure{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> ::std::result::Result<Structure, D::Error>{
+        let serde_structure = SerdeStructure::deserialize(deserializer)?;
+        let mut structure = Structure::new(&serde_structure.name)
+            .ok_or_else(|| D::Error::custom(format!("invalid structure name {}", serde_structure.name)))?;
+        for (field, value) in serde_structure.fields{
+            if !structure.set(&field, value){
+                return Err(D::Error::custom(format!("invalid value for field {}", field)));
+            }
+        }
+        Ok(structure)
+    }
+}
@@ -0,0 +1,143 @@
+use ffi::*;
+use util::*;
+
+use std::sync::Mutex;
+
+/// Description of an element or device provider implemented in Rust, as
+/// shown by gst-inspect-1.0
+#[derive(Clone,Copy,Debug)]
+pub struct Metadata{
+    pub long_name: &'static str,
+    /// Classes separated by '/', as "Source/Video" or "Codec/Decoder/Audio"
+    pub classification: &'static str,
+    pub description: &'static str,
+    pub author: &'static str,
+}
+
+static REGISTER: Mutex<()> = Mutex::new(());
+
+// Registers a type deriving from parent whose instances end with a pointer
+// to the Rust implementation, after the parent's instance struct. Returns
+// the already registered type if there's one with the same name
+pub(crate) unsafe fn register_type(parent: GType, name: &str,
+        class_init: extern "C" fn(gpointer, gpointer),
+        instance_init: extern "C" fn(*mut GTypeInstance, gpointer)) -> GType{
+    let _lock = REGISTER.lock().unwrap();
+    let type_name = CString::new(name).unwrap();
+    let existing = g_type_from_name(type_name.as_ptr());
+    if existing != 0{
+        return existing;
+    }
+    let mut query: GTypeQuery = mem::zeroed();
+    g_type_query(parent, &mut query);
+    let mut info: GTypeInfo = mem::zeroed();
+    info.class_size = query.class_size as guint16;
+    info.class_init = Some(class_init);
+    info.instance_size = (query.instance_size as usize + mem::size_of::<gpointer>()) as guint16;
+    info.instance_init = Some(instance_init);
+    g_type_register_static(parent, type_name.as_ptr(), &info, 0)
+}
+
+// Name of the type registered for a factory name, as GstRs_mysource
+pub(crate) fn type_name(name: &str) -> String{
+    let name: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    format!("GstRs_{}", name)
+}
+
+unsafe fn type_of(instance: gpointer) -> GType{
+    (*(*(instance as *mut GTypeInstance)).g_class).g_type
+}
+
+unsafe fn imp_slot(instance: gpointer) -> *mut gpointer{
+    let mut query: GTypeQuery = mem::zeroed();
+    g_type_query(type_of(instance), &mut query);
+    (instance as *mut u8).offset(query.instance_size as isize - mem::size_of::<gpointer>() as isize) as *mut gpointer
+}
+
+/// The Rust implementation of an instance of a type registered with
+/// register_type
+pub(crate) unsafe fn imp<'a, T>(instance: gpointer) -> &'a T{
+    &*(*imp_slot(instance) as *const T)
+}
+
+/// The class of the type the Rust type derives from, to chain up to its
+/// virtual methods
+pub(crate) unsafe fn parent_class<C>(instance: gpointer) -> *const C{
+    g_type_class_peek(g_type_parent(type_of(instance))) as *const C
+}
+
+pub(crate) extern "C" fn instance_init<T: Default>(instance: *mut GTypeInstance, _class: gpointer){
+    unsafe{
+        *imp_slot(instance as gpointer) = Box::into_raw(Box::new(T::default())) as gpointer;
+    }
+}
+
+/// Frees the Rust implementation when the instance is destroyed, has to be
+/// installed by every class_init
+pub(crate) unsafe fn install_finalize<T>(class: gpointer){
+    (*(class as *mut GObjectClass)).finalize = Some(finalize::<T>);
+}
+
+extern "C" fn finalize<T>(object: *mut GObject){
+    unsafe{
+        let slot = imp_slot(object as gpointer);
+        if *slot != ptr::null_mut(){
+            drop(Box::from_raw(*slot as *mut T));
+            *slot = ptr::null_mut();
+        }
+        if let Some(finalize) = (*parent_class::<GObjectClass>(object as gpointer)).finalize{
+            finalize(object);
+        }
+    }
+}
+
+/// A pad template of an element implemented in Rust, the pads of the
+/// element have to be created from one of them
+#[derive(Clone,Copy,Debug)]
+pub struct PadTemplate{
+    /// Name of the pads, as "src" or "sink_%u" for request pads
+    pub name: &'static str,
+    pub direction: GstPadDirection,
+    pub presence: GstPadPresence,
+    /// Caps the pads accept, as a string, "ANY" for anything
+    pub caps: &'static str,
+}
+
+/// What all the elements implemented in Rust have in common, see the
+/// traits for each base class as BinImpl.
+///
+/// A new instance is created with Default for each element instantiated
+pub trait ElementImpl: Default + Send + Sync + 'static{
+    fn metadata() -> Metadata;
+
+    fn pad_templates() -> Vec<PadTemplate>{
+        Vec::new()
+    }
+}
+
+// Sets the metadata and pad templates of the class of an element
+// implemented in Rust, has to be called by every class_init
+pub(crate) unsafe fn element_class_init<T: ElementImpl>(class: gpointer){
+    install_finalize::<T>(class);
+    let element_class = class as *mut GstElementClass;
+    let metadata = T::metadata();
+    gst_element_class_set_metadata(element_class, to_c_str!(metadata.long_name),
+        to_c_str!(metadata.classification), to_c_str!(metadata.description), to_c_str!(metadata.author));
+    for template in T::pad_templates(){
+        let caps = gst_caps_from_string(to_c_str!(template.caps));
+        let pad_template = gst_pad_template_new(to_c_str!(template.name), template.direction, template.presence, caps);
+        gst_mini_object_unref(caps as *mut GstMiniObject);
+        gst_element_class_add_pad_template(element_class, pad_template);
+    }
+}
+
+// Registers the type of an element implemented in Rust and the factory
+// to create it by name
+pub(crate) unsafe fn register_element(parent: GType, name: &str, rank: u32,
+        class_init: extern "C" fn(gpointer, gpointer),
+        instance_init: extern "C" fn(*mut GTypeInstance, gpointer)) -> bool{
+    let element_type = register_type(parent, &type_name(name), class_init, instance_init);
+    element_type != 0 && gst_element_register(ptr::null_mut(), to_c_str!(name), rank, element_type) == 1
+}
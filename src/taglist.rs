@@ -0,0 +1,168 @@
+use ffi::*;
+use util::*;
+use value::Value;
+use std::fmt::{self, Debug, Display, Formatter};
+
+#[cfg(feature="serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+#[cfg(feature="serde")]
+use serde::de::Error as SerdeError;
+#[cfg(feature="serde")]
+use std::collections::BTreeMap;
+
+unsafe impl Send for TagList {}
+
+/// A list of media metadata tags as found in TAG messages and events
+pub struct TagList{
+    taglist: *mut GstTagList
+}
+
+impl Drop for TagList{
+    fn drop(&mut self){
+        unsafe{
+            gst_mini_object_unref(self.taglist as *mut GstMiniObject);
+        }
+    }
+}
+
+impl TagList{
+    /// Creates a new empty tag list
+    pub fn new() -> TagList{
+        unsafe{
+            TagList{ taglist: gst_tag_list_new_empty() }
+        }
+    }
+
+    /// Parses a tag list from its string representation as in
+    /// `taglist, title=(string)"Song", track-number=(uint)3`
+    pub fn from_string(desc: &str) -> Option<TagList>{
+        unsafe{
+            TagList::new_from_gst_taglist(gst_tag_list_new_from_string(to_c_str!(desc)), true)
+        }
+    }
+
+    pub unsafe fn new_from_gst_taglist(taglist: *mut GstTagList, owned: bool) -> Option<TagList>{
+        if taglist != ptr::null_mut(){
+            if !owned{
+                gst_mini_object_ref(taglist as *mut GstMiniObject);
+            }
+            Some(TagList{ taglist: taglist })
+        }else{
+            None
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        unsafe{
+            gst_tag_list_is_empty(self.taglist) == 1
+        }
+    }
+
+    /// Returns the names of all the tags in the list
+    pub fn tags(&self) -> Vec<String>{
+        unsafe{
+            (0..gst_tag_list_n_tags(self.taglist) as u32)
+                .map(|i| from_c_str!(gst_tag_list_nth_tag_name(self.taglist, i)).to_string())
+                .collect()
+        }
+    }
+
+    /// Returns the value of tag. If the tag has several values they are
+    /// merged into one by the merge function of the tag, strings are
+    /// joined with commas and some tags keep only the first value
+    pub fn get(&self, tag: &str) -> Option<Value>{
+        unsafe{
+            let mut gvalue: GValue = mem::zeroed();
+            if gst_tag_list_copy_value(&mut gvalue, self.taglist, to_c_str!(tag)) == 1{
+                let value = Value::from_gvalue(&gvalue);
+                g_value_unset(&mut gvalue);
+                value
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Appends value to tag, value has to be of the type registered for
+    /// the tag. Values of type List are appended one by one.
+    ///
+    /// Returns false if the value couldn't be converted
+    pub fn add<V: Into<Value>>(&mut self, tag: &str, value: V) -> bool{
+        match value.into(){
+            Value::List(values) => values.into_iter().all(|value| self.add(tag, value)),
+            value => unsafe{
+                match value.to_gvalue(){
+                    Some(mut gvalue) => {
+                        self.taglist = gst_mini_object_make_writable(self.taglist as *mut GstMiniObject) as *mut GstTagList;
+                        gst_tag_list_add_value(self.taglist, GST_TAG_MERGE_APPEND, to_c_str!(tag), &gvalue);
+                        g_value_unset(&mut gvalue);
+                        true
+                    }
+                    None => false
+                }
+            }
+        }
+    }
+
+    pub fn remove_tag(&mut self, tag: &str){
+        unsafe{
+            self.taglist = gst_mini_object_make_writable(self.taglist as *mut GstMiniObject) as *mut GstTagList;
+            gst_tag_list_remove_tag(self.taglist, to_c_str!(tag));
+        }
+    }
+
+    pub unsafe fn gst_taglist(&self) -> *const GstTagList{
+        self.taglist
+    }
+
+    pub unsafe fn gst_taglist_mut(&mut self) -> *mut GstTagList{
+        self.taglist
+    }
+}
+
+impl ::Transfer<GstTagList> for TagList{
+    unsafe fn transfer(self) -> *mut GstTagList{
+        let taglist = self.taglist;
+        mem::forget(self);
+        taglist
+    }
+}
+
+/// Formats the tag list in the same format accepted by from_string
+impl Display for TagList{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        unsafe{
+            let c_str = gst_tag_list_to_string(self.taglist);
+            let ret = fmt.write_str(from_c_str!(c_str));
+            g_free(c_str as gpointer);
+            ret
+        }
+    }
+}
+
+impl Debug for TagList{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "TagList({})", self)
+    }
+}
+
+#[cfg(feature="serde")]
+impl Serialize for TagList{
+    fn serialize<S: Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>{
+        serializer.collect_map(self.tags().into_iter().filter_map(|tag| self.get(&tag).map(|value| (tag, value))))
+    }
+}
+
+#[cfg(feature="serde")]
+impl<'de> Deserialize<'de> for TagList{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> ::std::result::Result<TagList, D::Error>{
+        let tags = BTreeMap::<String, Value>::deserialize(deserializer)?;
+        let mut taglist = TagList::new();
+        for (tag, value) in tags{
+            if !taglist.add(&tag, value){
+                return Err(D::Error::custom(format!("invalid value for tag {}", tag)));
+            }
+        }
+        Ok(taglist)
+    }
+}
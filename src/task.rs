@@ -0,0 +1,362 @@
+use ffi::*;
+use util::*;
+use error::{Error, Result};
+use pad::Pad;
+
+/// A thread running a function in a loop until paused or stopped, as the
+/// streaming thread of a source or demuxer that pulls its data. The
+/// thread comes from the TaskPool of the task.
+pub struct Task{
+    task: *mut GstTask
+}
+
+unsafe impl Sync for Task {}
+unsafe impl Send for Task {}
+
+extern "C" fn task_func<F: FnMut()>(data: gpointer){
+    unsafe{
+        // the task only calls it from its thread, with the lock taken
+        (*(data as *mut F))();
+    }
+}
+
+extern "C" fn free_rec_mutex(data: gpointer){
+    unsafe{
+        g_rec_mutex_clear(data as *mut GRecMutex);
+        drop(Box::from_raw(data as *mut GRecMutex));
+    }
+}
+
+impl Task{
+    /// Creates a stopped task that calls f in a loop once started
+    pub fn new<F: FnMut() + Send + 'static>(f: F) -> Option<Task>{
+        unsafe{
+            let task = gst_task_new(Some(task_func::<F>), Box::into_raw(Box::new(f)) as gpointer, Some(box_destroy_notify::<F>));
+            if task == ptr::null_mut(){
+                return None;
+            }
+            // the task can't start without a lock, which has to live as
+            // long as the task
+            let lock = Box::into_raw(Box::new(mem::zeroed::<GRecMutex>()));
+            g_rec_mutex_init(lock);
+            gst_task_set_lock(task, lock);
+            g_object_set_data_full(task as *mut GObject, to_c_str!("gst-rs-task-lock"),
+                lock as gpointer, Some(free_rec_mutex));
+            Some(Task{ task: task })
+        }
+    }
+
+    pub unsafe fn new_from_gst_task(task: *mut GstTask, owned: bool) -> Option<Task>{
+        if task != ptr::null_mut(){
+            if !owned{
+                gst_object_ref(task as gpointer);
+            }
+            Some(Task{ task: task })
+        }else{
+            None
+        }
+    }
+
+    /// Starts calling the function in a thread of the pool, or resumes a
+    /// paused task
+    pub fn start(&self) -> bool{
+        unsafe{
+            gst_task_start(self.task) == 1
+        }
+    }
+
+    /// Stops calling the function but keeps the thread
+    pub fn pause(&self) -> bool{
+        unsafe{
+            gst_task_pause(self.task) == 1
+        }
+    }
+
+    /// Stops calling the function, the current call is not interrupted.
+    /// Call join to wait for it to finish
+    pub fn stop(&self) -> bool{
+        unsafe{
+            gst_task_stop(self.task) == 1
+        }
+    }
+
+    /// Waits for a stopped task to finish and returns its thread to the
+    /// pool. Can't be called from the function itself
+    pub fn join(&self) -> bool{
+        unsafe{
+            gst_task_join(self.task) == 1
+        }
+    }
+
+    pub fn state(&self) -> GstTaskState{
+        unsafe{
+            gst_task_get_state(self.task)
+        }
+    }
+
+    /// The pool the thread of the task comes from, the default one unless
+    /// changed with set_pool
+    pub fn pool(&self) -> Option<TaskPool>{
+        unsafe{
+            TaskPool::new_from_gst_task_pool(gst_task_get_pool(self.task), true)
+        }
+    }
+
+    /// Takes the thread from pool next time the task starts
+    pub fn set_pool(&self, pool: &TaskPool){
+        unsafe{
+            gst_task_set_pool(self.task, pool.pool);
+        }
+    }
+
+    pub unsafe fn gst_task(&self) -> *mut GstTask{
+        self.task
+    }
+}
+
+impl Clone for Task{
+    fn clone(&self) -> Task{
+        unsafe{
+            gst_object_ref(self.task as gpointer);
+            Task{ task: self.task }
+        }
+    }
+}
+
+impl Drop for Task{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.task as gpointer);
+        }
+    }
+}
+
+impl Pad{
+    /// Starts a task calling f in a loop with the stream lock of the pad
+    /// taken, as the loop of a source or demuxer in pull mode pushing
+    /// buffers on this pad. A running task is replaced
+    pub fn start_task<F: FnMut() + Send + 'static>(&self, f: F) -> bool{
+        unsafe{
+            gst_pad_start_task(self.gst_pad() as *mut GstPad, Some(task_func::<F>),
+                Box::into_raw(Box::new(f)) as gpointer, Some(box_destroy_notify::<F>)) == 1
+        }
+    }
+
+    /// Pauses the task of the pad, waiting for the current call to
+    /// finish
+    pub fn pause_task(&self) -> bool{
+        unsafe{
+            gst_pad_pause_task(self.gst_pad() as *mut GstPad) == 1
+        }
+    }
+
+    /// Stops the task of the pad and waits for it to finish. The function
+    /// has to return for this to complete, so unblock it before, as when
+    /// flushing
+    pub fn stop_task(&self) -> bool{
+        unsafe{
+            gst_pad_stop_task(self.gst_pad() as *mut GstPad) == 1
+        }
+    }
+}
+
+/// The threads used by tasks. The default pool creates threads as needed
+/// and keeps them for reuse, a custom one can be implemented with
+/// TaskPoolImpl to run the streaming threads in the thread pool of the
+/// application
+pub struct TaskPool{
+    pool: *mut GstTaskPool
+}
+
+unsafe impl Sync for TaskPool {}
+unsafe impl Send for TaskPool {}
+
+impl TaskPool{
+    /// A pool with the default implementation, it has to be prepared
+    /// before use
+    pub fn new() -> Option<TaskPool>{
+        unsafe{
+            TaskPool::new_from_gst_task_pool(gst_task_pool_new(), true)
+        }
+    }
+
+    /// A pool running the jobs with pool
+    pub fn new_custom<P: TaskPoolImpl>(pool: P) -> TaskPool{
+        unsafe{
+            let task_pool = g_object_new(rs_task_pool_type(), ptr::null::<gchar>()) as *mut RsTaskPool;
+            gst_object_ref_sink(task_pool as gpointer);
+            let imp: Box<dyn TaskPoolImpl> = Box::new(pool);
+            (*task_pool).imp = Box::into_raw(Box::new(imp));
+            TaskPool{ pool: task_pool as *mut GstTaskPool }
+        }
+    }
+
+    pub unsafe fn new_from_gst_task_pool(pool: *mut GstTaskPool, owned: bool) -> Option<TaskPool>{
+        if pool != ptr::null_mut(){
+            if !owned{
+                gst_object_ref(pool as gpointer);
+            }
+            Some(TaskPool{ pool: pool })
+        }else{
+            None
+        }
+    }
+
+    /// Prepares the pool to run tasks, as starting its threads
+    pub fn prepare(&self) -> Result<()>{
+        unsafe{
+            let mut error = ptr::null_mut::<GError>();
+            gst_task_pool_prepare(self.pool, &mut error);
+            if error == ptr::null_mut(){
+                Ok(())
+            }else{
+                Err(Error::new_from_g_error(error))
+            }
+        }
+    }
+
+    /// Waits for the jobs to finish and frees the threads
+    pub fn cleanup(&self){
+        unsafe{
+            gst_task_pool_cleanup(self.pool);
+        }
+    }
+
+    pub unsafe fn gst_task_pool(&self) -> *mut GstTaskPool{
+        self.pool
+    }
+}
+
+impl Clone for TaskPool{
+    fn clone(&self) -> TaskPool{
+        unsafe{
+            gst_object_ref(self.pool as gpointer);
+            TaskPool{ pool: self.pool }
+        }
+    }
+}
+
+impl Drop for TaskPool{
+    fn drop(&mut self){
+        unsafe{
+            gst_object_unref(self.pool as gpointer);
+        }
+    }
+}
+
+/// A thread pool of the application used by GStreamer tasks, as set with
+/// TaskPool::new_custom
+pub trait TaskPoolImpl: Send + Sync + 'static{
+    /// Called before the first job, to start the threads
+    fn prepare(&self){}
+
+    /// Called when the pool is not used anymore, to stop the threads
+    fn cleanup(&self){}
+
+    /// Runs job in a thread of the pool, returns false if it can't. A
+    /// task keeps the thread until it's stopped, so the pool needs at
+    /// least one thread per running task
+    fn push(&self, job: TaskPoolJob) -> bool;
+}
+
+/// A function a TaskPoolImpl has to run in one of its threads
+pub struct TaskPoolJob{
+    func: extern "C" fn(gpointer),
+    data: gpointer,
+}
+
+unsafe impl Send for TaskPoolJob {}
+
+impl TaskPoolJob{
+    pub fn run(self){
+        (self.func)(self.data)
+    }
+}
+
+#[repr(C)]
+struct RsTaskPool{
+    parent: GstTaskPool,
+    imp: *mut Box<dyn TaskPoolImpl>,
+}
+
+static REGISTER: ::std::sync::Once = ::std::sync::Once::new();
+static mut RS_TASK_POOL_TYPE: GType = 0;
+static mut PARENT_CLASS: *mut GObjectClass = 0 as *mut GObjectClass;
+
+fn rs_task_pool_type() -> GType{
+    unsafe{
+        REGISTER.call_once(|| {
+            let mut info: GTypeInfo = mem::zeroed();
+            info.class_size = mem::size_of::<GstTaskPoolClass>() as guint16;
+            info.class_init = Some(mem::transmute(rs_task_pool_class_init as extern "C" fn(gpointer, gpointer)));
+            info.instance_size = mem::size_of::<RsTaskPool>() as guint16;
+            RS_TASK_POOL_TYPE = g_type_register_static(gst_task_pool_get_type(),
+                to_c_str!("GstRsTaskPool"), &info, 0);
+        });
+        RS_TASK_POOL_TYPE
+    }
+}
+
+extern "C" fn rs_task_pool_class_init(class: gpointer, _data: gpointer){
+    unsafe{
+        PARENT_CLASS = g_type_class_peek_parent(class) as *mut GObjectClass;
+        let pool_class = class as *mut GstTaskPoolClass;
+        (*pool_class).prepare = Some(rs_task_pool_prepare);
+        (*pool_class).cleanup = Some(rs_task_pool_cleanup);
+        (*pool_class).push = Some(rs_task_pool_push);
+        (*pool_class).join = Some(rs_task_pool_join);
+        (*pool_class).parent_class.parent_class.finalize = Some(rs_task_pool_finalize);
+    }
+}
+
+unsafe fn rs_task_pool_imp<'a>(pool: *mut GstTaskPool) -> Option<&'a Box<dyn TaskPoolImpl>>{
+    let imp = (*(pool as *mut RsTaskPool)).imp;
+    if imp != ptr::null_mut(){ Some(&*imp) }else{ None }
+}
+
+extern "C" fn rs_task_pool_prepare(pool: *mut GstTaskPool, _error: *mut *mut GError){
+    unsafe{
+        if let Some(imp) = rs_task_pool_imp(pool){
+            imp.prepare();
+        }
+    }
+}
+
+extern "C" fn rs_task_pool_cleanup(pool: *mut GstTaskPool){
+    unsafe{
+        if let Some(imp) = rs_task_pool_imp(pool){
+            imp.cleanup();
+        }
+    }
+}
+
+extern "C" fn rs_task_pool_push(pool: *mut GstTaskPool, func: GstTaskPoolFunction, user_data: gpointer, error: *mut *mut GError) -> gpointer{
+    unsafe{
+        let pushed = match (rs_task_pool_imp(pool), func){
+            (Some(imp), Some(func)) => imp.push(TaskPoolJob{ func: func, data: user_data }),
+            _ => false
+        };
+        if !pushed && error != ptr::null_mut(){
+            *error = g_error_new_literal(gst_core_error_quark(), GST_CORE_ERROR_FAILED as gint,
+                to_c_str!("The task pool couldn't run the job"));
+        }
+        // the job can't be joined, tasks wait for their function to
+        // return by themselves
+        ptr::null_mut()
+    }
+}
+
+extern "C" fn rs_task_pool_join(_pool: *mut GstTaskPool, _id: gpointer){}
+
+extern "C" fn rs_task_pool_finalize(object: *mut GObject){
+    unsafe{
+        let pool = object as *mut RsTaskPool;
+        if (*pool).imp != ptr::null_mut(){
+            drop(Box::from_raw((*pool).imp));
+            (*pool).imp = ptr::null_mut();
+        }
+        if let Some(finalize) = (*PARENT_CLASS).finalize{
+            finalize(object);
+        }
+    }
+}
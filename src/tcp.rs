@@ -0,0 +1,200 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+use value::Value;
+use gio::*;
+
+fn int_property(element: &Element, name: &str) -> i32{
+    match element.property(name){
+        Some(Value::Int(value)) => value,
+        _ => 0
+    }
+}
+
+extern "C" fn client_callback<F: Fn(&str) + Send + Sync + 'static>(_element: *mut GstElement, socket: *mut GSocket, data: gpointer){
+    unsafe{
+        (*(data as *const F))(&socket_remote_address(socket));
+    }
+}
+
+/// Wrapper around tcpserversink, which listens on a port and sends the
+/// stream to every client that connects, as a quick way to watch a stream
+/// from another machine with tcpclientsrc or a player
+pub struct TcpServerSink{
+    tcpserversink: Element
+}
+
+unsafe impl Sync for TcpServerSink {}
+unsafe impl Send for TcpServerSink {}
+
+impl TcpServerSink{
+    pub fn new(name: &str) -> Option<TcpServerSink>{
+        Element::new("tcpserversink", name).map(|tcpserversink| TcpServerSink{ tcpserversink: tcpserversink })
+    }
+
+    pub fn new_from_element(element: Element) -> TcpServerSink{
+        TcpServerSink{ tcpserversink: element }
+    }
+
+    /// Address to listen on, as "0.0.0.0" for all the interfaces
+    pub fn set_host(&self, host: &str){
+        self.tcpserversink.set("host", to_c_str!(host));
+    }
+
+    /// Port to listen on, 0 to let the system pick one, see current_port
+    pub fn set_port(&self, port: u16){
+        self.tcpserversink.set("port", port as gint);
+    }
+
+    /// The port the sink listens on once it's in READY, the one picked by
+    /// the system if the port was set to 0
+    pub fn current_port(&self) -> u16{
+        int_property(&self.tcpserversink, "current-port") as u16
+    }
+
+    /// Number of clients connected
+    pub fn num_clients(&self) -> u32{
+        match self.tcpserversink.property("num-handles"){
+            Some(Value::UInt(clients)) => clients,
+            _ => 0
+        }
+    }
+
+    /// Calls f with the address of each client that connects, as
+    /// "192.168.1.20:53412", from the thread accepting the connection
+    pub fn on_client_added<F: Fn(&str) + Send + Sync + 'static>(&self, f: F){
+        self.connect_client_signal("client-added", f);
+    }
+
+    /// Calls f with the address of each client that disconnects or is
+    /// removed, the address can be empty if the connection is already
+    /// closed
+    pub fn on_client_removed<F: Fn(&str) + Send + Sync + 'static>(&self, f: F){
+        self.connect_client_signal("client-socket-removed", f);
+    }
+
+    fn connect_client_signal<F: Fn(&str) + Send + Sync + 'static>(&self, signal: &str, f: F){
+        unsafe{
+            connect_boxed(self.tcpserversink.gst_element() as gpointer, signal,
+                client_callback::<F> as extern "C" fn(*mut GstElement, *mut GSocket, gpointer) as gpointer,
+                Box::new(f));
+        }
+    }
+}
+
+impl ElementT for TcpServerSink{
+    fn as_element(&self) -> &Element{
+        &self.tcpserversink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.tcpserversink
+    }
+}
+
+impl ::Transfer for TcpServerSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.tcpserversink.transfer()
+    }
+}
+
+/// Wrapper around tcpclientsrc, which connects to a server and receives
+/// a stream from it
+pub struct TcpClientSrc{
+    tcpclientsrc: Element
+}
+
+unsafe impl Sync for TcpClientSrc {}
+unsafe impl Send for TcpClientSrc {}
+
+impl TcpClientSrc{
+    pub fn new(name: &str) -> Option<TcpClientSrc>{
+        Element::new("tcpclientsrc", name).map(|tcpclientsrc| TcpClientSrc{ tcpclientsrc: tcpclientsrc })
+    }
+
+    pub fn new_from_element(element: Element) -> TcpClientSrc{
+        TcpClientSrc{ tcpclientsrc: element }
+    }
+
+    /// Server to connect to
+    pub fn set_host(&self, host: &str){
+        self.tcpclientsrc.set("host", to_c_str!(host));
+    }
+
+    pub fn set_port(&self, port: u16){
+        self.tcpclientsrc.set("port", port as gint);
+    }
+
+    /// Seconds without data after which the source fails, 0 to wait
+    /// forever
+    pub fn set_timeout(&self, timeout: u32){
+        self.tcpclientsrc.set("timeout", timeout);
+    }
+}
+
+impl ElementT for TcpClientSrc{
+    fn as_element(&self) -> &Element{
+        &self.tcpclientsrc
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.tcpclientsrc
+    }
+}
+
+impl ::Transfer for TcpClientSrc{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.tcpclientsrc.transfer()
+    }
+}
+
+/// Wrapper around tcpserversrc, which listens on a port and receives a
+/// stream from the first client that connects
+pub struct TcpServerSrc{
+    tcpserversrc: Element
+}
+
+unsafe impl Sync for TcpServerSrc {}
+unsafe impl Send for TcpServerSrc {}
+
+impl TcpServerSrc{
+    pub fn new(name: &str) -> Option<TcpServerSrc>{
+        Element::new("tcpserversrc", name).map(|tcpserversrc| TcpServerSrc{ tcpserversrc: tcpserversrc })
+    }
+
+    pub fn new_from_element(element: Element) -> TcpServerSrc{
+        TcpServerSrc{ tcpserversrc: element }
+    }
+
+    /// Address to listen on, as "0.0.0.0" for all the interfaces
+    pub fn set_host(&self, host: &str){
+        self.tcpserversrc.set("host", to_c_str!(host));
+    }
+
+    /// Port to listen on, 0 to let the system pick one, see current_port
+    pub fn set_port(&self, port: u16){
+        self.tcpserversrc.set("port", port as gint);
+    }
+
+    /// The port the source listens on once it's in READY, the one picked
+    /// by the system if the port was set to 0
+    pub fn current_port(&self) -> u16{
+        int_property(&self.tcpserversrc, "current-port") as u16
+    }
+}
+
+impl ElementT for TcpServerSrc{
+    fn as_element(&self) -> &Element{
+        &self.tcpserversrc
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.tcpserversrc
+    }
+}
+
+impl ::Transfer for TcpServerSrc{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.tcpserversrc.transfer()
+    }
+}
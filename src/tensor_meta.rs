@@ -0,0 +1,204 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use meta::MetaApi;
+
+use std::sync::Once;
+
+/// Elements of a tensor, in row major order
+#[derive(Clone,Debug,PartialEq)]
+pub enum TensorData{
+    U8(Vec<u8>),
+    I8(Vec<i8>),
+    U16(Vec<u16>),
+    I16(Vec<i16>),
+    U32(Vec<u32>),
+    I32(Vec<i32>),
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+}
+
+impl TensorData{
+    pub fn len(&self) -> usize{
+        match *self{
+            TensorData::U8(ref data) => data.len(),
+            TensorData::I8(ref data) => data.len(),
+            TensorData::U16(ref data) => data.len(),
+            TensorData::I16(ref data) => data.len(),
+            TensorData::U32(ref data) => data.len(),
+            TensorData::I32(ref data) => data.len(),
+            TensorData::F32(ref data) => data.len(),
+            TensorData::F64(ref data) => data.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+}
+
+/// A named n-dimensional tensor, as the input or output of an inference
+/// model
+#[derive(Clone,Debug,PartialEq)]
+pub struct Tensor{
+    name: String,
+    dims: Vec<usize>,
+    data: TensorData,
+}
+
+impl Tensor{
+    /// Creates a tensor with the given dimensions, returns None if the
+    /// number of elements in data doesn't match them
+    pub fn new(name: &str, dims: &[usize], data: TensorData) -> Option<Tensor>{
+        if dims.iter().product::<usize>() != data.len(){
+            return None;
+        }
+        Some(Tensor{
+            name: name.to_string(),
+            dims: dims.to_vec(),
+            data: data,
+        })
+    }
+
+    pub fn name(&self) -> &str{
+        &self.name
+    }
+
+    pub fn dims(&self) -> &[usize]{
+        &self.dims
+    }
+
+    pub fn data(&self) -> &TensorData{
+        &self.data
+    }
+
+    pub fn into_data(self) -> TensorData{
+        self.data
+    }
+}
+
+/// A meta carrying the tensors of an inference on the buffer.
+///
+/// GStreamer's own GstTensorMeta and analytics metas need versions newer
+/// than the ones this crate supports, so this is a meta registered by the
+/// crate. It's only visible to code using this crate, as an appsink
+/// callback reading the tensors attached by an application element
+/// upstream, and it's copied along with the buffer.
+#[repr(C)]
+pub struct TensorMeta{
+    meta: GstMeta,
+    tensors: *mut Vec<Tensor>,
+}
+
+static REGISTER: Once = Once::new();
+static mut TENSOR_META_API: GType = 0;
+static mut TENSOR_META_INFO: *const GstMetaInfo = 0 as *const GstMetaInfo;
+
+fn tensor_meta_info() -> *const GstMetaInfo{
+    unsafe{
+        REGISTER.call_once(|| {
+            let tag = CString::new("tensor").unwrap();
+            let mut tags = [tag.as_ptr(), ptr::null()];
+            TENSOR_META_API = gst_meta_api_type_register(to_c_str!("GstRsTensorMetaAPI"), tags.as_mut_ptr());
+            TENSOR_META_INFO = gst_meta_register(TENSOR_META_API, to_c_str!("GstRsTensorMeta"),
+                mem::size_of::<TensorMeta>() as gsize,
+                Some(tensor_meta_init), Some(tensor_meta_free), Some(tensor_meta_transform));
+        });
+        TENSOR_META_INFO
+    }
+}
+
+extern "C" fn tensor_meta_init(meta: *mut GstMeta, _params: gpointer, _buffer: *mut GstBuffer) -> gboolean{
+    unsafe{
+        (*(meta as *mut TensorMeta)).tensors = Box::into_raw(Box::new(Vec::new()));
+    }
+    1
+}
+
+extern "C" fn tensor_meta_free(meta: *mut GstMeta, _buffer: *mut GstBuffer){
+    unsafe{
+        let meta = meta as *mut TensorMeta;
+        if (*meta).tensors != ptr::null_mut(){
+            drop(Box::from_raw((*meta).tensors));
+            (*meta).tensors = ptr::null_mut();
+        }
+    }
+}
+
+extern "C" fn tensor_meta_transform(transbuf: *mut GstBuffer, meta: *mut GstMeta, _buffer: *mut GstBuffer,
+        transform_type: GQuark, _data: gpointer) -> gboolean{
+    unsafe{
+        if transform_type != g_quark_from_string(to_c_str!("gst-copy")){
+            return 0;
+        }
+        let dest = gst_buffer_add_meta(transbuf, tensor_meta_info(), ptr::null_mut()) as *mut TensorMeta;
+        if dest == ptr::null_mut(){
+            return 0;
+        }
+        *(*dest).tensors = (*(*(meta as *mut TensorMeta)).tensors).clone();
+        1
+    }
+}
+
+unsafe impl MetaApi for TensorMeta{
+    fn api_type() -> GType{
+        tensor_meta_info();
+        unsafe{ TENSOR_META_API }
+    }
+}
+
+impl TensorMeta{
+    pub fn tensors(&self) -> &[Tensor]{
+        unsafe{ &*self.tensors }
+    }
+
+    /// Returns the tensor with the given name
+    pub fn tensor(&self, name: &str) -> Option<&Tensor>{
+        self.tensors().iter().find(|tensor| tensor.name == name)
+    }
+
+    pub fn add_tensor(&mut self, tensor: Tensor){
+        unsafe{ (*self.tensors).push(tensor) }
+    }
+}
+
+impl Buffer{
+    /// Returns the tensor meta of the buffer, adding an empty one if it
+    /// didn't have any. Returns None if the buffer isn't writable
+    pub fn tensor_meta_mut(&mut self) -> Option<&mut TensorMeta>{
+        unsafe{
+            let buffer = self.gst_buffer_mut();
+            if gst_mini_object_is_writable(buffer as *const GstMiniObject) == 0{
+                return None;
+            }
+            let mut meta = gst_buffer_get_meta(buffer, TensorMeta::api_type());
+            if meta == ptr::null_mut(){
+                meta = gst_buffer_add_meta(buffer, tensor_meta_info(), ptr::null_mut());
+                if meta == ptr::null_mut(){
+                    return None;
+                }
+            }
+            Some(&mut *(meta as *mut TensorMeta))
+        }
+    }
+
+    /// Attaches a tensor to the buffer, returns false if the buffer isn't
+    /// writable
+    pub fn add_tensor(&mut self, tensor: Tensor) -> bool{
+        match self.tensor_meta_mut(){
+            Some(meta) => {
+                meta.add_tensor(tensor);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// The tensors attached to the buffer
+    pub fn tensors(&self) -> &[Tensor]{
+        match self.meta::<TensorMeta>(){
+            Some(meta) => meta.tensors(),
+            None => &[]
+        }
+    }
+}
@@ -0,0 +1,391 @@
+//! Helpers to test pipelines without devices or media files, needs the
+//! `test-support` feature.
+//!
+//! fakesrc produces empty buffers as fast as possible and fakesink
+//! consumes them, so a pipeline can be run to EOS deterministically and
+//! the buffers that reached the sink checked with a Recorder:
+//!
+//! ```ignore
+//! let mut pipeline = Pipeline::new("test").unwrap();
+//! let src = FakeSrc::new("src").unwrap();
+//! src.set_num_buffers(10);
+//! let sink = FakeSink::new("sink").unwrap();
+//! let recorder = sink.recorder();
+//! ...
+//! run_to_eos(&mut pipeline, 5 * 1000000000).unwrap();
+//! recorder.assert_buffers(10);
+//! ```
+
+use ffi::*;
+use util::*;
+use base_sink::BaseSinkT;
+use buffer::Buffer;
+use caps::Caps;
+use element::{Element, ElementT};
+use identity::Identity;
+use pad::Pad;
+use pipeline::PipelineT;
+use error::{Error, Result};
+
+use std::sync::{Arc, Mutex};
+
+/// Wrapper around fakesrc
+pub struct FakeSrc{
+    fakesrc: Element
+}
+
+unsafe impl Sync for FakeSrc {}
+unsafe impl Send for FakeSrc {}
+
+impl FakeSrc{
+    /// A fakesrc that doesn't print every buffer
+    pub fn new(name: &str) -> Option<FakeSrc>{
+        let fakesrc = Element::new("fakesrc", name)?;
+        fakesrc.set("silent", 1 as gboolean);
+        Some(FakeSrc{ fakesrc: fakesrc })
+    }
+
+    /// Buffers to produce before EOS, -1 for unlimited
+    pub fn set_num_buffers(&self, num_buffers: i32){
+        self.fakesrc.set("num-buffers", num_buffers);
+    }
+
+    pub fn set_silent(&self, silent: bool){
+        self.fakesrc.set("silent", silent as gboolean);
+    }
+
+    /// Whether it behaves as a live source, as a camera
+    pub fn set_is_live(&self, is_live: bool){
+        self.fakesrc.set("is-live", is_live as gboolean);
+    }
+
+    /// Produces buffers of size bytes, instead of empty buffers
+    pub fn set_buffer_size(&self, size: i32){
+        // FAKE_SRC_SIZETYPE_FIXED
+        self.fakesrc.set("sizetype", 2 as gint);
+        self.fakesrc.set("sizemax", size);
+    }
+
+    /// Calls f with every buffer before it's pushed, from the streaming
+    /// thread
+    pub fn on_handoff<F: Fn(&Buffer, &Pad) + Send + Sync + 'static>(&self, f: F){
+        self.fakesrc.set("signal-handoffs", 1 as gboolean);
+        unsafe{
+            connect_handoff(&self.fakesrc, Box::new(f));
+        }
+    }
+}
+
+impl ElementT for FakeSrc{
+    fn as_element(&self) -> &Element{
+        &self.fakesrc
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.fakesrc
+    }
+}
+
+impl ::Transfer for FakeSrc{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.fakesrc.transfer()
+    }
+}
+
+/// Wrapper around fakesink
+pub struct FakeSink{
+    fakesink: Element
+}
+
+unsafe impl Sync for FakeSink {}
+unsafe impl Send for FakeSink {}
+
+impl FakeSink{
+    /// A fakesink that doesn't print every buffer
+    pub fn new(name: &str) -> Option<FakeSink>{
+        let fakesink = Element::new("fakesink", name)?;
+        fakesink.set("silent", 1 as gboolean);
+        Some(FakeSink{ fakesink: fakesink })
+    }
+
+    pub fn set_silent(&self, silent: bool){
+        self.fakesink.set("silent", silent as gboolean);
+    }
+
+    /// Buffers to consume before posting EOS, -1 for unlimited
+    pub fn set_num_buffers(&self, num_buffers: i32){
+        self.fakesink.set("num-buffers", num_buffers);
+    }
+
+    /// Calls f with every buffer the sink consumes, from the streaming
+    /// thread
+    pub fn on_handoff<F: Fn(&Buffer, &Pad) + Send + Sync + 'static>(&self, f: F){
+        self.fakesink.set("signal-handoffs", 1 as gboolean);
+        unsafe{
+            connect_handoff(&self.fakesink, Box::new(f));
+        }
+    }
+
+    /// Records the buffers the sink consumes from now on
+    pub fn recorder(&self) -> Recorder{
+        let recorder = Recorder::default();
+        let recorded = recorder.clone();
+        self.on_handoff(move |buffer, pad| recorded.record(buffer, pad));
+        recorder
+    }
+}
+
+impl ElementT for FakeSink{
+    fn as_element(&self) -> &Element{
+        &self.fakesink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.fakesink
+    }
+}
+
+impl BaseSinkT for FakeSink{}
+
+impl ::Transfer for FakeSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.fakesink.transfer()
+    }
+}
+
+/// An identity that doesn't print every buffer, to insert between
+/// elements under test and tap the data with on_handoff
+pub fn identity(name: &str) -> Option<Identity>{
+    let identity = Identity::new(name)?;
+    identity.set_silent(true);
+    Some(identity)
+}
+
+/// Timestamps and size of a buffer seen by a Recorder
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct BufferInfo{
+    pub pts: Option<u64>,
+    pub duration: Option<u64>,
+    pub size: u64,
+}
+
+#[derive(Default)]
+struct Recorded{
+    buffers: Vec<BufferInfo>,
+    caps: Option<Caps>,
+}
+
+/// The buffers consumed by a FakeSink, created with FakeSink::recorder.
+/// Clones share the same buffers
+#[derive(Clone,Default)]
+pub struct Recorder{
+    recorded: Arc<Mutex<Recorded>>
+}
+
+unsafe impl Sync for Recorder {}
+unsafe impl Send for Recorder {}
+
+impl Recorder{
+    fn record(&self, buffer: &Buffer, pad: &Pad){
+        let pts = buffer.pts();
+        let duration = buffer.duration();
+        let mut recorded = self.recorded.lock().unwrap();
+        recorded.buffers.push(BufferInfo{
+            pts: if pts == GST_CLOCK_TIME_NONE{ None }else{ Some(pts) },
+            duration: if duration == GST_CLOCK_TIME_NONE{ None }else{ Some(duration) },
+            size: buffer.size(),
+        });
+        if let Some(caps) = pad.current_caps(){
+            recorded.caps = Some(caps);
+        }
+    }
+
+    pub fn buffers(&self) -> Vec<BufferInfo>{
+        self.recorded.lock().unwrap().buffers.clone()
+    }
+
+    pub fn count(&self) -> usize{
+        self.recorded.lock().unwrap().buffers.len()
+    }
+
+    /// The caps of the last buffer recorded
+    pub fn caps(&self) -> Option<Caps>{
+        self.recorded.lock().unwrap().caps.clone()
+    }
+
+    /// Panics if the number of buffers recorded isn't n
+    pub fn assert_buffers(&self, n: usize){
+        let count = self.count();
+        assert!(count == n, "expected {} buffers, the sink received {}", n, count);
+    }
+
+    /// Panics if the caps of the last buffer aren't a subset of caps, so
+    /// "video/x-raw, width=(int)320" matches any 320 pixels wide video
+    pub fn assert_caps(&self, caps: &str){
+        let expected = Caps::from_string(caps).unwrap_or_else(|| panic!("invalid caps {}", caps));
+        match self.caps(){
+            Some(current) => unsafe{
+                assert!(gst_caps_is_subset(current.gst_caps(), expected.gst_caps()) == 1,
+                    "expected caps {}, the sink received {}", expected, current);
+            },
+            None => panic!("expected caps {}, the sink didn't negotiate", expected)
+        }
+    }
+
+    /// Panics if the timestamps of the recorded buffers go backwards
+    pub fn assert_increasing_pts(&self){
+        let buffers = self.buffers();
+        for pair in buffers.windows(2){
+            if let (Some(previous), Some(next)) = (pair[0].pts, pair[1].pts){
+                assert!(next >= previous, "pts went backwards from {} to {}", previous, next);
+            }
+        }
+    }
+}
+
+/// Sets pipeline to PLAYING, waits up to timeout nanoseconds for EOS and
+/// sets it back to NULL. Returns the first error posted or a timeout
+/// error
+pub fn run_to_eos<P: PipelineT>(pipeline: &mut P, timeout: GstClockTime) -> Result<()>{
+    if pipeline.set_state(GST_STATE_PLAYING) == GST_STATE_CHANGE_FAILURE{
+        let err = match pipeline.as_pipeline_mut().wait_for_message(0, 0){
+            Some(Err(err)) => err,
+            _ => Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_STATE_CHANGE as i32,
+                "couldn't set the pipeline to PLAYING")
+        };
+        pipeline.set_state(GST_STATE_NULL);
+        return Err(err);
+    }
+    let result = match pipeline.as_pipeline_mut().wait_for_message(timeout, GST_MESSAGE_EOS){
+        Some(Ok(_)) => Ok(()),
+        Some(Err(err)) => Err(err),
+        None => Err(Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_FAILED as i32,
+            "timed out waiting for EOS"))
+    };
+    pipeline.set_state(GST_STATE_NULL);
+    result
+}
+
+type HandoffCallback = Box<dyn Fn(&Buffer, &Pad) + Send + Sync>;
+
+unsafe fn connect_handoff(element: &Element, f: HandoffCallback){
+    connect_boxed(element.gst_element() as gpointer, "handoff",
+        handoff as extern "C" fn(*mut GstElement, *mut GstBuffer, *mut GstPad, gpointer) as gpointer,
+        Box::new(f));
+}
+
+extern "C" fn handoff(_element: *mut GstElement, buffer: *mut GstBuffer, pad: *mut GstPad, data: gpointer){
+    unsafe{
+        let f = &*(data as *const HandoffCallback);
+        if let (Some(buffer), Some(pad)) = (Buffer::new(buffer, false), Pad::new(pad, false)){
+            f(&buffer, &pad);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use bin::BinT;
+    use pipeline::Pipeline;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const TIMEOUT: GstClockTime = 5 * 1000000000;
+
+    // src ! identity ! sink in a new pipeline, the pipeline takes new
+    // references so the tests can keep using the wrappers
+    fn pipeline(src: &FakeSrc, identity: &Identity, sink: &FakeSink) -> Pipeline{
+        let mut pipeline = Pipeline::new("test").unwrap();
+        let (mut src_ref, mut identity_ref, mut sink_ref) = (src.to_element(), identity.to_element(), sink.to_element());
+        assert!(pipeline.add(src.to_element()) && pipeline.add(identity.to_element()) && pipeline.add(sink.to_element()));
+        assert!(src_ref.link(&mut identity_ref) && identity_ref.link(&mut sink_ref));
+        pipeline
+    }
+
+    #[test]
+    fn records_every_buffer(){
+        ::init();
+        let src = FakeSrc::new("src").unwrap();
+        src.set_num_buffers(10);
+        src.set_buffer_size(64);
+        let identity = identity("identity").unwrap();
+        let sink = FakeSink::new("sink").unwrap();
+        let recorder = sink.recorder();
+        let mut pipeline = pipeline(&src, &identity, &sink);
+        run_to_eos(&mut pipeline, TIMEOUT).unwrap();
+        recorder.assert_buffers(10);
+        assert!(recorder.buffers().iter().all(|buffer| buffer.size == 64));
+        recorder.assert_increasing_pts();
+    }
+
+    #[test]
+    fn handoffs_see_the_same_buffers(){
+        ::init();
+        let src = FakeSrc::new("src").unwrap();
+        src.set_num_buffers(5);
+        let pushed = Arc::new(AtomicUsize::new(0));
+        let passed = Arc::new(AtomicUsize::new(0));
+        let counter = pushed.clone();
+        src.on_handoff(move |_, _| { counter.fetch_add(1, Ordering::SeqCst); });
+        let identity = identity("identity").unwrap();
+        let counter = passed.clone();
+        identity.on_handoff(move |_| { counter.fetch_add(1, Ordering::SeqCst); });
+        let sink = FakeSink::new("sink").unwrap();
+        let recorder = sink.recorder();
+        let mut pipeline = pipeline(&src, &identity, &sink);
+        run_to_eos(&mut pipeline, TIMEOUT).unwrap();
+        assert_eq!(pushed.load(Ordering::SeqCst), 5);
+        assert_eq!(passed.load(Ordering::SeqCst), 5);
+        recorder.assert_buffers(5);
+    }
+
+    #[test]
+    fn sink_stops_after_num_buffers(){
+        ::init();
+        let src = FakeSrc::new("src").unwrap();
+        let identity = identity("identity").unwrap();
+        let sink = FakeSink::new("sink").unwrap();
+        sink.set_num_buffers(3);
+        let recorder = sink.recorder();
+        let mut pipeline = pipeline(&src, &identity, &sink);
+        run_to_eos(&mut pipeline, TIMEOUT).unwrap();
+        recorder.assert_buffers(3);
+    }
+
+    #[test]
+    fn run_to_eos_reports_errors(){
+        ::init();
+        let src = FakeSrc::new("src").unwrap();
+        let identity = identity("identity").unwrap();
+        identity.set_error_after(2);
+        let sink = FakeSink::new("sink").unwrap();
+        let mut pipeline = pipeline(&src, &identity, &sink);
+        assert!(run_to_eos(&mut pipeline, TIMEOUT).is_err());
+    }
+
+    #[test]
+    fn run_to_eos_times_out(){
+        ::init();
+        let src = FakeSrc::new("src").unwrap();
+        src.set_is_live(true);
+        let identity = identity("identity").unwrap();
+        let sink = FakeSink::new("sink").unwrap();
+        let mut pipeline = pipeline(&src, &identity, &sink);
+        assert!(run_to_eos(&mut pipeline, 100 * 1000000).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 4 buffers")]
+    fn assert_buffers_panics_on_mismatch(){
+        ::init();
+        let src = FakeSrc::new("src").unwrap();
+        src.set_num_buffers(2);
+        let identity = identity("identity").unwrap();
+        let sink = FakeSink::new("sink").unwrap();
+        let recorder = sink.recorder();
+        let mut pipeline = pipeline(&src, &identity, &sink);
+        run_to_eos(&mut pipeline, TIMEOUT).unwrap();
+        recorder.assert_buffers(4);
+    }
+}
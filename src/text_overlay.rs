@@ -0,0 +1,228 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+
+use std::sync::mpsc::Receiver;
+use std::thread::{self, JoinHandle};
+
+/// Horizontal alignment of the text, the halignment property
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum HAlignment{
+    Left = 0,
+    Center = 1,
+    Right = 2,
+    /// Uses the xpos property, set with TextOverlayT::set_position
+    Position = 4,
+}
+
+/// Vertical alignment of the text, the valignment property
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum VAlignment{
+    Baseline = 0,
+    Bottom = 1,
+    Top = 2,
+    /// Uses the ypos property, set with TextOverlayT::set_position
+    Position = 3,
+    Center = 4,
+}
+
+/// Which time timeoverlay renders, the time-mode property
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum TimeMode{
+    BufferTime = 0,
+    StreamTime = 1,
+    RunningTime = 2,
+    /// The timecode meta of the buffers
+    TimeCode = 3,
+}
+
+/// Properties shared by textoverlay, timeoverlay and clockoverlay
+pub trait TextOverlayT: ElementT{
+    /// Text to render, for timeoverlay and clockoverlay it's shown
+    /// before the time. Can be changed while playing
+    fn set_text(&self, text: &str){
+        self.as_element().set("text", to_c_str!(text));
+    }
+
+    fn text(&self) -> String{
+        unsafe{
+            let c_str = self.as_element().get::<*mut gchar>("text");
+            if c_str != ptr::null_mut(){
+                let text = from_c_str!(c_str).to_string();
+                g_free(c_str as gpointer);
+                text
+            }else{
+                String::new()
+            }
+        }
+    }
+
+    /// Font in pango format as in "Sans Bold 24"
+    fn set_font_desc(&self, font: &str){
+        self.as_element().set("font-desc", to_c_str!(font));
+    }
+
+    fn set_halignment(&self, halignment: HAlignment){
+        self.as_element().set("halignment", halignment as i32);
+    }
+
+    fn set_valignment(&self, valignment: VAlignment){
+        self.as_element().set("valignment", valignment as i32);
+    }
+
+    /// Places the text at a relative position of the frame, from 0.0 to
+    /// 1.0 in both axes
+    fn set_position(&self, x: f64, y: f64){
+        self.set_halignment(HAlignment::Position);
+        self.set_valignment(VAlignment::Position);
+        self.as_element().set("xpos", x);
+        self.as_element().set("ypos", y);
+    }
+
+    /// Draws a darker background behind the text
+    fn set_shaded_background(&self, shaded: bool){
+        self.as_element().set("shaded-background", shaded as gboolean);
+    }
+
+    /// Color of the text as 0xAARRGGBB
+    fn set_color(&self, argb: u32){
+        self.as_element().set("color", argb);
+    }
+
+    fn set_draw_outline(&self, outline: bool){
+        self.as_element().set("draw-outline", outline as gboolean);
+    }
+
+    fn set_draw_shadow(&self, shadow: bool){
+        self.as_element().set("draw-shadow", shadow as gboolean);
+    }
+
+    /// Hides the text without removing the element
+    fn set_silent(&self, silent: bool){
+        self.as_element().set("silent", silent as gboolean);
+    }
+
+    /// Updates the text with every string received on texts, from a
+    /// thread that ends when the sender is dropped
+    fn set_text_from(&self, texts: Receiver<String>) -> JoinHandle<()>{
+        let element = self.to_element();
+        thread::spawn(move || {
+            for text in texts{
+                element.set("text", to_c_str!(text.as_str()));
+            }
+        })
+    }
+}
+
+/// Renders a text over the video
+pub struct TextOverlay{
+    textoverlay: Element
+}
+
+/// Renders the time of the buffers over the video
+pub struct TimeOverlay{
+    timeoverlay: Element
+}
+
+/// Renders the wall clock time over the video
+pub struct ClockOverlay{
+    clockoverlay: Element
+}
+
+unsafe impl Sync for TextOverlay {}
+unsafe impl Send for TextOverlay {}
+unsafe impl Sync for TimeOverlay {}
+unsafe impl Send for TimeOverlay {}
+unsafe impl Sync for ClockOverlay {}
+unsafe impl Send for ClockOverlay {}
+
+impl TextOverlay{
+    pub fn new(name: &str) -> Option<TextOverlay>{
+        Element::new("textoverlay", name).map(|textoverlay| TextOverlay{ textoverlay: textoverlay })
+    }
+
+    pub fn new_from_element(element: Element) -> TextOverlay{
+        TextOverlay{ textoverlay: element }
+    }
+}
+
+impl TimeOverlay{
+    pub fn new(name: &str) -> Option<TimeOverlay>{
+        Element::new("timeoverlay", name).map(|timeoverlay| TimeOverlay{ timeoverlay: timeoverlay })
+    }
+
+    pub fn new_from_element(element: Element) -> TimeOverlay{
+        TimeOverlay{ timeoverlay: element }
+    }
+
+    pub fn set_time_mode(&self, mode: TimeMode){
+        self.timeoverlay.set("time-mode", mode as i32);
+    }
+}
+
+impl ClockOverlay{
+    pub fn new(name: &str) -> Option<ClockOverlay>{
+        Element::new("clockoverlay", name).map(|clockoverlay| ClockOverlay{ clockoverlay: clockoverlay })
+    }
+
+    pub fn new_from_element(element: Element) -> ClockOverlay{
+        ClockOverlay{ clockoverlay: element }
+    }
+
+    /// Format of the time in strftime format, "%H:%M:%S" by default
+    pub fn set_time_format(&self, format: &str){
+        self.clockoverlay.set("time-format", to_c_str!(format));
+    }
+}
+
+impl ElementT for TextOverlay{
+    fn as_element(&self) -> &Element{
+        &self.textoverlay
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.textoverlay
+    }
+}
+
+impl ElementT for TimeOverlay{
+    fn as_element(&self) -> &Element{
+        &self.timeoverlay
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.timeoverlay
+    }
+}
+
+impl ElementT for ClockOverlay{
+    fn as_element(&self) -> &Element{
+        &self.clockoverlay
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.clockoverlay
+    }
+}
+
+impl TextOverlayT for TextOverlay{}
+impl TextOverlayT for TimeOverlay{}
+impl TextOverlayT for ClockOverlay{}
+
+impl ::Transfer for TextOverlay{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.textoverlay.transfer()
+    }
+}
+
+impl ::Transfer for TimeOverlay{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.timeoverlay.transfer()
+    }
+}
+
+impl ::Transfer for ClockOverlay{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.clockoverlay.transfer()
+    }
+}
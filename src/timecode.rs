@@ -0,0 +1,316 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use element::{Element, ElementT};
+
+use std::os::raw::{c_int, c_uint};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+
+// GstVideoTimeCode was added in GStreamer 1.10, after ffi.rs was
+// generated
+pub type GstVideoTimeCodeFlags = c_uint;
+pub const GST_VIDEO_TIME_CODE_FLAGS_NONE: GstVideoTimeCodeFlags = 0;
+pub const GST_VIDEO_TIME_CODE_FLAGS_DROP_FRAME: GstVideoTimeCodeFlags = 1 << 0;
+pub const GST_VIDEO_TIME_CODE_FLAGS_INTERLACED: GstVideoTimeCodeFlags = 1 << 1;
+
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct GstVideoTimeCodeConfig{
+    pub fps_n: guint,
+    pub fps_d: guint,
+    pub flags: GstVideoTimeCodeFlags,
+    pub latest_daily_jam: *mut GDateTime,
+}
+
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct GstVideoTimeCode{
+    pub config: GstVideoTimeCodeConfig,
+    pub hours: guint,
+    pub minutes: guint,
+    pub seconds: guint,
+    pub frames: guint,
+    pub field_count: guint,
+}
+
+#[repr(C)]
+pub struct GstVideoTimeCodeMeta{
+    pub meta: GstMeta,
+    pub tc: GstVideoTimeCode,
+}
+
+extern "C"{
+    fn gst_video_time_code_new(fps_n: guint, fps_d: guint, latest_daily_jam: *mut GDateTime,
+        flags: GstVideoTimeCodeFlags, hours: guint, minutes: guint, seconds: guint, frames: guint,
+        field_count: guint) -> *mut GstVideoTimeCode;
+    fn gst_video_time_code_copy(tc: *const GstVideoTimeCode) -> *mut GstVideoTimeCode;
+    fn gst_video_time_code_free(tc: *mut GstVideoTimeCode);
+    fn gst_video_time_code_is_valid(tc: *const GstVideoTimeCode) -> gboolean;
+    fn gst_video_time_code_increment_frame(tc: *mut GstVideoTimeCode);
+    fn gst_video_time_code_add_frames(tc: *mut GstVideoTimeCode, frames: gint64);
+    fn gst_video_time_code_compare(tc1: *const GstVideoTimeCode, tc2: *const GstVideoTimeCode) -> c_int;
+    fn gst_video_time_code_frames_since_daily_jam(tc: *const GstVideoTimeCode) -> guint64;
+    fn gst_video_time_code_to_string(tc: *const GstVideoTimeCode) -> *mut gchar;
+    fn gst_video_time_code_meta_api_get_type() -> GType;
+    fn gst_buffer_add_video_time_code_meta(buffer: *mut GstBuffer, tc: *mut GstVideoTimeCode) -> *mut GstVideoTimeCodeMeta;
+}
+
+/// A SMPTE timecode as hours:minutes:seconds:frames at a framerate.
+///
+/// With drop frame timecodes, used with 30000/1001 and 60000/1001 fps,
+/// frame numbers 0 and 1 (0 to 3 at 60fps) are skipped every minute
+/// except every tenth minute so the timecode follows the wall clock,
+/// add_frames and increment_frame take care of it.
+pub struct VideoTimeCode{
+    tc: *mut GstVideoTimeCode
+}
+
+unsafe impl Send for VideoTimeCode {}
+
+impl Drop for VideoTimeCode{
+    fn drop(&mut self){
+        unsafe{
+            gst_video_time_code_free(self.tc);
+        }
+    }
+}
+
+impl Clone for VideoTimeCode{
+    fn clone(&self) -> VideoTimeCode{
+        unsafe{
+            VideoTimeCode{ tc: gst_video_time_code_copy(self.tc) }
+        }
+    }
+}
+
+impl VideoTimeCode{
+    /// Creates a timecode, returns None if it's not valid for the
+    /// framerate, like frame 30 at 30fps or a dropped frame
+    pub fn new(fps_n: u32, fps_d: u32, drop_frame: bool, hours: u32, minutes: u32, seconds: u32, frames: u32) -> Option<VideoTimeCode>{
+        let flags = if drop_frame{
+            GST_VIDEO_TIME_CODE_FLAGS_DROP_FRAME
+        }else{
+            GST_VIDEO_TIME_CODE_FLAGS_NONE
+        };
+        unsafe{
+            let tc = VideoTimeCode::new_from_gst_time_code(gst_video_time_code_new(fps_n, fps_d,
+                ptr::null_mut(), flags, hours, minutes, seconds, frames, 0))?;
+            if tc.is_valid(){
+                Some(tc)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Creates a copy of a raw GstVideoTimeCode
+    pub unsafe fn new_from_gst_time_code_ref(tc: *const GstVideoTimeCode) -> Option<VideoTimeCode>{
+        if tc != ptr::null(){
+            VideoTimeCode::new_from_gst_time_code(gst_video_time_code_copy(tc))
+        }else{
+            None
+        }
+    }
+
+    /// Takes ownership of a raw GstVideoTimeCode
+    pub unsafe fn new_from_gst_time_code(tc: *mut GstVideoTimeCode) -> Option<VideoTimeCode>{
+        if tc != ptr::null_mut(){
+            Some(VideoTimeCode{ tc: tc })
+        }else{
+            None
+        }
+    }
+
+    pub fn hours(&self) -> u32{
+        unsafe{ (*self.tc).hours }
+    }
+
+    pub fn minutes(&self) -> u32{
+        unsafe{ (*self.tc).minutes }
+    }
+
+    pub fn seconds(&self) -> u32{
+        unsafe{ (*self.tc).seconds }
+    }
+
+    pub fn frames(&self) -> u32{
+        unsafe{ (*self.tc).frames }
+    }
+
+    /// Framerate as a (numerator, denominator) fraction
+    pub fn fps(&self) -> (u32, u32){
+        unsafe{ ((*self.tc).config.fps_n, (*self.tc).config.fps_d) }
+    }
+
+    pub fn is_drop_frame(&self) -> bool{
+        unsafe{ (*self.tc).config.flags & GST_VIDEO_TIME_CODE_FLAGS_DROP_FRAME != 0 }
+    }
+
+    pub fn is_interlaced(&self) -> bool{
+        unsafe{ (*self.tc).config.flags & GST_VIDEO_TIME_CODE_FLAGS_INTERLACED != 0 }
+    }
+
+    pub fn is_valid(&self) -> bool{
+        unsafe{
+            gst_video_time_code_is_valid(self.tc) == 1
+        }
+    }
+
+    pub fn increment_frame(&mut self){
+        unsafe{
+            gst_video_time_code_increment_frame(self.tc);
+        }
+    }
+
+    /// Adds frames to the timecode, can be negative
+    pub fn add_frames(&mut self, frames: i64){
+        unsafe{
+            gst_video_time_code_add_frames(self.tc, frames);
+        }
+    }
+
+    /// Number of frames since 00:00:00:00, taking dropped frames into
+    /// account
+    pub fn frames_since_daily_jam(&self) -> u64{
+        unsafe{
+            gst_video_time_code_frames_since_daily_jam(self.tc)
+        }
+    }
+
+    pub unsafe fn gst_time_code(&self) -> *const GstVideoTimeCode{
+        self.tc
+    }
+
+    pub unsafe fn gst_time_code_mut(&mut self) -> *mut GstVideoTimeCode{
+        self.tc
+    }
+}
+
+impl PartialEq for VideoTimeCode{
+    fn eq(&self, other: &VideoTimeCode) -> bool{
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+/// Timecodes are only comparable if they have the same framerate
+impl PartialOrd for VideoTimeCode{
+    fn partial_cmp(&self, other: &VideoTimeCode) -> Option<Ordering>{
+        if self.fps() != other.fps(){
+            return None;
+        }
+        unsafe{
+            match gst_video_time_code_compare(self.tc, other.tc){
+                -1 => Some(Ordering::Less),
+                0 => Some(Ordering::Equal),
+                _ => Some(Ordering::Greater),
+            }
+        }
+    }
+}
+
+/// Formats the timecode as hh:mm:ss:ff, or hh:mm:ss;ff for drop frame
+impl Display for VideoTimeCode{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        unsafe{
+            let c_str = gst_video_time_code_to_string(self.tc);
+            let ret = fmt.write_str(from_c_str!(c_str));
+            g_free(c_str as gpointer);
+            ret
+        }
+    }
+}
+
+impl Debug for VideoTimeCode{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        let (fps_n, fps_d) = self.fps();
+        write!(fmt, "VideoTimeCode({} @ {}/{})", self, fps_n, fps_d)
+    }
+}
+
+impl Buffer{
+    /// Returns the timecode attached to this buffer, usually by a capture
+    /// card source, a decoder or timecodestamper
+    pub fn time_code(&self) -> Option<VideoTimeCode>{
+        unsafe{
+            let meta = gst_buffer_get_meta(self.gst_buffer() as *mut GstBuffer,
+                gst_video_time_code_meta_api_get_type()) as *mut GstVideoTimeCodeMeta;
+            if meta != ptr::null_mut(){
+                VideoTimeCode::new_from_gst_time_code_ref(&(*meta).tc)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Attaches a timecode to this buffer, replacing any previous one
+    pub fn set_time_code(&mut self, tc: &VideoTimeCode) -> bool{
+        unsafe{
+            let buffer = self.gst_buffer_mut();
+            let meta = gst_buffer_get_meta(buffer, gst_video_time_code_meta_api_get_type());
+            if meta != ptr::null_mut(){
+                gst_buffer_remove_meta(buffer, meta);
+            }
+            gst_buffer_add_video_time_code_meta(buffer, tc.tc) != ptr::null_mut()
+        }
+    }
+}
+
+/// Wrapper around timecodestamper, which attaches a timecode to every
+/// video buffer passing through it, counting from first_timecode
+pub struct TimeCodeStamper{
+    timecodestamper: Element
+}
+
+unsafe impl Sync for TimeCodeStamper {}
+unsafe impl Send for TimeCodeStamper {}
+
+impl TimeCodeStamper{
+    pub fn new(name: &str) -> Option<TimeCodeStamper>{
+        Element::new("timecodestamper", name).map(|timecodestamper| TimeCodeStamper{ timecodestamper: timecodestamper })
+    }
+
+    pub fn new_from_element(element: Element) -> TimeCodeStamper{
+        TimeCodeStamper{ timecodestamper: element }
+    }
+
+    /// Uses drop frame timecodes for 30000/1001 and 60000/1001 fps
+    pub fn set_drop_frame(&self, drop_frame: bool){
+        self.timecodestamper.set("drop-frame", drop_frame as gboolean);
+    }
+
+    /// Replaces the timecodes already present in the buffers, by default
+    /// they are kept
+    pub fn set_override_existing(&mut self, override_existing: bool){
+        if self.timecodestamper.property("set").is_some(){
+            let set = if override_existing { "always" } else { "keep" };
+            self.timecodestamper.set_property("set", &set.into()).ok();
+        }else{
+            self.timecodestamper.set("override-existing", override_existing as gboolean);
+        }
+    }
+
+    /// Timecode of the first buffer, the framerate of tc is ignored in
+    /// favour of the one of the stream
+    pub fn set_first_timecode(&self, tc: &VideoTimeCode){
+        unsafe{
+            self.timecodestamper.set("first-timecode", tc.gst_time_code());
+        }
+    }
+}
+
+impl ElementT for TimeCodeStamper{
+    fn as_element(&self) -> &Element{
+        &self.timecodestamper
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.timecodestamper
+    }
+}
+
+impl ::Transfer for TimeCodeStamper{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.timecodestamper.transfer()
+    }
+}
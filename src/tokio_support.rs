@@ -0,0 +1,365 @@
+//! Integration with the tokio runtime, enabled with the `tokio` feature.
+//!
+//! Bus messages and appsink samples are delivered as `Stream`s and state
+//! changes and seeks can be awaited, so pipelines can be driven from async
+//! code without running a MainLoop or a dedicated thread per pipeline.
+
+use ffi::*;
+use util::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::os::raw::c_void;
+
+use futures_core::Stream;
+use tokio::sync::mpsc;
+use tokio::task::{self, JoinHandle};
+
+use appsink;
+use appsrc::AppSrc;
+use buffer::Buffer;
+use bus::Bus;
+use element::Element;
+use element::ElementT;
+use message::Message;
+use sample::Sample;
+
+/// Stream of the messages posted on a bus.
+///
+/// The messages are forwarded from the bus sync handler so no MainLoop is
+/// needed. While the stream exists messages are not dispatched to watches
+/// added with Bus::add_watch or returned by Bus::pop, once it's dropped
+/// the sync handler is removed and the bus works as before.
+pub struct MessageStream{
+    receiver: mpsc::UnboundedReceiver<Message>,
+    bus: Bus
+}
+
+impl Stream for MessageStream{
+    type Item = Message;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Message>>{
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for MessageStream{
+    fn drop(&mut self){
+        unsafe{
+            let bus = self.bus.gst_bus() as *mut GstBus;
+            gst_bus_set_sync_handler(bus, None, ptr::null_mut(), None);
+            g_object_set_data(bus as *mut GObject, to_c_str!(SYNC_HANDLER_KEY), ptr::null_mut());
+        }
+    }
+}
+
+// Marks the buses with a MessageStream, GStreamer refuses to replace a
+// sync handler
+const SYNC_HANDLER_KEY: &'static str = "gst-rs-sync-handler";
+
+impl Bus{
+    /// Returns a stream of all the messages posted on this bus from now
+    /// on, None if the bus already has a MessageStream. Sync handlers
+    /// installed from C can't be detected, GStreamer keeps them and warns
+    /// instead of installing the one of the stream
+    pub fn stream(&self) -> Option<MessageStream>{
+        let (sender, receiver) = mpsc::unbounded_channel();
+        unsafe{
+            let bus = self.gst_bus() as *mut GstBus;
+            if g_object_get_data(bus as *mut GObject, to_c_str!(SYNC_HANDLER_KEY)) != ptr::null_mut(){
+                return None;
+            }
+            g_object_set_data(bus as *mut GObject, to_c_str!(SYNC_HANDLER_KEY), 1 as gpointer);
+            let sender = Box::new(sender);
+            gst_bus_set_sync_handler(bus, Some(bus_sync_handler), Box::into_raw(sender) as gpointer, Some(box_destroy_notify::<mpsc::UnboundedSender<Message>>));
+            Some(MessageStream{ receiver: receiver, bus: Bus::new(bus, false).unwrap() })
+        }
+    }
+}
+
+extern "C" fn bus_sync_handler(_bus: *mut GstBus, msg: *mut GstMessage, data: gpointer) -> GstBusSyncReply{
+    unsafe{
+        let sender = data as *mut mpsc::UnboundedSender<Message>;
+        if let Some(msg) = Message::new(msg){
+            (*sender).send(msg).ok();
+        }
+        GST_BUS_DROP
+    }
+}
+
+/// Future resolving to the result of a state change once the element has
+/// completed it, see AsyncElementT::set_state_async
+pub struct StateChange{
+    ret: Option<GstStateChangeReturn>,
+    handle: Option<JoinHandle<GstStateChangeReturn>>
+}
+
+impl StateChange{
+    fn ready(ret: GstStateChangeReturn) -> StateChange{
+        StateChange{ ret: Some(ret), handle: None }
+    }
+
+    fn wait(element: Element) -> StateChange{
+        let handle = task::spawn_blocking(move || element.get_state(GST_CLOCK_TIME_NONE).2);
+        StateChange{ ret: None, handle: Some(handle) }
+    }
+}
+
+impl Future for StateChange{
+    type Output = GstStateChangeReturn;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<GstStateChangeReturn>{
+        if let Some(ret) = self.ret.take(){
+            return Poll::Ready(ret);
+        }
+        match self.handle{
+            Some(ref mut handle) => match Pin::new(handle).poll(cx){
+                Poll::Ready(Ok(ret)) => Poll::Ready(ret),
+                Poll::Ready(Err(_)) => Poll::Ready(GST_STATE_CHANGE_FAILURE),
+                Poll::Pending => Poll::Pending
+            },
+            None => Poll::Ready(GST_STATE_CHANGE_FAILURE)
+        }
+    }
+}
+
+/// Awaitable versions of the ElementT state change and seek methods.
+///
+/// Waiting for the completion is done in tokio's blocking thread pool so
+/// these need to be called from within a tokio runtime.
+pub trait AsyncElementT: ElementT{
+    /// Sets the state of the element and resolves once the state change
+    /// has completed, with GST_STATE_CHANGE_SUCCESS, NO_PREROLL or FAILURE
+    fn set_state_async(&mut self, state: GstState) -> StateChange{
+        let ret = self.set_state(state);
+        if ret == GST_STATE_CHANGE_ASYNC{
+            StateChange::wait(self.to_element())
+        }else{
+            StateChange::ready(ret)
+        }
+    }
+
+    /// Performs a flushing seek to pos in ns and resolves once the pipeline
+    /// has prerolled again at the new position
+    fn set_position_ns_async(&mut self, ns: i64) -> StateChange{
+        if self.set_position_ns(ns){
+            StateChange::wait(self.to_element())
+        }else{
+            StateChange::ready(GST_STATE_CHANGE_FAILURE)
+        }
+    }
+
+    /// Like seek_simple but resolves once the seek has completed. The flags
+    /// should contain GST_SEEK_FLAG_FLUSH for the pipeline to preroll again.
+    fn seek_simple_async(&mut self, format: GstFormat, flags: GstSeekFlags, pos: i64) -> StateChange{
+        if self.seek_simple(format, flags, pos){
+            StateChange::wait(self.to_element())
+        }else{
+            StateChange::ready(GST_STATE_CHANGE_FAILURE)
+        }
+    }
+}
+
+impl<E: ElementT> AsyncElementT for E{}
+
+/// Stream of the samples received by an appsink.
+///
+/// The appsink streaming thread blocks when capacity samples are waiting
+/// to be consumed, so a slow consumer applies backpressure to the pipeline.
+pub struct SampleStream{
+    appsink: Element,
+    receiver: mpsc::Receiver<appsink::Message>
+}
+
+unsafe impl Sync for SampleStream {}
+unsafe impl Send for SampleStream {}
+
+impl SampleStream{
+    pub fn new(name: &str, capacity: usize) -> Option<SampleStream>{
+        Element::new("appsink", name).map(|appsink| SampleStream::new_from_element(appsink, capacity))
+    }
+
+    pub fn new_from_element(element: Element, capacity: usize) -> SampleStream{
+        let (sender, receiver) = mpsc::channel(capacity);
+        unsafe{
+            let mut gst_callbacks = GstAppSinkCallbacks{
+                eos: Some(sample_stream_eos),
+                new_preroll: Some(sample_stream_new_preroll),
+                new_sample: Some(sample_stream_new_sample),
+                _gst_reserved: [ptr::null_mut::<c_void>();4]
+            };
+            let sender = Box::new(sender);
+            gst_app_sink_set_callbacks(element.gst_element() as *mut GstAppSink, &mut gst_callbacks, Box::into_raw(sender) as gpointer, Some(box_destroy_notify::<mpsc::Sender<appsink::Message>>));
+        }
+        SampleStream{ appsink: element, receiver: receiver }
+    }
+}
+
+impl Stream for SampleStream{
+    type Item = appsink::Message;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<appsink::Message>>{
+        self.receiver.poll_recv(cx)
+    }
+}
+
+unsafe fn sample_stream_send(data: gpointer, msg: appsink::Message) -> GstFlowReturn{
+    let sender = data as *mut mpsc::Sender<appsink::Message>;
+    match (*sender).blocking_send(msg){
+        Ok(()) => GST_FLOW_OK,
+        Err(_) => GST_FLOW_EOS
+    }
+}
+
+extern "C" fn sample_stream_new_sample(elt: *mut GstAppSink, data: gpointer) -> GstFlowReturn{
+    unsafe{
+        match Sample::new(gst_app_sink_pull_sample(elt), true){
+            Some(sample) => sample_stream_send(data, appsink::Message::NewSample(sample)),
+            None => GST_FLOW_EOS
+        }
+    }
+}
+
+extern "C" fn sample_stream_new_preroll(elt: *mut GstAppSink, data: gpointer) -> GstFlowReturn{
+    unsafe{
+        match Sample::new(gst_app_sink_pull_preroll(elt), true){
+            Some(sample) => sample_stream_send(data, appsink::Message::NewPreroll(sample)),
+            None => GST_FLOW_EOS
+        }
+    }
+}
+
+extern "C" fn sample_stream_eos(_elt: *mut GstAppSink, data: gpointer){
+    unsafe{
+        sample_stream_send(data, appsink::Message::Eos);
+    }
+}
+
+impl ElementT for SampleStream{
+    fn as_element(&self) -> &Element{
+        &self.appsink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.appsink
+    }
+}
+
+impl ::Transfer for SampleStream{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.appsink.transfer()
+    }
+}
+
+struct NeedData{
+    need_data: bool,
+    waker: Option<Waker>
+}
+
+/// An appsrc whose push_buffer waits until the element signals need-data,
+/// so producers are throttled by the pipeline instead of filling the
+/// appsrc internal queue.
+pub struct AsyncAppSrc{
+    appsrc: AppSrc,
+    state: Arc<Mutex<NeedData>>
+}
+
+impl AsyncAppSrc{
+    pub fn new(name: &str) -> Option<AsyncAppSrc>{
+        Element::new("appsrc", name).map(AsyncAppSrc::new_from_element)
+    }
+
+    pub fn new_from_element(element: Element) -> AsyncAppSrc{
+        let state = Arc::new(Mutex::new(NeedData{ need_data: false, waker: None }));
+        let mut appsrc = AppSrc::new_from_element(element);
+        unsafe{
+            let mut gst_callbacks = GstAppSrcCallbacks{
+                need_data: Some(async_appsrc_need_data),
+                enough_data: Some(async_appsrc_enough_data),
+                seek_data: None,
+                _gst_reserved: [ptr::null_mut::<c_void>();4]
+            };
+            let data = Box::new(state.clone());
+            gst_app_src_set_callbacks(appsrc.gst_appsrc_mut(), &mut gst_callbacks, Box::into_raw(data) as gpointer, Some(box_destroy_notify::<Arc<Mutex<NeedData>>>));
+        }
+        AsyncAppSrc{ appsrc: appsrc, state: state }
+    }
+
+    /// Waits until the appsrc needs more data and pushes buffer
+    pub fn push_buffer(&mut self, buffer: Buffer) -> PushBuffer{
+        PushBuffer{ appsrc: self, buffer: Some(buffer) }
+    }
+
+    /// Signals the end of the stream, doesn't wait for need-data
+    pub fn end_of_stream(&mut self) -> GstFlowReturn{
+        self.appsrc.end_of_stream()
+    }
+
+    pub fn appsrc(&self) -> &AppSrc{
+        &self.appsrc
+    }
+
+    pub fn appsrc_mut(&mut self) -> &mut AppSrc{
+        &mut self.appsrc
+    }
+}
+
+/// Future returned by AsyncAppSrc::push_buffer
+pub struct PushBuffer<'a>{
+    appsrc: &'a mut AsyncAppSrc,
+    buffer: Option<Buffer>
+}
+
+impl<'a> Future for PushBuffer<'a>{
+    type Output = GstFlowReturn;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<GstFlowReturn>{
+        let this = &mut *self;
+        {
+            let mut state = this.appsrc.state.lock().unwrap();
+            if !state.need_data{
+                state.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+        match this.buffer.take(){
+            Some(buffer) => Poll::Ready(this.appsrc.appsrc.push_buffer(buffer)),
+            None => Poll::Ready(GST_FLOW_ERROR)
+        }
+    }
+}
+
+extern "C" fn async_appsrc_need_data(_src: *mut GstAppSrc, _length: guint, data: gpointer){
+    unsafe{
+        let state = &*(data as *const Arc<Mutex<NeedData>>);
+        let mut state = state.lock().unwrap();
+        state.need_data = true;
+        if let Some(waker) = state.waker.take(){
+            waker.wake();
+        }
+    }
+}
+
+extern "C" fn async_appsrc_enough_data(_src: *mut GstAppSrc, data: gpointer){
+    unsafe{
+        let state = &*(data as *const Arc<Mutex<NeedData>>);
+        state.lock().unwrap().need_data = false;
+    }
+}
+
+impl ElementT for AsyncAppSrc{
+    fn as_element(&self) -> &Element{
+        self.appsrc.as_element()
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        self.appsrc.as_element_mut()
+    }
+}
+
+impl ::Transfer for AsyncAppSrc{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.appsrc.transfer()
+    }
+}
@@ -0,0 +1,224 @@
+use ffi::*;
+use util::*;
+use bin::{Bin, BinT};
+use element::{Element, ElementT};
+use pad::Pad;
+
+/// A pad of an element in a PipelineGraph
+#[derive(Clone,Debug,PartialEq)]
+pub struct PadNode{
+    pub name: String,
+    pub direction: GstPadDirection,
+    /// The negotiated caps, None until the pad is negotiated
+    pub caps: Option<String>,
+    /// Path of the element and name of the pad this pad is linked to
+    pub peer: Option<(String, String)>,
+}
+
+/// An element in a PipelineGraph, bins include the elements inside them
+#[derive(Clone,Debug)]
+pub struct ElementNode{
+    pub name: String,
+    /// Path of the element from the top level bin, as
+    /// `/pipeline0/decodebin0/h264parse0`, unique in the pipeline
+    pub path: String,
+    pub factory: Option<String>,
+    pub state: GstState,
+    pub pending_state: GstState,
+    pub pads: Vec<PadNode>,
+    pub children: Vec<ElementNode>,
+}
+
+impl PartialEq for ElementNode{
+    fn eq(&self, other: &ElementNode) -> bool{
+        self.name == other.name && self.path == other.path && self.factory == other.factory &&
+            self.state as i32 == other.state as i32 && self.pending_state as i32 == other.pending_state as i32 &&
+            self.pads == other.pads && self.children == other.children
+    }
+}
+
+impl ElementNode{
+    pub fn is_bin(&self) -> bool{
+        !self.children.is_empty()
+    }
+
+    pub fn pad(&self, name: &str) -> Option<&PadNode>{
+        self.pads.iter().find(|pad| pad.name == name)
+    }
+}
+
+/// A link between a source and a sink pad of two elements
+#[derive(Clone,Debug,PartialEq)]
+pub struct Link{
+    pub src_element: String,
+    pub src_pad: String,
+    pub sink_element: String,
+    pub sink_pad: String,
+    pub caps: Option<String>,
+}
+
+/// A snapshot of the structure of a bin or pipeline, as returned by
+/// BinT::topology, so the shape of a running pipeline can be checked
+/// or shown without parsing a DOT dump:
+///
+/// ```ignore
+/// let graph = pipeline.topology();
+/// assert!(graph.is_linked("src", "sink"));
+/// for link in graph.links(){
+///     println!("{}:{} -> {}:{} {:?}", link.src_element, link.src_pad,
+///         link.sink_element, link.sink_pad, link.caps);
+/// }
+/// ```
+///
+/// Elements are identified by name in the lookup functions, their path
+/// is used in the pad peers and links so elements with the same name in
+/// different bins can be told apart. Links through ghost pads are
+/// reported between the ghost pad of the bin and the outside element
+/// and between the ghost pad and the inner element.
+#[derive(Clone,Debug,PartialEq)]
+pub struct PipelineGraph{
+    pub root: ElementNode,
+}
+
+impl PipelineGraph{
+    pub fn new(bin: &Bin) -> PipelineGraph{
+        PipelineGraph{ root: element_node(bin.as_element(), Some(bin)) }
+    }
+
+    /// The elements in the graph, recursively, not including the root bin
+    pub fn elements(&self) -> Vec<&ElementNode>{
+        let mut elements = Vec::new();
+        collect_elements(&self.root, &mut elements);
+        elements
+    }
+
+    /// Finds an element by name in any of the bins of the graph
+    pub fn find(&self, name: &str) -> Option<&ElementNode>{
+        self.elements().into_iter().find(|element| element.name == name)
+    }
+
+    /// Finds an element by its path
+    pub fn find_by_path(&self, path: &str) -> Option<&ElementNode>{
+        if self.root.path == path{
+            return Some(&self.root);
+        }
+        self.elements().into_iter().find(|element| element.path == path)
+    }
+
+    /// All the links between the pads in the graph
+    pub fn links(&self) -> Vec<Link>{
+        let mut nodes = self.elements();
+        nodes.insert(0, &self.root);
+        nodes.iter()
+            .flat_map(|element| element.pads.iter()
+                .filter(|pad| pad.direction == GST_PAD_SRC)
+                .filter_map(move |pad| pad.peer.as_ref().map(|&(ref sink_element, ref sink_pad)| Link{
+                    src_element: element.path.clone(),
+                    src_pad: pad.name.clone(),
+                    sink_element: sink_element.clone(),
+                    sink_pad: sink_pad.clone(),
+                    caps: pad.caps.clone(),
+                })))
+            .collect()
+    }
+
+    /// True if a source pad of the element named src is linked to a sink
+    /// pad of the element named sink
+    pub fn is_linked(&self, src: &str, sink: &str) -> bool{
+        match (self.find(src), self.find(sink)){
+            (Some(src), Some(sink)) => src.pads.iter()
+                .filter(|pad| pad.direction == GST_PAD_SRC)
+                .any(|pad| pad.peer.as_ref().map(|&(ref peer, _)| *peer == sink.path).unwrap_or(false)),
+            _ => false
+        }
+    }
+
+    /// Elements with pads that aren't linked, usually a sign of a
+    /// dynamic pad that wasn't handled or a failed link
+    pub fn unlinked_elements(&self) -> Vec<&ElementNode>{
+        self.elements().into_iter()
+            .filter(|element| !element.is_bin() && element.pads.iter().any(|pad| pad.peer.is_none()))
+            .collect()
+    }
+}
+
+fn collect_elements<'a>(node: &'a ElementNode, elements: &mut Vec<&'a ElementNode>){
+    for child in node.children.iter(){
+        elements.push(child);
+        collect_elements(child, elements);
+    }
+}
+
+unsafe fn path_string(object: *mut GstObject) -> String{
+    let c_path = gst_object_get_path_string(object);
+    let path = from_c_str!(c_path).to_string();
+    g_free(c_path as gpointer);
+    path
+}
+
+unsafe fn is_a(object: gpointer, gtype: GType) -> bool{
+    g_type_check_instance_is_a(object as *mut GTypeInstance, gtype) != 0
+}
+
+// Returns the path of the element owning a pad and the pad name, for the
+// internal pads of ghost pads the ghost pad is used instead
+unsafe fn pad_owner(pad: &Pad) -> Option<(String, String)>{
+    let mut pad_ptr = pad.gst_pad() as *mut GstObject;
+    let mut ghost = ptr::null_mut();
+    let mut parent = gst_object_get_parent(pad_ptr);
+    if parent != ptr::null_mut() && is_a(parent as gpointer, gst_pad_get_type()){
+        ghost = parent;
+        pad_ptr = ghost;
+        parent = gst_object_get_parent(ghost);
+    }
+    let owner = if parent != ptr::null_mut() && is_a(parent as gpointer, gst_element_get_type()){
+        let c_name = gst_object_get_name(pad_ptr);
+        let name = from_c_str!(c_name).to_string();
+        g_free(c_name as gpointer);
+        Some((path_string(parent), name))
+    }else{
+        None
+    };
+    if parent != ptr::null_mut(){
+        gst_object_unref(parent as gpointer);
+    }
+    if ghost != ptr::null_mut(){
+        gst_object_unref(ghost as gpointer);
+    }
+    owner
+}
+
+fn element_node(element: &Element, bin: Option<&Bin>) -> ElementNode{
+    let (state, pending_state, _) = element.get_state(0);
+    let pads = element.pads().iter().map(|pad| PadNode{
+        name: pad.name(),
+        direction: pad.direction(),
+        caps: pad.current_caps().map(|caps| caps.to_string()),
+        peer: pad.peer().and_then(|peer| unsafe{ pad_owner(&peer) }),
+    }).collect();
+    let children = match bin{
+        Some(bin) => bin.children().iter().map(|child| child_node(child)).collect(),
+        None => Vec::new()
+    };
+    ElementNode{
+        name: element.name(),
+        path: unsafe{ path_string(element.gst_element() as *mut GstObject) },
+        factory: element.factory_name(),
+        state: state,
+        pending_state: pending_state,
+        pads: pads,
+        children: children,
+    }
+}
+
+fn child_node(element: &Element) -> ElementNode{
+    unsafe{
+        if is_a(element.gst_element() as gpointer, gst_bin_get_type()){
+            gst_object_ref(element.gst_element() as gpointer);
+            if let Some(bin) = Bin::new_from_gst_bin(element.gst_element() as *mut GstBin){
+                return element_node(element, Some(&bin));
+            }
+        }
+        element_node(element, None)
+    }
+}
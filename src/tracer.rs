@@ -0,0 +1,307 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use element::Element;
+use pad::Pad;
+use structure::Structure;
+use value::Value;
+
+use std::sync::Once;
+
+/// Receives the tracing hooks GStreamer calls from the streaming and
+/// application threads, register it with register_tracer.
+///
+/// Hooks run in the middle of the data flow so they should be fast and
+/// never block, collecting the data and processing it in another thread.
+/// ts is the time in nanoseconds since GStreamer was initialized.
+pub trait Tracer: Send + Sync + 'static{
+    /// Called before a buffer is pushed from a source pad
+    fn pad_push_pre(&self, _ts: u64, _pad: &Pad, _buffer: &Buffer){}
+
+    /// Called after a buffer push returned
+    fn pad_push_post(&self, _ts: u64, _pad: &Pad, _result: GstFlowReturn){}
+
+    /// Called after a query was answered by the pad's peer, including the
+    /// latency queries used to configure the pipeline latency
+    fn pad_query_post(&self, _ts: u64, _pad: &Pad, _query: *mut GstQuery, _result: bool){}
+
+    /// Called before an element changes state
+    fn element_change_state_pre(&self, _ts: u64, _element: &Element, _transition: GstStateChange){}
+
+    /// Called after an element changed state
+    fn element_change_state_post(&self, _ts: u64, _element: &Element, _transition: GstStateChange, _result: GstStateChangeReturn){}
+}
+
+// The tracer API isn't in ffi.rs, tracers were made public in GStreamer 1.8
+extern "C"{
+    fn gst_tracer_get_type() -> GType;
+    fn gst_tracing_register_hook(tracer: gpointer, detail: *const gchar, func: GCallback);
+}
+
+#[repr(C)]
+struct GstTracerClass{
+    parent_class: GstObjectClass,
+    _gst_reserved: [gpointer; 4],
+}
+
+#[repr(C)]
+struct RsTracer{
+    parent: GstObject,
+    _priv: gpointer,
+    _gst_reserved: [gpointer; 4],
+    tracer: gpointer,
+}
+
+static REGISTER: Once = Once::new();
+static mut RS_TRACER_TYPE: GType = 0;
+
+fn rs_tracer_type() -> GType{
+    unsafe{
+        REGISTER.call_once(|| {
+            let mut info: GTypeInfo = mem::zeroed();
+            info.class_size = mem::size_of::<GstTracerClass>() as guint16;
+            info.instance_size = mem::size_of::<RsTracer>() as guint16;
+            RS_TRACER_TYPE = g_type_register_static(gst_tracer_get_type(), to_c_str!("GstRsTracer"), &info, 0);
+        });
+        RS_TRACER_TYPE
+    }
+}
+
+unsafe fn tracer<'a, T: Tracer>(object: gpointer) -> &'a T{
+    &*((*(object as *mut RsTracer)).tracer as *const T)
+}
+
+extern "C" fn pad_push_pre<T: Tracer>(object: gpointer, ts: GstClockTime, pad: *mut GstPad, buffer: *mut GstBuffer){
+    unsafe{
+        if let (Some(pad), Some(buffer)) = (Pad::new(pad, false), Buffer::new(buffer, false)){
+            tracer::<T>(object).pad_push_pre(ts, &pad, &buffer);
+        }
+    }
+}
+
+extern "C" fn pad_push_post<T: Tracer>(object: gpointer, ts: GstClockTime, pad: *mut GstPad, result: GstFlowReturn){
+    unsafe{
+        if let Some(pad) = Pad::new(pad, false){
+            tracer::<T>(object).pad_push_post(ts, &pad, result);
+        }
+    }
+}
+
+extern "C" fn pad_query_post<T: Tracer>(object: gpointer, ts: GstClockTime, pad: *mut GstPad, query: *mut GstQuery, result: gboolean){
+    unsafe{
+        if let Some(pad) = Pad::new(pad, false){
+            tracer::<T>(object).pad_query_post(ts, &pad, query, result != 0);
+        }
+    }
+}
+
+extern "C" fn element_change_state_pre<T: Tracer>(object: gpointer, ts: GstClockTime, element: *mut GstElement, transition: GstStateChange){
+    unsafe{
+        gst_object_ref(element as gpointer);
+        if let Some(element) = Element::new_from_gst_element(element){
+            tracer::<T>(object).element_change_state_pre(ts, &element, transition);
+        }
+    }
+}
+
+extern "C" fn element_change_state_post<T: Tracer>(object: gpointer, ts: GstClockTime, element: *mut GstElement,
+        transition: GstStateChange, result: GstStateChangeReturn){
+    unsafe{
+        gst_object_ref(element as gpointer);
+        if let Some(element) = Element::new_from_gst_element(element){
+            tracer::<T>(object).element_change_state_post(ts, &element, transition, result);
+        }
+    }
+}
+
+/// Registers a tracer that will receive the hooks for every pipeline in
+/// the process. GStreamer has no way to remove a tracer once registered
+/// so it stays active until the process exits
+pub fn register_tracer<T: Tracer>(tracer: T){
+    unsafe{
+        let object = g_object_new(rs_tracer_type(), ptr::null::<gchar>()) as *mut RsTracer;
+        (*object).tracer = Box::into_raw(Box::new(tracer)) as gpointer;
+        let hooks: [(&str, GCallback); 5] = [
+            ("pad-push-pre", Some(mem::transmute(pad_push_pre::<T> as extern "C" fn(gpointer, GstClockTime, *mut GstPad, *mut GstBuffer)))),
+            ("pad-push-post", Some(mem::transmute(pad_push_post::<T> as extern "C" fn(gpointer, GstClockTime, *mut GstPad, GstFlowReturn)))),
+            ("pad-query-post", Some(mem::transmute(pad_query_post::<T> as extern "C" fn(gpointer, GstClockTime, *mut GstPad, *mut GstQuery, gboolean)))),
+            ("element-change-state-pre", Some(mem::transmute(element_change_state_pre::<T> as extern "C" fn(gpointer, GstClockTime, *mut GstElement, GstStateChange)))),
+            ("element-change-state-post", Some(mem::transmute(element_change_state_post::<T> as extern "C" fn(gpointer, GstClockTime, *mut GstElement, GstStateChange, GstStateChangeReturn)))),
+        ];
+        for &(detail, func) in hooks.iter(){
+            let detail = CString::new(detail).unwrap();
+            gst_tracing_register_hook(object as gpointer, detail.as_ptr(), func);
+        }
+    }
+}
+
+/// Enables GStreamer's own tracers, as "latency(flags=pipeline+element)"
+/// or "stats", through GST_TRACERS. Tracers are only loaded by init so
+/// this has to be called before it, returns false if GStreamer is already
+/// initialized. Their output can be received with add_tracer_record_handler
+pub fn enable_tracers(tracers: &[&str]) -> bool{
+    if unsafe{ gst_is_initialized() } != 0{
+        return false;
+    }
+    ::std::env::set_var("GST_TRACERS", tracers.join(";"));
+    true
+}
+
+/// A latency measured by the latency tracer from a source to a sink
+#[derive(Clone,Debug,PartialEq)]
+pub struct LatencyRecord{
+    pub src_element: String,
+    pub src_pad: String,
+    pub sink_element: String,
+    pub sink_pad: String,
+    /// Latency in nanoseconds
+    pub time: u64,
+    pub ts: u64,
+}
+
+/// The processing latency of an element measured by the latency tracer
+/// with flags=element
+#[derive(Clone,Debug,PartialEq)]
+pub struct ElementLatencyRecord{
+    pub element: String,
+    pub src_pad: String,
+    /// Latency in nanoseconds
+    pub time: u64,
+    pub ts: u64,
+}
+
+/// An entry logged by a tracer
+#[derive(Clone,Debug,PartialEq)]
+pub enum TracerRecord{
+    Latency(LatencyRecord),
+    ElementLatency(ElementLatencyRecord),
+    /// Any other entry, as the ones from the stats tracer, with the
+    /// record name as the structure name
+    Other(Structure),
+}
+
+impl TracerRecord{
+    /// Parses an entry as logged by the tracers, as
+    /// "latency, src=(string)src, sink=(string)sink, time=(guint64)1234, ts=(guint64)5678;"
+    pub fn parse(entry: &str) -> Option<TracerRecord>{
+        let structure = Structure::from_string(entry.trim())?;
+        let string = |field: &str| match structure.get(field){
+            Some(Value::String(value)) => value,
+            _ => String::new()
+        };
+        let uint64 = |field: &str| match structure.get(field){
+            Some(Value::UInt64(value)) => value,
+            Some(Value::UInt(value)) => value as u64,
+            _ => 0
+        };
+        let record = match structure.name().as_str(){
+            "latency" => TracerRecord::Latency(LatencyRecord{
+                src_element: string("src-element"),
+                src_pad: string("src"),
+                sink_element: string("sink-element"),
+                sink_pad: string("sink"),
+                time: uint64("time"),
+                ts: uint64("ts"),
+            }),
+            "element-latency" => TracerRecord::ElementLatency(ElementLatencyRecord{
+                element: string("element"),
+                src_pad: string("src"),
+                time: uint64("time"),
+                ts: uint64("ts"),
+            }),
+            _ => TracerRecord::Other(structure.clone())
+        };
+        Some(record)
+    }
+}
+
+extern "C" fn tracer_log_function<F: Fn(TracerRecord) + Send + Sync + 'static>(category: *mut GstDebugCategory,
+        _level: GstDebugLevel, _file: *const gchar, _function: *const gchar, _line: gint, _object: *mut GObject,
+        message: *mut GstDebugMessage, user_data: gpointer){
+    unsafe{
+        if from_c_str!(gst_debug_category_get_name(category)) != "GST_TRACER"{
+            return;
+        }
+        let entry = gst_debug_message_get(message);
+        if entry == ptr::null(){
+            return;
+        }
+        if let Some(record) = TracerRecord::parse(from_c_str!(entry)){
+            (*(user_data as *const F))(record);
+        }
+    }
+}
+
+/// Calls handler with every entry logged by the tracers enabled with
+/// enable_tracers, so their measurements can be collected without
+/// parsing the debug log. Must be called after init, it raises the
+/// GST_TRACER debug category to the trace level the tracers log at
+pub fn add_tracer_record_handler<F: Fn(TracerRecord) + Send + Sync + 'static>(handler: F){
+    unsafe{
+        gst_debug_set_threshold_for_name(to_c_str!("GST_TRACER"), GST_LEVEL_TRACE);
+        gst_debug_add_log_function(Some(tracer_log_function::<F>),
+            Box::into_raw(Box::new(handler)) as gpointer, Some(box_destroy_notify::<F>));
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn latency(){
+        ::init();
+        let record = TracerRecord::parse("latency, src-element-id=(string)0x1, src-element=(string)src, \
+            src=(string)src, sink-element-id=(string)0x2, sink-element=(string)sink, sink=(string)sink, \
+            time=(guint64)1234, ts=(guint64)5678;").unwrap();
+        assert_eq!(record, TracerRecord::Latency(LatencyRecord{
+            src_element: "src".to_string(),
+            src_pad: "src".to_string(),
+            sink_element: "sink".to_string(),
+            sink_pad: "sink".to_string(),
+            time: 1234,
+            ts: 5678,
+        }));
+    }
+
+    #[test]
+    fn element_latency(){
+        ::init();
+        let record = TracerRecord::parse("element-latency, element-id=(string)0x1, element=(string)identity0, \
+            src=(string)src, time=(guint64)100, ts=(guint64)200;").unwrap();
+        assert_eq!(record, TracerRecord::ElementLatency(ElementLatencyRecord{
+            element: "identity0".to_string(),
+            src_pad: "src".to_string(),
+            time: 100,
+            ts: 200,
+        }));
+    }
+
+    #[test]
+    fn other_records(){
+        ::init();
+        match TracerRecord::parse("thread-rusage, thread-id=(guint64)1, ts=(guint64)2;"){
+            Some(TracerRecord::Other(structure)) => assert_eq!(structure.name(), "thread-rusage"),
+            other => panic!("expected an other record, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn missing_fields_are_empty(){
+        ::init();
+        match TracerRecord::parse("latency, time=(guint)10;"){
+            Some(TracerRecord::Latency(record)) => {
+                assert_eq!(record.time, 10);
+                assert_eq!(record.ts, 0);
+                assert!(record.src_element.is_empty());
+            }
+            other => panic!("expected a latency record, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn invalid_entry(){
+        ::init();
+        assert_eq!(TracerRecord::parse("latency, time=(guint64)abc;"), None);
+    }
+}
@@ -0,0 +1,276 @@
+use ffi::*;
+use util::*;
+use bin::BinT;
+use caps::Caps;
+use element::{Element, ElementT};
+use element_factory::ElementFactory;
+use encoder_config::EncoderConfig;
+use encoding_profile::EncodingProfile;
+use pipeline::Pipeline;
+use error::Error;
+
+use std::fmt;
+use std::result;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+// How often run checks for cancellation and reports the progress
+const PROGRESS_INTERVAL: GstClockTime = 200 * 1000000;
+
+/// The progress of a Transcoder, passed to the callback of run
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct TranscodeProgress{
+    /// Position in the input in nanoseconds
+    pub position: u64,
+    /// Duration of the input in nanoseconds, None for streams without a
+    /// known duration
+    pub duration: Option<u64>,
+    /// Percentage of the input transcoded, 0 to 100
+    pub percent: Option<f64>,
+    /// Estimated time left, from the speed of the transcoding so far
+    pub eta: Option<Duration>,
+}
+
+#[derive(Debug)]
+pub enum TranscodeError{
+    /// The element, or any of the alternatives separated by " or ", isn't
+    /// installed
+    MissingElement(String),
+    /// An element posted an error, as a file that can't be read or a
+    /// format that can't be decoded
+    Pipeline(Error),
+    StateChangeFailed,
+    /// Stopped with TranscodeCanceller::cancel, the output is incomplete
+    Cancelled,
+}
+
+impl fmt::Display for TranscodeError{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result{
+        match *self{
+            TranscodeError::MissingElement(ref name) => write!(fmt, "missing element {}", name),
+            TranscodeError::Pipeline(ref err) => write!(fmt, "{}", err.message()),
+            TranscodeError::StateChangeFailed => write!(fmt, "couldn't start the transcoding pipeline"),
+            TranscodeError::Cancelled => write!(fmt, "transcoding cancelled"),
+        }
+    }
+}
+
+/// Cancels a running Transcoder from another thread
+#[derive(Clone)]
+pub struct TranscodeCanceller{
+    cancelled: Arc<AtomicBool>
+}
+
+impl TranscodeCanceller{
+    pub fn cancel(&self){
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool{
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Converts a file or stream to another format, blocking the calling
+/// thread until it's done.
+///
+/// Uses uritranscodebin when it's installed, otherwise a uridecodebin
+/// linked to an encodebin. Streams of the input without a profile in the
+/// container are dropped.
+///
+/// ```ignore
+/// let transcoder = Transcoder::with_encoders("file:///tmp/in.mkv", "file:///tmp/out.mp4",
+///     &Caps::from_string("video/quicktime").unwrap(),
+///     &[EncoderConfig::h264(4000), EncoderConfig::aac(128)]);
+/// let canceller = transcoder.canceller();
+/// transcoder.run(|progress| println!("{:?}% {:?} left", progress.percent, progress.eta))?;
+/// ```
+pub struct Transcoder{
+    input_uri: String,
+    output_uri: String,
+    profile: EncodingProfile,
+    configs: Vec<EncoderConfig>,
+    use_transcodebin: bool,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Transcoder{
+    /// Transcodes to profile, which has to be a container profile
+    pub fn new(input_uri: &str, output_uri: &str, profile: EncodingProfile) -> Transcoder{
+        Transcoder{
+            input_uri: input_uri.to_string(),
+            output_uri: output_uri.to_string(),
+            profile: profile,
+            configs: Vec::new(),
+            use_transcodebin: true,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Transcodes to the container format with a stream for each config,
+    /// with the encoders the configs pick on this machine and their
+    /// properties
+    pub fn with_encoders(input_uri: &str, output_uri: &str, container: &Caps, configs: &[EncoderConfig]) -> Transcoder{
+        let mut profile = EncodingProfile::container(container);
+        for config in configs{
+            profile.add_profile(EncodingProfile::from_encoder_config(config));
+        }
+        let mut transcoder = Transcoder::new(input_uri, output_uri, profile);
+        transcoder.configs = configs.to_vec();
+        transcoder
+    }
+
+    /// Whether to use uritranscodebin when it's installed, true by
+    /// default
+    pub fn set_use_transcodebin(&mut self, use_transcodebin: bool){
+        self.use_transcodebin = use_transcodebin;
+    }
+
+    pub fn canceller(&self) -> TranscodeCanceller{
+        TranscodeCanceller{ cancelled: self.cancelled.clone() }
+    }
+
+    /// Transcodes the input, calling progress periodically from this
+    /// thread. Returns once the output is complete, an element fails or
+    /// it's cancelled
+    pub fn run<F: FnMut(&TranscodeProgress)>(&self, mut progress: F) -> result::Result<(), TranscodeError>{
+        let mut pipeline = self.build()?;
+        if pipeline.set_state(GST_STATE_PLAYING) == GST_STATE_CHANGE_FAILURE{
+            let err = match pipeline.wait_for_message(0, 0){
+                Some(Err(err)) => TranscodeError::Pipeline(err),
+                _ => TranscodeError::StateChangeFailed
+            };
+            pipeline.set_state(GST_STATE_NULL);
+            return Err(err);
+        }
+
+        let start = Instant::now();
+        let result = loop{
+            if self.cancelled.load(Ordering::SeqCst){
+                break Err(TranscodeError::Cancelled);
+            }
+            match pipeline.wait_for_message(PROGRESS_INTERVAL, GST_MESSAGE_EOS){
+                Some(Ok(_)) => break Ok(()),
+                Some(Err(err)) => break Err(TranscodeError::Pipeline(err)),
+                None => {}
+            }
+            if let Some(position) = pipeline.position_ns(){
+                progress(&transcode_progress(position as u64, pipeline.duration_ns(), start.elapsed()));
+            }
+        };
+        pipeline.set_state(GST_STATE_NULL);
+        result
+    }
+
+    fn build(&self) -> result::Result<Pipeline, TranscodeError>{
+        for config in &self.configs{
+            if config.available_factory().is_none(){
+                return Err(TranscodeError::MissingElement(config.encoder_factories().join(" or ")));
+            }
+        }
+        let mut pipeline = Pipeline::new("transcoder").ok_or(TranscodeError::StateChangeFailed)?;
+        unsafe{
+            if !self.configs.is_empty(){
+                let configs = Box::new(self.configs.clone());
+                connect_boxed(pipeline.gst_element() as gpointer, "deep-element-added",
+                    deep_element_added as extern "C" fn(*mut GstBin, *mut GstBin, *mut GstElement, gpointer) as gpointer,
+                    configs);
+            }
+        }
+
+        if self.use_transcodebin && ElementFactory::find("uritranscodebin").is_some(){
+            let transcodebin = new_element("uritranscodebin")?;
+            transcodebin.set("source-uri", to_c_str!(self.input_uri.as_str()));
+            transcodebin.set("dest-uri", to_c_str!(self.output_uri.as_str()));
+            transcodebin.set("profile", unsafe{ self.profile.gst_encoding_profile() });
+            pipeline.add(transcodebin);
+            return Ok(pipeline);
+        }
+
+        let decodebin = new_element("uridecodebin")?;
+        decodebin.set("uri", to_c_str!(self.input_uri.as_str()));
+        let encodebin = new_element("encodebin")?;
+        encodebin.set("profile", unsafe{ self.profile.gst_encoding_profile() });
+        let sink = unsafe{
+            let mut error = ptr::null_mut::<GError>();
+            let sink = gst_element_make_from_uri(GST_URI_SINK, to_c_str!(self.output_uri.as_str()), ptr::null(), &mut error);
+            if error != ptr::null_mut(){
+                return Err(TranscodeError::Pipeline(Error::new_from_g_error(error)));
+            }
+            gst_object_ref_sink(sink as gpointer);
+            Element::new_from_gst_element(sink).ok_or(TranscodeError::StateChangeFailed)?
+        };
+
+        let mut decodebin_ref = decodebin.to_element();
+        let mut encodebin_ref = encodebin.to_element();
+        let mut sink_ref = sink.to_element();
+        if !pipeline.add(decodebin) || !pipeline.add(encodebin) || !pipeline.add(sink) ||
+            !encodebin_ref.link(&mut sink_ref){
+            return Err(TranscodeError::StateChangeFailed);
+        }
+        unsafe{
+            let encodebin = Box::new(encodebin_ref);
+            connect_boxed(decodebin_ref.gst_element_mut() as gpointer, "pad-added",
+                pad_added as extern "C" fn(*mut GstElement, *mut GstPad, gpointer) as gpointer,
+                encodebin);
+        }
+        Ok(pipeline)
+    }
+}
+
+fn new_element(factory: &str) -> result::Result<Element, TranscodeError>{
+    Element::new(factory, "").ok_or_else(|| TranscodeError::MissingElement(factory.to_string()))
+}
+
+fn transcode_progress(position: u64, duration: Option<i64>, elapsed: Duration) -> TranscodeProgress{
+    let duration = duration.filter(|&duration| duration > 0).map(|duration| duration as u64);
+    let fraction = duration.map(|duration| (position as f64 / duration as f64).min(1.0));
+    let eta = match fraction{
+        Some(fraction) if fraction > 0.0 => Some(elapsed.mul_f64((1.0 - fraction) / fraction)),
+        _ => None
+    };
+    TranscodeProgress{
+        position: position,
+        duration: duration,
+        percent: fraction.map(|fraction| fraction * 100.0),
+        eta: eta,
+    }
+}
+
+// Links each stream uridecodebin exposes to a pad encodebin requests for
+// its caps, so it gets the profile of its type. Streams encodebin has no
+// profile for are left unlinked
+extern "C" fn pad_added(_element: *mut GstElement, pad: *mut GstPad, data: gpointer){
+    unsafe{
+        let encodebin = &*(data as *const Element);
+        let mut caps = gst_pad_get_current_caps(pad);
+        if caps == ptr::null_mut(){
+            caps = gst_pad_query_caps(pad, ptr::null_mut());
+        }
+        let mut encodebin_pad = ptr::null_mut::<GstPad>();
+        g_signal_emit_by_name(encodebin.gst_element() as gpointer, to_c_str!("request-pad"), caps, &mut encodebin_pad);
+        gst_mini_object_unref(caps as *mut GstMiniObject);
+        if encodebin_pad != ptr::null_mut(){
+            gst_pad_link(pad, encodebin_pad);
+            gst_object_unref(encodebin_pad as gpointer);
+        }
+    }
+}
+
+// Applies the properties of the configs to the encoders encodebin creates
+extern "C" fn deep_element_added(_bin: *mut GstBin, _sub_bin: *mut GstBin, element: *mut GstElement, data: gpointer){
+    unsafe{
+        let configs = &*(data as *const Vec<EncoderConfig>);
+        gst_object_ref(element as gpointer);
+        let element = match Element::new_from_gst_element(element){
+            Some(element) => element,
+            None => return
+        };
+        if let Some(factory) = element.factory_name(){
+            if let Some(config) = configs.iter().find(|config| config.encoder_factories().contains(&factory.as_str())){
+                config.configure(&factory, &element);
+            }
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use element::{Element, ElementT};
+use error::{Error, Result};
+use value::Value;
+use gio::*;
+
+use std::net::UdpSocket;
+#[cfg(unix)]
+use std::os::unix::io::IntoRawFd;
+#[cfg(windows)]
+use std::os::windows::io::IntoRawSocket;
+
+// Wraps a socket in a GSocket that takes ownership of it, to be passed to
+// the socket property of the udp elements
+fn gsocket_from_udp(socket: UdpSocket) -> Result<*mut GSocket>{
+    #[cfg(unix)]
+    let fd = socket.into_raw_fd() as gint;
+    #[cfg(windows)]
+    let fd = socket.into_raw_socket() as gint;
+    unsafe{
+        let mut error = ptr::null_mut();
+        let gsocket = g_socket_new_from_fd(fd, &mut error);
+        if gsocket == ptr::null_mut(){
+            #[cfg(unix)]
+            drop(<UdpSocket as ::std::os::unix::io::FromRawFd>::from_raw_fd(fd));
+            #[cfg(windows)]
+            drop(<UdpSocket as ::std::os::windows::io::FromRawSocket>::from_raw_socket(fd as u64));
+            Err(Error::new_from_g_error(error))
+        }else{
+            Ok(gsocket)
+        }
+    }
+}
+
+fn string_property(element: &Element, name: &str) -> Option<String>{
+    match element.property(name){
+        Some(Value::String(value)) => Some(value),
+        _ => None
+    }
+}
+
+/// Properties shared by udpsink and multiudpsink
+pub trait UdpSinkT: ElementT{
+    /// Time to live of the packets sent to unicast addresses
+    fn set_ttl(&self, ttl: i32){
+        self.as_element().set("ttl", ttl);
+    }
+
+    /// Time to live of the packets sent to multicast addresses, the
+    /// number of routers they can go through
+    fn set_ttl_mc(&self, ttl: i32){
+        self.as_element().set("ttl-mc", ttl);
+    }
+
+    /// Whether multicast packets are also received by the sending host
+    fn set_loop(&self, enabled: bool){
+        self.as_element().set("loop", enabled as gboolean);
+    }
+
+    /// Network interface used to send multicast packets, as "eth0"
+    fn set_multicast_iface(&self, iface: &str){
+        self.as_element().set("multicast-iface", to_c_str!(iface));
+    }
+
+    /// Size of the kernel send buffer in bytes, 0 for the default
+    fn set_buffer_size(&self, size: i32){
+        self.as_element().set("buffer-size", size);
+    }
+
+    /// Whether the sink joins the multicast groups it sends to
+    fn set_auto_multicast(&self, enabled: bool){
+        self.as_element().set("auto-multicast", enabled as gboolean);
+    }
+
+    /// Local address and port the packets are sent from
+    fn set_bind_address(&self, address: &str, port: u16){
+        self.as_element().set("bind-address", to_c_str!(address));
+        self.as_element().set("bind-port", port as gint);
+    }
+
+    /// Sends from an already bound socket instead of creating one, the
+    /// element takes ownership of it
+    fn set_socket(&self, socket: UdpSocket) -> Result<()>{
+        let gsocket = gsocket_from_udp(socket)?;
+        unsafe{
+            self.as_element().set("socket", gsocket);
+            self.as_element().set("close-socket", 1 as gboolean);
+            g_object_unref(gsocket as gpointer);
+        }
+        Ok(())
+    }
+}
+
+/// Wrapper around udpsrc, receiving packets from a unicast or multicast
+/// address, as the MPEG-TS or RTP stream of a camera
+pub struct UdpSrc{
+    udpsrc: Element
+}
+
+unsafe impl Sync for UdpSrc {}
+unsafe impl Send for UdpSrc {}
+
+impl UdpSrc{
+    pub fn new(name: &str) -> Option<UdpSrc>{
+        Element::new("udpsrc", name).map(|udpsrc| UdpSrc{ udpsrc: udpsrc })
+    }
+
+    pub fn new_from_element(element: Element) -> UdpSrc{
+        UdpSrc{ udpsrc: element }
+    }
+
+    /// Address to receive from, a multicast group is joined automatically
+    pub fn set_address(&self, address: &str){
+        self.udpsrc.set("address", to_c_str!(address));
+    }
+
+    pub fn address(&self) -> Option<String>{
+        string_property(&self.udpsrc, "address")
+    }
+
+    pub fn set_port(&self, port: u16){
+        self.udpsrc.set("port", port as gint);
+    }
+
+    /// The port to receive from, with port 0 the one picked by the system
+    /// once the element is in READY
+    pub fn port(&self) -> u16{
+        match self.udpsrc.property("port"){
+            Some(Value::Int(port)) => port as u16,
+            _ => 0
+        }
+    }
+
+    /// Sets the address and port as an uri, as udp://239.0.0.1:5000
+    pub fn set_uri(&self, uri: &str){
+        self.udpsrc.set("uri", to_c_str!(uri));
+    }
+
+    /// Network interface used to join the multicast group, as "eth0"
+    pub fn set_multicast_iface(&self, iface: &str){
+        self.udpsrc.set("multicast-iface", to_c_str!(iface));
+    }
+
+    /// Whether the socket can be bound while another one uses the port
+    pub fn set_reuse(&self, reuse: bool){
+        self.udpsrc.set("reuse", reuse as gboolean);
+    }
+
+    /// Whether multicast packets sent from this host are received
+    pub fn set_loop(&self, enabled: bool){
+        self.udpsrc.set("loop", enabled as gboolean);
+    }
+
+    /// Size of the kernel receive buffer in bytes, 0 for the default.
+    /// High bitrate streams need a bigger one to not lose packets
+    pub fn set_buffer_size(&self, size: i32){
+        self.udpsrc.set("buffer-size", size);
+    }
+
+    pub fn set_auto_multicast(&self, enabled: bool){
+        self.udpsrc.set("auto-multicast", enabled as gboolean);
+    }
+
+    /// Caps of the received stream, as "application/x-rtp,media=video,
+    /// clock-rate=90000,encoding-name=H264", the packets carry no
+    /// description of their content
+    pub fn set_caps(&self, caps: &Caps){
+        unsafe{
+            self.udpsrc.set("caps", caps.gst_caps());
+        }
+    }
+
+    /// Time in nanoseconds without packets after which an element message
+    /// named GstUDPSrcTimeout is posted, 0 to disable
+    pub fn set_timeout(&self, timeout: u64){
+        self.udpsrc.set("timeout", timeout);
+    }
+
+    /// Receives from an already bound socket instead of creating one, the
+    /// element takes ownership of it
+    pub fn set_socket(&self, socket: UdpSocket) -> Result<()>{
+        let gsocket = gsocket_from_udp(socket)?;
+        unsafe{
+            self.udpsrc.set("socket", gsocket);
+            self.udpsrc.set("close-socket", 1 as gboolean);
+            g_object_unref(gsocket as gpointer);
+        }
+        Ok(())
+    }
+}
+
+impl ElementT for UdpSrc{
+    fn as_element(&self) -> &Element{
+        &self.udpsrc
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.udpsrc
+    }
+}
+
+impl ::Transfer for UdpSrc{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.udpsrc.transfer()
+    }
+}
+
+/// Wrapper around udpsink, sending packets to a single host
+pub struct UdpSink{
+    udpsink: Element
+}
+
+unsafe impl Sync for UdpSink {}
+unsafe impl Send for UdpSink {}
+
+impl UdpSink{
+    pub fn new(name: &str) -> Option<UdpSink>{
+        Element::new("udpsink", name).map(|udpsink| UdpSink{ udpsink: udpsink })
+    }
+
+    pub fn new_from_element(element: Element) -> UdpSink{
+        UdpSink{ udpsink: element }
+    }
+
+    /// Host to send to, unicast or multicast
+    pub fn set_host(&self, host: &str){
+        self.udpsink.set("host", to_c_str!(host));
+    }
+
+    pub fn host(&self) -> Option<String>{
+        string_property(&self.udpsink, "host")
+    }
+
+    pub fn set_port(&self, port: u16){
+        self.udpsink.set("port", port as gint);
+    }
+
+    pub fn port(&self) -> u16{
+        match self.udpsink.property("port"){
+            Some(Value::Int(port)) => port as u16,
+            _ => 0
+        }
+    }
+}
+
+impl UdpSinkT for UdpSink{}
+
+impl ElementT for UdpSink{
+    fn as_element(&self) -> &Element{
+        &self.udpsink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.udpsink
+    }
+}
+
+impl ::Transfer for UdpSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.udpsink.transfer()
+    }
+}
+
+/// Wrapper around multiudpsink, sending the same packets to several
+/// hosts which can be added and removed while playing
+pub struct MultiUdpSink{
+    multiudpsink: Element
+}
+
+unsafe impl Sync for MultiUdpSink {}
+unsafe impl Send for MultiUdpSink {}
+
+impl MultiUdpSink{
+    pub fn new(name: &str) -> Option<MultiUdpSink>{
+        Element::new("multiudpsink", name).map(|multiudpsink| MultiUdpSink{ multiudpsink: multiudpsink })
+    }
+
+    pub fn new_from_element(element: Element) -> MultiUdpSink{
+        MultiUdpSink{ multiudpsink: element }
+    }
+
+    pub fn add_client(&self, host: &str, port: u16){
+        unsafe{
+            g_signal_emit_by_name(self.multiudpsink.gst_element() as gpointer, to_c_str!("add"), to_c_str!(host), port as gint);
+        }
+    }
+
+    pub fn remove_client(&self, host: &str, port: u16){
+        unsafe{
+            g_signal_emit_by_name(self.multiudpsink.gst_element() as gpointer, to_c_str!("remove"), to_c_str!(host), port as gint);
+        }
+    }
+
+    pub fn clear_clients(&self){
+        unsafe{
+            g_signal_emit_by_name(self.multiudpsink.gst_element() as gpointer, to_c_str!("clear"));
+        }
+    }
+
+    /// Replaces the clients with the passed hosts and ports
+    pub fn set_clients(&self, clients: &[(&str, u16)]){
+        let clients = clients.iter()
+            .map(|&(host, port)| format!("{}:{}", host, port))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.multiudpsink.set("clients", to_c_str!(clients));
+    }
+
+    /// The hosts and ports packets are sent to
+    pub fn clients(&self) -> Vec<(String, u16)>{
+        string_property(&self.multiudpsink, "clients").map(|clients| {
+            clients.split(',')
+                .filter_map(|client| {
+                    let mut parts = client.trim().rsplitn(2, ':');
+                    let port = parts.next()?.parse().ok()?;
+                    let host = parts.next()?;
+                    Some((host.to_string(), port))
+                })
+                .collect()
+        }).unwrap_or_default()
+    }
+}
+
+impl UdpSinkT for MultiUdpSink{}
+
+impl ElementT for MultiUdpSink{
+    fn as_element(&self) -> &Element{
+        &self.multiudpsink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.multiudpsink
+    }
+}
+
+impl ::Transfer for MultiUdpSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.multiudpsink.transfer()
+    }
+}
@@ -0,0 +1,395 @@
+use ffi::*;
+use util::*;
+use element::{Element, ElementT};
+use error::{Error, Result};
+
+use std::fmt::{self, Debug, Display, Formatter};
+
+// GstUri was added in GStreamer 1.6, after ffi.rs was generated
+pub enum GstUri {}
+
+pub const GST_URI_NO_PORT: guint = 0;
+
+extern "C"{
+    fn gst_uri_new(scheme: *const gchar, userinfo: *const gchar, host: *const gchar, port: guint,
+        path: *const gchar, query: *const gchar, fragment: *const gchar) -> *mut GstUri;
+    fn gst_uri_from_string(uri: *const gchar) -> *mut GstUri;
+    fn gst_uri_to_string(uri: *const GstUri) -> *mut gchar;
+    fn gst_uri_join(base_uri: *mut GstUri, ref_uri: *mut GstUri) -> *mut GstUri;
+    fn gst_uri_equal(first: *const GstUri, second: *const GstUri) -> gboolean;
+    fn gst_uri_get_scheme(uri: *const GstUri) -> *const gchar;
+    fn gst_uri_set_scheme(uri: *mut GstUri, scheme: *const gchar) -> gboolean;
+    fn gst_uri_get_userinfo(uri: *const GstUri) -> *const gchar;
+    fn gst_uri_set_userinfo(uri: *mut GstUri, userinfo: *const gchar) -> gboolean;
+    fn gst_uri_get_host(uri: *const GstUri) -> *const gchar;
+    fn gst_uri_set_host(uri: *mut GstUri, host: *const gchar) -> gboolean;
+    fn gst_uri_get_port(uri: *const GstUri) -> guint;
+    fn gst_uri_set_port(uri: *mut GstUri, port: guint) -> gboolean;
+    fn gst_uri_get_path(uri: *const GstUri) -> *mut gchar;
+    fn gst_uri_set_path(uri: *mut GstUri, path: *const gchar) -> gboolean;
+    fn gst_uri_get_query_string(uri: *const GstUri) -> *mut gchar;
+    fn gst_uri_get_query_value(uri: *const GstUri, query_key: *const gchar) -> *const gchar;
+    fn gst_uri_set_query_value(uri: *mut GstUri, query_key: *const gchar, query_value: *const gchar) -> gboolean;
+    fn gst_uri_remove_query_key(uri: *mut GstUri, query_key: *const gchar) -> gboolean;
+    fn gst_uri_get_fragment(uri: *const GstUri) -> *const gchar;
+    fn gst_uri_set_fragment(uri: *mut GstUri, fragment: *const gchar) -> gboolean;
+}
+
+/// Whether an element handling a URI reads from it or writes to it
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum UriType{
+    Src,
+    Sink,
+}
+
+impl UriType{
+    pub fn to_gst(self) -> GstURIType{
+        match self{
+            UriType::Src => GST_URI_SRC,
+            UriType::Sink => GST_URI_SINK,
+        }
+    }
+
+    pub fn from_gst(uri_type: GstURIType) -> Option<UriType>{
+        match uri_type{
+            GST_URI_SRC => Some(UriType::Src),
+            GST_URI_SINK => Some(UriType::Sink),
+            _ => None
+        }
+    }
+}
+
+unsafe fn optional_str(c_str: *const gchar) -> Option<String>{
+    if c_str != ptr::null(){
+        Some(from_c_str!(c_str).to_string())
+    }else{
+        None
+    }
+}
+
+unsafe fn take_optional_str(c_str: *mut gchar) -> Option<String>{
+    let string = optional_str(c_str);
+    if c_str != ptr::null_mut(){
+        g_free(c_str as gpointer);
+    }
+    string
+}
+
+fn optional_c_str(string: Option<&str>) -> Option<CString>{
+    string.map(|string| CString::new(string).unwrap())
+}
+
+fn c_ptr(c_str: &Option<CString>) -> *const gchar{
+    c_str.as_ref().map(|c_str| c_str.as_ptr()).unwrap_or(ptr::null())
+}
+
+/// A parsed RFC 3986 URI, as in `rtsp://user@camera.local:554/stream?tcp=1`.
+///
+/// Percent encoded characters are decoded when getting the components
+/// and encoded again when converting the URI to a string
+pub struct Uri{
+    uri: *mut GstUri
+}
+
+unsafe impl Send for Uri {}
+
+impl Drop for Uri{
+    fn drop(&mut self){
+        unsafe{
+            gst_mini_object_unref(self.uri as *mut GstMiniObject);
+        }
+    }
+}
+
+impl Clone for Uri{
+    fn clone(&self) -> Uri{
+        unsafe{
+            gst_mini_object_ref(self.uri as *mut GstMiniObject);
+            Uri{ uri: self.uri }
+        }
+    }
+}
+
+impl PartialEq for Uri{
+    fn eq(&self, other: &Uri) -> bool{
+        unsafe{
+            gst_uri_equal(self.uri, other.uri) != 0
+        }
+    }
+}
+
+impl Uri{
+    /// Parses a URI, returns None if it's not valid
+    pub fn from_string(uri: &str) -> Option<Uri>{
+        unsafe{
+            Uri::new_from_gst_uri(gst_uri_from_string(to_c_str!(uri)), true)
+        }
+    }
+
+    /// Builds a URI from its components, port 0 means no port
+    pub fn new(scheme: Option<&str>, userinfo: Option<&str>, host: Option<&str>, port: u32,
+            path: Option<&str>, query: Option<&str>, fragment: Option<&str>) -> Option<Uri>{
+        let scheme = optional_c_str(scheme);
+        let userinfo = optional_c_str(userinfo);
+        let host = optional_c_str(host);
+        let path = optional_c_str(path);
+        let query = optional_c_str(query);
+        let fragment = optional_c_str(fragment);
+        unsafe{
+            Uri::new_from_gst_uri(gst_uri_new(c_ptr(&scheme), c_ptr(&userinfo), c_ptr(&host), port,
+                c_ptr(&path), c_ptr(&query), c_ptr(&fragment)), true)
+        }
+    }
+
+    /// Wraps a raw GstUri, if owned is false a new reference is taken
+    pub unsafe fn new_from_gst_uri(uri: *mut GstUri, owned: bool) -> Option<Uri>{
+        if uri != ptr::null_mut(){
+            if !owned{
+                gst_mini_object_ref(uri as *mut GstMiniObject);
+            }
+            Some(Uri{ uri: uri })
+        }else{
+            None
+        }
+    }
+
+    /// Resolves a reference relative to this URI, as a link found in a
+    /// playlist downloaded from it
+    pub fn join(&self, reference: &str) -> Option<Uri>{
+        unsafe{
+            let reference = match Uri::from_string(reference){
+                Some(reference) => reference,
+                None => return None
+            };
+            Uri::new_from_gst_uri(gst_uri_join(self.uri, reference.uri), true)
+        }
+    }
+
+    pub fn scheme(&self) -> Option<String>{
+        unsafe{ optional_str(gst_uri_get_scheme(self.uri)) }
+    }
+
+    pub fn userinfo(&self) -> Option<String>{
+        unsafe{ optional_str(gst_uri_get_userinfo(self.uri)) }
+    }
+
+    pub fn host(&self) -> Option<String>{
+        unsafe{ optional_str(gst_uri_get_host(self.uri)) }
+    }
+
+    pub fn port(&self) -> Option<u32>{
+        unsafe{
+            match gst_uri_get_port(self.uri){
+                GST_URI_NO_PORT => None,
+                port => Some(port)
+            }
+        }
+    }
+
+    pub fn path(&self) -> Option<String>{
+        unsafe{ take_optional_str(gst_uri_get_path(self.uri)) }
+    }
+
+    pub fn query_string(&self) -> Option<String>{
+        unsafe{ take_optional_str(gst_uri_get_query_string(self.uri)) }
+    }
+
+    /// Value of a key of the query, as "1" for tcp in `rtsp://host/?tcp=1`
+    pub fn query_value(&self, key: &str) -> Option<String>{
+        unsafe{ optional_str(gst_uri_get_query_value(self.uri, to_c_str!(key))) }
+    }
+
+    pub fn fragment(&self) -> Option<String>{
+        unsafe{ optional_str(gst_uri_get_fragment(self.uri)) }
+    }
+
+    pub fn set_scheme(&mut self, scheme: Option<&str>) -> bool{
+        let scheme = optional_c_str(scheme);
+        unsafe{ gst_uri_set_scheme(self.make_writable(), c_ptr(&scheme)) != 0 }
+    }
+
+    pub fn set_userinfo(&mut self, userinfo: Option<&str>) -> bool{
+        let userinfo = optional_c_str(userinfo);
+        unsafe{ gst_uri_set_userinfo(self.make_writable(), c_ptr(&userinfo)) != 0 }
+    }
+
+    pub fn set_host(&mut self, host: Option<&str>) -> bool{
+        let host = optional_c_str(host);
+        unsafe{ gst_uri_set_host(self.make_writable(), c_ptr(&host)) != 0 }
+    }
+
+    pub fn set_port(&mut self, port: Option<u32>) -> bool{
+        unsafe{ gst_uri_set_port(self.make_writable(), port.unwrap_or(GST_URI_NO_PORT)) != 0 }
+    }
+
+    pub fn set_path(&mut self, path: Option<&str>) -> bool{
+        let path = optional_c_str(path);
+        unsafe{ gst_uri_set_path(self.make_writable(), c_ptr(&path)) != 0 }
+    }
+
+    /// Sets a key of the query, a None value adds the key without a value
+    pub fn set_query_value(&mut self, key: &str, value: Option<&str>) -> bool{
+        let value = optional_c_str(value);
+        unsafe{ gst_uri_set_query_value(self.make_writable(), to_c_str!(key), c_ptr(&value)) != 0 }
+    }
+
+    pub fn remove_query_key(&mut self, key: &str) -> bool{
+        unsafe{ gst_uri_remove_query_key(self.make_writable(), to_c_str!(key)) != 0 }
+    }
+
+    pub fn set_fragment(&mut self, fragment: Option<&str>) -> bool{
+        let fragment = optional_c_str(fragment);
+        unsafe{ gst_uri_set_fragment(self.make_writable(), c_ptr(&fragment)) != 0 }
+    }
+
+    fn make_writable(&mut self) -> *mut GstUri{
+        unsafe{
+            self.uri = gst_mini_object_make_writable(self.uri as *mut GstMiniObject) as *mut GstUri;
+            self.uri
+        }
+    }
+
+    pub unsafe fn gst_uri(&self) -> *const GstUri{
+        self.uri
+    }
+
+    pub unsafe fn gst_uri_mut(&mut self) -> *mut GstUri{
+        self.make_writable()
+    }
+}
+
+impl Display for Uri{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        unsafe{
+            let c_str = gst_uri_to_string(self.uri);
+            let ret = fmt.write_str(from_c_str!(c_str));
+            g_free(c_str as gpointer);
+            ret
+        }
+    }
+}
+
+impl Debug for Uri{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "Uri({})", self)
+    }
+}
+
+/// True if uri has a valid protocol followed by ://
+pub fn uri_is_valid(uri: &str) -> bool{
+    unsafe{
+        gst_uri_is_valid(to_c_str!(uri)) != 0
+    }
+}
+
+/// True if there's an element able to read (Src) or write (Sink) URIs
+/// with the given protocol, as "rtsp" or "file"
+pub fn uri_protocol_is_supported(uri_type: UriType, protocol: &str) -> bool{
+    unsafe{
+        gst_uri_protocol_is_supported(uri_type.to_gst(), to_c_str!(protocol)) != 0
+    }
+}
+
+/// Converts a file:// URI to a local path
+pub fn uri_to_filename(uri: &str) -> Result<String>{
+    unsafe{
+        let mut err: *mut GError = ptr::null_mut();
+        let filename = g_filename_from_uri(to_c_str!(uri), ptr::null_mut(), &mut err);
+        if err != ptr::null_mut(){
+            Err(Error::new_from_g_error(err))
+        }else{
+            Ok(take_optional_str(filename).unwrap_or_default())
+        }
+    }
+}
+
+/// Implemented by elements handling URIs, as filesrc, rtspsrc or
+/// souphttpsrc. Every method returns None or an error if the element
+/// doesn't implement the GstURIHandler interface
+pub trait UriHandlerT: ElementT{
+    fn is_uri_handler(&self) -> bool{
+        unsafe{
+            g_type_check_instance_is_a(self.gst_element() as *mut GTypeInstance, gst_uri_handler_get_type()) != 0
+        }
+    }
+
+    fn uri_type(&self) -> Option<UriType>{
+        if !self.is_uri_handler(){
+            return None;
+        }
+        unsafe{
+            UriType::from_gst(gst_uri_handler_get_uri_type(self.gst_element() as *mut GstURIHandler))
+        }
+    }
+
+    /// Protocols supported by the element, as ["http", "https"]
+    fn protocols(&self) -> Vec<String>{
+        if !self.is_uri_handler(){
+            return Vec::new();
+        }
+        unsafe{
+            let mut protocols = Vec::new();
+            let mut protocol = gst_uri_handler_get_protocols(self.gst_element() as *mut GstURIHandler);
+            while protocol != ptr::null() && *protocol != ptr::null(){
+                protocols.push(from_c_str!(*protocol).to_string());
+                protocol = protocol.offset(1);
+            }
+            protocols
+        }
+    }
+
+    fn uri(&self) -> Option<String>{
+        if !self.is_uri_handler(){
+            return None;
+        }
+        unsafe{
+            take_optional_str(gst_uri_handler_get_uri(self.gst_element() as *mut GstURIHandler))
+        }
+    }
+
+    /// Sets the URI to read from or write to, usually only possible in
+    /// the READY or NULL states
+    fn set_uri(&mut self, uri: &str) -> Result<()>{
+        if !self.is_uri_handler(){
+            return Err(Error::new(unsafe{ gst_uri_error_quark() }, GST_URI_ERROR_UNSUPPORTED_PROTOCOL as i32,
+                &format!("{} is not a URI handler", self.name())));
+        }
+        unsafe{
+            let mut err: *mut GError = ptr::null_mut();
+            if gst_uri_handler_set_uri(self.gst_element_mut() as *mut GstURIHandler, to_c_str!(uri), &mut err) != 0{
+                Ok(())
+            }else if err != ptr::null_mut(){
+                Err(Error::new_from_g_error(err))
+            }else{
+                Err(Error::new(gst_uri_error_quark(), GST_URI_ERROR_BAD_URI as i32, &format!("couldn't set uri {} on {}", uri, self.name())))
+            }
+        }
+    }
+}
+
+impl<E: ElementT> UriHandlerT for E {}
+
+impl Element{
+    /// Creates the highest ranked element able to handle uri, as a
+    /// souphttpsrc for an http:// source. If name is empty a unique name
+    /// is generated
+    pub fn make_from_uri(uri_type: UriType, uri: &str, name: &str) -> Result<Element>{
+        unsafe{
+            let name = if name != "" {
+                Some(CString::new(name).unwrap())
+            } else {
+                None
+            };
+            let mut err: *mut GError = ptr::null_mut();
+            let element = gst_element_make_from_uri(uri_type.to_gst(), to_c_str!(uri), c_ptr(&name), &mut err);
+            if err != ptr::null_mut(){
+                return Err(Error::new_from_g_error(err));
+            }
+            if element != ptr::null_mut(){
+                gst_object_ref_sink(element as gpointer);
+            }
+            Element::new_from_gst_element(element)
+                .ok_or_else(|| Error::new(gst_uri_error_quark(), GST_URI_ERROR_UNSUPPORTED_PROTOCOL as i32, &format!("no element can handle {}", uri)))
+        }
+    }
+}
@@ -4,6 +4,8 @@ pub use std::str;
 pub use std::mem;
 pub use std::ptr;
 
+use ffi::*;
+
 /// Converts nanoseconds to seconds
 pub fn ns_to_s(ns: u64) -> f64{
     (ns as f64) / 1000000000.0
@@ -14,6 +16,37 @@ pub fn s_to_ns(s: f64) -> u64{
     (s * 1000000000.0) as u64
 }
 
+/// Collects the objects returned by a GstIterator, each with a new
+/// reference, and frees the iterator. If the iterated collection changes
+/// meanwhile the iteration starts again
+pub unsafe fn iterator_objects(it: *mut GstIterator) -> Vec<gpointer>{
+    let mut objects = Vec::new();
+    if it == ptr::null_mut(){
+        return objects;
+    }
+    let mut value: GValue = mem::zeroed();
+    loop{
+        match gst_iterator_next(it, &mut value){
+            GST_ITERATOR_OK => {
+                objects.push(gst_object_ref(g_value_get_object(&value)));
+                g_value_reset(&mut value);
+            }
+            GST_ITERATOR_RESYNC => {
+                for object in objects.drain(..){
+                    gst_object_unref(object);
+                }
+                gst_iterator_resync(it);
+            }
+            _ => break
+        }
+    }
+    if value.g_type != 0{
+        g_value_unset(&mut value);
+    }
+    gst_iterator_free(it);
+    objects
+}
+
 macro_rules! to_c_str{
 	($string: expr) => (
 		CString::new($string).unwrap().as_ptr()
@@ -25,3 +58,27 @@ macro_rules! from_c_str{
 		str::from_utf8(CStr::from_ptr($c_string).to_bytes()).unwrap();
 	);
 }
+
+/// Drops the Box<T> passed as user data, to be used as the GDestroyNotify
+/// of probes, tasks and callbacks
+pub(crate) extern "C" fn box_destroy_notify<T>(data: gpointer){
+    unsafe{
+        drop(Box::from_raw(data as *mut T));
+    }
+}
+
+/// Drops the Box<T> passed as the data of a signal handler, to be used as
+/// its GClosureNotify
+pub(crate) extern "C" fn box_closure_notify<T>(data: gpointer, _closure: *mut GClosure){
+    unsafe{
+        drop(Box::from_raw(data as *mut T));
+    }
+}
+
+/// Connects handler to signal of instance passing it data, which is
+/// dropped when the handler is disconnected or the instance finalized.
+/// Returns the id of the handler
+pub(crate) unsafe fn connect_boxed<T>(instance: gpointer, signal: &str, handler: gpointer, data: Box<T>) -> gulong{
+    g_signal_connect_data(instance, to_c_str!(signal), Some(mem::transmute(handler)),
+        Box::into_raw(data) as gpointer, Some(box_closure_notify::<T>), 0)
+}
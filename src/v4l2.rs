@@ -0,0 +1,157 @@
+use ffi::*;
+use element::{Element, ElementT};
+use structure::Structure;
+use value::Value;
+
+use std::collections::BTreeMap;
+
+/// Wrapper around v4l2src, the Linux camera source, giving access to the
+/// camera controls through its extra-controls property.
+///
+/// Controls are identified by their V4L2 name in lowercase with
+/// underscores, as listed by `v4l2-ctl --list-ctrls`, e.g.
+/// "exposure_time_absolute" or "focus_absolute". They are applied when
+/// the device is opened and immediately if it's already running.
+pub struct V4l2Src{
+    v4l2src: Element
+}
+
+unsafe impl Sync for V4l2Src {}
+unsafe impl Send for V4l2Src {}
+
+impl V4l2Src{
+    pub fn new(name: &str) -> Option<V4l2Src>{
+        Element::new("v4l2src", name).map(|v4l2src| V4l2Src{ v4l2src: v4l2src })
+    }
+
+    pub fn new_from_element(element: Element) -> V4l2Src{
+        V4l2Src{ v4l2src: element }
+    }
+
+    /// Sets the device to capture from, as in /dev/video0
+    pub fn set_device(&mut self, device: &str){
+        self.v4l2src.set_property("device", &Value::String(device.to_string())).ok();
+    }
+
+    pub fn device(&self) -> Option<String>{
+        match self.v4l2src.property("device"){
+            Some(Value::String(device)) => Some(device),
+            _ => None
+        }
+    }
+
+    /// Returns the controls set so far, by name
+    pub fn controls(&self) -> BTreeMap<String, i32>{
+        self.extra_controls().map(|controls| {
+            controls.fields().into_iter()
+                .filter_map(|(name, value)| match value{
+                    Value::Int(value) => Some((name, value)),
+                    Value::Bool(value) => Some((name, value as i32)),
+                    _ => None
+                })
+                .collect()
+        }).unwrap_or_default()
+    }
+
+    pub fn control(&self, name: &str) -> Option<i32>{
+        self.controls().get(name).cloned()
+    }
+
+    /// Sets a single control, keeping the rest
+    pub fn set_control(&mut self, name: &str, value: i32) -> bool{
+        let mut controls = self.controls();
+        controls.insert(name.to_string(), value);
+        self.set_controls(&controls)
+    }
+
+    /// Removes a control so the driver default is used the next time the
+    /// device is opened
+    pub fn remove_control(&mut self, name: &str) -> bool{
+        let mut controls = self.controls();
+        controls.remove(name);
+        self.set_controls(&controls)
+    }
+
+    /// Replaces all the controls with the passed ones
+    pub fn set_controls(&mut self, controls: &BTreeMap<String, i32>) -> bool{
+        let mut structure = match Structure::new("controls"){
+            Some(structure) => structure,
+            None => return false
+        };
+        for (name, value) in controls{
+            if !structure.set(name, *value){
+                return false;
+            }
+        }
+        unsafe{
+            self.v4l2src.set("extra-controls", structure.gst_structure());
+        }
+        true
+    }
+
+    /// Sets the exposure time in units of 100µs, switching the camera to
+    /// manual exposure
+    pub fn set_exposure(&mut self, exposure: i32) -> bool{
+        self.set_control("auto_exposure", 1) && self.set_control("exposure_time_absolute", exposure)
+    }
+
+    /// Lets the camera choose the exposure time
+    pub fn set_auto_exposure(&mut self) -> bool{
+        self.remove_control("exposure_time_absolute") && self.set_control("auto_exposure", 3)
+    }
+
+    pub fn set_gain(&mut self, gain: i32) -> bool{
+        self.set_control("gain", gain)
+    }
+
+    /// Sets the focus distance, disabling autofocus
+    pub fn set_focus(&mut self, focus: i32) -> bool{
+        self.set_control("focus_automatic_continuous", 0) && self.set_control("focus_absolute", focus)
+    }
+
+    pub fn set_auto_focus(&mut self, auto_focus: bool) -> bool{
+        if auto_focus{
+            self.remove_control("focus_absolute");
+        }
+        self.set_control("focus_automatic_continuous", auto_focus as i32)
+    }
+
+    pub fn set_brightness(&mut self, brightness: i32) -> bool{
+        self.set_control("brightness", brightness)
+    }
+
+    pub fn set_contrast(&mut self, contrast: i32) -> bool{
+        self.set_control("contrast", contrast)
+    }
+
+    pub fn set_saturation(&mut self, saturation: i32) -> bool{
+        self.set_control("saturation", saturation)
+    }
+
+    /// Sets the white balance in Kelvin, disabling automatic white balance
+    pub fn set_white_balance_temperature(&mut self, temperature: i32) -> bool{
+        self.set_control("white_balance_automatic", 0) && self.set_control("white_balance_temperature", temperature)
+    }
+
+    fn extra_controls(&self) -> Option<Structure>{
+        unsafe{
+            Structure::new_from_gst_structure(self.v4l2src.get::<*mut GstStructure>("extra-controls"))
+        }
+    }
+}
+
+impl ElementT for V4l2Src{
+    fn as_element(&self) -> &Element{
+        &self.v4l2src
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.v4l2src
+    }
+}
+
+impl ::Transfer for V4l2Src{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.v4l2src.transfer()
+    }
+}
@@ -0,0 +1,346 @@
+//! gst-validate integration, needs the `validate` feature and
+//! libgstvalidate-1.0.
+//!
+//! A Runner collects the issues gst-validate finds in a pipeline, as
+//! buffers out of the segment, seeks that aren't handled or wrong
+//! timestamps, so tests can check a pipeline behaves correctly and not
+//! only that it doesn't post errors:
+//!
+//! ```ignore
+//! let runner = validate::Runner::new();
+//! let mut pipeline = Pipeline::new_from_str("videotestsrc num-buffers=300 ! x264enc ! mp4mux ! filesink location=/tmp/out.mp4")?;
+//! let scenario = validate::Scenario::new()
+//!     .wait(Duration::from_secs(1))
+//!     .seek(5 * 1000000000, "accurate+flush")
+//!     .wait(Duration::from_secs(1))
+//!     .stop();
+//! let reports = runner.run_scenario(&mut pipeline, &scenario, 30 * 1000000000)?;
+//! assert!(reports.iter().all(|report| report.level != validate::ReportLevel::Critical));
+//! ```
+
+use ffi::*;
+use util::*;
+use element::ElementT;
+use pipeline::Pipeline;
+use error::{Error, Result};
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::process;
+use std::sync::Once;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+pub enum GstValidateRunner{}
+pub enum GstValidateMonitor{}
+pub enum GstValidateScenario{}
+pub enum GstValidateReporter{}
+
+// Head of GstValidateIssue, the fields are public in the C API
+#[repr(C)]
+struct GstValidateIssue{
+    issue_id: GQuark,
+    summary: *mut gchar,
+    description: *mut gchar,
+    area: *mut gchar,
+    name: *mut gchar,
+    default_level: guint,
+}
+
+// Head of GstValidateReport, the fields before the private ones
+#[repr(C)]
+struct GstValidateReport{
+    mini_object: GstMiniObject,
+    issue: *mut GstValidateIssue,
+    level: guint,
+    reporter: *mut GstValidateReporter,
+    timestamp: GstClockTime,
+    message: *mut gchar,
+}
+
+#[cfg_attr(not(target_os="macos"), link(name = "gstvalidate-1.0"))]
+extern "C"{
+    fn gst_validate_init();
+    fn gst_validate_runner_new() -> *mut GstValidateRunner;
+    fn gst_validate_runner_get_reports_count(runner: *mut GstValidateRunner) -> guint;
+    fn gst_validate_runner_get_reports(runner: *mut GstValidateRunner) -> *mut GList;
+    fn gst_validate_runner_exit(runner: *mut GstValidateRunner, print_result: gboolean) -> gint;
+    fn gst_validate_monitor_factory_create(target: *mut GstObject, runner: *mut GstValidateRunner,
+        parent: *mut GstValidateMonitor) -> *mut GstValidateMonitor;
+    fn gst_validate_scenario_factory_create(runner: *mut GstValidateRunner, pipeline: *mut GstElement,
+        scenario_name: *const gchar) -> *mut GstValidateScenario;
+    fn gst_validate_reporter_get_name(reporter: *mut GstValidateReporter) -> *mut gchar;
+    fn gst_validate_report_unref(report: *mut GstValidateReport);
+}
+
+static INIT: Once = Once::new();
+
+// Used to name the scenario files of the runs in the same process
+static SCENARIO_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+// How long run_scenario waits for bus messages before dispatching the
+// scenario actions again
+const POLL_INTERVAL: GstClockTime = 10 * 1000000;
+
+/// Severity of a Report
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum ReportLevel{
+    /// The pipeline misbehaves in a way users would notice, as a failed
+    /// seek or an error
+    Critical,
+    Warning,
+    /// Doesn't follow the expected behavior of GStreamer elements but may
+    /// work correctly
+    Issue,
+    Ignore,
+}
+
+impl ReportLevel{
+    fn from_gst(level: guint) -> ReportLevel{
+        match level{
+            0 => ReportLevel::Critical,
+            1 => ReportLevel::Warning,
+            2 => ReportLevel::Issue,
+            _ => ReportLevel::Ignore,
+        }
+    }
+}
+
+/// An issue found by gst-validate
+#[derive(Clone,Debug,PartialEq)]
+pub struct Report{
+    /// Id of the kind of issue, as "event::seek-not-handled"
+    pub issue_id: String,
+    pub summary: String,
+    pub level: ReportLevel,
+    /// Name of the element or pad that caused it
+    pub reporter: String,
+    /// Details about this occurrence of the issue
+    pub message: String,
+    /// Running time of the pipeline when the issue was found
+    pub timestamp: Option<u64>,
+}
+
+impl Report{
+    unsafe fn new(report: *mut GstValidateReport) -> Report{
+        let issue = (*report).issue;
+        let reporter = if (*report).reporter != ptr::null_mut(){
+            let name = gst_validate_reporter_get_name((*report).reporter);
+            if name != ptr::null_mut(){ from_c_str!(name).to_string() }else{ String::new() }
+        }else{
+            String::new()
+        };
+        Report{
+            issue_id: from_c_str!(g_quark_to_string((*issue).issue_id)).to_string(),
+            summary: from_c_str!((*issue).summary).to_string(),
+            level: ReportLevel::from_gst((*report).level),
+            reporter: reporter,
+            message: if (*report).message != ptr::null_mut(){ from_c_str!((*report).message).to_string() }else{ String::new() },
+            timestamp: if (*report).timestamp == GST_CLOCK_TIME_NONE{ None }else{ Some((*report).timestamp) },
+        }
+    }
+}
+
+/// Collects the issues found by the monitors and scenarios created with
+/// it
+pub struct Runner{
+    runner: *mut GstValidateRunner
+}
+
+unsafe impl Sync for Runner {}
+unsafe impl Send for Runner {}
+
+impl Drop for Runner{
+    fn drop(&mut self){
+        unsafe{
+            g_object_unref(self.runner as gpointer);
+        }
+    }
+}
+
+impl Runner{
+    /// Initializes gst-validate the first time, gst::init has to be
+    /// called before
+    pub fn new() -> Runner{
+        unsafe{
+            INIT.call_once(|| gst_validate_init());
+            Runner{ runner: gst_validate_runner_new() }
+        }
+    }
+
+    /// Starts monitoring element, usually the pipeline, and everything
+    /// added to it. The issues are reported while the Monitor is alive
+    pub fn monitor<E: ElementT>(&self, element: &E) -> Monitor{
+        unsafe{
+            Monitor{ monitor: gst_validate_monitor_factory_create(element.gst_element() as *mut GstObject,
+                self.runner, ptr::null_mut()) }
+        }
+    }
+
+    /// Monitors pipeline and runs scenario on it, setting it to PLAYING.
+    /// Returns the issues found once the pipeline posts EOS, an error,
+    /// the scenario stops it or timeout nanoseconds pass. The pipeline
+    /// is left in NULL
+    pub fn run_scenario(&self, pipeline: &mut Pipeline, scenario: &Scenario, timeout: GstClockTime) -> Result<Vec<Report>>{
+        let path = env::temp_dir().join(format!("gst-rs-{}-{}.scenario", process::id(),
+            SCENARIO_COUNT.fetch_add(1, Ordering::SeqCst)));
+        fs::write(&path, scenario.to_string()).map_err(|err| Error::new(unsafe{ gst_resource_error_quark() },
+            GST_RESOURCE_ERROR_OPEN_WRITE as i32, &format!("couldn't write {}: {}", path.display(), err)))?;
+
+        let _monitor = self.monitor(pipeline);
+        let validate_scenario = unsafe{
+            gst_validate_scenario_factory_create(self.runner, pipeline.gst_element() as *mut GstElement,
+                to_c_str!(path.to_string_lossy().as_ref()))
+        };
+        let _ = fs::remove_file(&path);
+        if validate_scenario == ptr::null_mut(){
+            return Err(Error::new(unsafe{ gst_core_error_quark() }, GST_CORE_ERROR_FAILED as i32,
+                "couldn't load the scenario"));
+        }
+
+        pipeline.set_state(GST_STATE_PLAYING);
+        let start = Instant::now();
+        let timeout = Duration::from_nanos(timeout);
+        while start.elapsed() < timeout{
+            // the actions of the scenario are dispatched from the default
+            // main context
+            unsafe{
+                while g_main_context_iteration(ptr::null_mut(), 0) != 0{}
+            }
+            if pipeline.wait_for_message(POLL_INTERVAL, GST_MESSAGE_EOS | GST_MESSAGE_REQUEST_STATE).is_some(){
+                break;
+            }
+        }
+        pipeline.set_state(GST_STATE_NULL);
+        unsafe{
+            g_object_unref(validate_scenario as gpointer);
+        }
+        Ok(self.reports())
+    }
+
+    /// The issues reported so far
+    pub fn reports(&self) -> Vec<Report>{
+        unsafe{
+            let list = gst_validate_runner_get_reports(self.runner);
+            let mut reports = Vec::new();
+            let mut node = list;
+            while node != ptr::null_mut(){
+                let report = (*node).data as *mut GstValidateReport;
+                reports.push(Report::new(report));
+                gst_validate_report_unref(report);
+                node = (*node).next;
+            }
+            g_list_free(list);
+            reports
+        }
+    }
+
+    pub fn reports_count(&self) -> u32{
+        unsafe{
+            gst_validate_runner_get_reports_count(self.runner)
+        }
+    }
+
+    /// The exit code gst-validate-1.0 would return, non 0 if any critical
+    /// issue was found
+    pub fn exit_code(&self) -> i32{
+        unsafe{
+            gst_validate_runner_exit(self.runner, 0)
+        }
+    }
+
+    pub unsafe fn gst_validate_runner(&self) -> *mut GstValidateRunner{
+        self.runner
+    }
+}
+
+/// Monitors an element for a Runner while it's alive
+pub struct Monitor{
+    monitor: *mut GstValidateMonitor
+}
+
+unsafe impl Send for Monitor {}
+
+impl Drop for Monitor{
+    fn drop(&mut self){
+        unsafe{
+            if self.monitor != ptr::null_mut(){
+                g_object_unref(self.monitor as gpointer);
+            }
+        }
+    }
+}
+
+/// A sequence of actions run by Runner::run_scenario, in the format of
+/// the .scenario files of gst-validate
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Scenario{
+    actions: Vec<String>
+}
+
+impl Scenario{
+    pub fn new() -> Scenario{
+        Scenario::default()
+    }
+
+    /// Seeks to start nanoseconds, flags are the names of the seek flags
+    /// separated by +, as "accurate+flush"
+    pub fn seek(self, start: u64, flags: &str) -> Scenario{
+        self.action(&format!("seek, start={}, flags={}", seconds(start), flags))
+    }
+
+    /// Seeks at rate, as -1.0 to play backwards from start
+    pub fn seek_with_rate(self, start: u64, rate: f64, flags: &str) -> Scenario{
+        self.action(&format!("seek, start={}, rate={}, flags={}", seconds(start), rate, flags))
+    }
+
+    pub fn play(self) -> Scenario{
+        self.action("play")
+    }
+
+    pub fn pause(self) -> Scenario{
+        self.action("pause")
+    }
+
+    /// Sets the state of the pipeline, as "null", "ready", "paused" or
+    /// "playing"
+    pub fn set_state(self, state: &str) -> Scenario{
+        self.action(&format!("set-state, state={}", state))
+    }
+
+    /// Waits before running the next action
+    pub fn wait(self, duration: Duration) -> Scenario{
+        self.action(&format!("wait, duration={}", duration.as_secs_f64()))
+    }
+
+    /// Sends EOS to the pipeline
+    pub fn eos(self) -> Scenario{
+        self.action("eos")
+    }
+
+    /// Ends the run
+    pub fn stop(self) -> Scenario{
+        self.action("stop")
+    }
+
+    /// Any other action, in the syntax of the .scenario files, as
+    /// "set-property, target-element-name=enc, property-name=bitrate, property-value=1000"
+    pub fn action(mut self, action: &str) -> Scenario{
+        self.actions.push(action.to_string());
+        self
+    }
+}
+
+impl fmt::Display for Scenario{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result{
+        for action in &self.actions{
+            writeln!(fmt, "{};", action)?;
+        }
+        Ok(())
+    }
+}
+
+fn seconds(ns: u64) -> f64{
+    ns as f64 / 1000000000.0
+}
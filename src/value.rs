@@ -0,0 +1,387 @@
+use ffi::*;
+use util::*;
+
+#[cfg(feature="serde")]
+use serde::{Serialize, Deserialize};
+
+pub(crate) const G_TYPE_BOOLEAN: GType = 5 << 2;
+pub(crate) const G_TYPE_INT: GType = 6 << 2;
+pub(crate) const G_TYPE_UINT: GType = 7 << 2;
+pub(crate) const G_TYPE_INT64: GType = 10 << 2;
+pub(crate) const G_TYPE_UINT64: GType = 11 << 2;
+pub(crate) const G_TYPE_FLOAT: GType = 14 << 2;
+pub(crate) const G_TYPE_DOUBLE: GType = 15 << 2;
+pub(crate) const G_TYPE_STRING: GType = 16 << 2;
+
+// GstFlagSet was added in GStreamer 1.6, after ffi.rs was generated
+extern "C"{
+    fn gst_flagset_get_type() -> GType;
+    fn gst_value_set_flagset(value: *mut GValue, flags: guint, mask: guint);
+    fn gst_value_get_flagset_flags(value: *const GValue) -> guint;
+    fn gst_value_get_flagset_mask(value: *const GValue) -> guint;
+}
+
+/// A field value of a Structure, Caps or TagList.
+///
+/// Types that don't have a direct representation are kept as Other with
+/// their GStreamer serialization, so they can still be compared, printed
+/// and set back.
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature="serde", derive(Serialize,Deserialize))]
+pub enum Value{
+    Bool(bool),
+    Int(i32),
+    UInt(u32),
+    Int64(i64),
+    UInt64(u64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Fraction(i32,i32),
+    IntRange(i32,i32),
+    Int64Range(i64,i64),
+    DoubleRange(f64,f64),
+    FractionRange((i32,i32),(i32,i32)),
+    List(Vec<Value>),
+    Array(Vec<Value>),
+    /// A 64 bit mask, as the channel-mask of audio caps
+    Bitmask(u64),
+    /// Flags and the mask of the flags that are set or unset, the type
+    /// name is "GstFlagSet" or the name of its subclass, as
+    /// GstVideoMultiviewFlagsSet, so it can be set back with its type
+    FlagSet{type_name: String, flags: u32, mask: u32},
+    Other{type_name: String, value: String},
+}
+
+impl Value{
+    /// Converts a GValue into a Value, returns None if the value can't
+    /// be represented
+    pub unsafe fn from_gvalue(gvalue: *const GValue) -> Option<Value>{
+        if gvalue == ptr::null(){
+            return None;
+        }
+        let ty = (*gvalue).g_type;
+        let value = match ty{
+            G_TYPE_BOOLEAN => Value::Bool(g_value_get_boolean(gvalue) != 0),
+            G_TYPE_INT => Value::Int(g_value_get_int(gvalue)),
+            G_TYPE_UINT => Value::UInt(g_value_get_uint(gvalue)),
+            G_TYPE_INT64 => Value::Int64(g_value_get_int64(gvalue)),
+            G_TYPE_UINT64 => Value::UInt64(g_value_get_uint64(gvalue)),
+            G_TYPE_FLOAT => Value::Float(g_value_get_float(gvalue)),
+            G_TYPE_DOUBLE => Value::Double(g_value_get_double(gvalue)),
+            G_TYPE_STRING => {
+                let string = g_value_get_string(gvalue);
+                if string != ptr::null(){
+                    Value::String(from_c_str!(string).to_string())
+                }else{
+                    Value::String("".to_string())
+                }
+            }
+            _ if ty == gst_fraction_get_type() =>
+                Value::Fraction(gst_value_get_fraction_numerator(gvalue), gst_value_get_fraction_denominator(gvalue)),
+            _ if ty == gst_int_range_get_type() =>
+                Value::IntRange(gst_value_get_int_range_min(gvalue), gst_value_get_int_range_max(gvalue)),
+            _ if ty == gst_int64_range_get_type() =>
+                Value::Int64Range(gst_value_get_int64_range_min(gvalue), gst_value_get_int64_range_max(gvalue)),
+            _ if ty == gst_double_range_get_type() =>
+                Value::DoubleRange(gst_value_get_double_range_min(gvalue), gst_value_get_double_range_max(gvalue)),
+            _ if ty == gst_fraction_range_get_type() => {
+                let min = gst_value_get_fraction_range_min(gvalue);
+                let max = gst_value_get_fraction_range_max(gvalue);
+                Value::FractionRange(
+                    (gst_value_get_fraction_numerator(min), gst_value_get_fraction_denominator(min)),
+                    (gst_value_get_fraction_numerator(max), gst_value_get_fraction_denominator(max)))
+            }
+            _ if ty == gst_value_list_get_type() => {
+                let values = (0..gst_value_list_get_size(gvalue))
+                    .filter_map(|i| Value::from_gvalue(gst_value_list_get_value(gvalue, i)))
+                    .collect();
+                Value::List(values)
+            }
+            _ if ty == gst_value_array_get_type() => {
+                let values = (0..gst_value_array_get_size(gvalue))
+                    .filter_map(|i| Value::from_gvalue(gst_value_array_get_value(gvalue, i)))
+                    .collect();
+                Value::Array(values)
+            }
+            _ if ty == gst_bitmask_get_type() => Value::Bitmask(gst_value_get_bitmask(gvalue)),
+            _ if g_type_is_a(ty, gst_flagset_get_type()) != 0 => Value::FlagSet{
+                type_name: from_c_str!(g_type_name(ty)).to_string(),
+                flags: gst_value_get_flagset_flags(gvalue),
+                mask: gst_value_get_flagset_mask(gvalue),
+            },
+            _ => {
+                let serialized = gst_value_serialize(gvalue);
+                if serialized == ptr::null_mut(){
+                    return None;
+                }
+                let value = from_c_str!(serialized).to_string();
+                g_free(serialized as gpointer);
+                Value::Other{ type_name: from_c_str!(g_type_name(ty)).to_string(), value: value }
+            }
+        };
+        Some(value)
+    }
+
+    /// Converts this Value into a newly initialized GValue that has to be
+    /// released with g_value_unset or transferred to a function that takes
+    /// ownership of it.
+    ///
+    /// Returns None if the value is of type Other and its type is not
+    /// registered or the string can't be deserialized into it, or if any
+    /// value of a List or Array can't be converted.
+    pub unsafe fn to_gvalue(&self) -> Option<GValue>{
+        let mut gvalue: GValue = mem::zeroed();
+        match *self{
+            Value::Bool(v) => {
+                g_value_init(&mut gvalue, G_TYPE_BOOLEAN);
+                g_value_set_boolean(&mut gvalue, v as gboolean);
+            }
+            Value::Int(v) => {
+                g_value_init(&mut gvalue, G_TYPE_INT);
+                g_value_set_int(&mut gvalue, v);
+            }
+            Value::UInt(v) => {
+                g_value_init(&mut gvalue, G_TYPE_UINT);
+                g_value_set_uint(&mut gvalue, v);
+            }
+            Value::Int64(v) => {
+                g_value_init(&mut gvalue, G_TYPE_INT64);
+                g_value_set_int64(&mut gvalue, v);
+            }
+            Value::UInt64(v) => {
+                g_value_init(&mut gvalue, G_TYPE_UINT64);
+                g_value_set_uint64(&mut gvalue, v);
+            }
+            Value::Float(v) => {
+                g_value_init(&mut gvalue, G_TYPE_FLOAT);
+                g_value_set_float(&mut gvalue, v);
+            }
+            Value::Double(v) => {
+                g_value_init(&mut gvalue, G_TYPE_DOUBLE);
+                g_value_set_double(&mut gvalue, v);
+            }
+            Value::String(ref v) => {
+                g_value_init(&mut gvalue, G_TYPE_STRING);
+                g_value_set_string(&mut gvalue, to_c_str!(v.as_str()));
+            }
+            Value::Fraction(n, d) => {
+                g_value_init(&mut gvalue, gst_fraction_get_type());
+                gst_value_set_fraction(&mut gvalue, n, d);
+            }
+            Value::IntRange(min, max) => {
+                g_value_init(&mut gvalue, gst_int_range_get_type());
+                gst_value_set_int_range(&mut gvalue, min, max);
+            }
+            Value::Int64Range(min, max) => {
+                g_value_init(&mut gvalue, gst_int64_range_get_type());
+                gst_value_set_int64_range(&mut gvalue, min, max);
+            }
+            Value::DoubleRange(min, max) => {
+                g_value_init(&mut gvalue, gst_double_range_get_type());
+                gst_value_set_double_range(&mut gvalue, min, max);
+            }
+            Value::FractionRange((min_n, min_d), (max_n, max_d)) => {
+                g_value_init(&mut gvalue, gst_fraction_range_get_type());
+                gst_value_set_fraction_range_full(&mut gvalue, min_n, min_d, max_n, max_d);
+            }
+            Value::List(ref values) => {
+                g_value_init(&mut gvalue, gst_value_list_get_type());
+                for value in values{
+                    match value.to_gvalue(){
+                        Some(mut v) => gst_value_list_append_and_take_value(&mut gvalue, &mut v),
+                        None => {
+                            g_value_unset(&mut gvalue);
+                            return None;
+                        }
+                    }
+                }
+            }
+            Value::Array(ref values) => {
+                g_value_init(&mut gvalue, gst_value_array_get_type());
+                for value in values{
+                    match value.to_gvalue(){
+                        Some(mut v) => gst_value_array_append_and_take_value(&mut gvalue, &mut v),
+                        None => {
+                            g_value_unset(&mut gvalue);
+                            return None;
+                        }
+                    }
+                }
+            }
+            Value::Bitmask(v) => {
+                g_value_init(&mut gvalue, gst_bitmask_get_type());
+                gst_value_set_bitmask(&mut gvalue, v);
+            }
+            Value::FlagSet{ref type_name, flags, mask} => {
+                let ty = g_type_from_name(to_c_str!(type_name.as_str()));
+                if ty == 0 || g_type_is_a(ty, gst_flagset_get_type()) == 0{
+                    return None;
+                }
+                g_value_init(&mut gvalue, ty);
+                gst_value_set_flagset(&mut gvalue, flags, mask);
+            }
+            Value::Other{ref type_name, ref value} => {
+                let ty = g_type_from_name(to_c_str!(type_name.as_str()));
+                if ty == 0{
+                    return None;
+                }
+                g_value_init(&mut gvalue, ty);
+                if gst_value_deserialize(&mut gvalue, to_c_str!(value.as_str())) == 0{
+                    g_value_unset(&mut gvalue);
+                    return None;
+                }
+            }
+        }
+        Some(gvalue)
+    }
+}
+
+impl Value{
+    /// Parses a GstFlagSet from the "flags/mask" syntax, or "flags:mask"
+    /// as GStreamer serializes them, with both numbers in hexadecimal and
+    /// an optional 0x prefix. The nicks GStreamer appends after a second
+    /// colon, as in "00000001:00000003:+mixed-mono", are ignored since the
+    /// numbers already hold the value. If the mask is omitted all the
+    /// flags are considered set or unset. Numbers that don't fit in 32
+    /// bits are rejected
+    pub fn flagset_from_str(flagset: &str) -> Option<Value>{
+        let flagset = flagset.trim();
+        let (flags, mask) = if flagset.contains(':'){
+            let mut parts = flagset.splitn(3, ':');
+            (parts.next()?, parts.next())
+        }else{
+            let mut parts = flagset.splitn(2, '/');
+            (parts.next()?, parts.next())
+        };
+        let flags = parse_hex_u32(flags)?;
+        let mask = match mask{
+            Some(mask) => parse_hex_u32(mask)?,
+            None => 0xffffffff
+        };
+        unsafe{
+            Some(Value::FlagSet{
+                type_name: from_c_str!(g_type_name(gst_flagset_get_type())).to_string(),
+                flags: flags,
+                mask: mask,
+            })
+        }
+    }
+
+    /// Parses a bitmask in hexadecimal with an optional 0x prefix
+    pub fn bitmask_from_str(bitmask: &str) -> Option<Value>{
+        parse_hex(bitmask).map(Value::Bitmask)
+    }
+}
+
+fn parse_hex(value: &str) -> Option<u64>{
+    let value = value.trim();
+    let value = value.trim_start_matches("0x").trim_start_matches("0X");
+    u64::from_str_radix(value, 16).ok()
+}
+
+fn parse_hex_u32(value: &str) -> Option<u32>{
+    parse_hex(value).and_then(|value| if value <= u32::max_value() as u64{ Some(value as u32) }else{ None })
+}
+
+impl From<bool> for Value{
+    fn from(v: bool) -> Value{
+        Value::Bool(v)
+    }
+}
+
+impl From<i32> for Value{
+    fn from(v: i32) -> Value{
+        Value::Int(v)
+    }
+}
+
+impl From<u32> for Value{
+    fn from(v: u32) -> Value{
+        Value::UInt(v)
+    }
+}
+
+impl From<i64> for Value{
+    fn from(v: i64) -> Value{
+        Value::Int64(v)
+    }
+}
+
+impl From<u64> for Value{
+    fn from(v: u64) -> Value{
+        Value::UInt64(v)
+    }
+}
+
+impl From<f32> for Value{
+    fn from(v: f32) -> Value{
+        Value::Float(v)
+    }
+}
+
+impl From<f64> for Value{
+    fn from(v: f64) -> Value{
+        Value::Double(v)
+    }
+}
+
+impl<'a> From<&'a str> for Value{
+    fn from(v: &'a str) -> Value{
+        Value::String(v.to_string())
+    }
+}
+
+impl From<String> for Value{
+    fn from(v: String) -> Value{
+        Value::String(v)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn flagset(value: &str) -> Option<(u32, u32)>{
+        match Value::flagset_from_str(value)?{
+            Value::FlagSet{flags, mask, ..} => Some((flags, mask)),
+            _ => None
+        }
+    }
+
+    #[test]
+    fn flagset_syntaxes(){
+        ::init();
+        assert_eq!(flagset("1/3"), Some((1, 3)));
+        assert_eq!(flagset("0x10/0xff"), Some((0x10, 0xff)));
+        assert_eq!(flagset("00000001:00000003"), Some((1, 3)));
+        assert_eq!(flagset(" 00000001:00000003:+mixed-mono "), Some((1, 3)));
+        assert_eq!(flagset("4"), Some((4, 0xffffffff)));
+    }
+
+    #[test]
+    fn invalid_flagsets(){
+        ::init();
+        assert_eq!(flagset(""), None);
+        assert_eq!(flagset("1/"), None);
+        assert_eq!(flagset("x/3"), None);
+        assert_eq!(flagset("100000000/1"), None);
+        assert_eq!(flagset("1:100000000"), None);
+    }
+
+    #[test]
+    fn flagset_type(){
+        ::init();
+        match Value::flagset_from_str("1/3"){
+            Some(Value::FlagSet{type_name, ..}) => assert_eq!(type_name, "GstFlagSet"),
+            other => panic!("expected a flagset, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn bitmask(){
+        assert_eq!(Value::bitmask_from_str("0x3f"), Some(Value::Bitmask(0x3f)));
+        assert_eq!(Value::bitmask_from_str("ffffffffffffffff"), Some(Value::Bitmask(u64::max_value())));
+        assert_eq!(Value::bitmask_from_str("10000000000000000"), None);
+    }
+}
@@ -0,0 +1,634 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use caps::Caps;
+use element::{Element, ElementT};
+use subclass::*;
+use videoinfo::VideoInfo;
+
+/// The format of the input or output of a video codec element, with the
+/// caps and the VideoInfo parsed from them
+pub struct VideoCodecState{
+    state: *mut GstVideoCodecState
+}
+
+unsafe impl Sync for VideoCodecState {}
+unsafe impl Send for VideoCodecState {}
+
+impl Drop for VideoCodecState{
+    fn drop(&mut self){
+        unsafe{
+            gst_video_codec_state_unref(self.state);
+        }
+    }
+}
+
+impl Clone for VideoCodecState{
+    fn clone(&self) -> VideoCodecState{
+        unsafe{
+            VideoCodecState{ state: gst_video_codec_state_ref(self.state) }
+        }
+    }
+}
+
+impl VideoCodecState{
+    pub unsafe fn new(state: *mut GstVideoCodecState, owned: bool) -> Option<VideoCodecState>{
+        if state != ptr::null_mut(){
+            if !owned{
+                gst_video_codec_state_ref(state);
+            }
+            Some(VideoCodecState{ state: state })
+        }else{
+            None
+        }
+    }
+
+    pub fn info(&self) -> &VideoInfo{
+        unsafe{
+            &(*self.state).info
+        }
+    }
+
+    pub fn caps(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new((*self.state).caps, false)
+        }
+    }
+
+    /// Codec specific data from the caps, as the avcC of h264 in mp4
+    pub fn codec_data(&self) -> Option<Buffer>{
+        unsafe{
+            Buffer::new((*self.state).codec_data, false)
+        }
+    }
+
+    pub unsafe fn gst_video_codec_state(&self) -> *mut GstVideoCodecState{
+        self.state
+    }
+}
+
+/// A frame going through a video decoder or encoder, holding the input
+/// buffer with its timestamps and, once processed, the output buffer.
+/// Dropping it without passing it to finish_frame or drop_frame keeps it
+/// pending in the element
+pub struct VideoCodecFrame{
+    frame: *mut GstVideoCodecFrame
+}
+
+unsafe impl Sync for VideoCodecFrame {}
+unsafe impl Send for VideoCodecFrame {}
+
+impl Drop for VideoCodecFrame{
+    fn drop(&mut self){
+        unsafe{
+            gst_video_codec_frame_unref(self.frame);
+        }
+    }
+}
+
+impl VideoCodecFrame{
+    pub unsafe fn new(frame: *mut GstVideoCodecFrame, owned: bool) -> Option<VideoCodecFrame>{
+        if frame != ptr::null_mut(){
+            if !owned{
+                gst_video_codec_frame_ref(frame);
+            }
+            Some(VideoCodecFrame{ frame: frame })
+        }else{
+            None
+        }
+    }
+
+    /// Number identifying the frame, as passed to the element's frame()
+    pub fn system_frame_number(&self) -> u32{
+        unsafe{ (*self.frame).system_frame_number }
+    }
+
+    pub fn pts(&self) -> u64{
+        unsafe{ (*self.frame).pts }
+    }
+
+    pub fn set_pts(&mut self, pts: u64){
+        unsafe{ (*self.frame).pts = pts }
+    }
+
+    pub fn dts(&self) -> u64{
+        unsafe{ (*self.frame).dts }
+    }
+
+    pub fn set_dts(&mut self, dts: u64){
+        unsafe{ (*self.frame).dts = dts }
+    }
+
+    pub fn duration(&self) -> u64{
+        unsafe{ (*self.frame).duration }
+    }
+
+    pub fn set_duration(&mut self, duration: u64){
+        unsafe{ (*self.frame).duration = duration }
+    }
+
+    /// Whether the frame is a keyframe, set by the encoder on its output
+    pub fn is_sync_point(&self) -> bool{
+        unsafe{ (*self.frame).flags & GST_VIDEO_CODEC_FRAME_FLAG_SYNC_POINT != 0 }
+    }
+
+    pub fn set_sync_point(&mut self, sync_point: bool){
+        unsafe{
+            if sync_point{
+                (*self.frame).flags |= GST_VIDEO_CODEC_FRAME_FLAG_SYNC_POINT;
+            }else{
+                (*self.frame).flags &= !GST_VIDEO_CODEC_FRAME_FLAG_SYNC_POINT;
+            }
+        }
+    }
+
+    /// Whether the encoder was asked to encode this frame as a keyframe
+    pub fn is_force_keyframe(&self) -> bool{
+        unsafe{ (*self.frame).flags & GST_VIDEO_CODEC_FRAME_FLAG_FORCE_KEYFRAME != 0 }
+    }
+
+    /// Whether the frame is only decoded as a reference for the next ones
+    /// and shouldn't be output
+    pub fn is_decode_only(&self) -> bool{
+        unsafe{ (*self.frame).flags & GST_VIDEO_CODEC_FRAME_FLAG_DECODE_ONLY != 0 }
+    }
+
+    /// Time in running time by which the frame has to be output to be on
+    /// time, to skip work when late
+    pub fn deadline(&self) -> u64{
+        unsafe{ (*self.frame).deadline }
+    }
+
+    pub fn input_buffer(&self) -> Option<Buffer>{
+        unsafe{ Buffer::new((*self.frame).input_buffer, false) }
+    }
+
+    pub fn output_buffer(&self) -> Option<Buffer>{
+        unsafe{ Buffer::new((*self.frame).output_buffer, false) }
+    }
+
+    pub fn set_output_buffer(&mut self, buffer: Buffer){
+        unsafe{
+            if (*self.frame).output_buffer != ptr::null_mut(){
+                gst_mini_object_unref((*self.frame).output_buffer as *mut GstMiniObject);
+            }
+            (*self.frame).output_buffer = ::Transfer::transfer(buffer);
+        }
+    }
+
+    pub unsafe fn gst_video_codec_frame(&self) -> *mut GstVideoCodecFrame{
+        self.frame
+    }
+
+    unsafe fn transfer(self) -> *mut GstVideoCodecFrame{
+        let frame = self.frame;
+        mem::forget(self);
+        frame
+    }
+}
+
+unsafe fn frames_list(list: *mut GList) -> Vec<VideoCodecFrame>{
+    let mut frames = Vec::new();
+    let mut node = list;
+    while node != ptr::null_mut(){
+        if let Some(frame) = VideoCodecFrame::new((*node).data as *mut GstVideoCodecFrame, true){
+            frames.push(frame);
+        }
+        node = (*node).next;
+    }
+    g_list_free(list);
+    frames
+}
+
+/// A video decoder implemented in Rust, on top of GstVideoDecoder which
+/// handles the timestamps, QoS and negotiation, as a wrapper for a Rust
+/// decoder. Register it with register_video_decoder.
+///
+/// The pad templates have to be named "sink" and "src"
+pub trait VideoDecoderImpl: ElementImpl{
+    /// Called when the element starts, to create the decoder
+    fn start(&self, _decoder: &VideoDecoder) -> bool{
+        true
+    }
+
+    fn stop(&self, _decoder: &VideoDecoder) -> bool{
+        true
+    }
+
+    /// Called with the format of the input when it's known or changes,
+    /// the output format has to be set with set_output_state here or once
+    /// known from the first frames
+    fn set_format(&self, _decoder: &VideoDecoder, _state: &VideoCodecState) -> bool{
+        true
+    }
+
+    /// Decodes an input frame. Once decoded, usually in decode order for
+    /// codecs with reordering, each frame is output with finish_frame
+    /// after allocate_output_frame and filling its output buffer, or
+    /// dropped with drop_frame
+    fn handle_frame(&self, decoder: &VideoDecoder, frame: VideoCodecFrame) -> GstFlowReturn;
+
+    /// Called at EOS to output the frames still in the decoder
+    fn finish(&self, _decoder: &VideoDecoder) -> GstFlowReturn{
+        GST_FLOW_OK
+    }
+
+    /// Called on seeks to discard the frames in the decoder
+    fn flush(&self, _decoder: &VideoDecoder) -> bool{
+        true
+    }
+}
+
+/// A video decoder as passed to the methods of a VideoDecoderImpl
+pub struct VideoDecoder{
+    decoder: Element
+}
+
+unsafe impl Sync for VideoDecoder {}
+unsafe impl Send for VideoDecoder {}
+
+impl VideoDecoder{
+    unsafe fn new(decoder: *mut GstVideoDecoder) -> VideoDecoder{
+        gst_object_ref(decoder as gpointer);
+        VideoDecoder{ decoder: Element::new_from_gst_element(decoder as *mut GstElement).unwrap() }
+    }
+
+    /// Sets the format of the output, with the framerate and aspect
+    /// ratio of reference, usually the input state
+    pub fn set_output_state(&self, format: GstVideoFormat, width: u32, height: u32, reference: Option<&VideoCodecState>) -> Option<VideoCodecState>{
+        unsafe{
+            let reference = reference.map(|state| state.gst_video_codec_state()).unwrap_or(ptr::null_mut());
+            VideoCodecState::new(gst_video_decoder_set_output_state(self.gst_video_decoder(), format, width, height, reference), true)
+        }
+    }
+
+    pub fn output_state(&self) -> Option<VideoCodecState>{
+        unsafe{
+            VideoCodecState::new(gst_video_decoder_get_output_state(self.gst_video_decoder()), true)
+        }
+    }
+
+    /// Negotiates the output state with downstream, finish_frame does it
+    /// if needed
+    pub fn negotiate(&self) -> bool{
+        unsafe{
+            gst_video_decoder_negotiate(self.gst_video_decoder()) == 1
+        }
+    }
+
+    /// Allocates the output buffer of frame with the size of the output
+    /// state, from the negotiated buffer pool
+    pub fn allocate_output_frame(&self, frame: &mut VideoCodecFrame) -> GstFlowReturn{
+        unsafe{
+            gst_video_decoder_allocate_output_frame(self.gst_video_decoder(), frame.gst_video_codec_frame())
+        }
+    }
+
+    /// Outputs the decoded frame
+    pub fn finish_frame(&self, frame: VideoCodecFrame) -> GstFlowReturn{
+        unsafe{
+            gst_video_decoder_finish_frame(self.gst_video_decoder(), frame.transfer())
+        }
+    }
+
+    /// Discards a frame that won't be output, posting a QoS message
+    pub fn drop_frame(&self, frame: VideoCodecFrame) -> GstFlowReturn{
+        unsafe{
+            gst_video_decoder_drop_frame(self.gst_video_decoder(), frame.transfer())
+        }
+    }
+
+    /// The frame with the passed system_frame_number if still pending
+    pub fn frame(&self, system_frame_number: u32) -> Option<VideoCodecFrame>{
+        unsafe{
+            VideoCodecFrame::new(gst_video_decoder_get_frame(self.gst_video_decoder(), system_frame_number as i32), true)
+        }
+    }
+
+    pub fn oldest_frame(&self) -> Option<VideoCodecFrame>{
+        unsafe{
+            VideoCodecFrame::new(gst_video_decoder_get_oldest_frame(self.gst_video_decoder()), true)
+        }
+    }
+
+    /// The frames pending to be output
+    pub fn frames(&self) -> Vec<VideoCodecFrame>{
+        unsafe{
+            frames_list(gst_video_decoder_get_frames(self.gst_video_decoder()))
+        }
+    }
+
+    /// Whether each input buffer is a whole frame, true by default for
+    /// decoders implemented in Rust since they have no parse
+    pub fn set_packetized(&self, packetized: bool){
+        unsafe{
+            gst_video_decoder_set_packetized(self.gst_video_decoder(), packetized as gboolean);
+        }
+    }
+
+    /// Latency in nanoseconds added by the frames the decoder keeps
+    pub fn set_latency(&self, min: u64, max: Option<u64>){
+        unsafe{
+            gst_video_decoder_set_latency(self.gst_video_decoder(), min, max.unwrap_or(GST_CLOCK_TIME_NONE));
+        }
+    }
+
+    /// Number of consecutive decoding errors tolerated before failing,
+    /// -1 for no limit
+    pub fn set_max_errors(&self, max_errors: i32){
+        unsafe{
+            gst_video_decoder_set_max_errors(self.gst_video_decoder(), max_errors);
+        }
+    }
+
+    pub unsafe fn gst_video_decoder(&self) -> *mut GstVideoDecoder{
+        self.decoder.gst_element() as *mut GstVideoDecoder
+    }
+}
+
+impl ElementT for VideoDecoder{
+    fn as_element(&self) -> &Element{
+        &self.decoder
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.decoder
+    }
+}
+
+impl ::Transfer for VideoDecoder{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.decoder.transfer()
+    }
+}
+
+extern "C" fn decoder_class_init<T: VideoDecoderImpl>(class: gpointer, _data: gpointer){
+    unsafe{
+        element_class_init::<T>(class);
+        let decoder_class = class as *mut GstVideoDecoderClass;
+        (*decoder_class).start = Some(decoder_start::<T>);
+        (*decoder_class).stop = Some(decoder_stop::<T>);
+        (*decoder_class).set_format = Some(decoder_set_format::<T>);
+        (*decoder_class).handle_frame = Some(decoder_handle_frame::<T>);
+        (*decoder_class).finish = Some(decoder_finish::<T>);
+        (*decoder_class).flush = Some(decoder_flush::<T>);
+    }
+}
+
+extern "C" fn decoder_instance_init<T: VideoDecoderImpl>(instance: *mut GTypeInstance, class: gpointer){
+    instance_init::<T>(instance, class);
+    unsafe{
+        gst_video_decoder_set_packetized(instance as *mut GstVideoDecoder, 1);
+    }
+}
+
+extern "C" fn decoder_start<T: VideoDecoderImpl>(decoder: *mut GstVideoDecoder) -> gboolean{
+    unsafe{
+        imp::<T>(decoder as gpointer).start(&VideoDecoder::new(decoder)) as gboolean
+    }
+}
+
+extern "C" fn decoder_stop<T: VideoDecoderImpl>(decoder: *mut GstVideoDecoder) -> gboolean{
+    unsafe{
+        imp::<T>(decoder as gpointer).stop(&VideoDecoder::new(decoder)) as gboolean
+    }
+}
+
+extern "C" fn decoder_set_format<T: VideoDecoderImpl>(decoder: *mut GstVideoDecoder, state: *mut GstVideoCodecState) -> gboolean{
+    unsafe{
+        match VideoCodecState::new(state, false){
+            Some(state) => imp::<T>(decoder as gpointer).set_format(&VideoDecoder::new(decoder), &state) as gboolean,
+            None => 0
+        }
+    }
+}
+
+extern "C" fn decoder_handle_frame<T: VideoDecoderImpl>(decoder: *mut GstVideoDecoder, frame: *mut GstVideoCodecFrame) -> GstFlowReturn{
+    unsafe{
+        match VideoCodecFrame::new(frame, true){
+            Some(frame) => imp::<T>(decoder as gpointer).handle_frame(&VideoDecoder::new(decoder), frame),
+            None => GST_FLOW_ERROR
+        }
+    }
+}
+
+extern "C" fn decoder_finish<T: VideoDecoderImpl>(decoder: *mut GstVideoDecoder) -> GstFlowReturn{
+    unsafe{
+        imp::<T>(decoder as gpointer).finish(&VideoDecoder::new(decoder))
+    }
+}
+
+extern "C" fn decoder_flush<T: VideoDecoderImpl>(decoder: *mut GstVideoDecoder) -> gboolean{
+    unsafe{
+        imp::<T>(decoder as gpointer).flush(&VideoDecoder::new(decoder)) as gboolean
+    }
+}
+
+/// Registers a video decoder implemented in Rust so it can be created by
+/// name with Element::new, in launch lines, and picked by decodebin if
+/// the rank is GST_RANK_PRIMARY or higher and the classification
+/// contains "Decoder/Video"
+pub fn register_video_decoder<T: VideoDecoderImpl>(name: &str, rank: u32) -> bool{
+    unsafe{
+        register_element(gst_video_decoder_get_type(), name, rank, decoder_class_init::<T>, decoder_instance_init::<T>)
+    }
+}
+
+/// A video encoder implemented in Rust, on top of GstVideoEncoder which
+/// handles the timestamps, keyframe requests and negotiation. Register it
+/// with register_video_encoder.
+///
+/// The pad templates have to be named "sink" and "src"
+pub trait VideoEncoderImpl: ElementImpl{
+    /// Called when the element starts, to create the encoder
+    fn start(&self, _encoder: &VideoEncoder) -> bool{
+        true
+    }
+
+    fn stop(&self, _encoder: &VideoEncoder) -> bool{
+        true
+    }
+
+    /// Called with the format of the raw input when it's known or
+    /// changes, the output caps have to be set here with set_output_state
+    fn set_format(&self, encoder: &VideoEncoder, state: &VideoCodecState) -> bool;
+
+    /// Encodes an input frame. Once encoded each frame is output with
+    /// finish_frame after setting its output buffer, marking keyframes
+    /// with set_sync_point
+    fn handle_frame(&self, encoder: &VideoEncoder, frame: VideoCodecFrame) -> GstFlowReturn;
+
+    /// Called at EOS to output the frames still in the encoder
+    fn finish(&self, _encoder: &VideoEncoder) -> GstFlowReturn{
+        GST_FLOW_OK
+    }
+
+    /// Called on seeks to discard the frames in the encoder
+    fn flush(&self, _encoder: &VideoEncoder) -> bool{
+        true
+    }
+}
+
+/// A video encoder as passed to the methods of a VideoEncoderImpl
+pub struct VideoEncoder{
+    encoder: Element
+}
+
+unsafe impl Sync for VideoEncoder {}
+unsafe impl Send for VideoEncoder {}
+
+impl VideoEncoder{
+    unsafe fn new(encoder: *mut GstVideoEncoder) -> VideoEncoder{
+        gst_object_ref(encoder as gpointer);
+        VideoEncoder{ encoder: Element::new_from_gst_element(encoder as *mut GstElement).unwrap() }
+    }
+
+    /// Sets the caps of the output, as "video/x-h264,profile=high", the
+    /// framerate and size are taken from reference, usually the input
+    /// state
+    pub fn set_output_state(&self, caps: &Caps, reference: Option<&VideoCodecState>) -> Option<VideoCodecState>{
+        unsafe{
+            let reference = reference.map(|state| state.gst_video_codec_state()).unwrap_or(ptr::null_mut());
+            let caps = gst_mini_object_ref(caps.gst_caps() as *mut GstMiniObject) as *mut GstCaps;
+            VideoCodecState::new(gst_video_encoder_set_output_state(self.gst_video_encoder(), caps, reference), true)
+        }
+    }
+
+    pub fn output_state(&self) -> Option<VideoCodecState>{
+        unsafe{
+            VideoCodecState::new(gst_video_encoder_get_output_state(self.gst_video_encoder()), true)
+        }
+    }
+
+    pub fn negotiate(&self) -> bool{
+        unsafe{
+            gst_video_encoder_negotiate(self.gst_video_encoder()) == 1
+        }
+    }
+
+    /// Allocates an output buffer of size bytes for frame
+    pub fn allocate_output_frame(&self, frame: &mut VideoCodecFrame, size: usize) -> GstFlowReturn{
+        unsafe{
+            gst_video_encoder_allocate_output_frame(self.gst_video_encoder(), frame.gst_video_codec_frame(), size as gsize)
+        }
+    }
+
+    /// Outputs the encoded frame, a frame without output buffer is
+    /// dropped
+    pub fn finish_frame(&self, frame: VideoCodecFrame) -> GstFlowReturn{
+        unsafe{
+            gst_video_encoder_finish_frame(self.gst_video_encoder(), frame.transfer())
+        }
+    }
+
+    pub fn frame(&self, system_frame_number: u32) -> Option<VideoCodecFrame>{
+        unsafe{
+            VideoCodecFrame::new(gst_video_encoder_get_frame(self.gst_video_encoder(), system_frame_number as i32), true)
+        }
+    }
+
+    pub fn oldest_frame(&self) -> Option<VideoCodecFrame>{
+        unsafe{
+            VideoCodecFrame::new(gst_video_encoder_get_oldest_frame(self.gst_video_encoder()), true)
+        }
+    }
+
+    pub fn frames(&self) -> Vec<VideoCodecFrame>{
+        unsafe{
+            frames_list(gst_video_encoder_get_frames(self.gst_video_encoder()))
+        }
+    }
+
+    /// Latency in nanoseconds added by the frames the encoder keeps, as
+    /// for lookahead
+    pub fn set_latency(&self, min: u64, max: Option<u64>){
+        unsafe{
+            gst_video_encoder_set_latency(self.gst_video_encoder(), min, max.unwrap_or(GST_CLOCK_TIME_NONE));
+        }
+    }
+
+    pub unsafe fn gst_video_encoder(&self) -> *mut GstVideoEncoder{
+        self.encoder.gst_element() as *mut GstVideoEncoder
+    }
+}
+
+impl ElementT for VideoEncoder{
+    fn as_element(&self) -> &Element{
+        &self.encoder
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.encoder
+    }
+}
+
+impl ::Transfer for VideoEncoder{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.encoder.transfer()
+    }
+}
+
+extern "C" fn encoder_class_init<T: VideoEncoderImpl>(class: gpointer, _data: gpointer){
+    unsafe{
+        element_class_init::<T>(class);
+        let encoder_class = class as *mut GstVideoEncoderClass;
+        (*encoder_class).start = Some(encoder_start::<T>);
+        (*encoder_class).stop = Some(encoder_stop::<T>);
+        (*encoder_class).set_format = Some(encoder_set_format::<T>);
+        (*encoder_class).handle_frame = Some(encoder_handle_frame::<T>);
+        (*encoder_class).finish = Some(encoder_finish::<T>);
+        (*encoder_class).flush = Some(encoder_flush::<T>);
+    }
+}
+
+extern "C" fn encoder_start<T: VideoEncoderImpl>(encoder: *mut GstVideoEncoder) -> gboolean{
+    unsafe{
+        imp::<T>(encoder as gpointer).start(&VideoEncoder::new(encoder)) as gboolean
+    }
+}
+
+extern "C" fn encoder_stop<T: VideoEncoderImpl>(encoder: *mut GstVideoEncoder) -> gboolean{
+    unsafe{
+        imp::<T>(encoder as gpointer).stop(&VideoEncoder::new(encoder)) as gboolean
+    }
+}
+
+extern "C" fn encoder_set_format<T: VideoEncoderImpl>(encoder: *mut GstVideoEncoder, state: *mut GstVideoCodecState) -> gboolean{
+    unsafe{
+        match VideoCodecState::new(state, false){
+            Some(state) => imp::<T>(encoder as gpointer).set_format(&VideoEncoder::new(encoder), &state) as gboolean,
+            None => 0
+        }
+    }
+}
+
+extern "C" fn encoder_handle_frame<T: VideoEncoderImpl>(encoder: *mut GstVideoEncoder, frame: *mut GstVideoCodecFrame) -> GstFlowReturn{
+    unsafe{
+        match VideoCodecFrame::new(frame, true){
+            Some(frame) => imp::<T>(encoder as gpointer).handle_frame(&VideoEncoder::new(encoder), frame),
+            None => GST_FLOW_ERROR
+        }
+    }
+}
+
+extern "C" fn encoder_finish<T: VideoEncoderImpl>(encoder: *mut GstVideoEncoder) -> GstFlowReturn{
+    unsafe{
+        imp::<T>(encoder as gpointer).finish(&VideoEncoder::new(encoder))
+    }
+}
+
+extern "C" fn encoder_flush<T: VideoEncoderImpl>(encoder: *mut GstVideoEncoder) -> gboolean{
+    unsafe{
+        imp::<T>(encoder as gpointer).flush(&VideoEncoder::new(encoder)) as gboolean
+    }
+}
+
+/// Registers a video encoder implemented in Rust so it can be created by
+/// name with Element::new, in launch lines, and picked by encodebin if
+/// the rank is GST_RANK_PRIMARY or higher and the classification
+/// contains "Encoder/Video"
+pub fn register_video_encoder<T: VideoEncoderImpl>(name: &str, rank: u32) -> bool{
+    unsafe{
+        register_element(gst_video_encoder_get_type(), name, rank, encoder_class_init::<T>, instance_init::<T>)
+    }
+}
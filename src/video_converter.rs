@@ -0,0 +1,222 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use videoframe::VideoFrame;
+use videoinfo::VideoInfo;
+use value::{G_TYPE_BOOLEAN, G_TYPE_INT, G_TYPE_UINT};
+
+pub enum GstVideoConverter{}
+
+extern "C"{
+    // added in GStreamer 1.6, after ffi.rs was generated
+    fn gst_video_converter_new(in_info: *mut GstVideoInfo, out_info: *mut GstVideoInfo,
+        config: *mut GstStructure) -> *mut GstVideoConverter;
+    fn gst_video_converter_free(convert: *mut GstVideoConverter);
+    fn gst_video_converter_frame(convert: *mut GstVideoConverter, src: *const GstVideoFrame, dest: *mut GstVideoFrame);
+    fn gst_video_converter_set_config(convert: *mut GstVideoConverter, config: *mut GstStructure) -> gboolean;
+    fn gst_video_resampler_method_get_type() -> GType;
+    fn gst_video_dither_method_get_type() -> GType;
+}
+
+/// Filter used to scale the frames, from the fastest to the best quality
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum VideoResamplerMethod{
+    Nearest,
+    Linear,
+    /// The default
+    Cubic,
+    Sinc,
+    Lanczos,
+}
+
+/// How to hide banding when reducing the bits per component
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum VideoDitherMethod{
+    None,
+    Verbatim,
+    FloydSteinberg,
+    SierraLite,
+    /// The default
+    Bayer,
+}
+
+/// Options of a VideoConverter, the defaults of GStreamer are used for
+/// those not set
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct VideoConverterConfig{
+    resampler_method: Option<VideoResamplerMethod>,
+    resampler_taps: Option<u32>,
+    dither_method: Option<VideoDitherMethod>,
+    threads: Option<u32>,
+    add_borders: Option<bool>,
+    border_argb: Option<u32>,
+    dest_rect: Option<(i32, i32, i32, i32)>,
+}
+
+impl VideoConverterConfig{
+    pub fn new() -> VideoConverterConfig{
+        VideoConverterConfig::default()
+    }
+
+    pub fn resampler_method(mut self, method: VideoResamplerMethod) -> VideoConverterConfig{
+        self.resampler_method = Some(method);
+        self
+    }
+
+    /// Taps of the scaling filter, more are slower and sharper, 0 picks
+    /// them from the method
+    pub fn resampler_taps(mut self, taps: u32) -> VideoConverterConfig{
+        self.resampler_taps = Some(taps);
+        self
+    }
+
+    pub fn dither_method(mut self, method: VideoDitherMethod) -> VideoConverterConfig{
+        self.dither_method = Some(method);
+        self
+    }
+
+    /// Threads used to convert each frame, 0 for one per CPU
+    pub fn threads(mut self, threads: u32) -> VideoConverterConfig{
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Whether to keep the aspect ratio adding borders of color argb, as
+    /// 0xff000000 for black
+    pub fn add_borders(mut self, add_borders: bool, argb: u32) -> VideoConverterConfig{
+        self.add_borders = Some(add_borders);
+        self.border_argb = Some(argb);
+        self
+    }
+
+    /// Converts into a rectangle of the output frame instead of the
+    /// whole frame
+    pub fn dest_rect(mut self, x: i32, y: i32, width: i32, height: i32) -> VideoConverterConfig{
+        self.dest_rect = Some((x, y, width, height));
+        self
+    }
+
+    unsafe fn to_gst_structure(&self) -> *mut GstStructure{
+        let config = gst_structure_new_empty(to_c_str!("GstVideoConverter"));
+        if let Some(method) = self.resampler_method{
+            gst_structure_set(config, to_c_str!("GstVideoConverter.resampler-method"), gst_video_resampler_method_get_type(),
+                method as gint, ptr::null::<gchar>());
+        }
+        if let Some(taps) = self.resampler_taps{
+            gst_structure_set(config, to_c_str!("GstVideoConverter.resampler-taps"), G_TYPE_UINT, taps, ptr::null::<gchar>());
+        }
+        if let Some(method) = self.dither_method{
+            gst_structure_set(config, to_c_str!("GstVideoConverter.dither-method"), gst_video_dither_method_get_type(),
+                method as gint, ptr::null::<gchar>());
+        }
+        if let Some(threads) = self.threads{
+            gst_structure_set(config, to_c_str!("GstVideoConverter.threads"), G_TYPE_UINT, threads, ptr::null::<gchar>());
+        }
+        if let Some(add_borders) = self.add_borders{
+            gst_structure_set(config, to_c_str!("GstVideoConverter.add-borders"), G_TYPE_BOOLEAN,
+                add_borders as gboolean, ptr::null::<gchar>());
+        }
+        if let Some(argb) = self.border_argb{
+            gst_structure_set(config, to_c_str!("GstVideoConverter.border-argb"), G_TYPE_UINT, argb, ptr::null::<gchar>());
+        }
+        if let Some((x, y, width, height)) = self.dest_rect{
+            gst_structure_set(config,
+                to_c_str!("GstVideoConverter.dest-x"), G_TYPE_INT, x,
+                to_c_str!("GstVideoConverter.dest-y"), G_TYPE_INT, y,
+                to_c_str!("GstVideoConverter.dest-width"), G_TYPE_INT, width,
+                to_c_str!("GstVideoConverter.dest-height"), G_TYPE_INT, height,
+                ptr::null::<gchar>());
+        }
+        config
+    }
+}
+
+/// Converts raw video frames between formats, colorspaces and sizes
+/// without a pipeline, as videoconvert ! videoscale do, to prepare
+/// frames for an appsrc or process those pulled from an appsink
+pub struct VideoConverter{
+    converter: *mut GstVideoConverter,
+    in_info: VideoInfo,
+    out_info: VideoInfo,
+}
+
+unsafe impl Send for VideoConverter {}
+
+impl Drop for VideoConverter{
+    fn drop(&mut self){
+        unsafe{
+            gst_video_converter_free(self.converter);
+        }
+    }
+}
+
+impl VideoConverter{
+    /// A converter from frames of in_info to frames of out_info, None if
+    /// the conversion isn't supported
+    pub fn new(in_info: &VideoInfo, out_info: &VideoInfo) -> Option<VideoConverter>{
+        VideoConverter::with_config(in_info, out_info, &VideoConverterConfig::default())
+    }
+
+    pub fn with_config(in_info: &VideoInfo, out_info: &VideoInfo, config: &VideoConverterConfig) -> Option<VideoConverter>{
+        let mut in_info = *in_info;
+        let mut out_info = *out_info;
+        unsafe{
+            let converter = gst_video_converter_new(&mut in_info, &mut out_info, config.to_gst_structure());
+            if converter == ptr::null_mut(){
+                return None;
+            }
+            Some(VideoConverter{ converter: converter, in_info: in_info, out_info: out_info })
+        }
+    }
+
+    /// Changes the options, the ones not set in config are kept
+    pub fn set_config(&mut self, config: &VideoConverterConfig) -> bool{
+        unsafe{
+            gst_video_converter_set_config(self.converter, config.to_gst_structure()) != 0
+        }
+    }
+
+    pub fn in_info(&self) -> &VideoInfo{
+        &self.in_info
+    }
+
+    pub fn out_info(&self) -> &VideoInfo{
+        &self.out_info
+    }
+
+    /// Converts a buffer with a frame of the input info into a new
+    /// buffer with the same timestamps
+    pub fn convert(&mut self, buffer: &Buffer) -> Option<Buffer>{
+        unsafe{
+            let mut in_frame: GstVideoFrame = mem::zeroed();
+            if gst_video_frame_map(&mut in_frame, &mut self.in_info, buffer.gst_buffer() as *mut GstBuffer, GST_MAP_READ) == 0{
+                return None;
+            }
+            let out_buffer = gst_buffer_new_allocate(ptr::null_mut(), self.out_info.size, ptr::null_mut());
+            let mut out_frame: GstVideoFrame = mem::zeroed();
+            if out_buffer == ptr::null_mut() ||
+                gst_video_frame_map(&mut out_frame, &mut self.out_info, out_buffer, GST_MAP_WRITE) == 0{
+                gst_video_frame_unmap(&mut in_frame);
+                if out_buffer != ptr::null_mut(){
+                    gst_mini_object_unref(out_buffer as *mut GstMiniObject);
+                }
+                return None;
+            }
+            gst_video_converter_frame(self.converter, &in_frame, &mut out_frame);
+            gst_video_frame_unmap(&mut out_frame);
+            gst_video_frame_unmap(&mut in_frame);
+            gst_buffer_copy_into(out_buffer, buffer.gst_buffer() as *mut GstBuffer,
+                GST_BUFFER_COPY_FLAGS | GST_BUFFER_COPY_TIMESTAMPS, 0, !0);
+            Buffer::new(out_buffer, true)
+        }
+    }
+
+    /// Converts a mapped frame, as the one returned by Sample::video_frame
+    pub fn convert_frame(&mut self, frame: &VideoFrame) -> Option<Buffer>{
+        self.convert(frame.buffer())
+    }
+
+    pub unsafe fn gst_video_converter(&self) -> *mut GstVideoConverter{
+        self.converter
+    }
+}
@@ -0,0 +1,113 @@
+use ffi::*;
+use element::Element;
+use element::ElementT;
+
+#[cfg(feature="raw-window-handle")]
+use raw_window_handle::RawWindowHandle;
+
+/// Wraps a video sink implementing the GstVideoOverlay interface so the
+/// video can be rendered into a window created by the application.
+///
+/// The window handle has to be set before the sink creates its own window,
+/// usually right after creating the sink or before setting the pipeline
+/// to PAUSED.
+pub struct VideoOverlay{
+    overlay: Element
+}
+
+unsafe impl Sync for VideoOverlay {}
+unsafe impl Send for VideoOverlay {}
+
+impl VideoOverlay{
+    /// Returns a VideoOverlay if the element implements the GstVideoOverlay
+    /// interface or None otherwise.
+    pub fn new_from_element(element: Element) -> Option<VideoOverlay>{
+        unsafe{
+            if g_type_check_instance_is_a(element.gst_element() as *mut GTypeInstance, gst_video_overlay_get_type()) == 1{
+                Some(VideoOverlay{ overlay: element })
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Tells the sink to render into the window identified by handle
+    /// (an XID, HWND, NSView* or wl_surface* depending on the platform).
+    pub fn set_window_handle(&mut self, handle: usize){
+        unsafe{
+            gst_video_overlay_set_window_handle(self.gst_video_overlay_mut(), handle as guintptr);
+        }
+    }
+
+    /// Translates a raw-window-handle into the platform specific handle
+    /// the sink expects and sets it.
+    ///
+    /// Returns false if the kind of window is not supported by GStreamer
+    /// video sinks.
+    #[cfg(feature="raw-window-handle")]
+    pub fn set_raw_window_handle(&mut self, handle: &RawWindowHandle) -> bool{
+        let handle = match *handle{
+            RawWindowHandle::Xlib(ref xlib) => xlib.window as usize,
+            RawWindowHandle::Xcb(ref xcb) => xcb.window as usize,
+            RawWindowHandle::Wayland(ref wayland) => wayland.surface as usize,
+            RawWindowHandle::Win32(ref win32) => win32.hwnd as usize,
+            RawWindowHandle::AppKit(ref appkit) => appkit.ns_view as usize,
+            _ => return false
+        };
+        if handle == 0 {
+            return false;
+        }
+        self.set_window_handle(handle);
+        true
+    }
+
+    /// Configures the area of the window the video will be drawn to, by
+    /// default the whole window is used.
+    ///
+    /// Returns false if the sink doesn't support render rectangles.
+    pub fn set_render_rectangle(&mut self, x: i32, y: i32, width: i32, height: i32) -> bool{
+        unsafe{
+            gst_video_overlay_set_render_rectangle(self.gst_video_overlay_mut(), x, y, width, height) == 1
+        }
+    }
+
+    /// Asks the sink to redraw the last frame, usually called when the
+    /// window is exposed or resized.
+    pub fn expose(&mut self){
+        unsafe{
+            gst_video_overlay_expose(self.gst_video_overlay_mut());
+        }
+    }
+
+    /// Enables or disables the handling of mouse and keyboard events by the
+    /// sink's window.
+    pub fn handle_events(&mut self, handle_events: bool){
+        unsafe{
+            gst_video_overlay_handle_events(self.gst_video_overlay_mut(), handle_events as gboolean);
+        }
+    }
+
+    pub unsafe fn gst_video_overlay(&self) -> *const GstVideoOverlay{
+        self.overlay.gst_element() as *const GstVideoOverlay
+    }
+
+    pub unsafe fn gst_video_overlay_mut(&mut self) -> *mut GstVideoOverlay{
+        self.overlay.gst_element_mut() as *mut GstVideoOverlay
+    }
+}
+
+impl ElementT for VideoOverlay{
+    fn as_element(&self) -> &Element{
+        &self.overlay
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.overlay
+    }
+}
+
+impl ::Transfer for VideoOverlay{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.overlay.transfer()
+    }
+}
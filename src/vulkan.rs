@@ -0,0 +1,137 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use context::Context;
+use element::{Element, ElementT};
+
+use std::os::raw::c_void;
+
+/// Caps feature of video in Vulkan images, as in
+/// `video/x-raw(memory:VulkanImage),format=RGBA`
+pub const CAPS_FEATURE_MEMORY_VULKAN_IMAGE: &'static str = "memory:VulkanImage";
+/// Caps feature of video in Vulkan buffers
+pub const CAPS_FEATURE_MEMORY_VULKAN_BUFFER: &'static str = "memory:VulkanBuffer";
+
+/// Context types used by the Vulkan elements to share the instance and
+/// device, each object is stored in a field named as the context type
+pub const VULKAN_INSTANCE_CONTEXT_TYPE: &'static str = "gst.vulkan.instance";
+pub const VULKAN_DEVICE_CONTEXT_TYPE: &'static str = "gst.vulkan.device";
+
+// Public head of GstVulkanInstance and GstVulkanDevice from
+// gst/vulkan/gstvulkaninstance.h and gstvulkandevice.h
+#[repr(C)]
+struct GstVulkanInstanceHead{
+    parent: GstObject,
+    instance: *mut c_void,
+}
+
+#[repr(C)]
+struct GstVulkanDeviceHead{
+    parent: GstObject,
+    instance: *mut c_void,
+    physical_device: *mut c_void,
+    device: *mut c_void,
+}
+
+/// Returns caps for video of the given format in Vulkan images
+pub fn vulkan_image_caps(format: &str) -> Option<Caps>{
+    Caps::from_string(&format!("video/x-raw({}),format={}", CAPS_FEATURE_MEMORY_VULKAN_IMAGE, format))
+}
+
+/// Returns the VkInstance handle of a gst.vulkan.instance context.
+///
+/// Together with vk_device this lets a renderer use the same Vulkan
+/// device as the pipeline, so the images of buffers with
+/// `memory:VulkanImage` caps can be sampled directly. Contexts are
+/// received in HAVE_CONTEXT messages and can be set on other pipelines
+/// with ElementT::set_context so they share the same device.
+pub fn vk_instance(context: &Context) -> Option<*mut c_void>{
+    if !context.has_context_type(VULKAN_INSTANCE_CONTEXT_TYPE){
+        return None;
+    }
+    unsafe{
+        let instance = context.object_field(VULKAN_INSTANCE_CONTEXT_TYPE) as *mut GstVulkanInstanceHead;
+        if instance != ptr::null_mut(){
+            Some((*instance).instance)
+        }else{
+            None
+        }
+    }
+}
+
+/// Returns the VkPhysicalDevice and VkDevice handles of a
+/// gst.vulkan.device context
+pub fn vk_device(context: &Context) -> Option<(*mut c_void, *mut c_void)>{
+    if !context.has_context_type(VULKAN_DEVICE_CONTEXT_TYPE){
+        return None;
+    }
+    unsafe{
+        let device = context.object_field(VULKAN_DEVICE_CONTEXT_TYPE) as *mut GstVulkanDeviceHead;
+        if device != ptr::null_mut(){
+            let physical_device = (*device).physical_device as *mut GstObject;
+            // GstVulkanPhysicalDevice stores the VkPhysicalDevice after
+            // its instance and device index
+            let vk_physical_device = if physical_device != ptr::null_mut(){
+                *((physical_device as *mut u8).offset(mem::size_of::<GstObject>() as isize
+                    + mem::size_of::<*mut c_void>() as isize
+                    + mem::size_of::<*mut c_void>() as isize) as *mut *mut c_void)
+            }else{
+                ptr::null_mut()
+            };
+            Some((vk_physical_device, (*device).device))
+        }else{
+            None
+        }
+    }
+}
+
+/// Wrapper around vulkansink, which renders video with Vulkan
+pub struct VulkanSink{
+    vulkansink: Element
+}
+
+unsafe impl Sync for VulkanSink {}
+unsafe impl Send for VulkanSink {}
+
+impl VulkanSink{
+    pub fn new(name: &str) -> Option<VulkanSink>{
+        Element::new("vulkansink", name).map(|vulkansink| VulkanSink{ vulkansink: vulkansink })
+    }
+
+    pub fn new_from_element(element: Element) -> VulkanSink{
+        VulkanSink{ vulkansink: element }
+    }
+
+    pub fn set_force_aspect_ratio(&self, force: bool){
+        self.vulkansink.set("force-aspect-ratio", force as gboolean);
+    }
+}
+
+impl ElementT for VulkanSink{
+    fn as_element(&self) -> &Element{
+        &self.vulkansink
+    }
+
+    fn as_element_mut(&mut self) -> &mut Element{
+        &mut self.vulkansink
+    }
+}
+
+impl ::Transfer for VulkanSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.vulkansink.transfer()
+    }
+}
+
+/// Creates a vulkanupload, which moves video in system memory to Vulkan
+/// images, to be followed by other Vulkan elements or an appsink with
+/// vulkan_image_caps
+pub fn vulkan_upload(name: &str) -> Option<Element>{
+    Element::new("vulkanupload", name)
+}
+
+/// Creates a vulkandownload, which moves video in Vulkan images back to
+/// system memory
+pub fn vulkan_download(name: &str) -> Option<Element>{
+    Element::new("vulkandownload", name)
+}
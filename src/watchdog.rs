@@ -0,0 +1,145 @@
+use ffi::*;
+use element::{Element, ElementT};
+use pipeline::{Pipeline, PipelineT};
+use bin::BinT;
+use topology::PipelineGraph;
+use value::Value;
+use error::Result;
+
+use std::cmp;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Shortest interval between checks of the position, so a zero timeout
+// checks once per interval instead of spinning
+const MIN_POLL: Duration = Duration::from_millis(10);
+
+/// What a Watchdog does after reporting a stall
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum RestartPolicy{
+    /// Only report the stall, the watchdog keeps running and reports
+    /// again if the pipeline stays stalled for another timeout
+    Never,
+    /// Sets the pipeline to NULL and back to PLAYING, up to max_restarts
+    /// times, after that stalls are only reported
+    Restart{ max_restarts: u32 },
+}
+
+/// Information about a stalled pipeline passed to the Watchdog callback
+#[derive(Clone,Debug)]
+pub struct StallInfo{
+    /// Last position reported by the pipeline in nanoseconds
+    pub position: Option<i64>,
+    /// Time since the position last advanced
+    pub stalled_for: Duration,
+    pub state: GstState,
+    pub pending_state: GstState,
+    /// Number of restarts done so far
+    pub restarts: u32,
+    /// True if the watchdog is going to restart the pipeline after the
+    /// callback returns
+    pub restarting: bool,
+    /// Structure and state of the pipeline when the stall was detected,
+    /// elements with a pending state change usually point to the cause
+    pub graph: PipelineGraph,
+}
+
+/// Watches a playing pipeline from a thread and calls a callback when its
+/// position stops advancing for longer than a timeout, as when a network
+/// source stops receiving data or a decoder deadlocks:
+///
+/// ```ignore
+/// let watchdog = Watchdog::new(&pipeline, Duration::from_secs(10),
+///     RestartPolicy::Restart{ max_restarts: 3 }, |info| {
+///         println!("pipeline stalled at {:?} for {:?}", info.position, info.stalled_for);
+///     });
+/// ```
+///
+/// The pipeline isn't considered stalled while it's not in PLAYING, so
+/// pausing it doesn't trigger the watchdog. The position of live pipelines
+/// follows the clock even when no data arrives, for those the watchdog
+/// element, see watchdog_element, or a PipelineMonitor are more reliable.
+/// A zero timeout reports a stall as soon as one check sees no progress.
+///
+/// The watchdog stops when dropped.
+pub struct Watchdog{
+    running: Arc<AtomicBool>,
+}
+
+impl Watchdog{
+    pub fn new<P, F>(pipeline: &P, timeout: Duration, policy: RestartPolicy, on_stall: F) -> Watchdog
+        where P: PipelineT, F: Fn(&StallInfo) + Send + 'static
+    {
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+        let mut pipeline = pipeline.to_pipeline();
+        thread::spawn(move || {
+            let poll = cmp::max(timeout / 4, MIN_POLL);
+            let mut last_position = None;
+            let mut last_progress = Instant::now();
+            let mut restarts = 0;
+            while thread_running.load(Ordering::SeqCst){
+                thread::sleep(poll);
+                let (state, pending_state, _) = pipeline.get_state(0);
+                let position = pipeline.position_ns();
+                if state as i32 != GST_STATE_PLAYING as i32 || position != last_position{
+                    last_position = position;
+                    last_progress = Instant::now();
+                    continue;
+                }
+                let stalled_for = last_progress.elapsed();
+                if stalled_for < timeout || !thread_running.load(Ordering::SeqCst){
+                    continue;
+                }
+                let restarting = match policy{
+                    RestartPolicy::Restart{ max_restarts } => restarts < max_restarts,
+                    RestartPolicy::Never => false,
+                };
+                on_stall(&StallInfo{
+                    position: position,
+                    stalled_for: stalled_for,
+                    state: state,
+                    pending_state: pending_state,
+                    restarts: restarts,
+                    restarting: restarting,
+                    graph: pipeline.topology(),
+                });
+                if restarting{
+                    restart(&mut pipeline);
+                    restarts += 1;
+                    last_position = None;
+                }
+                last_progress = Instant::now();
+            }
+        });
+        Watchdog{ running: running }
+    }
+
+    pub fn stop(&self){
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Drop for Watchdog{
+    fn drop(&mut self){
+        self.stop();
+    }
+}
+
+fn restart(pipeline: &mut Pipeline){
+    pipeline.set_state(GST_STATE_NULL);
+    pipeline.set_state(GST_STATE_PLAYING);
+}
+
+/// Creates a watchdog element, from gst-plugins-bad, that posts an error
+/// on the bus if no buffers or events go through it for timeout, to be
+/// linked after a live source where the position keeps advancing without
+/// data. The timeout is clamped to between 1 millisecond, since 0 disables
+/// the element, and i32::MAX milliseconds
+pub fn watchdog_element(timeout: Duration) -> Result<Element>{
+    let ms = timeout.as_secs().saturating_mul(1000).saturating_add(timeout.subsec_nanos() as u64 / 1000000);
+    let ms = cmp::min(cmp::max(ms, 1), i32::max_value() as u64) as i32;
+    Element::with_properties("watchdog", &[("timeout", Value::Int(ms))])
+}